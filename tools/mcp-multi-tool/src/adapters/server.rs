@@ -1,25 +1,52 @@
 use anyhow::Result;
+use parking_lot::Mutex;
 use rmcp::{ErrorData as McpError, ServerHandler, model::*};
-use serde_json::{Value, json};
+use serde_json::{Map, Value, json};
 use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
     sync::Arc,
-    time::{Instant, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
 
 use crate::{
     app::{
-        error_budget::{ErrorBudget, FreezeReport, RecordOutcome},
-        inspector_service::{CallOutcome, InspectorService},
-        registry::ToolRegistry,
+        bench, conformance,
+        error_budget::{ErrorBudget, FreezeReport, RecordOutcome, ThawReport},
+        inspector_service::{
+            CallCancelled, CallOutcome, CallTimedOut, CapabilityNotSupported, InspectorService,
+            InvalidArguments, InvalidHeaders, RetriesExhausted, SessionNotFound,
+            ShellTargetsDisabled, StdioEnv, StreamMaxDurationExceeded, TargetUnreachable,
+            TlsConfigError, UnsupportedProtocol, probe_descriptor,
+        },
+        registry::{ADMIN_TOOLS, ToolRegistry},
+        sessions::redact_target,
+        stdio_transport::SpawnTimeout,
     },
     domain::run::{InspectionRun, RunState},
-    infra::{config::IdempotencyConflictPolicy, metrics, outbox::Outbox},
+    infra::{
+        compliance_history::ComplianceHistory,
+        config::{
+            DottedToolNamePolicy, DuplicateExternalRefPolicy, EmptyResultPolicy,
+            IdempotencyConflictPolicy,
+        },
+        event_sink::EventSink,
+        health, metrics,
+        process_stats::StatsReport,
+    },
     shared::{
         idempotency::{ClaimOutcome, IdempotencyStore},
+        recent_events::RecentEvents,
+        target_aliases::{TargetAliases, probe_request},
         types::{
-            CallRequest, CallTrace, DescribeRequest, InspectionRunEvent, ProbeRequest,
-            TargetDescriptor,
+            BenchRequest, CallRequest, CallTarget, CallTrace, CancelRequest, CompareRequest,
+            ComplianceHistoryRequest, DEFAULT_COMPLIANCE_HISTORY_LIMIT, DescribeRequest,
+            DisconnectRequest, FallbackAttempt, GetPromptRequest, IdempotencyImportRequest,
+            IdempotencySnapshotEntry, InspectionRunEvent, JsonLimits, OutputSchemaCheck,
+            ProbeRequest, ReadResourceRequest, ReleaseTrack, SetLoggingLevelRequest,
+            TargetDescriptor, ToolListReport, ToolNamePolicy, TraceWarning, TransportBenchRequest,
         },
     },
 };
@@ -28,17 +55,48 @@ use crate::{
 pub struct InspectorServer {
     svc: InspectorService,
     registry: ToolRegistry,
-    outbox: Arc<Outbox>,
+    outbox: Arc<dyn EventSink>,
     idempotency: Arc<IdempotencyStore>,
     conflict_policy: IdempotencyConflictPolicy,
     error_budget: Arc<ErrorBudget>,
+    empty_result_policy: EmptyResultPolicy,
+    duplicate_external_ref_policy: DuplicateExternalRefPolicy,
+    dotted_tool_name_policy: DottedToolNamePolicy,
+    /// Underscored alias -> dotted downstream name, learned from `inspector_list_tools`.
+    dotted_aliases: Arc<Mutex<BTreeMap<String, String>>>,
+    recent_events: Arc<RecentEvents>,
+    tool_arg_defaults: Arc<BTreeMap<String, Map<String, Value>>>,
+    target_aliases: Arc<TargetAliases>,
+    idempotency_auto_key: bool,
+    json_limits: JsonLimits,
+    tool_name_policy: ToolNamePolicy,
+    capture_target_capabilities: bool,
+    stream_slots: Option<Arc<Semaphore>>,
+    /// Cancellation handles of in-flight `inspector_call` runs, for `inspector_cancel`.
+    running_calls: Arc<Mutex<HashMap<uuid::Uuid, CancellationToken>>>,
+    compliance_history: Option<Arc<ComplianceHistory>>,
+    /// Tools still served while the error budget is frozen; `None` serves every tool but
+    /// `inspector_call` and `inspector_bench`, which check the budget themselves.
+    freeze_allowed_tools: Option<Arc<BTreeSet<String>>>,
+}
+
+/// Keeps a run cancellable through `inspector_cancel` until dropped.
+struct RunningCall {
+    calls: Arc<Mutex<HashMap<uuid::Uuid, CancellationToken>>>,
+    run_id: uuid::Uuid,
+}
+
+impl Drop for RunningCall {
+    fn drop(&mut self) {
+        self.calls.lock().remove(&self.run_id);
+    }
 }
 
 impl InspectorServer {
     pub fn new(
         svc: InspectorService,
         registry: ToolRegistry,
-        outbox: Arc<Outbox>,
+        outbox: Arc<dyn EventSink>,
         idempotency: Arc<IdempotencyStore>,
         conflict_policy: IdempotencyConflictPolicy,
         error_budget: Arc<ErrorBudget>,
@@ -50,9 +108,138 @@ impl InspectorServer {
             idempotency,
             conflict_policy,
             error_budget,
+            empty_result_policy: EmptyResultPolicy::default(),
+            duplicate_external_ref_policy: DuplicateExternalRefPolicy::default(),
+            dotted_tool_name_policy: DottedToolNamePolicy::default(),
+            dotted_aliases: Arc::default(),
+            recent_events: Arc::new(RecentEvents::default()),
+            tool_arg_defaults: Arc::default(),
+            target_aliases: Arc::default(),
+            idempotency_auto_key: false,
+            json_limits: JsonLimits::default(),
+            tool_name_policy: ToolNamePolicy::default(),
+            capture_target_capabilities: false,
+            stream_slots: None,
+            running_calls: Arc::default(),
+            compliance_history: None,
+            freeze_allowed_tools: None,
         }
     }
 
+    /// Serve only `tools` (plus help and the diagnosis and teardown tools) while the error
+    /// budget is frozen; the rest fail with `ERROR_BUDGET_EXHAUSTED`. Dotted names match
+    /// their underscored form.
+    pub fn with_freeze_allowed_tools(mut self, tools: Option<Vec<String>>) -> Self {
+        self.freeze_allowed_tools = tools.map(|tools| {
+            Arc::new(
+                tools
+                    .into_iter()
+                    .map(|name| name.replace('.', "_"))
+                    .collect(),
+            )
+        });
+        self
+    }
+
+    /// Whether `name` is refused outright while the budget is frozen. `inspector_call` and
+    /// `inspector_bench` are not: they consult the budget themselves and record the refusal.
+    /// Health, the budget window, cancel and disconnect always answer, so a frozen server
+    /// can still be diagnosed and its runs and sessions torn down.
+    fn blocked_by_freeze(&self, name: &str) -> bool {
+        let name = name.replace('.', "_");
+        if matches!(
+            name.as_str(),
+            "help"
+                | "inspector_help"
+                | "inspector_call"
+                | "inspector_bench"
+                | "inspector_health"
+                | "inspector_budget_window"
+                | "inspector_cancel"
+                | "inspector_disconnect"
+        ) {
+            return false;
+        }
+        self.freeze_allowed_tools
+            .as_ref()
+            .is_some_and(|allowed| !allowed.contains(&name))
+    }
+
+    /// Serve `inspector_compliance_history` from `history`.
+    pub fn with_compliance_history(mut self, history: Option<Arc<ComplianceHistory>>) -> Self {
+        self.compliance_history = history;
+        self
+    }
+
+    pub fn with_recent_events(mut self, recent_events: Arc<RecentEvents>) -> Self {
+        self.recent_events = recent_events;
+        self
+    }
+
+    pub fn with_empty_result_policy(mut self, policy: EmptyResultPolicy) -> Self {
+        self.empty_result_policy = policy;
+        self
+    }
+
+    pub fn with_duplicate_external_ref_policy(
+        mut self,
+        policy: DuplicateExternalRefPolicy,
+    ) -> Self {
+        self.duplicate_external_ref_policy = policy;
+        self
+    }
+
+    pub fn with_dotted_tool_name_policy(mut self, policy: DottedToolNamePolicy) -> Self {
+        self.dotted_tool_name_policy = policy;
+        self
+    }
+
+    /// Base arguments per downstream tool, merged under the caller's `arguments_json`.
+    pub fn with_tool_arg_defaults(
+        mut self,
+        defaults: BTreeMap<String, Map<String, Value>>,
+    ) -> Self {
+        self.tool_arg_defaults = Arc::new(defaults);
+        self
+    }
+
+    /// Configured targets that requests can name with `target_alias`.
+    pub fn with_target_aliases(mut self, aliases: TargetAliases) -> Self {
+        self.target_aliases = Arc::new(aliases);
+        self
+    }
+
+    /// Derive an idempotency key for calls that do not supply one, deduplicating identical calls.
+    pub fn with_idempotency_auto_key(mut self, enabled: bool) -> Self {
+        self.idempotency_auto_key = enabled;
+        self
+    }
+
+    /// Reject `arguments_json` nested deeper or larger than `limits` with `ARGUMENTS_TOO_COMPLEX`.
+    pub fn with_json_limits(mut self, limits: JsonLimits) -> Self {
+        self.json_limits = limits;
+        self
+    }
+
+    /// Refuse downstream tool names the policy forbids with `DOWNSTREAM_TOOL_FORBIDDEN`.
+    pub fn with_tool_name_policy(mut self, policy: ToolNamePolicy) -> Self {
+        self.tool_name_policy = policy;
+        self
+    }
+
+    /// Cap `stream: true` calls in flight at once; calls over the cap fail with
+    /// `STREAM_LIMIT`. Non-stream calls never take a slot.
+    pub fn with_max_concurrent_streams(mut self, limit: Option<usize>) -> Self {
+        self.stream_slots = limit.map(|limit| Arc::new(Semaphore::new(limit)));
+        self
+    }
+
+    /// Record the target's advertised capabilities on each successful call event.
+    pub fn with_capture_target_capabilities(mut self, enabled: bool) -> Self {
+        self.capture_target_capabilities = enabled;
+        self
+    }
+
     fn idempotency_conflict_response(
         &self,
         existing: Option<InspectionRunEvent>,
@@ -79,6 +266,68 @@ impl InspectorServer {
         }))
     }
 
+    fn register_call(&self, run_id: uuid::Uuid, cancellation: CancellationToken) -> RunningCall {
+        self.running_calls.lock().insert(run_id, cancellation);
+        RunningCall {
+            calls: Arc::clone(&self.running_calls),
+            run_id,
+        }
+    }
+
+    /// Call `req` against one target, pointing `descriptor` (and the idempotency record) at it.
+    async fn call_target(
+        &self,
+        svc: &InspectorService,
+        target: &CallTarget,
+        req: &CallRequest,
+        descriptor: &mut TargetDescriptor,
+        claimed_key: Option<&str>,
+    ) -> Result<CallOutcome> {
+        *descriptor = TargetDescriptor {
+            transport: "stdio".into(),
+            command: None,
+            url: None,
+            headers: None,
+            socket_path: None,
+        };
+        if let Some(http) = target.http.as_ref() {
+            descriptor.transport = "http".into();
+            descriptor.url = Some(http.url.clone());
+            descriptor.headers = http.headers.clone();
+        } else if let Some(sse) = target.sse.as_ref() {
+            descriptor.transport = "sse".into();
+            descriptor.url = Some(sse.url.clone());
+            descriptor.headers = sse.headers.clone();
+        } else if let Some(unix) = target.unix.as_ref() {
+            descriptor.transport = "unix".into();
+            descriptor.socket_path = Some(unix.path.clone());
+        } else if let Some(stdio) = target.stdio.as_ref() {
+            descriptor.command = Some(stdio.command.clone());
+        } else {
+            anyhow::bail!("fallback target sets none of 'stdio', 'sse', 'http' or 'unix'");
+        }
+        if let Some(key) = claimed_key {
+            self.idempotency.set_target(key, descriptor.clone());
+        }
+        if let Some(http) = target.http.as_ref() {
+            svc.call_http(http, req).await
+        } else if let Some(sse) = target.sse.as_ref() {
+            svc.call_sse(sse, req).await
+        } else if let Some(unix) = target.unix.as_ref() {
+            svc.call_unix(unix, req).await
+        } else {
+            let stdio = target.stdio.as_ref().expect("checked above");
+            let (program, args) = if stdio.shell {
+                svc.shell_invocation(&stdio.command, &stdio.args)?
+            } else {
+                (stdio.command.clone(), stdio.args.clone())
+            };
+            svc.call_stdio(program, args, StdioEnv::from(stdio), stdio.cwd.clone(), req)
+                .await
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn build_event(
         &self,
         run: &InspectionRun,
@@ -89,6 +338,7 @@ impl InspectorServer {
         response: Option<Value>,
         error: Option<String>,
         external_reference: Option<String>,
+        requested_tool_name: Option<String>,
     ) -> InspectionRunEvent {
         let started_at_str = started_at.to_string();
         InspectionRunEvent {
@@ -104,9 +354,228 @@ impl InspectorServer {
             error,
             idempotency_key: request.idempotency_key.clone(),
             external_reference,
+            target_capabilities: None,
+            requested_tool_name,
+        }
+    }
+
+    /// Settle `run` from the outcome of a target inspection other than a tool call
+    /// (`inspector_list_prompts`, `inspector_get_prompt`) and append its run event.
+    #[allow(clippy::too_many_arguments)]
+    fn finish_inspection(
+        &self,
+        run: &mut InspectionRun,
+        tool_name: &str,
+        request: Value,
+        target: TargetDescriptor,
+        started_at: OffsetDateTime,
+        timer: Instant,
+        outcome: Result<Value>,
+    ) -> Result<CallToolResult, CallToolResult> {
+        match &outcome {
+            Ok(_) => run.capture(),
+            Err(_) => run.fail(),
+        }
+        let event = InspectionRunEvent {
+            event_id: uuid::Uuid::new_v4(),
+            run_id: run.id,
+            tool_name: tool_name.to_string(),
+            state: run.state.as_str().to_string(),
+            started_at: started_at.to_string(),
+            duration_ms: timer.elapsed().as_millis() as u64,
+            target: Some(target),
+            request: Some(request),
+            response: outcome.as_ref().ok().cloned(),
+            error: outcome.as_ref().err().map(|err| format!("{err:#}")),
+            idempotency_key: None,
+            external_reference: None,
+            target_capabilities: None,
+            requested_tool_name: None,
+        };
+        if let Err(err) = self.record_event(&event) {
+            tracing::error!(run_id = %run.id, error = %err, "failed to append inspection event to outbox");
+        }
+        match outcome {
+            Ok(value) => Ok(CallToolResult::structured(value)),
+            Err(err) => Err(CallToolResult::structured_error(capability_error_payload(
+                &err,
+            ))),
+        }
+    }
+
+    /// Persist a freeze transition so availability windows can be rebuilt from the outbox.
+    fn append_freeze_transition(
+        &self,
+        run_id: uuid::Uuid,
+        tool_name: &str,
+        state: &str,
+        details: Value,
+    ) {
+        let event = InspectionRunEvent {
+            event_id: uuid::Uuid::new_v4(),
+            run_id,
+            tool_name: tool_name.to_string(),
+            state: state.to_string(),
+            started_at: OffsetDateTime::now_utc().to_string(),
+            duration_ms: 0,
+            target: None,
+            request: None,
+            response: Some(details),
+            error: None,
+            idempotency_key: None,
+            external_reference: None,
+            target_capabilities: None,
+            requested_tool_name: None,
+        };
+        if let Err(e) = self.record_event(&event) {
+            tracing::error!(%run_id, error=%e, state, "failed to append freeze transition to outbox");
+        }
+    }
+
+    /// Persist that a pooled stdio target was found exited and respawned, so flapping
+    /// downstream processes show up in the outbox.
+    fn target_restarted(
+        &self,
+        run_id: uuid::Uuid,
+        tool_name: &str,
+        target: &TargetDescriptor,
+        restarts: u64,
+    ) {
+        let event = InspectionRunEvent {
+            event_id: uuid::Uuid::new_v4(),
+            run_id,
+            tool_name: tool_name.to_string(),
+            state: "target_restarted".to_string(),
+            started_at: OffsetDateTime::now_utc().to_string(),
+            duration_ms: 0,
+            target: Some(target.clone()),
+            request: None,
+            response: Some(json!({ "code": "TARGET_RESTARTED", "restarts": restarts })),
+            error: None,
+            idempotency_key: None,
+            external_reference: None,
+            target_capabilities: None,
+            requested_tool_name: None,
+        };
+        if let Err(e) = self.record_event(&event) {
+            tracing::error!(%run_id, error=%e, "failed to append target restart to outbox");
+        }
+    }
+
+    fn freeze_triggered(&self, run_id: uuid::Uuid, tool_name: &str, report: &FreezeReport) {
+        metrics::set_error_budget_frozen(true);
+        tracing::warn!(%run_id, success_rate = report.success_rate, sample_size = report.sample_size, "error budget freeze triggered");
+        let details = json!({
+            "success_rate": report.success_rate,
+            "sample_size": report.sample_size,
+            "frozen_until": format_until(report.until),
+        });
+        self.append_freeze_transition(run_id, tool_name, "freeze_triggered", details);
+    }
+
+    fn freeze_cleared(&self, run_id: uuid::Uuid, tool_name: &str, report: &ThawReport) {
+        metrics::set_error_budget_frozen(false);
+        tracing::info!(%run_id, success_rate = report.success_rate, sample_size = report.sample_size, "error budget freeze lifted");
+        let details = json!({
+            "success_rate": report.success_rate,
+            "sample_size": report.sample_size,
+        });
+        self.append_freeze_transition(run_id, tool_name, "freeze_cleared", details);
+    }
+
+    fn observe_budget_outcome(&self, run_id: uuid::Uuid, tool_name: &str, outcome: RecordOutcome) {
+        match outcome {
+            RecordOutcome::FreezeTriggered(report) => {
+                self.freeze_triggered(run_id, tool_name, &report)
+            }
+            RecordOutcome::FreezeCleared(report) => self.freeze_cleared(run_id, tool_name, &report),
+            RecordOutcome::None => {}
         }
     }
 
+    /// Under `DottedToolNamePolicy::Underscore`, rename dotted tools to their underscored
+    /// alias and remember the mapping. A name whose alias another tool already uses stays
+    /// dotted rather than becoming ambiguous.
+    fn underscore_dotted_names(&self, tools: &mut [Tool]) {
+        if self.dotted_tool_name_policy != DottedToolNamePolicy::Underscore {
+            return;
+        }
+        let taken: std::collections::BTreeSet<String> =
+            tools.iter().map(|tool| tool.name.to_string()).collect();
+        let mut aliases = self.dotted_aliases.lock();
+        for tool in tools.iter_mut().filter(|tool| tool.name.contains('.')) {
+            let alias = tool.name.replace('.', "_");
+            if taken.contains(&alias) {
+                continue;
+            }
+            aliases.insert(alias.clone(), tool.name.to_string());
+            tool.name = alias.into();
+        }
+    }
+
+    /// Swap a learned underscored alias in `req` for the dotted downstream name, returning
+    /// the name the client asked for so the run event can record it.
+    fn resolve_dotted_alias(&self, req: &mut CallRequest) -> Option<String> {
+        if self.dotted_tool_name_policy != DottedToolNamePolicy::Underscore {
+            return None;
+        }
+        let original = self.dotted_aliases.lock().get(&req.tool_name).cloned()?;
+        Some(std::mem::replace(&mut req.tool_name, original))
+    }
+
+    /// Settle what `inspector_call` can from the request alone, returning the name the
+    /// client asked for when it was a dotted alias. The JSON limits come first, so nothing
+    /// is derived from arguments they reject.
+    fn prepare_call(&self, req: &mut CallRequest) -> Result<Option<String>, CallToolResult> {
+        if let Err(err) = self.json_limits.check(&req.arguments_json) {
+            return Err(CallToolResult::structured_error(json!({
+                "error": err.to_string(),
+                "code": "ARGUMENTS_TOO_COMPLEX",
+            })));
+        }
+        let requested_tool_name = self.resolve_dotted_alias(req);
+        if let Err(err) = self.svc.check_target_blocks(req) {
+            return Err(CallToolResult::structured_error(json!({
+                "error": err.to_string(),
+                "code": "AMBIGUOUS_TARGET",
+                "target": err.location,
+                "blocks": err.blocks,
+                "target_alias": err.target_alias,
+            })));
+        }
+        if let Err(err) = self.tool_name_policy.check(&req.tool_name) {
+            return Err(CallToolResult::structured_error(json!({
+                "error": err.to_string(),
+                "code": "DOWNSTREAM_TOOL_FORBIDDEN",
+            })));
+        }
+        if let Some(defaults) = self.tool_arg_defaults.get(&req.tool_name) {
+            req.apply_arg_defaults(defaults);
+        }
+        if self.idempotency_auto_key && req.idempotency_key.is_none() {
+            req.idempotency_key = Some(req.derived_idempotency_key());
+        }
+        if let Err(err) = req.arguments_object() {
+            return Err(CallToolResult::structured_error(json!({
+                "error": err.to_string(),
+                "code": "BAD_ARGUMENTS",
+            })));
+        }
+        if let Err(err) = req.progress_token() {
+            return Err(CallToolResult::structured_error(json!({
+                "error": err.to_string(),
+                "code": "INVALID_PROGRESS_TOKEN",
+            })));
+        }
+        Ok(requested_tool_name)
+    }
+
+    /// Keep the event in the recent-events tail and persist it to the outbox.
+    fn record_event(&self, event: &InspectionRunEvent) -> Result<()> {
+        self.recent_events.push(event.clone());
+        self.outbox.append(event)
+    }
+
     fn snapshot_result(&self, result: &CallToolResult) -> Option<Value> {
         serde_json::to_value(result).ok()
     }
@@ -114,7 +583,7 @@ impl InspectorServer {
     fn attach_trace(result: &mut CallToolResult, trace: &CallTrace) {
         match serde_json::to_value(trace) {
             Ok(value) => {
-                let mut meta = result.meta.take().unwrap_or_else(Meta::new);
+                let mut meta = result.meta.take().unwrap_or_default();
                 meta.insert("trace".into(), value);
                 result.meta = Some(meta);
             }
@@ -136,6 +605,7 @@ impl ServerHandler for InspectorServer {
         let capabilities = ServerCapabilities::builder()
             .enable_tools()
             .enable_tool_list_changed()
+            .enable_resources()
             .build();
         let init = ServerInfo {
             // echo back the protocol requested by client for compatibility
@@ -171,19 +641,83 @@ impl ServerHandler for InspectorServer {
         }
     }
 
+    fn list_resources(
+        &self,
+        _request: Option<rmcp::model::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> impl std::future::Future<Output = Result<ListResourcesResult, McpError>> + Send + '_ {
+        let resources = [
+            (
+                TOOLS_RESOURCE_URI,
+                "tools",
+                "Tool catalog as returned by tools/list.",
+                "application/json",
+            ),
+            (
+                HELP_RESOURCE_URI,
+                "help",
+                "The help manual, one JSON object per line.",
+                "application/x-ndjson",
+            ),
+        ]
+        .into_iter()
+        .map(|(uri, name, description, mime_type)| {
+            let mut resource = RawResource::new(uri, name);
+            resource.description = Some(description.into());
+            resource.mime_type = Some(mime_type.into());
+            resource.no_annotation()
+        })
+        .collect();
+        std::future::ready(Ok(ListResourcesResult {
+            resources,
+            next_cursor: None,
+        }))
+    }
+
+    fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> impl std::future::Future<Output = Result<ReadResourceResult, McpError>> + Send + '_ {
+        let text = match request.uri.as_str() {
+            TOOLS_RESOURCE_URI => serde_json::to_string_pretty(&self.registry.list())
+                .map_err(|err| McpError::internal_error(err.to_string(), None)),
+            HELP_RESOURCE_URI => Ok(help_lines(
+                self.registry.release_track(),
+                &self.target_aliases,
+            )
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")),
+            other => Err(McpError::resource_not_found(
+                format!("unknown resource: {other}"),
+                None,
+            )),
+        };
+        std::future::ready(text.map(|text| ReadResourceResult {
+            contents: vec![ResourceContents::text(text, request.uri)],
+        }))
+    }
+
     fn call_tool(
         &self,
         request: CallToolRequestParam,
-        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+        context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
     ) -> impl std::future::Future<Output = Result<CallToolResult, McpError>> + Send + '_ {
         let this = self.clone();
         async move {
             tracing::info!(tool = %request.name, "call_tool received");
+            let received = Instant::now();
             let mut run = InspectionRun::new();
             run.start();
             let run_id = run.id;
             let name = request.name.as_ref();
-            let args_map = request.arguments.unwrap_or_default();
+            let mut args_map = request.arguments.unwrap_or_default();
+            let alias_resolved = this.target_aliases.resolve(
+                &mut args_map,
+                matches!(name, "inspector_call" | "inspector.call"),
+            );
             let args_val = serde_json::Value::Object(args_map);
             let mut failure = |msg: &str| {
                 run.fail();
@@ -191,7 +725,10 @@ impl ServerHandler for InspectorServer {
             };
 
             let release_track = this.registry.release_track();
-            if !release_track.allows_inspector() && name != "help" && name != "inspector_help" {
+            // inspector_health stays callable in rollback so orchestrators can observe readiness.
+            if !release_track.allows_inspector()
+                && !matches!(name, "help" | "inspector_help" | "inspector_health")
+            {
                 let payload = serde_json::json!({
                     "error": "inspector disabled by release track",
                     "code": "RELEASE_TRACK_ROLLBACK"
@@ -199,119 +736,37 @@ impl ServerHandler for InspectorServer {
                 run.fail();
                 return Ok(CallToolResult::structured_error(payload));
             }
+            if ADMIN_TOOLS.contains(&name) && !this.registry.admin_tools_enabled() {
+                run.fail();
+                return Ok(CallToolResult::structured_error(json!({
+                    "error": "admin tools are disabled; set ADMIN_TOOLS_ENABLED=true",
+                    "code": "ADMIN_TOOLS_DISABLED"
+                })));
+            }
 
-            let result: Result<CallToolResult, CallToolResult> = match name {
-                "help" | "inspector_help" => {
-                    let mut lines = vec![
-                        serde_json::json!({
-                            "section": "summary",
-                            "server": "mcp-multi-tool",
-                            "version": env!("CARGO_PKG_VERSION"),
-                            "protocol": "MCP",
-                            "release_track": release_track.as_str(),
-                            "transports": ["stdio", "sse", "http"]
-                        }),
-                        serde_json::json!({
-                            "section": "tool",
-                            "name": "inspector_probe",
-                            "summary": "Probe a downstream MCP server and measure latency.",
-                            "arguments": {
-                                "transport": "string stdio|sse|http",
-                                "command": "optional string",
-                                "args": "optional array<string>",
-                                "env": "optional map",
-                                "cwd": "optional string",
-                                "url": "optional string",
-                                "headers": "optional map",
-                                "auth_token": "optional string",
-                                "handshake_timeout_ms": "optional int"
-                            },
-                            "returns": "ProbeResult"
-                        }),
-                        serde_json::json!({
-                            "section": "tool",
-                            "name": "inspector_list_tools",
-                            "summary": "List tools exposed by the target MCP.",
-                            "arguments": {
-                                "transport": "string stdio|sse|http",
-                                "command": "optional string",
-                                "args": "optional array<string>",
-                                "env": "optional map",
-                                "cwd": "optional string",
-                                "url": "optional string",
-                                "headers": "optional map",
-                                "auth_token": "optional string",
-                                "handshake_timeout_ms": "optional int"
-                            },
-                            "returns": "array<Tool>"
-                        }),
-                        serde_json::json!({
-                            "section": "tool",
-                            "name": "inspector_describe",
-                            "summary": "Fetch JSON schema and annotations for a tool.",
-                            "arguments": {
-                                "tool_name": "string",
-                                "transport": "optional string",
-                                "command": "optional string",
-                                "args": "optional array<string>",
-                                "env": "optional map",
-                                "cwd": "optional string",
-                                "url": "optional string",
-                                "headers": "optional map",
-                                "auth_token": "optional string",
-                                "handshake_timeout_ms": "optional int"
-                            },
-                            "returns": "Tool"
-                        }),
-                        serde_json::json!({
-                            "section": "tool",
-                            "name": "inspector_call",
-                            "summary": "Invoke a downstream tool with optional streaming.",
-                            "arguments": {
-                                "tool_name": "string",
-                                "arguments_json": "object",
-                                "idempotency_key": "optional string",
-                                "external_reference": "optional string",
-                                "stream": "boolean",
-                                "stdio": "optional target",
-                                "sse": "optional target",
-                                "http": "optional target"
-                            },
-                            "returns": "CallToolResult",
-                            "notes": [
-                                "Set stream=true to capture progress notifications.",
-                                "When the error budget is exhausted the server returns ERROR_BUDGET_EXHAUSTED until the success rate recovers."
-                            ]
-                        }),
-                        serde_json::json!({
-                            "section": "environment",
-                            "INSPECTOR_STDIO_CMD": "<command> [args...] required when no stdio target override is provided",
-                            "ERROR_BUDGET_*": "tune freeze threshold (see docs/howto/onboarding.md)",
-                            "RUST_LOG": "default info"
-                        }),
-                        serde_json::json!({
-                            "section": "workflow",
-                            "steps": [
-                                "inspector_probe",
-                                "inspector_list_tools",
-                                "inspector_describe",
-                                "inspector_call"
-                            ],
-                            "diagnostics": [
-                                "Prometheus /metrics -> inspector_lock_wait_ms histogram",
-                                "Outbox JSONL/SQLite at data/outbox"
-                            ]
-                        }),
-                    ];
-
-                    if !release_track.allows_inspector() {
-                        lines.push(serde_json::json!({
-                            "section": "notice",
-                            "code": "release_track_rollback",
-                            "message": "Inspector tools temporarily disabled; set RELEASE_TRACK=stable or canary to re-enable."
-                        }));
+            if this.blocked_by_freeze(name) {
+                match this.error_budget.admit_now() {
+                    Err(report) => {
+                        metrics::set_error_budget_frozen(true);
+                        run.fail();
+                        return Ok(CallToolResult::structured_error(freeze_payload(&report)));
                     }
+                    Ok(Some(report)) => this.freeze_cleared(run_id, name, &report),
+                    Ok(None) => {}
+                }
+            }
+            if let Err(unknown) = alias_resolved {
+                run.fail();
+                return Ok(CallToolResult::structured_error(json!({
+                    "error": unknown.to_string(),
+                    "code": "TARGET_ALIAS_NOT_FOUND",
+                    "aliases": unknown.known,
+                })));
+            }
 
+            let result: Result<CallToolResult, CallToolResult> = match name {
+                "help" | "inspector_help" => {
+                    let lines = help_lines(release_track, &this.target_aliases);
                     let payload = serde_json::json!({
                         "format": "jsonl",
                         "lines": lines
@@ -321,6 +776,12 @@ impl ServerHandler for InspectorServer {
                     });
                     Ok(CallToolResult::structured(payload))
                 }
+                "inspector_health" | "inspector.health" => {
+                    let report = health::evaluate(this.outbox.as_ref(), release_track);
+                    Ok(CallToolResult::structured(
+                        serde_json::to_value(report).unwrap(),
+                    ))
+                }
                 // New names without dots (Codex-safe)
                 "inspector_probe" | "inspector.probe" => {
                     match serde_json::from_value::<ProbeRequest>(args_val) {
@@ -328,7 +789,9 @@ impl ServerHandler for InspectorServer {
                             Ok(res) => Ok(CallToolResult::structured(
                                 serde_json::to_value(res).unwrap(),
                             )),
-                            Err(e) => Err(failure(&e.to_string())),
+                            Err(e) => Err(tls_config_error(&e)
+                                .or_else(|| shell_targets_disabled(&e))
+                                .unwrap_or_else(|| failure(&e.to_string()))),
                         },
                         Err(e) => Err(failure(&e.to_string())),
                     }
@@ -336,28 +799,364 @@ impl ServerHandler for InspectorServer {
                 "inspector_list_tools" | "inspector.list_tools" => {
                     match serde_json::from_value::<ProbeRequest>(args_val) {
                         Ok(req) => match this.svc.list_tools(req).await {
-                            Ok(tools) => Ok(CallToolResult::structured(json!({
-                                "tools": tools
+                            Ok(mut tools) => {
+                                this.underscore_dotted_names(&mut tools);
+                                Ok(CallToolResult::structured(json!(ToolListReport::from(
+                                    tools
+                                ))))
+                            }
+                            Err(e) => Err(session_not_found(&e)
+                                .or_else(|| tls_config_error(&e))
+                                .or_else(|| shell_targets_disabled(&e))
+                                .unwrap_or_else(|| failure(&e.to_string()))),
+                        },
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_compare" | "inspector.compare" => {
+                    match serde_json::from_value::<CompareRequest>(args_val) {
+                        Ok(req) => match this.svc.compare(req).await {
+                            Ok(diff) => Ok(CallToolResult::structured(json!(diff))),
+                            Err(e) => Err(failure(&format!("{e:#}"))),
+                        },
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_stats" | "inspector.stats" => {
+                    let report =
+                        StatsReport::collect(this.svc.sessions().len(), this.idempotency.len());
+                    Ok(CallToolResult::structured(json!(report)))
+                }
+                "inspector_sessions" | "inspector.sessions" => {
+                    let sessions = this.svc.sessions();
+                    Ok(CallToolResult::structured(json!({
+                        "count": sessions.len(),
+                        "sessions": sessions,
+                    })))
+                }
+                "inspector_schema_sizes" | "inspector.schema_sizes" => {
+                    match serde_json::from_value::<ProbeRequest>(args_val) {
+                        Ok(req) => match this.svc.schema_sizes(req).await {
+                            Ok(report) => Ok(CallToolResult::structured(
+                                serde_json::to_value(report).unwrap(),
+                            )),
+                            Err(e) => Err(failure(&e.to_string())),
+                        },
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_schema_hash" | "inspector.schema_hash" => {
+                    match serde_json::from_value::<DescribeRequest>(args_val) {
+                        Ok(req) => match this.svc.schema_hash(req).await {
+                            Ok(report) => Ok(CallToolResult::structured(
+                                serde_json::to_value(report).unwrap(),
+                            )),
+                            Err(e) => Err(failure(&e.to_string())),
+                        },
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_set_logging_level" | "inspector.set_logging_level" => {
+                    match serde_json::from_value::<SetLoggingLevelRequest>(args_val) {
+                        Ok(req) => match this.svc.set_logging_level(req).await {
+                            Ok(level) => Ok(CallToolResult::structured(json!({
+                                "level": level,
+                                "applied": true,
                             }))),
                             Err(e) => Err(failure(&e.to_string())),
                         },
                         Err(e) => Err(failure(&e.to_string())),
                     }
                 }
+                "inspector_list_resources" | "inspector.list_resources" => {
+                    match serde_json::from_value::<ProbeRequest>(args_val) {
+                        Ok(req) => match this.svc.list_resources(req).await {
+                            Ok(report) => Ok(CallToolResult::structured(json!(report))),
+                            Err(e) => {
+                                run.fail();
+                                Err(CallToolResult::structured_error(capability_error_payload(
+                                    &e,
+                                )))
+                            }
+                        },
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_read_resource" | "inspector.read_resource" => {
+                    match serde_json::from_value::<ReadResourceRequest>(args_val) {
+                        Ok(req) => match this.svc.read_resource(req).await {
+                            Ok(report) => Ok(CallToolResult::structured(json!(report))),
+                            Err(e) => {
+                                run.fail();
+                                Err(CallToolResult::structured_error(capability_error_payload(
+                                    &e,
+                                )))
+                            }
+                        },
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_list_prompts" | "inspector.list_prompts" => {
+                    match serde_json::from_value::<ProbeRequest>(args_val) {
+                        Ok(req) => {
+                            let started_at = OffsetDateTime::now_utc();
+                            let timer = Instant::now();
+                            let outcome = this.svc.list_prompts(&req).await.map(|r| json!(r));
+                            this.finish_inspection(
+                                &mut run,
+                                "inspector_list_prompts",
+                                json!(req),
+                                probe_descriptor(&req),
+                                started_at,
+                                timer,
+                                outcome,
+                            )
+                        }
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_get_prompt" | "inspector.get_prompt" => {
+                    match serde_json::from_value::<GetPromptRequest>(args_val) {
+                        Ok(req) => {
+                            let started_at = OffsetDateTime::now_utc();
+                            let timer = Instant::now();
+                            let outcome = this.svc.get_prompt(&req).await.map(|r| json!(r));
+                            this.finish_inspection(
+                                &mut run,
+                                "inspector_get_prompt",
+                                json!(req),
+                                probe_descriptor(&req.probe),
+                                started_at,
+                                timer,
+                                outcome,
+                            )
+                        }
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_transport_bench" | "inspector.transport_bench" => {
+                    match serde_json::from_value::<TransportBenchRequest>(args_val) {
+                        Ok(req) => match this.svc.transport_bench(req).await {
+                            Ok(report) => Ok(CallToolResult::structured(json!(report))),
+                            Err(e) => Err(failure(&e.to_string())),
+                        },
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_compliance_history" | "inspector.compliance_history" => {
+                    match serde_json::from_value::<ComplianceHistoryRequest>(args_val) {
+                        Ok(req) => match this.compliance_history.as_ref() {
+                            None => Err(failure(
+                                "compliance history is disabled; set COMPLIANCE_HISTORY_DB",
+                            )),
+                            Some(history) => match history.report(
+                                req.target.as_deref(),
+                                req.limit.unwrap_or(DEFAULT_COMPLIANCE_HISTORY_LIMIT),
+                            ) {
+                                Ok(report) => Ok(CallToolResult::structured(json!(report))),
+                                Err(e) => Err(failure(&format!("{e:#}"))),
+                            },
+                        },
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_bench" | "inspector.bench" => {
+                    match serde_json::from_value::<BenchRequest>(args_val) {
+                        Ok(req) => match this.error_budget.admit_now() {
+                            Err(report) => {
+                                metrics::set_error_budget_frozen(true);
+                                run.fail();
+                                Err(CallToolResult::structured_error(freeze_payload(&report)))
+                            }
+                            Ok(thawed) => {
+                                if let Some(report) = thawed {
+                                    this.freeze_cleared(run_id, "inspector_bench", &report);
+                                }
+                                let started_at = OffsetDateTime::now_utc();
+                                let timer = Instant::now();
+                                let outcome = bench::run(&this.svc, &req, &this.error_budget)
+                                    .await
+                                    .map(|outcome| {
+                                        for transition in outcome.budget_transitions {
+                                            this.observe_budget_outcome(
+                                                run_id,
+                                                "inspector_bench",
+                                                transition,
+                                            );
+                                        }
+                                        json!(outcome.report)
+                                    });
+                                this.finish_inspection(
+                                    &mut run,
+                                    "inspector_bench",
+                                    json!(req),
+                                    probe_descriptor(&req.probe),
+                                    started_at,
+                                    timer,
+                                    outcome,
+                                )
+                            }
+                        },
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_initialize" | "inspector.initialize" => {
+                    match serde_json::from_value::<ProbeRequest>(args_val) {
+                        Ok(req) => match this.svc.initialize(req).await {
+                            Ok(report) => Ok(CallToolResult::structured(json!(report))),
+                            Err(e) => Err(failure(&e.to_string())),
+                        },
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_conformance" | "inspector.conformance" => {
+                    match serde_json::from_value::<ProbeRequest>(args_val) {
+                        Ok(req) => match conformance::run(&this.svc, req).await {
+                            Ok(report) => Ok(CallToolResult::structured(json!(report))),
+                            Err(e) => Err(failure(&e.to_string())),
+                        },
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_idempotency_export" => {
+                    let records: Vec<IdempotencySnapshotEntry> = this
+                        .idempotency
+                        .export_completed()
+                        .into_iter()
+                        .map(|mut entry| {
+                            // Arguments may carry secrets; a duplicate reply only needs the outcome.
+                            entry.event.request = None;
+                            entry.event.target = entry.event.target.as_ref().map(redact_target);
+                            entry
+                        })
+                        .collect();
+                    Ok(CallToolResult::structured(json!({
+                        "count": records.len(),
+                        "records": records,
+                    })))
+                }
+                "inspector_idempotency_import" => {
+                    match serde_json::from_value::<IdempotencyImportRequest>(args_val) {
+                        Ok(req) => {
+                            let (imported, skipped) =
+                                this.idempotency.import_completed(req.records);
+                            Ok(CallToolResult::structured(json!({
+                                "imported": imported,
+                                "skipped": skipped,
+                            })))
+                        }
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_budget_window" => {
+                    let snapshot = this.error_budget.window_snapshot();
+                    let observations: Vec<Value> = snapshot
+                        .observations
+                        .iter()
+                        .map(|obs| json!({ "at": format_until(obs.at), "success": obs.success }))
+                        .collect();
+                    Ok(CallToolResult::structured(json!({
+                        "sample_size": snapshot.sample_size,
+                        "success_rate": snapshot.success_rate,
+                        "frozen_until": snapshot.frozen_until.map(format_until),
+                        "observations": observations,
+                    })))
+                }
                 "inspector_describe" | "inspector.describe" => {
                     match serde_json::from_value::<DescribeRequest>(args_val) {
                         Ok(req) => match this.svc.describe(req).await {
                             Ok(tool) => Ok(CallToolResult::structured(json!({
                                 "tool": tool
                             }))),
-                            Err(e) => Err(failure(&e.to_string())),
+                            Err(e) => Err(session_not_found(&e)
+                                .or_else(|| tls_config_error(&e))
+                                .or_else(|| shell_targets_disabled(&e))
+                                .unwrap_or_else(|| failure(&e.to_string()))),
                         },
                         Err(e) => Err(failure(&e.to_string())),
                     }
                 }
+                "inspector_cancel" | "inspector.cancel" => {
+                    match serde_json::from_value::<CancelRequest>(args_val) {
+                        Ok(req) => {
+                            let token = this.running_calls.lock().get(&req.run_id).cloned();
+                            match token {
+                                Some(token) => {
+                                    token.cancel();
+                                    Ok(CallToolResult::structured(json!({
+                                        "run_id": req.run_id,
+                                        "cancelled": true,
+                                    })))
+                                }
+                                None => Err(CallToolResult::structured_error(json!({
+                                    "error": format!("no in-flight inspector_call with run_id {}", req.run_id),
+                                    "code": "RUN_NOT_FOUND",
+                                }))),
+                            }
+                        }
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_connect" | "inspector.connect" => {
+                    match serde_json::from_value::<ProbeRequest>(args_val) {
+                        Ok(req) => match this.svc.connect_session(req).await {
+                            Ok(connected) => Ok(CallToolResult::structured(json!(connected))),
+                            Err(e) => Err(tls_config_error(&e)
+                                .or_else(|| shell_targets_disabled(&e))
+                                .unwrap_or_else(|| failure(&format!("{e:#}")))),
+                        },
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
+                "inspector_disconnect" | "inspector.disconnect" => {
+                    match serde_json::from_value::<DisconnectRequest>(args_val) {
+                        Ok(req) => {
+                            match this.svc.disconnect_session(req.session_id) {
+                                Ok(()) => Ok(CallToolResult::structured(json!({
+                                    "session_id": req.session_id,
+                                    "disconnected": true,
+                                }))),
+                                Err(e) => Err(session_not_found(&e)
+                                    .unwrap_or_else(|| failure(&e.to_string()))),
+                            }
+                        }
+                        Err(e) => Err(failure(&e.to_string())),
+                    }
+                }
                 "inspector_call" | "inspector.call" => {
                     match serde_json::from_value::<CallRequest>(args_val) {
-                        Ok(req) => {
+                        Ok(mut req) => {
+                            let requested_tool_name = match this.prepare_call(&mut req) {
+                                Ok(requested_tool_name) => requested_tool_name,
+                                Err(rejection) => {
+                                    run.fail();
+                                    return Ok(rejection);
+                                }
+                            };
+                            // Held until the call finishes; streams keep a progress subscription open.
+                            let _stream_slot = match (&this.stream_slots, req.stream) {
+                                (Some(slots), true) => {
+                                    match Arc::clone(slots).try_acquire_owned() {
+                                        Ok(permit) => Some(permit),
+                                        Err(_) => {
+                                            run.fail();
+                                            return Ok(CallToolResult::structured_error(json!({
+                                                "error": "too many concurrent streaming calls",
+                                                "code": "STREAM_LIMIT",
+                                            })));
+                                        }
+                                    }
+                                }
+                                _ => None,
+                            };
+                            // The upstream request's token fires on notifications/cancelled
+                            // and on disconnect; inspector_cancel fires it by run_id.
+                            let cancellation = context.ct.child_token();
+                            let _running = this.register_call(run_id, cancellation.clone());
+                            let mut svc = this.svc.clone().with_cancellation(cancellation);
+                            if let Some(budget) = caller_budget(&context.meta) {
+                                svc = svc.with_caller_budget(budget, received);
+                            }
                             let started_at = OffsetDateTime::now_utc();
                             let timer = Instant::now();
                             let admit_clock = SystemTime::now();
@@ -366,24 +1165,25 @@ impl ServerHandler for InspectorServer {
                                 command: None,
                                 url: None,
                                 headers: None,
+                                socket_path: None,
                             };
                             let mut external_reference = req.external_reference.clone();
-                            if let Some(ref ext) = external_reference {
-                                if let Some(existing) = this.idempotency.find_external_ref(ext) {
-                                    return match this.conflict_policy {
-                                        IdempotencyConflictPolicy::ReturnExisting => {
-                                            run.capture();
-                                            Ok(this.return_existing_event(existing))
-                                        }
-                                        IdempotencyConflictPolicy::Conflict409 => {
-                                            run.fail();
-                                            Ok(this.idempotency_conflict_response(
-                                                Some(existing),
-                                                "external reference conflict",
-                                            ))
-                                        }
-                                    };
-                                }
+                            if let Some(ref ext) = external_reference
+                                && let Some(existing) = this.idempotency.find_external_ref(ext)
+                            {
+                                return match this.conflict_policy {
+                                    IdempotencyConflictPolicy::ReturnExisting => {
+                                        run.capture();
+                                        Ok(this.return_existing_event(existing))
+                                    }
+                                    IdempotencyConflictPolicy::Conflict409 => {
+                                        run.fail();
+                                        Ok(this.idempotency_conflict_response(
+                                            Some(existing),
+                                            "external reference conflict",
+                                        ))
+                                    }
+                                };
                             }
                             let mut claimed_key: Option<String> = None;
                             if let Some(key) = req.idempotency_key.clone() {
@@ -392,24 +1192,35 @@ impl ServerHandler for InspectorServer {
                                         this.idempotency.begin(&key, run_id, &req);
                                         claimed_key = Some(key);
                                     }
+                                    ClaimOutcome::StoreFull => {
+                                        run.fail();
+                                        return Ok(CallToolResult::structured_error(json!({
+                                            "error": "idempotency store is full; retry later",
+                                            "code": "IDEMPOTENCY_STORE_FULL",
+                                        })));
+                                    }
                                     ClaimOutcome::InFlight => {
                                         run.fail();
-                                        let err = this.idempotency_conflict_response(
-                                            None,
-                                            "idempotency key already in-flight",
-                                        );
-                                        return Ok(err);
+                                        let mut payload = json!({
+                                            "error": "idempotency key already in-flight",
+                                            "code": "IDEMPOTENCY_CONFLICT",
+                                        });
+                                        // Lets the caller cancel the run holding the key.
+                                        if let Some(holder) = this.idempotency.in_flight_run(&key) {
+                                            payload["run_id"] = json!(holder);
+                                        }
+                                        return Ok(CallToolResult::structured_error(payload));
                                     }
                                     ClaimOutcome::Completed(event) => {
                                         return match this.conflict_policy {
                                             IdempotencyConflictPolicy::ReturnExisting => {
                                                 run.capture();
-                                                Ok(this.return_existing_event(event))
+                                                Ok(this.return_existing_event(*event))
                                             }
                                             IdempotencyConflictPolicy::Conflict409 => {
                                                 run.fail();
                                                 Ok(this.idempotency_conflict_response(
-                                                    Some(event),
+                                                    Some(*event),
                                                     "idempotency conflict",
                                                 ))
                                             }
@@ -422,8 +1233,8 @@ impl ServerHandler for InspectorServer {
                             }
                             match this.error_budget.admit(admit_clock) {
                                 Ok(thawed) => {
-                                    if thawed {
-                                        metrics::set_error_budget_frozen(false);
+                                    if let Some(report) = thawed {
+                                        this.freeze_cleared(run_id, &req.tool_name, &report);
                                     }
                                 }
                                 Err(report) => {
@@ -440,8 +1251,9 @@ impl ServerHandler for InspectorServer {
                                         None,
                                         Some("error budget exhausted".into()),
                                         external_reference.clone(),
+                                        requested_tool_name.clone(),
                                     );
-                                    if let Err(e) = this.outbox.append(&event) {
+                                    if let Err(e) = this.record_event(&event) {
                                         tracing::error!(%run_id, error=%e, "failed to append freeze event to outbox");
                                     }
                                     if let Some(ref ext) = external_reference {
@@ -454,38 +1266,20 @@ impl ServerHandler for InspectorServer {
                                     return Ok(CallToolResult::structured_error(payload));
                                 }
                             }
-                            let call_result = if let Some(http) = req.http.as_ref() {
-                                target_descriptor.transport = "http".into();
-                                target_descriptor.url = Some(http.url.clone());
-                                target_descriptor.headers = http.headers.clone();
-                                if let Some(key) = claimed_key.as_ref() {
-                                    this.idempotency.set_target(key, target_descriptor.clone());
-                                }
-                                this.svc.call_http(http, &req).await
-                            } else if let Some(sse) = req.sse.as_ref() {
-                                target_descriptor.transport = "sse".into();
-                                target_descriptor.url = Some(sse.url.clone());
-                                target_descriptor.headers = sse.headers.clone();
-                                if let Some(key) = claimed_key.as_ref() {
-                                    this.idempotency.set_target(key, target_descriptor.clone());
-                                }
-                                this.svc.call_sse(sse, &req).await
-                            } else if let Some(target) = req.stdio.as_ref() {
-                                target_descriptor.transport = "stdio".into();
-                                target_descriptor.command = Some(target.command.clone());
-                                if let Some(key) = claimed_key.as_ref() {
-                                    this.idempotency.set_target(key, target_descriptor.clone());
-                                }
-                                this.svc
-                                    .call_stdio(
-                                        target.command.clone(),
-                                        target.args.clone(),
-                                        target.env.clone(),
-                                        target.cwd.clone(),
-                                        &req,
-                                    )
+                            let targets = req.target_chain();
+                            let mut fallback_chain = Vec::new();
+                            let mut warnings: Vec<TraceWarning> = Vec::new();
+                            let call_result = if let Some(session_id) = req.session_id {
+                                svc.call_session(session_id, &req)
                                     .await
-                            } else {
+                                    .map(|(outcome, target)| {
+                                        if let Some(key) = claimed_key.as_ref() {
+                                            this.idempotency.set_target(key, target.clone());
+                                        }
+                                        target_descriptor = target;
+                                        outcome
+                                    })
+                            } else if targets.is_empty() {
                                 let default_cmd = std::env::var("INSPECTOR_STDIO_CMD").ok();
                                 let fallback: Result<(String, Vec<String>), CallToolResult> =
                                     if let Some(cmd) = default_cmd {
@@ -507,18 +1301,110 @@ impl ServerHandler for InspectorServer {
                                     };
                                 match fallback {
                                     Ok((program, args)) => {
-                                        this.svc
-                                            .call_stdio(program.clone(), args, None, None, &req)
-                                            .await
+                                        svc.call_stdio(
+                                            program.clone(),
+                                            args,
+                                            StdioEnv::default(),
+                                            None,
+                                            &req,
+                                        )
+                                        .await
                                     }
                                     Err(err) => return Ok(err),
                                 }
+                            } else {
+                                let mut result = Err(anyhow::anyhow!("no target attempted"));
+                                for (idx, target) in targets.iter().enumerate() {
+                                    result = this
+                                        .call_target(
+                                            &svc,
+                                            target,
+                                            &req,
+                                            &mut target_descriptor,
+                                            claimed_key.as_deref(),
+                                        )
+                                        .await;
+                                    if !req.fallback_targets.is_empty() {
+                                        fallback_chain.push(FallbackAttempt {
+                                            target: target_descriptor.clone(),
+                                            succeeded: result.is_ok(),
+                                            error: result.as_ref().err().map(|e| e.to_string()),
+                                        });
+                                    }
+                                    let unreachable = result.as_ref().is_err_and(|e| {
+                                        e.downcast_ref::<TargetUnreachable>().is_some()
+                                    });
+                                    if !unreachable {
+                                        break;
+                                    }
+                                    if idx + 1 < targets.len() {
+                                        tracing::warn!(%run_id, transport = %target_descriptor.transport, "target unreachable; trying next fallback target");
+                                    }
+                                }
+                                if result.is_ok() && fallback_chain.len() > 1 {
+                                    warnings.push(TraceWarning::new(
+                                        "FALLBACK_TARGET_USED",
+                                        format!(
+                                            "served by fallback {} target after {} unreachable target(s)",
+                                            target_descriptor.transport,
+                                            fallback_chain.len() - 1
+                                        ),
+                                    ));
+                                }
+                                result
+                            };
+                            // (code, violations) when a downstream success is turned into a failure.
+                            let mut rejection: Option<(&'static str, Vec<String>)> = None;
+                            let call_result = match call_result {
+                                Ok(outcome)
+                                    if this.empty_result_policy == EmptyResultPolicy::Error
+                                        && is_empty_result(&outcome.result) =>
+                                {
+                                    rejection = Some(("EMPTY_RESULT", Vec::new()));
+                                    Err(anyhow::anyhow!(
+                                        "downstream returned no content and no structured content"
+                                    ))
+                                }
+                                Ok(outcome)
+                                    if req.output_schema_check == OutputSchemaCheck::Enforce
+                                        && !outcome.output_schema_violations.is_empty() =>
+                                {
+                                    rejection = Some((
+                                        "OUTPUT_SCHEMA_VIOLATION",
+                                        outcome.output_schema_violations,
+                                    ));
+                                    Err(anyhow::anyhow!(
+                                        "downstream output does not match the tool's output_schema"
+                                    ))
+                                }
+                                other => other,
                             };
                             match call_result {
                                 Ok(CallOutcome {
                                     mut result,
                                     stream_events,
+                                    output_schema_violations,
+                                    attempts,
+                                    unknown_notifications,
+                                    target_capabilities,
+                                    first_event_ms,
+                                    target_restarted,
+                                    stream_drain,
                                 }) => {
+                                    if let Some(restarts) = target_restarted {
+                                        this.target_restarted(
+                                            run_id,
+                                            &req.tool_name,
+                                            &target_descriptor,
+                                            restarts,
+                                        );
+                                        warnings.push(TraceWarning::new(
+                                            "TARGET_RESTARTED",
+                                            format!(
+                                                "pooled stdio target had exited and was respawned (restart {restarts})"
+                                            ),
+                                        ));
+                                    }
                                     if matches!(run.state, RunState::Processing) {
                                         run.capture();
                                     } else {
@@ -531,7 +1417,7 @@ impl ServerHandler for InspectorServer {
                                     if let Some(meta_ref) = extract_external_reference(&result) {
                                         external_reference = Some(meta_ref);
                                     }
-                                    let event = this.build_event(
+                                    let mut event = this.build_event(
                                         &run,
                                         &req,
                                         started_at,
@@ -540,41 +1426,113 @@ impl ServerHandler for InspectorServer {
                                         this.snapshot_result(&result),
                                         None,
                                         external_reference.clone(),
+                                        requested_tool_name.clone(),
                                     );
-                                    let outbox_result = this.outbox.append(&event);
+                                    if this.capture_target_capabilities {
+                                        event.target_capabilities = target_capabilities
+                                            .and_then(|caps| serde_json::to_value(caps).ok());
+                                    }
+                                    let outbox_result = this.record_event(&event);
                                     let outbox_persisted = outbox_result.is_ok();
                                     if let Err(e) = outbox_result {
                                         tracing::error!(%run_id, error=%e, "failed to append outbox event");
+                                        warnings.push(TraceWarning::new(
+                                            "OUTBOX_APPEND_FAILED",
+                                            format!("run event not persisted to the outbox: {e}"),
+                                        ));
                                     }
                                     if let Some(ref ext) = external_reference {
-                                        this.idempotency.record_external_ref(ext, event.clone());
+                                        let prior = this
+                                            .idempotency
+                                            .find_external_ref(ext)
+                                            .filter(|prior| prior.run_id != run_id);
+                                        match prior {
+                                            Some(prior)
+                                                if this.duplicate_external_ref_policy
+                                                    == DuplicateExternalRefPolicy::Warn =>
+                                            {
+                                                tracing::warn!(%run_id, prior_run_id = %prior.run_id, external_reference = %ext, "success produced an external reference owned by another run");
+                                                warnings.push(TraceWarning::new(
+                                                    "DUPLICATE_EXTERNAL_REFERENCE",
+                                                    format!(
+                                                        "external reference '{ext}' already belongs to run {}; downstream may not be idempotent",
+                                                        prior.run_id
+                                                    ),
+                                                ));
+                                            }
+                                            _ => this
+                                                .idempotency
+                                                .record_external_ref(ext, event.clone()),
+                                        }
                                     }
                                     if let Some(key) = claimed_key {
                                         this.idempotency.complete(&key, event.clone());
                                     }
+                                    if !output_schema_violations.is_empty() {
+                                        tracing::warn!(%run_id, violations = output_schema_violations.len(), "downstream output violates output_schema");
+                                        warnings.push(TraceWarning {
+                                            code: "OUTPUT_SCHEMA_VIOLATION".into(),
+                                            message: "downstream output does not match the tool's output_schema".into(),
+                                            violations: output_schema_violations,
+                                        });
+                                    }
                                     let trace = CallTrace {
                                         event: event.clone(),
                                         stream_enabled: req.stream,
                                         stream_events,
                                         outbox_persisted,
+                                        warnings,
+                                        attempts,
+                                        unknown_notifications,
+                                        fallback_chain,
+                                        first_event_ms,
+                                        stream_drain,
                                     };
                                     Self::attach_trace(&mut result, &trace);
-                                    match this.error_budget.record_success_now() {
-                                        RecordOutcome::FreezeTriggered(report) => {
-                                            metrics::set_error_budget_frozen(true);
-                                            tracing::warn!(%run_id, success_rate = report.success_rate, sample_size = report.sample_size, "error budget freeze triggered on success");
-                                        }
-                                        RecordOutcome::FreezeCleared => {
-                                            metrics::set_error_budget_frozen(false);
-                                            tracing::info!(%run_id, "error budget freeze lifted");
-                                        }
-                                        RecordOutcome::None => {}
-                                    }
+                                    this.observe_budget_outcome(
+                                        run_id,
+                                        &req.tool_name,
+                                        this.error_budget.record_success_now(),
+                                    );
                                     Ok(result)
                                 }
                                 Err(error) => {
-                                    run.fail();
+                                    let cancelled =
+                                        error.chain().any(|cause| cause.is::<CallCancelled>());
+                                    if cancelled {
+                                        run.cancel();
+                                    } else {
+                                        run.fail();
+                                    }
                                     let message = error.to_string();
+                                    let attempts = error
+                                        .downcast_ref::<RetriesExhausted>()
+                                        .map(|exhausted| exhausted.attempts.clone())
+                                        .unwrap_or_default();
+                                    let spawn_timed_out =
+                                        error.chain().any(|cause| cause.is::<SpawnTimeout>());
+                                    let bad_headers =
+                                        error.chain().any(|cause| cause.is::<InvalidHeaders>());
+                                    let bad_tls =
+                                        error.chain().any(|cause| cause.is::<TlsConfigError>());
+                                    let shell_disabled = error
+                                        .chain()
+                                        .any(|cause| cause.is::<ShellTargetsDisabled>());
+                                    let call_timed_out =
+                                        error.chain().any(|cause| cause.is::<CallTimedOut>());
+                                    let session_missing =
+                                        error.chain().any(|cause| cause.is::<SessionNotFound>());
+                                    let unsupported_protocol = error
+                                        .chain()
+                                        .any(|cause| cause.is::<UnsupportedProtocol>());
+                                    let invalid_arguments = error
+                                        .chain()
+                                        .find_map(|cause| cause.downcast_ref::<InvalidArguments>());
+                                    let overran = error.chain().find_map(|cause| {
+                                        cause.downcast_ref::<StreamMaxDurationExceeded>()
+                                    });
+                                    let stream_events =
+                                        overran.map(|overran| overran.events.clone());
                                     let duration_ms = timer.elapsed().as_millis() as u64;
                                     let event = this.build_event(
                                         &run,
@@ -585,38 +1543,87 @@ impl ServerHandler for InspectorServer {
                                         None,
                                         Some(message.clone()),
                                         external_reference.clone(),
+                                        requested_tool_name.clone(),
                                     );
-                                    let outbox_result = this.outbox.append(&event);
+                                    let outbox_result = this.record_event(&event);
                                     let outbox_persisted = outbox_result.is_ok();
                                     if let Err(e) = outbox_result {
                                         tracing::error!(%run_id, error=%e, "failed to append failed event to outbox");
+                                        warnings.push(TraceWarning::new(
+                                            "OUTBOX_APPEND_FAILED",
+                                            format!("run event not persisted to the outbox: {e}"),
+                                        ));
                                     }
-                                    if let Some(ref ext) = external_reference {
+                                    // A cancelled run has no outcome to replay; retries start afresh.
+                                    if let Some(ref ext) = external_reference
+                                        && !cancelled
+                                    {
                                         this.idempotency.record_external_ref(ext, event.clone());
                                     }
                                     if let Some(key) = claimed_key {
-                                        this.idempotency.complete(&key, event.clone());
+                                        if cancelled {
+                                            this.idempotency.release(&key);
+                                        } else {
+                                            this.idempotency.complete(&key, event.clone());
+                                        }
+                                    }
+                                    let mut payload = json!({ "error": message });
+                                    if let Some((code, violations)) = rejection {
+                                        payload["code"] = json!(code);
+                                        if !violations.is_empty() {
+                                            payload["violations"] = json!(violations);
+                                        }
+                                    } else if cancelled {
+                                        payload["code"] = json!("CANCELLED");
+                                    } else if spawn_timed_out {
+                                        payload["code"] = json!("SPAWN_TIMEOUT");
+                                    } else if overran.is_some() {
+                                        payload["code"] = json!("STREAM_MAX_DURATION");
+                                    } else if bad_headers {
+                                        payload["code"] = json!("INVALID_HEADERS");
+                                    } else if bad_tls {
+                                        payload["code"] = json!("TLS_CONFIG_ERROR");
+                                    } else if shell_disabled {
+                                        payload["code"] = json!("SHELL_TARGETS_DISABLED");
+                                    } else if call_timed_out {
+                                        payload["code"] = json!("CALL_TIMEOUT");
+                                    } else if session_missing {
+                                        payload["code"] = json!("SESSION_NOT_FOUND");
+                                    } else if unsupported_protocol {
+                                        payload["code"] = json!("UNSUPPORTED_PROTOCOL");
+                                    } else if let Some(invalid) = invalid_arguments {
+                                        payload["code"] = json!("INVALID_ARGUMENTS");
+                                        payload["violations"] = json!(invalid.violations);
                                     }
-                                    let mut err_result = CallToolResult::structured_error(json!({
-                                        "error": message
-                                    }));
+                                    let mut err_result = CallToolResult::structured_error(payload);
                                     let trace = CallTrace {
                                         event: event.clone(),
                                         stream_enabled: req.stream,
-                                        stream_events: None,
+                                        stream_events,
                                         outbox_persisted,
+                                        warnings,
+                                        attempts,
+                                        unknown_notifications: Vec::new(),
+                                        fallback_chain,
+                                        first_event_ms: None,
+                                        stream_drain: None,
                                     };
                                     Self::attach_trace(&mut err_result, &trace);
-                                    match this.error_budget.record_failure_now() {
-                                        RecordOutcome::FreezeTriggered(report) => {
-                                            metrics::set_error_budget_frozen(true);
-                                            tracing::warn!(%run_id, success_rate = report.success_rate, sample_size = report.sample_size, "error budget freeze triggered");
-                                        }
-                                        RecordOutcome::FreezeCleared => {
-                                            metrics::set_error_budget_frozen(false);
-                                            tracing::info!(%run_id, "error budget freeze lifted");
-                                        }
-                                        RecordOutcome::None => {}
+                                    // The target was never called, or the client gave up on it,
+                                    // so its health is unknown.
+                                    if invalid_arguments.is_none()
+                                        && !cancelled
+                                        && !bad_headers
+                                        && !bad_tls
+                                        && !shell_disabled
+                                        && !session_missing
+                                        && !unsupported_protocol
+                                    {
+                                        this.observe_budget_outcome(
+                                            run_id,
+                                            &req.tool_name,
+                                            this.error_budget.record_failure_now(),
+                                        );
                                     }
                                     Err(err_result)
                                 }
@@ -646,6 +1653,7 @@ impl ServerHandler for InspectorServer {
         let capabilities = ServerCapabilities::builder()
             .enable_tools()
             .enable_tool_list_changed()
+            .enable_resources()
             .build();
         ServerInfo {
             capabilities,
@@ -660,24 +1668,670 @@ impl ServerHandler for InspectorServer {
         }
     }
 
-    fn on_initialized(
+    async fn on_initialized(
         &self,
         context: rmcp::service::NotificationContext<rmcp::service::RoleServer>,
-    ) -> impl std::future::Future<Output = ()> + Send + '_ {
-        async move {
-            tracing::info!("on_initialized -> schedule list_changed");
-            let peer = context.peer.clone();
-            tokio::spawn(async move {
-                if let Err(e) = peer.notify_tool_list_changed().await {
-                    tracing::warn!(error=%e, "tools/list_changed notify failed");
-                } else {
-                    tracing::info!("tools/list_changed notified");
-                }
-            });
-        }
+    ) {
+        tracing::info!("on_initialized -> schedule list_changed");
+        let peer = context.peer.clone();
+        tokio::spawn(async move {
+            if let Err(e) = peer.notify_tool_list_changed().await {
+                tracing::warn!(error=%e, "tools/list_changed notify failed");
+            } else {
+                tracing::info!("tools/list_changed notified");
+            }
+        });
     }
 }
 
+/// The tool catalog, readable by resource-oriented clients without calling `help`.
+const TOOLS_RESOURCE_URI: &str = "inspector://tools";
+const HELP_RESOURCE_URI: &str = "inspector://help";
+
+/// Entries of the `help` manual, one JSON object per JSONL line.
+fn help_lines(release_track: ReleaseTrack, target_aliases: &TargetAliases) -> Vec<Value> {
+    let mut lines = vec![
+        serde_json::json!({
+            "section": "summary",
+            "server": "mcp-multi-tool",
+            "version": env!("CARGO_PKG_VERSION"),
+            "protocol": "MCP",
+            "release_track": release_track.as_str(),
+            "transports": ["stdio", "sse", "http", "unix"]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_health",
+            "summary": "Report liveness (process serving) and readiness (outbox writable, default target resolvable, not in rollback).",
+            "arguments": {},
+            "returns": "{ live, ready, checks: { outbox_writable, default_target, release_track } }",
+            "notes": [
+                "Callable on every release track, including rollback."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_probe",
+            "summary": "Probe a downstream MCP server and measure latency.",
+            "arguments": {
+                "target_alias": "optional string, a configured target filling any fields left out",
+                "transport": "string stdio|sse|http|unix",
+                "command": "optional string",
+                "args": "optional array<string>",
+                "env": "optional map",
+                "env_clear": "optional bool, start with only env",
+                "inherit_basic": "optional bool, keep PATH and HOME under env_clear",
+                "cwd": "optional string",
+                "shell": "optional bool, run command via sh -c",
+                "url": "optional string",
+                "headers": "optional map",
+                "auth_token": "optional string",
+                "tls": "optional { ca_cert_path, client_cert_path, client_key_path, insecure_skip_verify } for https sse/http targets",
+                "proxy": "optional { url, username, password } HTTP proxy for sse/http targets, instead of TARGET_PROXY",
+                "socket_path": "optional string, unix socket of a unix target",
+                "handshake_timeout_ms": "optional int",
+                "protocol_version": "optional string, offered in initialize instead of the default",
+                "require_tools": "optional bool, ok only when the target lists at least one tool"
+            },
+            "returns": "ProbeResult",
+            "notes": [
+                "require_tools=true also lists the target's tools; a target that advertises no tools capability, lists none, or fails to list reports ok=false with the reason in error.",
+                "capabilities reports which of tools/resources/prompts/logging/completions the target advertised.",
+                "protocol_version in the result is the version the target answered initialize with.",
+                "tls.ca_cert_path trusts a private CA, client_cert_path with client_key_path presents a certificate for mTLS; unreadable or mismatched files fail with TLS_CONFIG_ERROR before connecting.",
+                "proxied is true when an sse/http target was reached through its proxy or TARGET_PROXY."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_list_resources",
+            "summary": "List every resource a target exposes, following pagination to the end.",
+            "arguments": {
+                "target_alias": "optional string, a configured target filling any fields left out",
+                "transport": "string stdio|sse|http|unix",
+                "command": "optional string",
+                "args": "optional array<string>",
+                "env": "optional map",
+                "env_clear": "optional bool, start with only env",
+                "inherit_basic": "optional bool, keep PATH and HOME under env_clear",
+                "cwd": "optional string",
+                "shell": "optional bool, run command via sh -c",
+                "url": "optional string",
+                "headers": "optional map",
+                "auth_token": "optional string",
+                "tls": "optional { ca_cert_path, client_cert_path, client_key_path, insecure_skip_verify } for https sse/http targets",
+                "proxy": "optional { url, username, password } HTTP proxy for sse/http targets, instead of TARGET_PROXY",
+                "socket_path": "optional string, unix socket of a unix target",
+                "handshake_timeout_ms": "optional int"
+            },
+            "returns": "{ resources: Resource[], count }",
+            "notes": [
+                "Fails with code CAPABILITY_NOT_SUPPORTED when the target does not advertise the resources capability."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_read_resource",
+            "summary": "Read one resource from a target and return its contents.",
+            "arguments": {
+                "uri": "string",
+                "target_alias": "optional string, a configured target filling any fields left out",
+                "transport": "string stdio|sse|http|unix",
+                "command": "optional string",
+                "args": "optional array<string>",
+                "env": "optional map",
+                "env_clear": "optional bool, start with only env",
+                "inherit_basic": "optional bool, keep PATH and HOME under env_clear",
+                "cwd": "optional string",
+                "shell": "optional bool, run command via sh -c",
+                "url": "optional string",
+                "headers": "optional map",
+                "auth_token": "optional string",
+                "tls": "optional { ca_cert_path, client_cert_path, client_key_path, insecure_skip_verify } for https sse/http targets",
+                "proxy": "optional { url, username, password } HTTP proxy for sse/http targets, instead of TARGET_PROXY",
+                "socket_path": "optional string, unix socket of a unix target",
+                "handshake_timeout_ms": "optional int"
+            },
+            "returns": "{ uri, contents: [{ uri, kind: text|blob, mime_type, text | blob }] }",
+            "notes": [
+                "blob contents stay base64-encoded as the target sent them.",
+                "Fails with code CAPABILITY_NOT_SUPPORTED when the target does not advertise the resources capability."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_list_prompts",
+            "summary": "List every prompt a target exposes, following pagination to the end.",
+            "arguments": {
+                "target_alias": "optional string, a configured target filling any fields left out",
+                "transport": "string stdio|sse|http|unix",
+                "command": "optional string",
+                "args": "optional array<string>",
+                "env": "optional map",
+                "env_clear": "optional bool, start with only env",
+                "inherit_basic": "optional bool, keep PATH and HOME under env_clear",
+                "cwd": "optional string",
+                "shell": "optional bool, run command via sh -c",
+                "url": "optional string",
+                "headers": "optional map",
+                "auth_token": "optional string",
+                "tls": "optional { ca_cert_path, client_cert_path, client_key_path, insecure_skip_verify } for https sse/http targets",
+                "proxy": "optional { url, username, password } HTTP proxy for sse/http targets, instead of TARGET_PROXY",
+                "socket_path": "optional string, unix socket of a unix target",
+                "handshake_timeout_ms": "optional int"
+            },
+            "returns": "{ prompts: Prompt[], count }",
+            "notes": [
+                "Appends an InspectionRunEvent with tool_name inspector_list_prompts to the outbox.",
+                "Fails with code CAPABILITY_NOT_SUPPORTED when the target does not advertise the prompts capability."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_get_prompt",
+            "summary": "Render one prompt of a target with the given arguments.",
+            "arguments": {
+                "prompt_name": "string",
+                "arguments": "optional object",
+                "target_alias": "optional string, a configured target filling any fields left out",
+                "transport": "string stdio|sse|http|unix",
+                "command": "optional string",
+                "args": "optional array<string>",
+                "env": "optional map",
+                "env_clear": "optional bool, start with only env",
+                "inherit_basic": "optional bool, keep PATH and HOME under env_clear",
+                "cwd": "optional string",
+                "shell": "optional bool, run command via sh -c",
+                "url": "optional string",
+                "headers": "optional map",
+                "auth_token": "optional string",
+                "tls": "optional { ca_cert_path, client_cert_path, client_key_path, insecure_skip_verify } for https sse/http targets",
+                "proxy": "optional { url, username, password } HTTP proxy for sse/http targets, instead of TARGET_PROXY",
+                "socket_path": "optional string, unix socket of a unix target",
+                "handshake_timeout_ms": "optional int"
+            },
+            "returns": "{ prompt_name, description, messages: PromptMessage[] }",
+            "notes": [
+                "Appends an InspectionRunEvent with tool_name inspector_get_prompt to the outbox.",
+                "Fails with code CAPABILITY_NOT_SUPPORTED when the target does not advertise the prompts capability."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_bench",
+            "summary": "Call one target tool repeatedly and report latency percentiles and throughput.",
+            "arguments": {
+                "tool_name": "string",
+                "arguments_json": "optional object",
+                "iterations": "int 1..=10000",
+                "concurrency": "optional int 1..=64 (default 1)",
+                "transport": "string stdio|sse|http|unix",
+                "command": "optional string",
+                "url": "optional string",
+                "auth_token": "optional string",
+                "handshake_timeout_ms": "optional int"
+            },
+            "returns": "{ tool_name, iterations, completed, concurrency, errors, latency: { min_ms, p50_ms, p90_ms, p99_ms, max_ms }, duration_ms, throughput_per_sec, stopped_by_freeze }",
+            "notes": [
+                "Each concurrent worker opens its own session; percentiles use the nearest-rank method.",
+                "Every call counts against the error budget; if it freezes mid-run the remaining calls are skipped and stopped_by_freeze is true.",
+                "Appends a single InspectionRunEvent with tool_name inspector_bench to the outbox."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_transport_bench",
+            "summary": "Compare handshake latency of one target over stdio, SSE and HTTP.",
+            "arguments": {
+                "stdio": "optional target",
+                "sse": "optional target",
+                "http": "optional target"
+            },
+            "returns": "{ ranking: array<{transport, ok, latency_ms, error}>, recommended }",
+            "notes": [
+                "Set at least one variant; all given variants are probed concurrently over fresh sessions.",
+                "ranking lists successful transports fastest first, then failed ones; recommended is the fastest success, or null when none connected."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_compliance_history",
+            "summary": "Query recent compliance runs and their pass-rate trend.",
+            "arguments": {
+                "target": "optional string, the run's command line or URL",
+                "limit": "optional int (default 20)"
+            },
+            "returns": "{ runs: array<{started_at, finished_at, target, pass_rate, cases, passed}> newest first, trend: { runs, latest_pass_rate, mean_pass_rate, change, direction: improving|regressing|steady } }",
+            "notes": [
+                "Runs are recorded by the compliance binary with --history-db; this tool reads the db named by COMPLIANCE_HISTORY_DB and fails when it is unset.",
+                "change is the latest pass rate minus the mean of the earlier runs returned; it and direction need at least two runs.",
+                "Only the most recent COMPLIANCE_HISTORY_RETENTION runs (default 500; --history-retention for the binary) are kept."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_initialize",
+            "summary": "Handshake with the target and return its complete InitializeResult.",
+            "arguments": {
+                "transport": "string stdio|sse|http|unix",
+                "command": "optional string",
+                "url": "optional string",
+                "auth_token": "optional string",
+                "handshake_timeout_ms": "optional int"
+            },
+            "returns": "{ result: InitializeResult, instructions_truncated }",
+            "notes": [
+                "Includes protocolVersion, full capabilities, serverInfo and instructions, which ProbeResult summarizes or omits.",
+                "instructions longer than 64 KiB are cut and instructions_truncated is set."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_set_logging_level",
+            "summary": "Forward logging/setLevel to the target to change its log verbosity.",
+            "arguments": {
+                "level": "string debug|info|notice|warning|error|critical|alert|emergency",
+                "transport": "string stdio|sse|http|unix",
+                "command": "optional string",
+                "url": "optional string",
+                "auth_token": "optional string"
+            },
+            "returns": "{ level, applied }",
+            "notes": [
+                "Fails with a clear error when the target does not advertise the logging capability."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_conformance",
+            "summary": "Check protocol conformance of a target and return a pass/fail checklist.",
+            "arguments": {
+                "transport": "string stdio|sse|http|unix",
+                "command": "optional string",
+                "args": "optional array<string>",
+                "env": "optional map",
+                "env_clear": "optional bool, start with only env",
+                "inherit_basic": "optional bool, keep PATH and HOME under env_clear",
+                "cwd": "optional string",
+                "shell": "optional bool, run command via sh -c",
+                "url": "optional string",
+                "headers": "optional map",
+                "auth_token": "optional string",
+                "handshake_timeout_ms": "optional int"
+            },
+            "returns": "{ transport, passed, checks: array<{name, passed, duration_ms, detail, skipped}> }",
+            "notes": [
+                "Checks: advertises_tools, list_tools_complete (follows next_cursor, no duplicates), help_structured (skipped without a help tool), unknown_tool_errors (error result and session still usable).",
+                "Unlike the compliance binary it measures protocol behaviour over one session, not latency."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_list_tools",
+            "summary": "List tools exposed by the target MCP.",
+            "arguments": {
+                "target_alias": "optional string, a configured target filling any fields left out",
+                "transport": "string stdio|sse|http|unix",
+                "command": "optional string",
+                "args": "optional array<string>",
+                "env": "optional map",
+                "env_clear": "optional bool, start with only env",
+                "inherit_basic": "optional bool, keep PATH and HOME under env_clear",
+                "cwd": "optional string",
+                "shell": "optional bool, run command via sh -c",
+                "url": "optional string",
+                "headers": "optional map",
+                "auth_token": "optional string",
+                "tls": "optional { ca_cert_path, client_cert_path, client_key_path, insecure_skip_verify } for https sse/http targets",
+                "proxy": "optional { url, username, password } HTTP proxy for sse/http targets, instead of TARGET_PROXY",
+                "socket_path": "optional string, unix socket of a unix target",
+                "handshake_timeout_ms": "optional int",
+                "reuse_session": "optional bool, default true",
+                "session_id": "optional uuid from inspector_connect, instead of the target fields",
+                "retry": "optional { max_attempts, initial_backoff_ms, max_backoff_ms, retry_on: array<connect|handshake|timeout> }"
+            },
+            "returns": "{ tools: Tool[], tool_count, has_tools, note? }",
+            "notes": [
+                "An idle session to the same target is reused when one is cached; reuse_session=false opens a fresh one.",
+                "retry repeats the listing on transient connect, handshake or timeout failures with exponential backoff (defaults: 3 attempts, 100 ms doubling up to 2000 ms).",
+                "An empty catalog is a successful list: has_tools is false and note explains that resource- or prompt-only servers may advertise no tools.",
+                "With DOTTED_TOOL_NAME_POLICY=underscore, dotted names are listed with underscores and inspector_call maps the alias back."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_compare",
+            "summary": "Diff the tool catalogs of two targets, e.g. staging against prod.",
+            "arguments": {
+                "target_a": "object, same fields as inspector_list_tools",
+                "target_b": "object, same fields as inspector_list_tools"
+            },
+            "returns": "{ identical, only_in_a: string[], only_in_b: string[], changed: [{ name, paths: string[] }] }",
+            "notes": [
+                "paths are JSON pointers into { description, inputSchema, outputSchema } where the two tools differ.",
+                "Object property order is ignored; arrays such as required are compared position by position."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_sessions",
+            "summary": "List downstream sessions currently open, with secrets in the target redacted.",
+            "arguments": {},
+            "returns": "{ count, sessions: array<{session_id, target, created_at, last_used, call_count, restarts}> }"
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_schema_sizes",
+            "summary": "Measure serialized input_schema bytes per target tool to spot context-expensive tools.",
+            "arguments": {
+                "transport": "string stdio|sse|http|unix",
+                "command": "optional string",
+                "args": "optional array<string>",
+                "env": "optional map",
+                "env_clear": "optional bool, start with only env",
+                "inherit_basic": "optional bool, keep PATH and HOME under env_clear",
+                "cwd": "optional string",
+                "shell": "optional bool, run command via sh -c",
+                "url": "optional string",
+                "headers": "optional map",
+                "auth_token": "optional string",
+                "handshake_timeout_ms": "optional int"
+            },
+            "returns": "{ tools: array<{name, bytes}> sorted descending, total_bytes }"
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_schema_hash",
+            "summary": "Canonicalize a tool's input_schema (sorted keys, normalized numbers) and hash it for drift detection.",
+            "arguments": {
+                "tool_name": "string",
+                "transport": "optional string",
+                "command": "optional string",
+                "args": "optional array<string>",
+                "env": "optional map",
+                "env_clear": "optional bool, start with only env",
+                "inherit_basic": "optional bool, keep PATH and HOME under env_clear",
+                "cwd": "optional string",
+                "shell": "optional bool, run command via sh -c",
+                "url": "optional string",
+                "headers": "optional map",
+                "auth_token": "optional string",
+                "handshake_timeout_ms": "optional int"
+            },
+            "returns": "{ tool_name, algorithm: \"sha256\", hash, canonical }"
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_describe",
+            "summary": "Fetch JSON schema and annotations for a tool.",
+            "arguments": {
+                "tool_name": "string",
+                "transport": "optional string",
+                "command": "optional string",
+                "args": "optional array<string>",
+                "env": "optional map",
+                "env_clear": "optional bool, start with only env",
+                "inherit_basic": "optional bool, keep PATH and HOME under env_clear",
+                "cwd": "optional string",
+                "shell": "optional bool, run command via sh -c",
+                "url": "optional string",
+                "headers": "optional map",
+                "auth_token": "optional string",
+                "tls": "optional { ca_cert_path, client_cert_path, client_key_path, insecure_skip_verify } for https sse/http targets",
+                "proxy": "optional { url, username, password } HTTP proxy for sse/http targets, instead of TARGET_PROXY",
+                "socket_path": "optional string, unix socket of a unix target",
+                "handshake_timeout_ms": "optional int",
+                "reuse_session": "optional bool, default true",
+                "session_id": "optional uuid from inspector_connect, instead of the target fields",
+                "retry": "optional { max_attempts, initial_backoff_ms, max_backoff_ms, retry_on: array<connect|handshake|timeout> }"
+            },
+            "returns": "Tool"
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_call",
+            "summary": "Invoke a downstream tool with optional streaming.",
+            "arguments": {
+                "tool_name": "string",
+                "arguments_json": "object or null",
+                "idempotency_key": "optional string",
+                "external_reference": "optional string",
+                "stream": "boolean",
+                "output_schema_check": "optional off|warn|enforce",
+                "call_timeout_ms": "optional number",
+                "stream_drain_timeout_ms": "optional number",
+                "progress_token": "optional string|integer",
+                "validate_arguments": "optional bool",
+                "reuse_session": "optional bool, default true",
+                "session_id": "optional uuid from inspector_connect, instead of stdio/sse/http/unix",
+                "retry": "optional { max_attempts, initial_backoff_ms, max_backoff_ms, retry_on: array<connect|handshake|timeout> }",
+                "target_alias": "optional string, a configured target filling any stdio/sse/http/unix fields left out",
+                "stdio": "optional target",
+                "sse": "optional target { url, headers, handshake_timeout_ms, tls, proxy }",
+                "http": "optional target { url, headers, auth_token, handshake_timeout_ms, tls, proxy }",
+                "unix": "optional target { path, handshake_timeout_ms }"
+            },
+            "returns": "CallToolResult",
+            "notes": [
+                "Set stream=true to capture progress notifications.",
+                "Set exactly one of stdio/sse/http/unix, directly or through target_alias; more fail with AMBIGUOUS_TARGET naming the blocks (and so does a fallback target with several), unless ALLOW_AMBIGUOUS_TARGET=true keeps the legacy pick of http, then sse, then unix, then stdio.",
+                "unix.path names a unix socket the target listens on; messages are newline-delimited JSON-RPC, as over stdio.",
+                "sse.tls and http.tls take PEM paths: ca_cert_path for a private CA, client_cert_path and client_key_path for mTLS. insecure_skip_verify is refused with TLS_CONFIG_ERROR unless ALLOW_INSECURE_TARGET_TLS=true.",
+                "stdio.shell=true runs stdio.command through sh -c (pipes, redirects, &&); args must then be empty, and it fails with SHELL_TARGETS_DISABLED unless ALLOW_SHELL_TARGETS=true.",
+                "stdio.env_clear=true starts the target with only stdio.env instead of inheriting the inspector's environment; stdio.inherit_basic=true keeps PATH and HOME. STDIO_ENV_CLEAR=true applies env_clear to every target.",
+                "output_schema_check=warn adds an OUTPUT_SCHEMA_VIOLATION entry to _meta.trace.warnings when structured_content does not match the tool's output_schema; enforce fails the call instead.",
+                "call_timeout_ms bounds the tool invocation after the handshake; on expiry the run fails with CALL_TIMEOUT.",
+                "retry repeats a handshake that fails with a transient connect, handshake or timeout failure (defaults: 3 attempts, 100 ms doubling up to 2000 ms); with an idempotency_key a failed call is retried too. _meta.trace.attempts records each attempt, and the run counts once against the error budget.",
+                "_meta.deadlineMs on the tools/call request (ms the caller will still wait) tightens that bound to the time left less DEADLINE_MARGIN_MS.",
+                "With MIN_PROTOCOL_VERSION set, a target that negotiated an older protocol version fails with UNSUPPORTED_PROTOCOL before the tool is invoked.",
+                "After a successful call the target session is kept idle (CONNECTION_CACHE_MAX_SIZE, CONNECTION_CACHE_IDLE_TTL_MS) and reused by the next list, describe or call to the same target, skipping the spawn and handshake; reuse_session=false opens a fresh one.",
+                "validate_arguments=true checks arguments_json against the tool's inputSchema first; violations fail with INVALID_ARGUMENTS and violations: [{ instance_path, message }], without calling the tool or counting against the error budget.",
+                "With MAX_CONCURRENT_STREAMS set, stream=true calls beyond the cap fail at once with STREAM_LIMIT; non-stream calls are not counted.",
+                "progress_token replaces the generated token of a stream=true call and is echoed on each chunk event; other types fail with INVALID_PROGRESS_TOKEN.",
+                "When the error budget is exhausted the server returns ERROR_BUDGET_EXHAUSTED until the success rate recovers.",
+                "A call cancelled by the client (notifications/cancelled, disconnect or inspector_cancel) fails with CANCELLED; its run event has state cancelled and its idempotency key is released for retries."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_connect",
+            "summary": "Open a session to a target and keep it for later requests.",
+            "arguments": "same target fields as inspector_list_tools",
+            "returns": "{ session_id, target, server_name, version, protocol_version }",
+            "notes": [
+                "Pass session_id to inspector_list_tools, inspector_describe or inspector_call instead of repeating the target; operations on one session run one at a time.",
+                "The session is listed by inspector_sessions under the same id. It stays open until inspector_disconnect or SESSION_IDLE_TIMEOUT_MS without use.",
+                "A session_id that was disconnected or closed after idling fails with SESSION_NOT_FOUND."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_disconnect",
+            "summary": "Close a session opened by inspector_connect.",
+            "arguments": { "session_id": "uuid" },
+            "returns": "{ session_id, disconnected: true }",
+            "notes": [
+                "Unknown or already closed sessions fail with SESSION_NOT_FOUND."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_cancel",
+            "summary": "Cancel an in-flight inspector_call (admin).",
+            "arguments": { "run_id": "uuid" },
+            "returns": "{ run_id, cancelled: true }",
+            "notes": [
+                "Listed and callable only with ADMIN_TOOLS_ENABLED=true; any caller holding a run_id could otherwise abort another client's run.",
+                "A retry with an in-flight idempotency_key is refused with IDEMPOTENCY_CONFLICT carrying that run's run_id.",
+                "Unknown or finished runs fail with RUN_NOT_FOUND."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_stats",
+            "summary": "Report process stats for capacity planning and leak hunting.",
+            "arguments": {},
+            "returns": "{ open_fds, resident_memory_bytes, tokio_alive_tasks, open_sessions, idempotency_records }",
+            "notes": [
+                "open_fds and resident_memory_bytes come from /proc/self and are null off Linux."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_idempotency_export",
+            "summary": "Export completed idempotency records to hand off to another instance (admin).",
+            "arguments": {},
+            "returns": "{ count, records: [{ key, event }] }",
+            "notes": [
+                "Listed and callable only with ADMIN_TOOLS_ENABLED=true.",
+                "Request payloads are dropped and target secrets redacted; in-flight runs are not exported."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_idempotency_import",
+            "summary": "Pre-seed the idempotency store from an export so a fresh instance answers duplicates (admin).",
+            "arguments": {
+                "records": "array from inspector_idempotency_export"
+            },
+            "returns": "{ imported, skipped }",
+            "notes": [
+                "Listed and callable only with ADMIN_TOOLS_ENABLED=true.",
+                "Keys already known locally are skipped, as are records beyond MAX_IDEMPOTENCY_RECORDS."
+            ]
+        }),
+        serde_json::json!({
+            "section": "tool",
+            "name": "inspector_budget_window",
+            "summary": "Show the observations in the error budget's sample window to explain a freeze (admin).",
+            "arguments": {},
+            "returns": "{ sample_size, success_rate, frozen_until?, observations: [{ at, success }] }",
+            "notes": [
+                "Listed and callable only with ADMIN_TOOLS_ENABLED=true.",
+                "observations are oldest first and capped to the newest 1000; sample_size counts the whole window.",
+                "With the error budget disabled the window is always empty."
+            ]
+        }),
+        serde_json::json!({
+            "section": "resources",
+            "uris": ["inspector://tools", "inspector://help"],
+            "notes": [
+                "inspector://tools is the tools/list catalog as JSON; inspector://help is this manual as JSONL."
+            ]
+        }),
+        serde_json::json!({
+            "section": "environment",
+            "INSPECTOR_STDIO_CMD": "<command> [args...] required when no stdio target override is provided",
+            "ERROR_BUDGET_*": "tune freeze threshold (see docs/howto/onboarding.md)",
+            "ERROR_BUDGET_FREEZE_ALLOWED_TOOLS": "comma-separated tools still served during a freeze; help always is",
+            "RUST_LOG": "default info"
+        }),
+        serde_json::json!({
+            "section": "workflow",
+            "steps": [
+                "inspector_probe",
+                "inspector_list_tools",
+                "inspector_describe",
+                "inspector_call"
+            ],
+            "diagnostics": [
+                "Prometheus /metrics -> inspector_lock_wait_ms histogram",
+                "Outbox JSONL/SQLite at data/outbox"
+            ]
+        }),
+    ];
+
+    if !target_aliases.is_empty() {
+        let aliases: Vec<Value> = target_aliases
+            .iter()
+            .map(|(alias, target)| {
+                json!({
+                    "alias": alias,
+                    "target": redact_target(&probe_descriptor(&probe_request(target))),
+                })
+            })
+            .collect();
+        lines.push(json!({
+            "section": "target_aliases",
+            "aliases": aliases,
+            "notes": [
+                "Any tool taking a target accepts target_alias; fields set in the request override the alias, and an unknown alias fails with TARGET_ALIAS_NOT_FOUND."
+            ]
+        }));
+    }
+
+    if !release_track.allows_inspector() {
+        lines.push(serde_json::json!({
+            "section": "notice",
+            "code": "release_track_rollback",
+            "message": "Inspector tools temporarily disabled; set RELEASE_TRACK=stable or canary to re-enable. inspector_health remains callable for readiness probes."
+        }));
+    }
+
+    lines
+}
+
+/// `{error}` payload, tagged `CAPABILITY_NOT_SUPPORTED` when the target lacks a capability.
+fn capability_error_payload(error: &anyhow::Error) -> serde_json::Value {
+    let mut payload = json!({"error": format!("{error:#}")});
+    if error
+        .chain()
+        .any(|cause| cause.is::<CapabilityNotSupported>())
+    {
+        payload["code"] = json!("CAPABILITY_NOT_SUPPORTED");
+    }
+    payload
+}
+
+// rmcp refuses to decode a result with no content blocks at all, so blank text
+// blocks are the closest a downstream can get to an empty result on the wire.
+fn is_empty_result(result: &CallToolResult) -> bool {
+    result.structured_content.is_none()
+        && result
+            .content
+            .iter()
+            .all(|block| block.as_text().is_some_and(|text| text.text.is_empty()))
+}
+
+/// The `SESSION_NOT_FOUND` result for an error caused by a stale `session_id`.
+fn session_not_found(error: &anyhow::Error) -> Option<CallToolResult> {
+    error
+        .chain()
+        .any(|cause| cause.is::<SessionNotFound>())
+        .then(|| {
+            CallToolResult::structured_error(json!({
+                "error": error.to_string(),
+                "code": "SESSION_NOT_FOUND",
+            }))
+        })
+}
+
+/// The `TLS_CONFIG_ERROR` result for an error caused by unusable target `tls` settings.
+fn tls_config_error(error: &anyhow::Error) -> Option<CallToolResult> {
+    error
+        .chain()
+        .any(|cause| cause.is::<TlsConfigError>())
+        .then(|| {
+            CallToolResult::structured_error(json!({
+                "error": error.to_string(),
+                "code": "TLS_CONFIG_ERROR",
+            }))
+        })
+}
+
+/// The `SHELL_TARGETS_DISABLED` result for a `shell: true` target the operator has not allowed.
+fn shell_targets_disabled(error: &anyhow::Error) -> Option<CallToolResult> {
+    error
+        .chain()
+        .any(|cause| cause.is::<ShellTargetsDisabled>())
+        .then(|| {
+            CallToolResult::structured_error(json!({
+                "error": error.to_string(),
+                "code": "SHELL_TARGETS_DISABLED",
+            }))
+        })
+}
+
 fn extract_external_reference(result: &CallToolResult) -> Option<String> {
     result.meta.as_ref().and_then(|meta| {
         meta.get("externalReference")
@@ -686,16 +2340,73 @@ fn extract_external_reference(result: &CallToolResult) -> Option<String> {
     })
 }
 
-fn freeze_payload(report: &FreezeReport) -> serde_json::Value {
-    let until_dt = OffsetDateTime::from(report.until);
-    let until = until_dt
+/// How long the caller will wait for this request, from `_meta.deadlineMs` (milliseconds
+/// remaining when it was sent). Anything but a non-negative integer is ignored.
+fn caller_budget(meta: &Meta) -> Option<Duration> {
+    meta.get("deadlineMs")
+        .and_then(Value::as_u64)
+        .map(Duration::from_millis)
+}
+
+fn format_until(until: SystemTime) -> String {
+    OffsetDateTime::from(until)
         .format(&Rfc3339)
-        .unwrap_or_else(|_| "1970-01-01T00:00:00Z".into());
+        .unwrap_or_else(|_| "1970-01-01T00:00:00Z".into())
+}
+
+fn freeze_payload(report: &FreezeReport) -> serde_json::Value {
     json!({
         "error": "error budget exhausted",
         "code": "ERROR_BUDGET_EXHAUSTED",
-        "frozen_until": until,
+        "frozen_until": format_until(report.until),
         "success_rate": report.success_rate,
         "sample_size": report.sample_size,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app::error_budget::ErrorBudgetParams, infra::outbox::Outbox};
+
+    fn server(dir: &std::path::Path) -> InspectorServer {
+        let outbox = Outbox::file(dir.join("events.jsonl"), dir.join("dlq.jsonl")).expect("outbox");
+        InspectorServer::new(
+            InspectorService::new(),
+            ToolRegistry::new(ReleaseTrack::Stable),
+            Arc::new(outbox),
+            Arc::new(IdempotencyStore::new()),
+            IdempotencyConflictPolicy::default(),
+            Arc::new(ErrorBudget::new(ErrorBudgetParams::disabled())),
+        )
+    }
+
+    fn call_request(arguments: Value) -> CallRequest {
+        serde_json::from_value(json!({"tool_name": "echo", "arguments_json": arguments}))
+            .expect("call request")
+    }
+
+    #[test]
+    fn json_limits_reject_before_an_idempotency_key_is_derived() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let server = server(dir.path())
+            .with_idempotency_auto_key(true)
+            .with_json_limits(JsonLimits {
+                max_depth: 2,
+                max_nodes: 100,
+            });
+
+        let mut nested = call_request(json!({"a": {"b": {"c": 1}}}));
+        let rejected = server.prepare_call(&mut nested).expect_err("too deep");
+        let payload = rejected.structured_content.expect("error payload");
+        assert_eq!(payload["code"], "ARGUMENTS_TOO_COMPLEX", "{payload}");
+        assert!(nested.idempotency_key.is_none());
+
+        let mut flat = call_request(json!({"a": 1}));
+        server.prepare_call(&mut flat).expect("within limits");
+        assert!(
+            flat.idempotency_key
+                .is_some_and(|key| key.starts_with("auto:"))
+        );
+    }
+}
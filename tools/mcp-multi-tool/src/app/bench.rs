@@ -0,0 +1,206 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+    time::{Duration, Instant, SystemTime},
+};
+
+use anyhow::{Result, anyhow};
+use parking_lot::Mutex;
+use rmcp::model::CallToolRequestParam;
+use serde::Serialize;
+
+use crate::{
+    app::{
+        error_budget::{ErrorBudget, RecordOutcome},
+        inspector_service::InspectorService,
+    },
+    infra::metrics::{LATENCY_HISTO, PendingGaugeGuard},
+    shared::{types::BenchRequest, utils::percentile},
+};
+
+/// Cap on `iterations`. Every latency sample is kept in memory until the percentiles are
+/// computed, so this bounds a run's memory as well as its length.
+pub const MAX_BENCH_ITERATIONS: u32 = 10_000;
+/// Upper bound on parallel workers, each of which holds its own session.
+pub const MAX_BENCH_CONCURRENCY: u32 = 64;
+
+/// Latency distribution in milliseconds, from microsecond samples.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub min_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencySummary {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        let mut micros: Vec<u64> = samples
+            .iter()
+            .map(|sample| sample.as_micros() as u64)
+            .collect();
+        micros.sort_unstable();
+        let ms = |micros: u64| micros as f64 / 1000.0;
+        Self {
+            min_ms: ms(micros.first().copied().unwrap_or(0)),
+            p50_ms: ms(percentile(&micros, 50.0)),
+            p90_ms: ms(percentile(&micros, 90.0)),
+            p99_ms: ms(percentile(&micros, 99.0)),
+            max_ms: ms(micros.last().copied().unwrap_or(0)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BenchReport {
+    pub tool_name: String,
+    pub iterations: u32,
+    /// Calls actually made; lower than `iterations` when a freeze stopped the run.
+    pub completed: u32,
+    pub concurrency: u32,
+    /// Calls that failed or returned `isError`.
+    pub errors: u32,
+    pub latency: LatencySummary,
+    pub duration_ms: u64,
+    /// Completed calls per second of wall-clock time.
+    pub throughput_per_sec: f64,
+    /// Set when the error budget froze mid-run and the remaining calls were skipped.
+    pub stopped_by_freeze: bool,
+}
+
+/// A finished bench plus the error-budget transitions its calls caused, for the caller
+/// to persist.
+pub struct BenchOutcome {
+    pub report: BenchReport,
+    pub budget_transitions: Vec<RecordOutcome>,
+}
+
+/// Call `req.tool_name` `iterations` times from `concurrency` workers, one session each,
+/// recording every call against `budget`.
+pub async fn run(
+    svc: &InspectorService,
+    req: &BenchRequest,
+    budget: &ErrorBudget,
+) -> Result<BenchOutcome> {
+    if req.iterations == 0 || req.iterations > MAX_BENCH_ITERATIONS {
+        return Err(anyhow!(
+            "iterations must be between 1 and {MAX_BENCH_ITERATIONS}"
+        ));
+    }
+    let concurrency = req.concurrency.unwrap_or(1);
+    if concurrency == 0 || concurrency > MAX_BENCH_CONCURRENCY {
+        return Err(anyhow!(
+            "concurrency must be between 1 and {MAX_BENCH_CONCURRENCY}"
+        ));
+    }
+    let concurrency = concurrency.min(req.iterations);
+    let arguments = match &req.arguments_json {
+        serde_json::Value::Object(map) => Some(map.clone()),
+        serde_json::Value::Null => None,
+        _ => return Err(anyhow!("arguments_json must be a JSON object")),
+    };
+    let _pending = PendingGaugeGuard::new();
+
+    let mut clients = Vec::with_capacity(concurrency as usize);
+    for _ in 0..concurrency {
+        clients.push(svc.connect(&req.probe).await?);
+    }
+
+    let next = AtomicU32::new(0);
+    let frozen = AtomicBool::new(false);
+    let samples = Mutex::new(Vec::with_capacity(req.iterations as usize));
+    let errors = AtomicU32::new(0);
+    let transitions = Mutex::new(Vec::new());
+    let started = Instant::now();
+    let workers = clients.iter().map(|(client, session)| async {
+        loop {
+            if frozen.load(Ordering::Relaxed) {
+                break;
+            }
+            match budget.admit_now() {
+                Ok(None) => {}
+                Ok(Some(thawed)) => transitions
+                    .lock()
+                    .push(RecordOutcome::FreezeCleared(thawed)),
+                Err(_) => {
+                    frozen.store(true, Ordering::Relaxed);
+                    break;
+                }
+            }
+            if next.fetch_add(1, Ordering::Relaxed) >= req.iterations {
+                break;
+            }
+            session.record_call();
+            let timer = Instant::now();
+            let result = client
+                .call_tool(CallToolRequestParam {
+                    name: req.tool_name.clone().into(),
+                    arguments: arguments.clone(),
+                })
+                .await;
+            let elapsed = timer.elapsed();
+            LATENCY_HISTO.observe(elapsed.as_secs_f64() * 1000.0);
+            samples.lock().push(elapsed);
+            let success = result.is_ok_and(|result| !result.is_error.unwrap_or(false));
+            if !success {
+                errors.fetch_add(1, Ordering::Relaxed);
+            }
+            match budget.record(success, SystemTime::now()) {
+                RecordOutcome::None => {}
+                transition => transitions.lock().push(transition),
+            }
+        }
+    });
+    futures::future::join_all(workers).await;
+    let elapsed = started.elapsed();
+
+    let samples = samples.into_inner();
+    let completed = samples.len() as u32;
+    Ok(BenchOutcome {
+        report: BenchReport {
+            tool_name: req.tool_name.clone(),
+            iterations: req.iterations,
+            completed,
+            concurrency,
+            errors: errors.into_inner(),
+            latency: LatencySummary::from_samples(&samples),
+            duration_ms: elapsed.as_millis() as u64,
+            throughput_per_sec: completed as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+            stopped_by_freeze: frozen.into_inner(),
+        },
+        budget_transitions: transitions.into_inner(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_uses_nearest_rank_percentiles() {
+        // 1..=100 ms, shuffled so the summary has to sort.
+        let samples: Vec<Duration> = (1..=100u64)
+            .map(|ms| Duration::from_millis((ms * 37) % 100 + 1))
+            .collect();
+        let summary = LatencySummary::from_samples(&samples);
+        assert_eq!(
+            summary,
+            LatencySummary {
+                min_ms: 1.0,
+                p50_ms: 50.0,
+                p90_ms: 90.0,
+                p99_ms: 99.0,
+                max_ms: 100.0,
+            }
+        );
+    }
+
+    #[test]
+    fn summary_keeps_sub_millisecond_precision() {
+        let samples = [Duration::from_micros(250), Duration::from_micros(1_500)];
+        let summary = LatencySummary::from_samples(&samples);
+        assert_eq!(summary.min_ms, 0.25);
+        assert_eq!(summary.max_ms, 1.5);
+        assert_eq!(LatencySummary::from_samples(&[]), LatencySummary::default());
+    }
+}
@@ -3,15 +3,21 @@ use serde::Serialize;
 use serde_json::{Value, json};
 use std::{
     collections::BTreeMap,
+    sync::Arc,
     time::{Duration, Instant},
 };
 use time::OffsetDateTime;
-use tokio::time::sleep;
+use tokio::time::{sleep, timeout};
 
 use crate::{
-    app::inspector_service::InspectorService,
-    shared::types::{
-        CallRequest, DescribeRequest, HttpTarget, ProbeRequest, SseTarget, TargetTransportKind,
+    app::inspector_service::{InspectorService, StdioEnv},
+    infra::compliance_history::ComplianceRun,
+    shared::{
+        types::{
+            CallRequest, DescribeRequest, HttpTarget, OutputSchemaCheck, ProbeRequest, ProbeResult,
+            SseTarget, TargetTransportKind,
+        },
+        utils::percentile,
     },
 };
 
@@ -42,6 +48,22 @@ impl ComplianceTarget {
             ..Default::default()
         }
     }
+
+    /// How runs against this target are labelled in the compliance history: the command
+    /// line, else the HTTP URL, else the SSE URL.
+    pub fn label(&self) -> String {
+        if let Some(command) = &self.command {
+            std::iter::once(command.as_str())
+                .chain(self.args.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(" ")
+        } else {
+            self.http_url
+                .clone()
+                .or_else(|| self.sse_url.clone())
+                .unwrap_or_default()
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -51,19 +73,204 @@ pub struct CaseResult {
     pub duration_ms: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub detail: Option<Value>,
+    /// Set when the case did not run; skipped cases are excluded from `pass_rate`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+    /// Transport the case exercised; filled in by `ComplianceSuite::run`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<TargetTransportKind>,
+}
+
+impl CaseResult {
+    pub fn skipped<N: Into<String>, R: Into<String>>(name: N, reason: R) -> Self {
+        Self {
+            name: name.into(),
+            passed: false,
+            duration_ms: 0,
+            detail: None,
+            skipped: Some(reason.into()),
+            transport: None,
+        }
+    }
+
+    pub fn is_skipped(&self) -> bool {
+        self.skipped.is_some()
+    }
+
+    fn timed_out<N: Into<String>>(name: N, limit: Duration) -> Self {
+        Self {
+            name: name.into(),
+            passed: false,
+            duration_ms: 0,
+            detail: Some(json!({
+                "error": format!(
+                    "overall timeout of {} ms exceeded before the case completed",
+                    limit.as_millis()
+                ),
+                "timed_out": true,
+            })),
+            skipped: None,
+            transport: None,
+        }
+    }
+}
+
+const TIMEOUT_CLEANUP_GRACE: Duration = Duration::from_millis(200);
+
+/// Target field a case needs in order to run.
+#[derive(Clone, Copy)]
+enum CaseTarget {
+    Command,
+    SseUrl,
+    HttpUrl,
+    Always,
+}
+
+impl CaseTarget {
+    /// The missing-command negative case probes stdio, so `Always` counts as stdio too.
+    fn transport(self) -> TargetTransportKind {
+        match self {
+            Self::Command | Self::Always => TargetTransportKind::Stdio,
+            Self::SseUrl => TargetTransportKind::Sse,
+            Self::HttpUrl => TargetTransportKind::Http,
+        }
+    }
+
+    fn missing_reason(self, target: &ComplianceTarget) -> Option<&'static str> {
+        match self {
+            Self::Command if target.command.is_none() => Some("no command provided"),
+            Self::SseUrl if target.sse_url.is_none() => Some("no sse_url provided"),
+            Self::HttpUrl if target.http_url.is_none() => Some("no http_url provided"),
+            _ => None,
+        }
+    }
+}
+
+/// Every case `run` attempts, in execution order.
+const PLANNED_CASES: &[(&str, CaseTarget)] = &[
+    ("probe_stdio", CaseTarget::Command),
+    ("list_tools", CaseTarget::Command),
+    ("list_tools_sse", CaseTarget::SseUrl),
+    ("list_tools_http", CaseTarget::HttpUrl),
+    ("describe_help", CaseTarget::Command),
+    ("describe_help_sse", CaseTarget::SseUrl),
+    ("describe_help_http", CaseTarget::HttpUrl),
+    ("call_help", CaseTarget::Command),
+    ("call_help_stream", CaseTarget::Command),
+    ("call_help_sse", CaseTarget::SseUrl),
+    ("call_help_http", CaseTarget::HttpUrl),
+    ("probe_sse", CaseTarget::SseUrl),
+    ("probe_http", CaseTarget::HttpUrl),
+    ("negative_missing_command", CaseTarget::Always),
+];
+
+fn planned_transport(name: &str) -> Option<TargetTransportKind> {
+    PLANNED_CASES
+        .iter()
+        .find(|(planned, _)| *planned == name)
+        .map(|(_, needs)| needs.transport())
+}
+
+/// Aggregate outcome of the cases that ran over one transport.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct TransportStats {
+    pub transport: TargetTransportKind,
+    pub cases: usize,
+    pub passed: usize,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+}
+
+impl TransportStats {
+    /// Group non-skipped cases by transport, in stdio, sse, http order.
+    pub fn from_cases(cases: &[CaseResult]) -> Vec<Self> {
+        [
+            TargetTransportKind::Stdio,
+            TargetTransportKind::Sse,
+            TargetTransportKind::Http,
+        ]
+        .into_iter()
+        .filter_map(|transport| {
+            let ran: Vec<&CaseResult> = cases
+                .iter()
+                .filter(|case| case.transport == Some(transport) && !case.is_skipped())
+                .collect();
+            if ran.is_empty() {
+                return None;
+            }
+            let mut durations: Vec<u64> = ran.iter().map(|case| case.duration_ms).collect();
+            durations.sort_unstable();
+            Some(Self {
+                transport,
+                cases: ran.len(),
+                passed: ran.iter().filter(|case| case.passed).count(),
+                p50_ms: percentile(&durations, 50.0),
+                p95_ms: percentile(&durations, 95.0),
+                max_ms: durations.last().copied().unwrap_or_default(),
+            })
+        })
+        .collect()
+    }
+}
+
+/// A probe passes only when the handshake succeeded and the target named itself, since
+/// probe results are diffed across releases by server name as well as version.
+fn probe_identified(res: &ProbeResult) -> bool {
+    res.ok
+        && res
+            .server_name
+            .as_deref()
+            .is_some_and(|name| !name.is_empty())
+}
+
+fn probe_error(res: &ProbeResult) -> Option<String> {
+    if res.error.is_some() || !res.ok || probe_identified(res) {
+        return res.error.clone();
+    }
+    Some("target did not report serverInfo.name".into())
+}
+
+fn transport_label(transport: TargetTransportKind) -> &'static str {
+    match transport {
+        TargetTransportKind::Stdio => "stdio",
+        TargetTransportKind::Sse => "sse",
+        TargetTransportKind::Http => "http",
+        TargetTransportKind::Unix => "unix",
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
 pub struct ComplianceReport {
     pub started_at: String,
     pub finished_at: String,
-    pub pass_rate: f64,
+    /// Share of the cases that ran which passed; `None` when every case was skipped.
+    pub pass_rate: Option<f64>,
     pub cases: Vec<CaseResult>,
+    /// Per-transport counts and latency, so a slow or flaky transport stands out.
+    pub by_transport: Vec<TransportStats>,
+    /// True when the overall timeout cut the run short.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
 }
 
 impl ComplianceReport {
+    /// A run where every case was skipped has nothing to fail, so it passes.
     pub fn passed(&self) -> bool {
-        self.pass_rate >= 0.95
+        self.pass_rate.is_none_or(|rate| rate >= 0.95)
+    }
+
+    /// Summary of this run for the compliance history; `None` when no case ran, since such
+    /// a run has no pass rate to trend.
+    pub fn history_entry(&self, target: &ComplianceTarget) -> Option<ComplianceRun> {
+        Some(ComplianceRun {
+            started_at: self.started_at.clone(),
+            finished_at: self.finished_at.clone(),
+            target: target.label(),
+            pass_rate: self.pass_rate?,
+            cases: self.cases.iter().filter(|case| !case.is_skipped()).count() as u64,
+            passed: self.passed(),
+        })
     }
 
     pub fn to_markdown(&self) -> String {
@@ -71,33 +278,66 @@ impl ComplianceReport {
         md.push_str("| Case | Status | Duration (ms) | Notes |\n");
         md.push_str("| --- | --- | --- | --- |\n");
         for case in &self.cases {
-            let status = if case.passed { "✅" } else { "❌" };
-            let notes = case
-                .detail
-                .as_ref()
-                .map(|v| serde_json::to_string(v).unwrap_or_default())
-                .unwrap_or_else(|| "-".into());
+            let status = if case.is_skipped() {
+                "⏭️ skipped"
+            } else if case.passed {
+                "✅"
+            } else {
+                "❌"
+            };
+            let notes = match (&case.skipped, &case.detail) {
+                (Some(reason), _) => reason.clone(),
+                (None, Some(detail)) => serde_json::to_string(detail).unwrap_or_default(),
+                (None, None) => "-".into(),
+            };
             md.push_str(&format!(
                 "| {} | {} | {} | {} |\n",
                 case.name, status, case.duration_ms, notes
             ));
         }
-        md.push_str(&format!(
-            "\nPass rate: {:.2}% (threshold 95%)",
-            self.pass_rate * 100.0
-        ));
+        match self.pass_rate {
+            Some(rate) => md.push_str(&format!(
+                "\nPass rate: {:.2}% (threshold 95%)",
+                rate * 100.0
+            )),
+            None => md.push_str("\nPass rate: n/a (every case was skipped)"),
+        }
+        if !self.by_transport.is_empty() {
+            md.push_str("\n\n| Transport | Cases | Passed | p50 (ms) | p95 (ms) | Max (ms) |\n");
+            md.push_str("| --- | --- | --- | --- | --- | --- |\n");
+            for stats in &self.by_transport {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} |\n",
+                    transport_label(stats.transport),
+                    stats.cases,
+                    stats.passed,
+                    stats.p50_ms,
+                    stats.p95_ms,
+                    stats.max_ms
+                ));
+            }
+        }
         md
     }
 }
 
+/// Called with every case result as the run produces it.
+pub type ProgressCallback = Arc<dyn Fn(&CaseResult) + Send + Sync>;
+
 pub struct ComplianceSuite {
     svc: InspectorService,
+    include_skipped: bool,
+    overall_timeout: Option<Duration>,
+    progress: Option<ProgressCallback>,
 }
 
 impl Default for ComplianceSuite {
     fn default() -> Self {
         Self {
             svc: InspectorService::new(),
+            include_skipped: false,
+            overall_timeout: None,
+            progress: None,
         }
     }
 }
@@ -107,96 +347,199 @@ impl ComplianceSuite {
         Self::default()
     }
 
+    /// Report cases whose target was not provided as skipped instead of omitting them.
+    pub fn with_include_skipped(mut self, include_skipped: bool) -> Self {
+        self.include_skipped = include_skipped;
+        self
+    }
+
+    /// Bound the whole run; cases not finished in time are reported as failed with a timeout.
+    pub fn with_overall_timeout(mut self, limit: Option<Duration>) -> Self {
+        self.overall_timeout = limit;
+        self
+    }
+
+    /// Invoke `callback` with each case as soon as it completes.
+    pub fn with_progress(mut self, callback: ProgressCallback) -> Self {
+        self.progress = Some(callback);
+        self
+    }
+
+    /// Tag a finished case with its transport, report it, and add it to the run.
+    fn push(&self, cases: &mut Vec<CaseResult>, mut case: CaseResult) {
+        case.transport = planned_transport(&case.name);
+        if let Some(progress) = self.progress.as_ref() {
+            progress(&case);
+        }
+        cases.push(case);
+    }
+
+    fn skipped(&self, name: &str, reason: &str) -> Option<CaseResult> {
+        self.include_skipped
+            .then(|| CaseResult::skipped(name, reason))
+    }
+
     pub async fn run(&self, target: ComplianceTarget) -> Result<ComplianceReport> {
         let started_at = OffsetDateTime::now_utc();
         let mut cases = Vec::new();
+        let timed_out = match self.overall_timeout {
+            Some(limit) => {
+                let outcome = timeout(limit, self.run_cases(&target, &mut cases)).await;
+                match outcome {
+                    Ok(result) => {
+                        result?;
+                        false
+                    }
+                    Err(_) => {
+                        // Dropped transports kill their child processes from spawned tasks;
+                        // let those run so a hung target does not outlive the report.
+                        sleep(TIMEOUT_CLEANUP_GRACE).await;
+                        self.mark_unreached(&target, &mut cases, limit);
+                        true
+                    }
+                }
+            }
+            None => {
+                self.run_cases(&target, &mut cases).await?;
+                false
+            }
+        };
+
+        let by_transport = TransportStats::from_cases(&cases);
+
+        let finished_at = OffsetDateTime::now_utc();
+        let pass_count = cases.iter().filter(|c| c.passed).count() as f64;
+        let total = cases.iter().filter(|c| !c.is_skipped()).count();
+        let pass_rate = (total > 0).then(|| pass_count / total as f64);
+
+        Ok(ComplianceReport {
+            started_at: started_at.to_string(),
+            finished_at: finished_at.to_string(),
+            pass_rate,
+            cases,
+            by_transport,
+            timed_out,
+        })
+    }
 
-        if let Some(case) = self.probe_stdio_case(&target).await? {
-            cases.push(case);
+    fn mark_unreached(
+        &self,
+        target: &ComplianceTarget,
+        cases: &mut Vec<CaseResult>,
+        limit: Duration,
+    ) {
+        for (name, needs) in PLANNED_CASES {
+            if cases.iter().any(|case| case.name == *name) {
+                continue;
+            }
+            let case = match needs.missing_reason(target) {
+                Some(reason) => self.skipped(name, reason),
+                None => Some(CaseResult::timed_out(*name, limit)),
+            };
+            if let Some(case) = case {
+                self.push(cases, case);
+            }
         }
-        if let Some(case) = self.list_tools_stdio_case(&target).await? {
-            cases.push(case);
+    }
+
+    async fn run_cases(
+        &self,
+        target: &ComplianceTarget,
+        cases: &mut Vec<CaseResult>,
+    ) -> Result<()> {
+        if let Some(case) = self.probe_stdio_case(target).await? {
+            self.push(cases, case);
         }
-        if let Some(case) = self.list_tools_sse_case(&target).await? {
-            cases.push(case);
+        if let Some(case) = self.list_tools_stdio_case(target).await? {
+            self.push(cases, case);
         }
-        if let Some(case) = self.list_tools_http_case(&target).await? {
-            cases.push(case);
+        if let Some(case) = self.list_tools_sse_case(target).await? {
+            self.push(cases, case);
         }
-        if let Some(case) = self.describe_stdio_case(&target).await? {
-            cases.push(case);
+        if let Some(case) = self.list_tools_http_case(target).await? {
+            self.push(cases, case);
         }
-        if let Some(case) = self.describe_sse_case(&target).await? {
-            cases.push(case);
+        if let Some(case) = self.describe_stdio_case(target).await? {
+            self.push(cases, case);
         }
-        if let Some(case) = self.describe_http_case(&target).await? {
-            cases.push(case);
+        if let Some(case) = self.describe_sse_case(target).await? {
+            self.push(cases, case);
+        }
+        if let Some(case) = self.describe_http_case(target).await? {
+            self.push(cases, case);
         }
         if target.sse_url.is_some() || target.http_url.is_some() {
             sleep(Duration::from_millis(200)).await;
         }
-        if let Some(case) = self.call_stdio_case(&target).await? {
-            cases.push(case);
+        if let Some(case) = self.call_stdio_case(target).await? {
+            self.push(cases, case);
         }
-        if let Some(case) = self.call_stdio_stream_case(&target).await? {
-            cases.push(case);
+        if let Some(case) = self.call_stdio_stream_case(target).await? {
+            self.push(cases, case);
         }
-        if let Some(case) = self.call_sse_case(&target).await? {
-            cases.push(case);
+        if let Some(case) = self.call_sse_case(target).await? {
+            self.push(cases, case);
         }
-        if let Some(case) = self.call_http_case(&target).await? {
-            cases.push(case);
+        if let Some(case) = self.call_http_case(target).await? {
+            self.push(cases, case);
         }
-        if let Some(sse_case) = self.probe_sse_case(&target).await? {
-            cases.push(sse_case);
+        if let Some(sse_case) = self.probe_sse_case(target).await? {
+            self.push(cases, sse_case);
         }
-        if let Some(http_case) = self.probe_http_case(&target).await? {
-            cases.push(http_case);
+        if let Some(http_case) = self.probe_http_case(target).await? {
+            self.push(cases, http_case);
         }
-        cases.push(self.missing_command_case().await?);
-
-        let finished_at = OffsetDateTime::now_utc();
-        let pass_count = cases.iter().filter(|c| c.passed).count() as f64;
-        let total = cases.len().max(1) as f64;
-        let pass_rate = pass_count / total;
-
-        Ok(ComplianceReport {
-            started_at: started_at.to_string(),
-            finished_at: finished_at.to_string(),
-            pass_rate,
-            cases,
-        })
+        let negative = self.missing_command_case().await?;
+        self.push(cases, negative);
+        Ok(())
     }
 
     async fn probe_stdio_case(&self, target: &ComplianceTarget) -> Result<Option<CaseResult>> {
         let Some(command) = target.command.as_ref() else {
-            return Ok(None);
+            return Ok(self.skipped("probe_stdio", "no command provided"));
         };
         let timer = Instant::now();
         let req = ProbeRequest {
+            target_alias: None,
             transport: Some(TargetTransportKind::Stdio),
             command: Some(command.clone()),
             args: Some(target.args.clone()),
             env: target.env.clone(),
+            env_clear: false,
+            inherit_basic: false,
             cwd: target.cwd.clone(),
+            shell: false,
             url: None,
             headers: None,
             auth_token: None,
+            tls: None,
+            proxy: None,
+            socket_path: None,
             handshake_timeout_ms: Some(15_000),
+            protocol_version: None,
+            require_tools: false,
+            reuse_session: Some(false),
+            session_id: None,
+            retry: None,
         };
         match self.svc.probe(req).await {
             Ok(res) => {
-                let passed = res.ok;
+                let passed = probe_identified(&res);
                 Ok(Some(CaseResult {
                     name: "probe_stdio".into(),
                     passed,
                     duration_ms: timer.elapsed().as_millis() as u64,
                     detail: Some(json!({
                         "transport": res.transport,
+                        "server_name": res.server_name,
+                        "title": res.title,
                         "version": res.version,
+                        "capabilities": res.capabilities,
                         "latency_ms": res.latency_ms,
-                        "error": res.error,
+                        "error": probe_error(&res),
                     })),
+                    skipped: None,
+                    transport: None,
                 }))
             }
             Err(err) => Ok(Some(CaseResult {
@@ -206,13 +549,15 @@ impl ComplianceSuite {
                 detail: Some(json!({
                     "error": err.to_string()
                 })),
+                skipped: None,
+                transport: None,
             })),
         }
     }
 
     async fn list_tools_stdio_case(&self, target: &ComplianceTarget) -> Result<Option<CaseResult>> {
         let Some(command) = target.command.as_ref() else {
-            return Ok(None);
+            return Ok(self.skipped("list_tools", "no command provided"));
         };
         let timer = Instant::now();
         let outcome = self
@@ -220,8 +565,10 @@ impl ComplianceSuite {
             .list_tools_stdio(
                 command.clone(),
                 target.args.clone(),
-                target.env.clone(),
+                StdioEnv::inherited(target.env.clone()),
                 target.cwd.clone(),
+                false,
+                None,
             )
             .await;
         match outcome {
@@ -234,6 +581,8 @@ impl ComplianceSuite {
                     detail: Some(json!({
                         "tool_count": tools.len(),
                     })),
+                    skipped: None,
+                    transport: None,
                 }))
             }
             Err(err) => Ok(Some(CaseResult {
@@ -243,21 +592,25 @@ impl ComplianceSuite {
                 detail: Some(json!({
                     "error": err.to_string()
                 })),
+                skipped: None,
+                transport: None,
             })),
         }
     }
 
     async fn list_tools_sse_case(&self, target: &ComplianceTarget) -> Result<Option<CaseResult>> {
         let Some(url) = target.sse_url.clone() else {
-            return Ok(None);
+            return Ok(self.skipped("list_tools_sse", "no sse_url provided"));
         };
         let timer = Instant::now();
         let sse_target = SseTarget {
             url: url.clone(),
             headers: None,
             handshake_timeout_ms: Some(15_000),
+            tls: None,
+            proxy: None,
         };
-        let outcome = self.svc.list_tools_sse(&sse_target).await;
+        let outcome = self.svc.list_tools_sse(&sse_target, false).await;
         Ok(Some(match outcome {
             Ok(tools) => CaseResult {
                 name: "list_tools_sse".into(),
@@ -267,19 +620,23 @@ impl ComplianceSuite {
                     "url": url,
                     "tool_count": tools.len(),
                 })),
+                skipped: None,
+                transport: None,
             },
             Err(err) => CaseResult {
                 name: "list_tools_sse".into(),
                 passed: false,
                 duration_ms: timer.elapsed().as_millis() as u64,
                 detail: Some(json!({"error": err.to_string()})),
+                skipped: None,
+                transport: None,
             },
         }))
     }
 
     async fn list_tools_http_case(&self, target: &ComplianceTarget) -> Result<Option<CaseResult>> {
         let Some(url) = target.http_url.clone() else {
-            return Ok(None);
+            return Ok(self.skipped("list_tools_http", "no http_url provided"));
         };
         let timer = Instant::now();
         let http_target = HttpTarget {
@@ -287,8 +644,10 @@ impl ComplianceSuite {
             headers: target.http_headers.clone(),
             auth_token: target.http_auth_token.clone(),
             handshake_timeout_ms: Some(15_000),
+            tls: None,
+            proxy: None,
         };
-        let outcome = self.svc.list_tools_http(&http_target).await;
+        let outcome = self.svc.list_tools_http(&http_target, false).await;
         Ok(Some(match outcome {
             Ok(tools) => CaseResult {
                 name: "list_tools_http".into(),
@@ -298,33 +657,49 @@ impl ComplianceSuite {
                     "url": url,
                     "tool_count": tools.len(),
                 })),
+                skipped: None,
+                transport: None,
             },
             Err(err) => CaseResult {
                 name: "list_tools_http".into(),
                 passed: false,
                 duration_ms: timer.elapsed().as_millis() as u64,
                 detail: Some(json!({"error": err.to_string()})),
+                skipped: None,
+                transport: None,
             },
         }))
     }
 
     async fn describe_stdio_case(&self, target: &ComplianceTarget) -> Result<Option<CaseResult>> {
         let Some(command) = target.command.as_ref() else {
-            return Ok(None);
+            return Ok(self.skipped("describe_help", "no command provided"));
         };
         let timer = Instant::now();
         let req = DescribeRequest {
             tool_name: "help".into(),
             probe: ProbeRequest {
+                target_alias: None,
                 transport: Some(TargetTransportKind::Stdio),
                 command: Some(command.clone()),
                 args: Some(target.args.clone()),
                 env: target.env.clone(),
+                env_clear: false,
+                inherit_basic: false,
                 cwd: target.cwd.clone(),
+                shell: false,
                 url: None,
                 headers: None,
                 auth_token: None,
+                tls: None,
+                proxy: None,
+                socket_path: None,
                 handshake_timeout_ms: Some(15_000),
+                protocol_version: None,
+                require_tools: false,
+                reuse_session: Some(false),
+                session_id: None,
+                retry: None,
             },
         };
         match self.svc.describe(req).await {
@@ -333,33 +708,49 @@ impl ComplianceSuite {
                 passed: tool.name.as_ref() == "help",
                 duration_ms: timer.elapsed().as_millis() as u64,
                 detail: serde_json::to_value(&tool).ok().map(|v| json!({"tool": v})),
+                skipped: None,
+                transport: None,
             })),
             Err(err) => Ok(Some(CaseResult {
                 name: "describe_help".into(),
                 passed: false,
                 duration_ms: timer.elapsed().as_millis() as u64,
                 detail: Some(json!({"error": err.to_string()})),
+                skipped: None,
+                transport: None,
             })),
         }
     }
 
     async fn describe_sse_case(&self, target: &ComplianceTarget) -> Result<Option<CaseResult>> {
         let Some(url) = target.sse_url.clone() else {
-            return Ok(None);
+            return Ok(self.skipped("describe_help_sse", "no sse_url provided"));
         };
         let timer = Instant::now();
         let req = DescribeRequest {
             tool_name: "help".into(),
             probe: ProbeRequest {
+                target_alias: None,
                 transport: Some(TargetTransportKind::Sse),
                 command: None,
                 args: None,
                 env: None,
+                env_clear: false,
+                inherit_basic: false,
                 cwd: None,
+                shell: false,
                 url: Some(url.clone()),
                 headers: None,
                 auth_token: None,
+                tls: None,
+                proxy: None,
+                socket_path: None,
                 handshake_timeout_ms: Some(15_000),
+                protocol_version: None,
+                require_tools: false,
+                reuse_session: Some(false),
+                session_id: None,
+                retry: None,
             },
         };
         match self.svc.describe(req).await {
@@ -368,33 +759,49 @@ impl ComplianceSuite {
                 passed: tool.name.as_ref() == "help",
                 duration_ms: timer.elapsed().as_millis() as u64,
                 detail: serde_json::to_value(&tool).ok().map(|v| json!({"tool": v})),
+                skipped: None,
+                transport: None,
             })),
             Err(err) => Ok(Some(CaseResult {
                 name: "describe_help_sse".into(),
                 passed: false,
                 duration_ms: timer.elapsed().as_millis() as u64,
                 detail: Some(json!({"error": err.to_string()})),
+                skipped: None,
+                transport: None,
             })),
         }
     }
 
     async fn describe_http_case(&self, target: &ComplianceTarget) -> Result<Option<CaseResult>> {
         let Some(url) = target.http_url.clone() else {
-            return Ok(None);
+            return Ok(self.skipped("describe_help_http", "no http_url provided"));
         };
         let timer = Instant::now();
         let req = DescribeRequest {
             tool_name: "help".into(),
             probe: ProbeRequest {
+                target_alias: None,
                 transport: Some(TargetTransportKind::Http),
                 command: None,
                 args: None,
                 env: None,
+                env_clear: false,
+                inherit_basic: false,
                 cwd: None,
+                shell: false,
                 url: Some(url.clone()),
                 headers: target.http_headers.clone(),
                 auth_token: target.http_auth_token.clone(),
+                tls: None,
+                proxy: None,
+                socket_path: None,
                 handshake_timeout_ms: Some(15_000),
+                protocol_version: None,
+                require_tools: false,
+                reuse_session: Some(false),
+                session_id: None,
+                retry: None,
             },
         };
         match self.svc.describe(req).await {
@@ -403,19 +810,23 @@ impl ComplianceSuite {
                 passed: tool.name.as_ref() == "help",
                 duration_ms: timer.elapsed().as_millis() as u64,
                 detail: serde_json::to_value(&tool).ok().map(|v| json!({"tool": v})),
+                skipped: None,
+                transport: None,
             })),
             Err(err) => Ok(Some(CaseResult {
                 name: "describe_help_http".into(),
                 passed: false,
                 duration_ms: timer.elapsed().as_millis() as u64,
                 detail: Some(json!({"error": err.to_string()})),
+                skipped: None,
+                transport: None,
             })),
         }
     }
 
     async fn call_stdio_case(&self, target: &ComplianceTarget) -> Result<Option<CaseResult>> {
         let Some(command) = target.command.as_ref() else {
-            return Ok(None);
+            return Ok(self.skipped("call_help", "no command provided"));
         };
         let timer = Instant::now();
         let request = CallRequest {
@@ -424,16 +835,28 @@ impl ComplianceSuite {
             idempotency_key: None,
             stream: false,
             external_reference: None,
+            output_schema_check: OutputSchemaCheck::Off,
+            target_alias: None,
             stdio: None,
             sse: None,
             http: None,
+            unix: None,
+            fallback_targets: Vec::new(),
+            reuse_session: Some(false),
+            session_id: None,
+            retry: None,
+            stream_max_duration_ms: None,
+            call_timeout_ms: None,
+            progress_token: None,
+            validate_arguments: false,
+            stream_drain_timeout_ms: None,
         };
         let outcome = self
             .svc
             .call_stdio(
                 command.clone(),
                 target.args.clone(),
-                target.env.clone(),
+                StdioEnv::inherited(target.env.clone()),
                 target.cwd.clone(),
                 &request,
             )
@@ -447,6 +870,8 @@ impl ComplianceSuite {
                     passed,
                     duration_ms: timer.elapsed().as_millis() as u64,
                     detail: self.snapshot(&result),
+                    skipped: None,
+                    transport: None,
                 }))
             }
             Err(err) => Ok(Some(CaseResult {
@@ -456,19 +881,23 @@ impl ComplianceSuite {
                 detail: Some(json!({
                     "error": err.to_string()
                 })),
+                skipped: None,
+                transport: None,
             })),
         }
     }
 
     async fn call_sse_case(&self, target: &ComplianceTarget) -> Result<Option<CaseResult>> {
         let Some(url) = target.sse_url.clone() else {
-            return Ok(None);
+            return Ok(self.skipped("call_help_sse", "no sse_url provided"));
         };
         let timer = Instant::now();
         let sse_target = SseTarget {
             url: url.clone(),
             headers: None,
             handshake_timeout_ms: Some(15_000),
+            tls: None,
+            proxy: None,
         };
         let request = CallRequest {
             tool_name: "help".into(),
@@ -476,9 +905,21 @@ impl ComplianceSuite {
             idempotency_key: None,
             stream: false,
             external_reference: None,
+            output_schema_check: OutputSchemaCheck::Off,
+            target_alias: None,
             stdio: None,
             sse: None,
             http: None,
+            unix: None,
+            fallback_targets: Vec::new(),
+            reuse_session: Some(false),
+            session_id: None,
+            retry: None,
+            stream_max_duration_ms: None,
+            call_timeout_ms: None,
+            progress_token: None,
+            validate_arguments: false,
+            stream_drain_timeout_ms: None,
         };
         let outcome = self.svc.call_sse(&sse_target, &request).await;
         Ok(Some(match outcome {
@@ -490,6 +931,8 @@ impl ComplianceSuite {
                     passed,
                     duration_ms: timer.elapsed().as_millis() as u64,
                     detail: self.snapshot(&result),
+                    skipped: None,
+                    transport: None,
                 }
             }
             Err(err) => CaseResult {
@@ -497,13 +940,15 @@ impl ComplianceSuite {
                 passed: false,
                 duration_ms: timer.elapsed().as_millis() as u64,
                 detail: Some(json!({"error": err.to_string()})),
+                skipped: None,
+                transport: None,
             },
         }))
     }
 
     async fn call_http_case(&self, target: &ComplianceTarget) -> Result<Option<CaseResult>> {
         let Some(url) = target.http_url.clone() else {
-            return Ok(None);
+            return Ok(self.skipped("call_help_http", "no http_url provided"));
         };
         let timer = Instant::now();
         let http_target = HttpTarget {
@@ -511,6 +956,8 @@ impl ComplianceSuite {
             headers: target.http_headers.clone(),
             auth_token: target.http_auth_token.clone(),
             handshake_timeout_ms: Some(15_000),
+            tls: None,
+            proxy: None,
         };
         let request = CallRequest {
             tool_name: "help".into(),
@@ -518,9 +965,21 @@ impl ComplianceSuite {
             idempotency_key: None,
             stream: false,
             external_reference: None,
+            output_schema_check: OutputSchemaCheck::Off,
+            target_alias: None,
             stdio: None,
             sse: None,
             http: None,
+            unix: None,
+            fallback_targets: Vec::new(),
+            reuse_session: Some(false),
+            session_id: None,
+            retry: None,
+            stream_max_duration_ms: None,
+            call_timeout_ms: None,
+            progress_token: None,
+            validate_arguments: false,
+            stream_drain_timeout_ms: None,
         };
         let outcome = self.svc.call_http(&http_target, &request).await;
         Ok(Some(match outcome {
@@ -532,6 +991,8 @@ impl ComplianceSuite {
                     passed,
                     duration_ms: timer.elapsed().as_millis() as u64,
                     detail: self.snapshot(&result),
+                    skipped: None,
+                    transport: None,
                 }
             }
             Err(err) => CaseResult {
@@ -539,6 +1000,8 @@ impl ComplianceSuite {
                 passed: false,
                 duration_ms: timer.elapsed().as_millis() as u64,
                 detail: Some(json!({"error": err.to_string()})),
+                skipped: None,
+                transport: None,
             },
         }))
     }
@@ -548,7 +1011,7 @@ impl ComplianceSuite {
         target: &ComplianceTarget,
     ) -> Result<Option<CaseResult>> {
         let Some(command) = target.command.as_ref() else {
-            return Ok(None);
+            return Ok(self.skipped("call_help_stream", "no command provided"));
         };
         let timer = Instant::now();
         let request = CallRequest {
@@ -557,16 +1020,28 @@ impl ComplianceSuite {
             idempotency_key: None,
             stream: true,
             external_reference: None,
+            output_schema_check: OutputSchemaCheck::Off,
+            target_alias: None,
             stdio: None,
             sse: None,
             http: None,
+            unix: None,
+            fallback_targets: Vec::new(),
+            reuse_session: Some(false),
+            session_id: None,
+            retry: None,
+            stream_max_duration_ms: None,
+            call_timeout_ms: None,
+            progress_token: None,
+            validate_arguments: false,
+            stream_drain_timeout_ms: None,
         };
         let outcome = self
             .svc
             .call_stdio(
                 command.clone(),
                 target.args.clone(),
-                target.env.clone(),
+                StdioEnv::inherited(target.env.clone()),
                 target.cwd.clone(),
                 &request,
             )
@@ -605,6 +1080,8 @@ impl ComplianceSuite {
                         "events": events,
                         "snapshot": self.snapshot(&result)
                     })),
+                    skipped: None,
+                    transport: None,
                 }))
             }
             Err(err) => Ok(Some(CaseResult {
@@ -612,75 +1089,108 @@ impl ComplianceSuite {
                 passed: false,
                 duration_ms: timer.elapsed().as_millis() as u64,
                 detail: Some(json!({"error": err.to_string()})),
+                skipped: None,
+                transport: None,
             })),
         }
     }
 
     async fn probe_sse_case(&self, target: &ComplianceTarget) -> Result<Option<CaseResult>> {
         let Some(url) = target.sse_url.clone() else {
-            return Ok(None);
+            return Ok(self.skipped("probe_sse", "no sse_url provided"));
         };
         let timer = Instant::now();
         let req = ProbeRequest {
+            target_alias: None,
             transport: Some(TargetTransportKind::Sse),
             command: None,
             args: None,
             env: None,
+            env_clear: false,
+            inherit_basic: false,
             cwd: None,
+            shell: false,
             url: Some(url.clone()),
             headers: None,
             auth_token: None,
+            tls: None,
+            proxy: None,
+            socket_path: None,
             handshake_timeout_ms: Some(15_000),
+            protocol_version: None,
+            require_tools: false,
+            reuse_session: Some(false),
+            session_id: None,
+            retry: None,
         };
         let outcome = self.svc.probe(req).await;
         Ok(Some(match outcome {
             Ok(res) => CaseResult {
                 name: "probe_sse".into(),
-                passed: res.ok,
+                passed: probe_identified(&res),
                 duration_ms: timer.elapsed().as_millis() as u64,
                 detail: Some(json!({
                     "url": url,
+                    "server_name": res.server_name,
+                    "title": res.title,
+                    "version": res.version,
+                    "capabilities": res.capabilities,
                     "latency_ms": res.latency_ms,
-                    "error": res.error,
+                    "error": probe_error(&res),
                 })),
+                skipped: None,
+                transport: None,
             },
             Err(err) => CaseResult {
                 name: "probe_sse".into(),
                 passed: false,
                 duration_ms: timer.elapsed().as_millis() as u64,
                 detail: Some(json!({"error": err.to_string()})),
+                skipped: None,
+                transport: None,
             },
         }))
     }
 
     async fn probe_http_case(&self, target: &ComplianceTarget) -> Result<Option<CaseResult>> {
         let Some(url) = target.http_url.clone() else {
-            return Ok(None);
+            return Ok(self.skipped("probe_http", "no http_url provided"));
         };
         let timer = Instant::now();
-        let mut req = ProbeRequest::default();
-        req.transport = Some(TargetTransportKind::Http);
-        req.url = Some(url.clone());
-        req.headers = target.http_headers.clone();
-        req.auth_token = target.http_auth_token.clone();
-        req.handshake_timeout_ms = Some(15_000);
+        let req = ProbeRequest {
+            target_alias: None,
+            transport: Some(TargetTransportKind::Http),
+            url: Some(url.clone()),
+            headers: target.http_headers.clone(),
+            auth_token: target.http_auth_token.clone(),
+            handshake_timeout_ms: Some(15_000),
+            ..ProbeRequest::default()
+        };
         let outcome = self.svc.probe(req).await;
         Ok(Some(match outcome {
             Ok(res) => CaseResult {
                 name: "probe_http".into(),
-                passed: res.ok,
+                passed: probe_identified(&res),
                 duration_ms: timer.elapsed().as_millis() as u64,
                 detail: Some(json!({
                     "url": url,
+                    "server_name": res.server_name,
+                    "title": res.title,
+                    "version": res.version,
+                    "capabilities": res.capabilities,
                     "latency_ms": res.latency_ms,
-                    "error": res.error,
+                    "error": probe_error(&res),
                 })),
+                skipped: None,
+                transport: None,
             },
             Err(err) => CaseResult {
                 name: "probe_http".into(),
                 passed: false,
                 duration_ms: timer.elapsed().as_millis() as u64,
                 detail: Some(json!({"error": err.to_string()})),
+                skipped: None,
+                transport: None,
             },
         }))
     }
@@ -688,15 +1198,27 @@ impl ComplianceSuite {
     async fn missing_command_case(&self) -> Result<CaseResult> {
         let timer = Instant::now();
         let req = ProbeRequest {
+            target_alias: None,
             transport: Some(TargetTransportKind::Stdio),
             command: None,
             args: None,
             env: None,
+            env_clear: false,
+            inherit_basic: false,
             cwd: None,
+            shell: false,
             url: None,
             headers: None,
             auth_token: None,
+            tls: None,
+            proxy: None,
+            socket_path: None,
             handshake_timeout_ms: Some(1000),
+            protocol_version: None,
+            require_tools: false,
+            reuse_session: Some(false),
+            session_id: None,
+            retry: None,
         };
         let probe = self.svc.probe(req).await;
         let (passed, detail) = match probe {
@@ -708,6 +1230,8 @@ impl ComplianceSuite {
             passed,
             duration_ms: timer.elapsed().as_millis() as u64,
             detail: Some(detail),
+            skipped: None,
+            transport: None,
         })
     }
 
@@ -725,15 +1249,122 @@ mod tests {
         let report = ComplianceReport {
             started_at: OffsetDateTime::now_utc().to_string(),
             finished_at: OffsetDateTime::now_utc().to_string(),
-            pass_rate: 0.96,
+            pass_rate: Some(0.96),
             cases: vec![CaseResult {
                 name: "sample".into(),
                 passed: true,
                 duration_ms: 10,
                 detail: None,
+                skipped: None,
+                transport: None,
             }],
+            by_transport: Vec::new(),
+            timed_out: false,
         };
         assert!(report.passed());
         assert!(report.to_markdown().contains("Pass rate"));
     }
+
+    #[test]
+    fn a_fully_skipped_run_has_no_pass_rate() {
+        let report = ComplianceReport {
+            started_at: OffsetDateTime::now_utc().to_string(),
+            finished_at: OffsetDateTime::now_utc().to_string(),
+            pass_rate: None,
+            cases: vec![CaseResult::skipped("probe_http", "no http_url provided")],
+            by_transport: Vec::new(),
+            timed_out: false,
+        };
+        assert!(report.passed());
+        assert!(report.to_markdown().contains("Pass rate: n/a"));
+        assert!(report.history_entry(&ComplianceTarget::default()).is_none());
+    }
+
+    #[tokio::test]
+    async fn absent_targets_reported_as_skipped_when_requested() -> Result<()> {
+        let target = ComplianceTarget::default();
+        let omitted = ComplianceSuite::new().run(target.clone()).await?;
+        assert!(omitted.cases.iter().all(|case| !case.is_skipped()));
+
+        let report = ComplianceSuite::new()
+            .with_include_skipped(true)
+            .run(target)
+            .await?;
+        let http = report
+            .cases
+            .iter()
+            .find(|case| case.name == "probe_http")
+            .expect("probe_http listed");
+        assert_eq!(http.skipped.as_deref(), Some("no http_url provided"));
+        assert_eq!(report.cases.iter().filter(|c| !c.is_skipped()).count(), 1);
+        assert_eq!(report.pass_rate, Some(1.0));
+        let md = report.to_markdown();
+        assert!(md.contains("| probe_http | ⏭️ skipped | 0 | no http_url provided |"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn progress_reports_cases_in_completion_order() -> Result<()> {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let report = ComplianceSuite::new()
+            .with_include_skipped(true)
+            .with_progress(Arc::new(move |case: &CaseResult| {
+                sink.lock().unwrap().push(case.name.clone());
+            }))
+            .run(ComplianceTarget::default())
+            .await?;
+        let names: Vec<String> = report.cases.iter().map(|c| c.name.clone()).collect();
+        assert_eq!(*seen.lock().unwrap(), names);
+        Ok(())
+    }
+
+    fn case(name: &str, passed: bool, duration_ms: u64) -> CaseResult {
+        CaseResult {
+            name: name.into(),
+            passed,
+            duration_ms,
+            detail: None,
+            skipped: None,
+            transport: planned_transport(name),
+        }
+    }
+
+    #[test]
+    fn transport_stats_group_mixed_cases() {
+        let cases = vec![
+            case("probe_stdio", true, 10),
+            case("list_tools", true, 30),
+            case("negative_missing_command", true, 2),
+            case("list_tools_http", true, 120),
+            case("call_help_http", false, 900),
+            case("probe_http", true, 200),
+            CaseResult {
+                transport: planned_transport("probe_sse"),
+                ..CaseResult::skipped("probe_sse", "no sse_url provided")
+            },
+        ];
+        let stats = TransportStats::from_cases(&cases);
+        assert_eq!(
+            stats,
+            vec![
+                TransportStats {
+                    transport: TargetTransportKind::Stdio,
+                    cases: 3,
+                    passed: 3,
+                    p50_ms: 10,
+                    p95_ms: 30,
+                    max_ms: 30,
+                },
+                TransportStats {
+                    transport: TargetTransportKind::Http,
+                    cases: 3,
+                    passed: 2,
+                    p50_ms: 200,
+                    p95_ms: 900,
+                    max_ms: 900,
+                },
+            ]
+        );
+    }
 }
@@ -0,0 +1,235 @@
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, PaginatedRequestParam, Tool},
+    service::{RunningService, ServiceError},
+};
+use serde::Serialize;
+use serde_json::json;
+use tokio::time::timeout;
+
+use crate::{
+    app::{compliance::CaseResult, inspector_service::InspectorService},
+    infra::metrics::PendingGaugeGuard,
+    shared::types::{ProbeRequest, TargetTransportKind},
+};
+
+/// Upper bound on `tools/list` pages followed before pagination is declared non-terminating.
+const MAX_TOOL_PAGES: usize = 50;
+/// Tool name no real server should expose, used to exercise the unknown-tool path.
+const MISSING_TOOL_NAME: &str = "__inspector_conformance_missing__";
+
+/// Pass/fail checklist of protocol behaviours a well-behaved MCP server should show.
+#[derive(Clone, Debug, Serialize)]
+pub struct ConformanceReport {
+    pub transport: TargetTransportKind,
+    /// True when every check that ran passed; skipped checks do not count.
+    pub passed: bool,
+    pub checks: Vec<CaseResult>,
+}
+
+/// Run the conformance checklist against one session to the target.
+pub async fn run(svc: &InspectorService, req: ProbeRequest) -> Result<ConformanceReport> {
+    let transport = req.transport.unwrap_or(TargetTransportKind::Stdio);
+    let _pending = PendingGaugeGuard::new();
+    let limit = svc.request_timeout(&req);
+    let (client, session) = svc.connect(&req).await?;
+    session.record_call();
+
+    let (pagination, tools) = list_pages(&client, limit).await;
+    let checks: Vec<CaseResult> = vec![
+        advertises_tools(&client, tools.as_deref()),
+        pagination,
+        help_structured(&client, tools.as_deref(), limit).await,
+        unknown_tool(&client, limit).await,
+    ]
+    .into_iter()
+    .map(|mut case| {
+        case.transport = Some(transport);
+        case
+    })
+    .collect();
+    let passed = checks
+        .iter()
+        .filter(|case| !case.is_skipped())
+        .all(|case| case.passed);
+    Ok(ConformanceReport {
+        transport,
+        passed,
+        checks,
+    })
+}
+
+fn check(name: &str, passed: bool, started: Instant, detail: serde_json::Value) -> CaseResult {
+    CaseResult {
+        name: name.into(),
+        passed,
+        duration_ms: started.elapsed().as_millis() as u64,
+        detail: Some(detail),
+        skipped: None,
+        transport: None,
+    }
+}
+
+fn timed_out(limit: Duration) -> String {
+    format!("no response within {}ms", limit.as_millis())
+}
+
+fn advertises_tools(client: &RunningService<RoleClient, ()>, tools: Option<&[Tool]>) -> CaseResult {
+    let started = Instant::now();
+    let capability = client
+        .peer_info()
+        .is_some_and(|info| info.capabilities.tools.is_some());
+    let count = tools.map_or(0, <[Tool]>::len);
+    check(
+        "advertises_tools",
+        capability && count > 0,
+        started,
+        json!({"tools_capability": capability, "tool_count": count}),
+    )
+}
+
+/// Follow `next_cursor` until the list completes; returns the check and the collected tools.
+/// Each page must arrive within `limit`.
+async fn list_pages(
+    client: &RunningService<RoleClient, ()>,
+    limit: Duration,
+) -> (CaseResult, Option<Vec<Tool>>) {
+    let started = Instant::now();
+    let mut tools = Vec::new();
+    let mut seen = HashSet::new();
+    let mut duplicates = Vec::new();
+    let mut cursor = None;
+    for page in 1..=MAX_TOOL_PAGES {
+        let page_result = timeout(
+            limit,
+            client.list_tools(Some(PaginatedRequestParam { cursor })),
+        )
+        .await;
+        let result = match page_result {
+            Ok(Ok(result)) => result,
+            failed => {
+                let error = match failed {
+                    Ok(Err(err)) => err.to_string(),
+                    _ => timed_out(limit),
+                };
+                let case = check(
+                    "list_tools_complete",
+                    false,
+                    started,
+                    json!({"error": error, "pages": page - 1}),
+                );
+                return (case, None);
+            }
+        };
+        for tool in result.tools {
+            if !seen.insert(tool.name.to_string()) {
+                duplicates.push(tool.name.to_string());
+            }
+            tools.push(tool);
+        }
+        cursor = result.next_cursor;
+        if cursor.is_none() {
+            let case = check(
+                "list_tools_complete",
+                duplicates.is_empty(),
+                started,
+                json!({"pages": page, "tool_count": tools.len(), "duplicates": duplicates}),
+            );
+            return (case, Some(tools));
+        }
+    }
+    let case = check(
+        "list_tools_complete",
+        false,
+        started,
+        json!({
+            "error": format!("pagination did not finish within {MAX_TOOL_PAGES} pages"),
+            "tool_count": tools.len(),
+        }),
+    );
+    (case, Some(tools))
+}
+
+async fn help_structured(
+    client: &RunningService<RoleClient, ()>,
+    tools: Option<&[Tool]>,
+    limit: Duration,
+) -> CaseResult {
+    if !tools.is_some_and(|tools| tools.iter().any(|tool| tool.name == "help")) {
+        return CaseResult::skipped("help_structured", "target has no help tool");
+    }
+    let started = Instant::now();
+    let outcome = timeout(
+        limit,
+        client.call_tool(CallToolRequestParam {
+            name: "help".into(),
+            arguments: Some(Default::default()),
+        }),
+    )
+    .await;
+    match outcome {
+        Ok(Ok(result)) => {
+            let is_error = result.is_error.unwrap_or(false);
+            let structured = result.structured_content.is_some();
+            check(
+                "help_structured",
+                structured && !is_error,
+                started,
+                json!({"structured_content": structured, "is_error": is_error}),
+            )
+        }
+        Ok(Err(err)) => check(
+            "help_structured",
+            false,
+            started,
+            json!({"error": err.to_string()}),
+        ),
+        Err(_) => check(
+            "help_structured",
+            false,
+            started,
+            json!({"error": timed_out(limit)}),
+        ),
+    }
+}
+
+/// An unknown tool must fail as an error (JSON-RPC or `is_error`) and leave the session usable.
+async fn unknown_tool(client: &RunningService<RoleClient, ()>, limit: Duration) -> CaseResult {
+    let started = Instant::now();
+    let outcome = timeout(
+        limit,
+        client.call_tool(CallToolRequestParam {
+            name: MISSING_TOOL_NAME.into(),
+            arguments: None,
+        }),
+    )
+    .await;
+    let mode = match &outcome {
+        Ok(Err(ServiceError::McpError(_))) => Some("jsonrpc_error"),
+        Ok(Ok(result)) if result.is_error.unwrap_or(false) => Some("is_error"),
+        _ => None,
+    };
+    let responsive = matches!(timeout(limit, client.list_tools(None)).await, Ok(Ok(_)));
+    let mut detail = json!({"mode": mode, "responsive_after": responsive});
+    match outcome {
+        Ok(Ok(result)) if mode.is_none() => {
+            detail["error"] = json!("unknown tool call reported success");
+            detail["result"] = serde_json::to_value(result).unwrap_or_default();
+        }
+        Ok(Err(err)) if mode.is_none() => detail["error"] = json!(err.to_string()),
+        Err(_) => detail["error"] = json!(timed_out(limit)),
+        _ => {}
+    }
+    check(
+        "unknown_tool_errors",
+        mode.is_some() && responsive,
+        started,
+        detail,
+    )
+}
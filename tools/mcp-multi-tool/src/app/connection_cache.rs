@@ -0,0 +1,164 @@
+use parking_lot::Mutex;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Idle downstream sessions kept for reuse, keyed by a normalized target.
+///
+/// A session is checked out for exclusive use and checked back in when the operation
+/// succeeds, so concurrent operations on one target never share a session; they open
+/// their own and the extras are evicted past `max_size`.
+#[derive(Debug)]
+pub struct ConnectionCache<C> {
+    idle_ttl: Duration,
+    max_size: usize,
+    idle: Mutex<VecDeque<IdleEntry<C>>>,
+}
+
+#[derive(Debug)]
+struct IdleEntry<C> {
+    key: String,
+    value: C,
+    since: Instant,
+}
+
+impl<C> Default for ConnectionCache<C> {
+    fn default() -> Self {
+        Self::new(Duration::ZERO, 0)
+    }
+}
+
+impl<C> ConnectionCache<C> {
+    /// Keep at most `max_size` idle sessions, each for at most `idle_ttl`; zero disables reuse.
+    pub fn new(idle_ttl: Duration, max_size: usize) -> Self {
+        Self {
+            idle_ttl,
+            max_size,
+            idle: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.max_size > 0 && self.idle_ttl > Duration::ZERO
+    }
+
+    /// Take the most recently used idle session for `key` that `usable` accepts. Expired
+    /// and unusable sessions are dropped on the way.
+    pub fn checkout(&self, key: &str, mut usable: impl FnMut(&C) -> bool) -> Option<C> {
+        let mut dropped = self.sweep_at(Instant::now());
+        let found = {
+            let mut idle = self.idle.lock();
+            let mut found = None;
+            while let Some(pos) = idle.iter().rposition(|entry| entry.key == key) {
+                let entry = idle.remove(pos).expect("position is in range");
+                if usable(&entry.value) {
+                    found = Some(entry.value);
+                    break;
+                }
+                dropped.push(entry.value);
+            }
+            found
+        };
+        drop(dropped);
+        found
+    }
+
+    /// Return `value` for reuse under `key`, evicting the longest-idle session past `max_size`.
+    pub fn checkin(&self, key: String, value: C) {
+        if !self.enabled() {
+            return;
+        }
+        let evicted = {
+            let mut idle = self.idle.lock();
+            idle.push_back(IdleEntry {
+                key,
+                value,
+                since: Instant::now(),
+            });
+            let excess = idle.len().saturating_sub(self.max_size);
+            idle.drain(..excess).collect::<Vec<_>>()
+        };
+        drop(evicted);
+    }
+
+    /// Drop sessions idle for longer than the TTL.
+    pub fn sweep(&self) {
+        drop(self.sweep_at(Instant::now()));
+    }
+
+    fn sweep_at(&self, now: Instant) -> Vec<C> {
+        let mut idle = self.idle.lock();
+        let mut expired = Vec::new();
+        while idle
+            .front()
+            .is_some_and(|entry| now.duration_since(entry.since) > self.idle_ttl)
+        {
+            expired.extend(idle.pop_front().map(|entry| entry.value));
+        }
+        expired
+    }
+
+    pub fn len(&self) -> usize {
+        self.idle.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(max_size: usize) -> ConnectionCache<u32> {
+        ConnectionCache::new(Duration::from_secs(60), max_size)
+    }
+
+    #[test]
+    fn checkout_takes_a_session_for_exclusive_use() {
+        let cache = cache(4);
+        assert_eq!(cache.checkout("a", |_| true), None);
+        cache.checkin("a".into(), 1);
+        cache.checkin("b".into(), 2);
+        assert_eq!(cache.checkout("a", |_| true), Some(1));
+        assert_eq!(cache.checkout("a", |_| true), None);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn unusable_sessions_are_evicted_on_checkout() {
+        let cache = cache(4);
+        cache.checkin("a".into(), 1);
+        cache.checkin("a".into(), 2);
+        assert_eq!(cache.checkout("a", |value| *value == 1), Some(1));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn longest_idle_session_is_evicted_past_max_size() {
+        let cache = cache(2);
+        cache.checkin("a".into(), 1);
+        cache.checkin("b".into(), 2);
+        cache.checkin("c".into(), 3);
+        assert_eq!(cache.checkout("a", |_| true), None);
+        assert_eq!(cache.checkout("c", |_| true), Some(3));
+    }
+
+    #[test]
+    fn expired_sessions_are_swept() {
+        let cache = ConnectionCache::new(Duration::from_millis(1), 4);
+        cache.checkin("a".into(), 1);
+        std::thread::sleep(Duration::from_millis(5));
+        cache.sweep();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn disabled_cache_keeps_nothing() {
+        let cache = cache(0);
+        cache.checkin("a".into(), 1);
+        assert!(cache.is_empty());
+    }
+}
@@ -12,6 +12,9 @@ pub struct ErrorBudgetParams {
     pub minimum_requests: usize,
     pub sample_window: Duration,
     pub freeze_duration: Duration,
+    /// After construction, observations are recorded but never trigger a freeze for this long;
+    /// once it ends they are dropped, so the window only judges post-warmup outcomes.
+    pub warmup: Duration,
 }
 
 impl ErrorBudgetParams {
@@ -22,6 +25,7 @@ impl ErrorBudgetParams {
             minimum_requests: 0,
             sample_window: Duration::from_secs(0),
             freeze_duration: Duration::from_secs(0),
+            warmup: Duration::from_secs(0),
         }
     }
 }
@@ -33,10 +37,29 @@ pub struct FreezeReport {
     pub sample_size: usize,
 }
 
-#[derive(Debug)]
-struct Observation {
-    at: SystemTime,
-    success: bool,
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThawReport {
+    pub success_rate: f64,
+    pub sample_size: usize,
+}
+
+/// Most observations [`ErrorBudget::window_snapshot`] returns; older ones are left out.
+pub const MAX_WINDOW_SNAPSHOT: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    pub at: SystemTime,
+    pub success: bool,
+}
+
+/// The observations inside the sample window, oldest first, for explaining a freeze.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowSnapshot {
+    pub observations: Vec<Observation>,
+    /// Observations in the window, including any left out of `observations`.
+    pub sample_size: usize,
+    pub success_rate: f64,
+    pub frozen_until: Option<SystemTime>,
 }
 
 #[derive(Debug, Default)]
@@ -49,6 +72,7 @@ struct ErrorBudgetState {
 pub struct ErrorBudget {
     params: ErrorBudgetParams,
     state: Mutex<ErrorBudgetState>,
+    warm_until: Option<SystemTime>,
 }
 
 type LockObserver = fn(&'static str, Duration);
@@ -69,7 +93,7 @@ pub fn configure_lock_observer(observer: LockObserver) {
 pub enum RecordOutcome {
     None,
     FreezeTriggered(FreezeReport),
-    FreezeCleared,
+    FreezeCleared(ThawReport),
 }
 
 impl ErrorBudget {
@@ -83,20 +107,30 @@ impl ErrorBudget {
         Self {
             params,
             state: Mutex::new(ErrorBudgetState::default()),
+            warm_until: None,
         }
+        .with_started_at(SystemTime::now())
+    }
+
+    /// Anchor the warmup window at `started_at` instead of construction time.
+    pub fn with_started_at(mut self, started_at: SystemTime) -> Self {
+        self.warm_until =
+            (self.params.warmup > Duration::ZERO).then(|| started_at + self.params.warmup);
+        self
     }
 
     pub fn disabled() -> Self {
         Self::new(ErrorBudgetParams::disabled())
     }
 
-    pub fn admit_now(&self) -> Result<bool, FreezeReport> {
+    pub fn admit_now(&self) -> Result<Option<ThawReport>, FreezeReport> {
         self.admit(SystemTime::now())
     }
 
-    pub fn admit(&self, now: SystemTime) -> Result<bool, FreezeReport> {
+    /// Returns `Ok(Some(_))` when this call lifted an expired freeze.
+    pub fn admit(&self, now: SystemTime) -> Result<Option<ThawReport>, FreezeReport> {
         if !self.params.enabled {
-            return Ok(false);
+            return Ok(None);
         }
         let wait = Instant::now();
         let mut state = self.state.lock();
@@ -112,9 +146,13 @@ impl ErrorBudget {
                 });
             }
             state.frozen_until = None;
-            return Ok(true);
+            let (success_rate, sample_size) = self.current_success_rate(&state);
+            return Ok(Some(ThawReport {
+                success_rate,
+                sample_size,
+            }));
         }
-        Ok(false)
+        Ok(None)
     }
 
     pub fn record_success_now(&self) -> RecordOutcome {
@@ -135,11 +173,11 @@ impl ErrorBudget {
         self.purge_old(now, &mut state);
 
         let mut thawed = false;
-        if let Some(until) = state.frozen_until {
-            if now >= until {
-                state.frozen_until = None;
-                thawed = true;
-            }
+        if let Some(until) = state.frozen_until
+            && now >= until
+        {
+            state.frozen_until = None;
+            thawed = true;
         }
 
         state
@@ -147,10 +185,14 @@ impl ErrorBudget {
             .push_back(Observation { at: now, success });
 
         if thawed {
-            return RecordOutcome::FreezeCleared;
+            let (success_rate, sample_size) = self.current_success_rate(&state);
+            return RecordOutcome::FreezeCleared(ThawReport {
+                success_rate,
+                sample_size,
+            });
         }
 
-        if state.frozen_until.is_some() {
+        if state.frozen_until.is_some() || self.warm_until.is_some_and(|until| now < until) {
             return RecordOutcome::None;
         }
 
@@ -170,12 +212,33 @@ impl ErrorBudget {
         }
     }
 
+    pub fn window_snapshot(&self) -> WindowSnapshot {
+        self.window_snapshot_at(SystemTime::now())
+    }
+
+    /// Observations still inside the sample window at `now`, capped to the newest
+    /// [`MAX_WINDOW_SNAPSHOT`].
+    pub fn window_snapshot_at(&self, now: SystemTime) -> WindowSnapshot {
+        let wait = Instant::now();
+        let mut state = self.state.lock();
+        record_lock_wait("error_budget_state", wait.elapsed());
+        self.purge_old(now, &mut state);
+        let (success_rate, sample_size) = self.current_success_rate(&state);
+        let skip = sample_size.saturating_sub(MAX_WINDOW_SNAPSHOT);
+        WindowSnapshot {
+            observations: state.observations.iter().skip(skip).cloned().collect(),
+            sample_size,
+            success_rate,
+            frozen_until: state.frozen_until.filter(|until| now < *until),
+        }
+    }
+
     fn purge_old(&self, now: SystemTime, state: &mut ErrorBudgetState) {
         let window = self.params.sample_window;
+        let warmed = self.warm_until.filter(|until| now >= *until);
         while let Some(front) = state.observations.front() {
-            if now
-                .duration_since(front.at)
-                .map_or(false, |age| age > window)
+            if now.duration_since(front.at).is_ok_and(|age| age > window)
+                || warmed.is_some_and(|until| front.at < until)
             {
                 state.observations.pop_front();
             } else {
@@ -206,6 +269,7 @@ mod tests {
             minimum_requests: 3,
             sample_window: Duration::from_secs(120),
             freeze_duration: Duration::from_secs(30),
+            warmup: Duration::ZERO,
         }
     }
 
@@ -216,11 +280,11 @@ mod tests {
     #[test]
     fn success_only_never_freezes() {
         let budget = ErrorBudget::new(params());
-        assert_eq!(budget.admit(ts(0)), Ok(false));
+        assert_eq!(budget.admit(ts(0)), Ok(None));
         assert_eq!(budget.record(true, ts(1)), RecordOutcome::None);
         assert_eq!(budget.record(true, ts(2)), RecordOutcome::None);
         assert_eq!(budget.record(true, ts(3)), RecordOutcome::None);
-        assert_eq!(budget.admit(ts(4)), Ok(false));
+        assert_eq!(budget.admit(ts(4)), Ok(None));
     }
 
     #[test]
@@ -251,11 +315,93 @@ mod tests {
             RecordOutcome::FreezeTriggered(_)
         ));
         assert!(budget.admit(ts(4)).is_err());
-        assert_eq!(budget.admit(ts(20)), Ok(true));
+        let thaw = budget.admit(ts(20)).unwrap().expect("freeze lifted");
+        assert_eq!(thaw.sample_size, 3);
         match budget.record(true, ts(21)) {
             RecordOutcome::FreezeTriggered(_)
-            | RecordOutcome::FreezeCleared
+            | RecordOutcome::FreezeCleared(_)
             | RecordOutcome::None => {}
         }
     }
+
+    #[test]
+    fn window_snapshot_reflects_recorded_observations() {
+        let budget = ErrorBudget::new(params());
+        budget.record(true, ts(1));
+        budget.record(false, ts(2));
+        budget.record(false, ts(3));
+        budget.record(false, ts(4));
+
+        let snapshot = budget.window_snapshot_at(ts(5));
+        let recorded: Vec<_> = snapshot
+            .observations
+            .iter()
+            .map(|obs| (obs.at, obs.success))
+            .collect();
+        assert_eq!(
+            recorded,
+            vec![
+                (ts(1), true),
+                (ts(2), false),
+                (ts(3), false),
+                (ts(4), false)
+            ]
+        );
+        assert_eq!(snapshot.sample_size, 4);
+        assert_eq!(snapshot.success_rate, 0.25);
+        assert_eq!(snapshot.frozen_until, Some(ts(3) + Duration::from_secs(30)));
+
+        let later = budget.window_snapshot_at(ts(124));
+        assert_eq!(later.observations.len(), 1);
+        assert_eq!(later.frozen_until, None);
+    }
+
+    #[test]
+    fn window_snapshot_is_bounded() {
+        let budget = ErrorBudget::new(ErrorBudgetParams {
+            minimum_requests: usize::MAX,
+            ..params()
+        });
+        for i in 0..(MAX_WINDOW_SNAPSHOT as u64 + 10) {
+            budget.record(true, ts(i / 100));
+        }
+        let snapshot = budget.window_snapshot_at(ts(100));
+        assert_eq!(snapshot.sample_size, MAX_WINDOW_SNAPSHOT + 10);
+        assert_eq!(snapshot.observations.len(), MAX_WINDOW_SNAPSHOT);
+    }
+
+    #[test]
+    fn warmup_collects_but_never_freezes() {
+        let mut params = params();
+        params.warmup = Duration::from_secs(60);
+        let budget = ErrorBudget::new(params).with_started_at(ts(0));
+        for second in 1..=5 {
+            assert_eq!(budget.record(false, ts(second)), RecordOutcome::None);
+        }
+        assert_eq!(budget.admit(ts(59)), Ok(None));
+        assert_eq!(budget.window_snapshot_at(ts(59)).sample_size, 5);
+        assert_eq!(budget.record(false, ts(60)), RecordOutcome::None);
+        assert_eq!(budget.record(false, ts(61)), RecordOutcome::None);
+        match budget.record(false, ts(62)) {
+            RecordOutcome::FreezeTriggered(freeze) => assert_eq!(freeze.sample_size, 3),
+            other => panic!("expected freeze after warmup, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn warmup_failures_leave_the_window_when_warmup_ends() {
+        let mut params = params();
+        params.warmup = Duration::from_secs(60);
+        let budget = ErrorBudget::new(params).with_started_at(ts(0));
+        for second in 1..=5 {
+            budget.record(false, ts(second));
+        }
+        assert_eq!(budget.window_snapshot_at(ts(60)).sample_size, 0);
+        for second in 60..63 {
+            assert_eq!(budget.record(true, ts(second)), RecordOutcome::None);
+        }
+        let snapshot = budget.window_snapshot_at(ts(63));
+        assert_eq!(snapshot.sample_size, 3);
+        assert_eq!(snapshot.success_rate, 1.0);
+    }
 }
@@ -1,112 +1,1403 @@
 use anyhow::{Context, Result};
 use futures::StreamExt;
+use rand::{SeedableRng, rngs::StdRng};
 use rmcp::{
     ClientHandler, RoleClient, ServiceExt,
     handler::client::progress::ProgressDispatcher,
     model::*,
-    service::PeerRequestOptions,
+    service::{ClientInitializeError, Peer, PeerRequestOptions, RunningService, ServiceError},
     transport::{
-        child_process::TokioChildProcess, sse_client::SseClientTransport,
+        sse_client::{SseClientConfig, SseClientTransport, SseTransportError},
         streamable_http_client::StreamableHttpClientTransport,
     },
 };
-use std::{collections::BTreeMap, env, process::Stdio, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap},
+    env,
+    future::Future,
+    process::Stdio,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
 use tokio::{process::Command, time::timeout};
+use tokio_util::sync::CancellationToken;
 
 use crate::{
-    infra::metrics::{LATENCY_HISTO, PendingGaugeGuard},
+    app::{
+        connection_cache::ConnectionCache,
+        session_manager::SessionManager,
+        sessions::{SessionHandle, SessionInfo, SessionRegistry, redact_target},
+        stdio_transport::{
+            ChildGuard, SpawnTimeout, StderrTail, StdioTransport, TargetStderr,
+            UnhandledNotifications, spawn_with_timeout,
+        },
+        target_tls::{self, PeerCertificate},
+    },
+    infra::metrics::{
+        self, CHILDREN_KILLED, COLD_HANDSHAKE_HISTO, CONNECTION_CACHE_HITS,
+        CONNECTION_CACHE_MISSES, LATENCY_HISTO, PendingGaugeGuard, TARGET_RESTARTS,
+    },
     shared::{
+        catalog_diff, cert_info, schema_hash, schema_validation,
         types::{
-            CallRequest, DescribeRequest, HttpTarget, ProbeRequest, ProbeResult, SseTarget,
-            StreamEvent, TargetTransportKind,
+            AmbiguousTarget, AttemptRecord, CallRequest, CatalogDiff, CompareRequest,
+            ConnectResult, DEFAULT_MAX_TOOL_LIST_PAGES, DEFAULT_SPAWN_TIMEOUT_MS,
+            DEFAULT_STREAM_DRAIN_TIMEOUT_MS, DescribeRequest, GetPromptRequest, HandshakeTimeouts,
+            HttpTarget, InitializeReport, PartialStreamPolicy, ProbeCapabilities, ProbeRequest,
+            ProbeResult, PromptListReport, PromptReport, ReadResourceRequest, ResourceListReport,
+            ResourceReadReport, RetryJitter, RetryOn, RetryPolicy, SchemaHashReport,
+            SchemaSizeReport, SetLoggingLevelRequest, SseTarget, StdioTarget, StreamDrainReport,
+            StreamEvent, TargetDescriptor, TargetProxy, TargetTls, TargetTransportKind,
+            ToolSchemaSize, TransportBenchEntry, TransportBenchReport, TransportBenchRequest,
+            UnixTarget,
         },
-        utils::{measure_latency, parse_command},
+        utils::{measure_latency, parse_command, shell_command, truncate_utf8},
     },
 };
 
-#[derive(Clone, Default)]
-pub struct InspectorService;
+/// Cap on `HANDSHAKE_RETRIES`. With the default backoff, five retries already sleep about
+/// 3 s in total, on top of up to six handshake timeouts, before the call gives up.
+pub const MAX_HANDSHAKE_RETRIES: u32 = 5;
+
+/// Longest `instructions` text `initialize` returns before cutting it.
+pub const MAX_INSTRUCTIONS_BYTES: usize = 64 * 1024;
+
+/// Variables a cleared stdio environment keeps when `inherit_basic` is set.
+const BASIC_ENV_VARS: &[&str] = &["PATH", "HOME"];
+
+/// Environment of a stdio target: `vars` on top of the inspector's own, or on top of an
+/// empty environment when `clear` is set.
+#[derive(Debug, Clone, Default)]
+pub struct StdioEnv {
+    pub vars: Option<BTreeMap<String, String>>,
+    pub clear: bool,
+    /// With `clear`, still pass the inspector's `PATH` and `HOME`.
+    pub inherit_basic: bool,
+}
+
+impl StdioEnv {
+    pub fn inherited(vars: Option<BTreeMap<String, String>>) -> Self {
+        Self {
+            vars,
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&StdioTarget> for StdioEnv {
+    fn from(target: &StdioTarget) -> Self {
+        Self {
+            vars: target.env.clone(),
+            clear: target.env_clear,
+            inherit_basic: target.inherit_basic,
+        }
+    }
+}
+
+impl From<&ProbeRequest> for StdioEnv {
+    fn from(req: &ProbeRequest) -> Self {
+        Self {
+            vars: req.env.clone(),
+            clear: req.env_clear,
+            inherit_basic: req.inherit_basic,
+        }
+    }
+}
+
+/// A transport probe's result, with the session it opened when the handshake succeeded.
+type ProbedTarget = (ProbeResult, Option<RunningService<RoleClient, ClientInfo>>);
+
+/// A downstream session as kept by the connection cache.
+type TargetSession = (RunningService<RoleClient, InspectorClient>, SessionHandle);
+
+/// A session opened by `inspector_connect`, with the request that described its target.
+struct ManagedTarget {
+    probe: ProbeRequest,
+    connected: TargetSession,
+}
+
+#[derive(Clone, Default)]
+pub struct InspectorService {
+    handshake_timeouts: HandshakeTimeouts,
+    sessions: Arc<SessionRegistry>,
+    /// Idle sessions reused by list/describe/call; disabled unless configured.
+    connections: Arc<ConnectionCache<TargetSession>>,
+    /// Sessions opened by `inspector_connect`, addressed by `session_id`.
+    managed: Arc<SessionManager<ManagedTarget>>,
+    /// Times each pooled stdio target's child was found exited, by connection key.
+    target_restarts: Arc<parking_lot::Mutex<HashMap<String, u64>>>,
+    stdio_lenient_framing: bool,
+    /// Start every stdio target with a cleared environment, whatever the request says.
+    stdio_env_clear: bool,
+    capture_unknown_notifications: bool,
+    stream_max_duration: Option<Duration>,
+    partial_stream_policy: PartialStreamPolicy,
+    call_timeout: Option<Duration>,
+    /// `None` means `DEFAULT_STREAM_DRAIN_TIMEOUT_MS`.
+    stream_drain_timeout: Option<Duration>,
+    handshake_retries: u32,
+    retry_jitter: RetryJitter,
+    /// Source of retry jitter; `None` draws from the thread RNG.
+    jitter_rng: Option<Arc<parking_lot::Mutex<StdRng>>>,
+    spawn_timeout: Option<Duration>,
+    max_tool_list_pages: Option<u32>,
+    /// Cancels the downstream tool call this service instance is making, if set.
+    cancellation: Option<CancellationToken>,
+    /// Taken off a caller's propagated deadline before it bounds the downstream call.
+    deadline_margin: Duration,
+    /// When the caller of the call this service instance is making stops waiting, if known.
+    caller_deadline: Option<Instant>,
+    /// Oldest protocol version a target may have negotiated for a tool call to go ahead.
+    min_protocol_version: Option<String>,
+    /// Honour `tls.insecure_skip_verify` on targets instead of refusing the request.
+    allow_insecure_target_tls: bool,
+    /// Honour `shell: true` on stdio targets instead of refusing the request.
+    allow_shell_targets: bool,
+    /// Settle call targets with several blocks by the legacy precedence instead of failing.
+    allow_ambiguous_target: bool,
+    /// Proxy for SSE and HTTP targets that do not name their own.
+    target_proxy: Option<TargetProxy>,
+}
+
+#[derive(Clone, Default)]
+struct InspectorClient {
+    progress_handler: ProgressDispatcher,
+    /// Whether the current call is `stream: true`; notifications are only kept then.
+    capturing: Arc<AtomicBool>,
+    /// Progress and logging notifications in arrival order, for `stream: true` calls.
+    notifications: Arc<std::sync::Mutex<Vec<StreamEvent>>>,
+    /// When the first `chunk` event arrived.
+    first_chunk_at: Arc<std::sync::Mutex<Option<Instant>>>,
+}
+
+impl InspectorClient {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn dispatcher(&self) -> ProgressDispatcher {
+        self.progress_handler.clone()
+    }
+
+    fn record(&self, event: StreamEvent) {
+        if !self.capturing.load(Ordering::Relaxed) {
+            return;
+        }
+        if event.event == "chunk"
+            && let Ok(mut first) = self.first_chunk_at.lock()
+        {
+            first.get_or_insert_with(Instant::now);
+        }
+        if let Ok(mut events) = self.notifications.lock() {
+            events.push(event);
+        }
+    }
+
+    /// Forget what an earlier call on a reused session recorded, and keep the next call's
+    /// notifications only when `capture` is set.
+    fn reset(&self, capture: bool) {
+        self.capturing.store(capture, Ordering::Relaxed);
+        self.take_notifications();
+        if let Ok(mut first) = self.first_chunk_at.lock() {
+            *first = None;
+        }
+    }
+
+    fn take_notifications(&self) -> Vec<StreamEvent> {
+        self.notifications
+            .lock()
+            .map(|mut events| std::mem::take(&mut *events))
+            .unwrap_or_default()
+    }
+
+    /// Milliseconds from `sent` to the first `chunk` event, if one arrived.
+    fn first_chunk_ms(&self, sent: Instant) -> Option<u64> {
+        let first = *self.first_chunk_at.lock().ok()?;
+        first.map(|at| at.saturating_duration_since(sent).as_millis() as u64)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CallOutcome {
+    pub result: CallToolResult,
+    pub stream_events: Option<Vec<StreamEvent>>,
+    /// Mismatches against the tool's `output_schema`; empty when valid or not checked.
+    pub output_schema_violations: Vec<String>,
+    /// Handshake attempts made before the call; empty when retries are disabled.
+    pub attempts: Vec<AttemptRecord>,
+    /// Notification methods the target sent that the client does not handle; stdio only,
+    /// and empty unless capture is enabled.
+    pub unknown_notifications: Vec<String>,
+    /// Capabilities the target advertised in this call's handshake.
+    pub target_capabilities: Option<ServerCapabilities>,
+    /// Milliseconds from sending a streaming call to its first `chunk` event.
+    pub first_event_ms: Option<u64>,
+    /// The target's restart count, when its pooled stdio child had exited and was respawned
+    /// for this call.
+    pub target_restarted: Option<u64>,
+    /// Chunks captured and dropped while draining a streaming call.
+    pub stream_drain: Option<StreamDrainReport>,
+}
+
+impl CallOutcome {
+    fn from_result(result: CallToolResult) -> Self {
+        Self {
+            result,
+            stream_events: None,
+            output_schema_violations: Vec::new(),
+            attempts: Vec::new(),
+            unknown_notifications: Vec::new(),
+            target_capabilities: None,
+            first_event_ms: None,
+            target_restarted: None,
+            stream_drain: None,
+        }
+    }
+
+    fn with_stream(result: CallToolResult, events: Vec<StreamEvent>) -> Self {
+        Self {
+            result,
+            stream_events: Some(events),
+            output_schema_violations: Vec::new(),
+            attempts: Vec::new(),
+            unknown_notifications: Vec::new(),
+            target_capabilities: None,
+            first_event_ms: None,
+            target_restarted: None,
+            stream_drain: None,
+        }
+    }
+
+    fn with_output_schema_violations(mut self, violations: Vec<String>) -> Self {
+        self.output_schema_violations = violations;
+        self
+    }
+
+    fn with_attempts(mut self, attempts: Vec<AttemptRecord>) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    fn with_unknown_notifications(mut self, methods: Vec<String>) -> Self {
+        self.unknown_notifications = methods;
+        self
+    }
+
+    fn with_target_capabilities(mut self, capabilities: Option<ServerCapabilities>) -> Self {
+        self.target_capabilities = capabilities;
+        self
+    }
+
+    fn with_target_restarted(mut self, restarts: Option<u64>) -> Self {
+        self.target_restarted = restarts;
+        self
+    }
+
+    fn with_first_event_ms(mut self, first_event_ms: Option<u64>) -> Self {
+        self.first_event_ms = first_event_ms;
+        self
+    }
+
+    fn with_stream_drain(mut self, report: StreamDrainReport) -> Self {
+        self.stream_drain = Some(report);
+        self
+    }
+}
+
+/// Retrying gave up, either out of attempts or on a failure it does not retry; carries the
+/// per-attempt records for the trace.
+#[derive(Debug)]
+pub struct RetriesExhausted {
+    /// What was retried: `handshake`, `call` or `list`.
+    pub phase: &'static str,
+    pub attempts: Vec<AttemptRecord>,
+    last_error: anyhow::Error,
+}
+
+impl std::fmt::Display for RetriesExhausted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let last = self
+            .attempts
+            .last()
+            .and_then(|attempt| attempt.error.as_deref())
+            .unwrap_or("unknown error");
+        write!(
+            f,
+            "{} failed after {} attempts: {last}",
+            self.phase,
+            self.attempts.len()
+        )
+    }
+}
+
+impl std::error::Error for RetriesExhausted {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.last_error.as_ref())
+    }
+}
+
+/// A streaming call ran past its `stream_max_duration_ms`; the request was cancelled.
+/// `events` holds what was streamed, ending with the terminal `error` event.
+#[derive(Debug)]
+pub struct StreamMaxDurationExceeded {
+    pub limit: Duration,
+    pub events: Vec<StreamEvent>,
+}
+
+impl std::fmt::Display for StreamMaxDurationExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "streaming call exceeded its maximum duration of {} ms",
+            self.limit.as_millis()
+        )
+    }
+}
+
+impl std::error::Error for StreamMaxDurationExceeded {}
+
+/// The target did not advertise the capability a request needs.
+#[derive(Debug)]
+pub struct CapabilityNotSupported {
+    pub capability: &'static str,
+}
+
+impl std::fmt::Display for CapabilityNotSupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "target does not advertise the {} capability",
+            self.capability
+        )
+    }
+}
+
+impl std::error::Error for CapabilityNotSupported {}
+
+/// `arguments_json` does not conform to the tool's `inputSchema`, found before calling it.
+#[derive(Debug)]
+pub struct InvalidArguments {
+    pub tool_name: String,
+    pub violations: Vec<schema_validation::Violation>,
+}
+
+impl std::fmt::Display for InvalidArguments {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "arguments for '{}' violate its input schema ({} violation(s))",
+            self.tool_name,
+            self.violations.len()
+        )
+    }
+}
+
+impl std::error::Error for InvalidArguments {}
+
+/// The target did not complete the `initialize` exchange within its handshake timeout.
+#[derive(Debug)]
+pub struct HandshakeTimedOut {
+    pub transport: &'static str,
+}
+
+impl std::fmt::Display for HandshakeTimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} handshake timed out", self.transport)
+    }
+}
+
+impl std::error::Error for HandshakeTimedOut {}
+
+/// The target did not answer the tool call within its `call_timeout_ms`.
+#[derive(Debug)]
+pub struct CallTimedOut {
+    pub limit: Duration,
+}
+
+impl std::fmt::Display for CallTimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "tool call did not complete within {} ms",
+            self.limit.as_millis()
+        )
+    }
+}
+
+impl std::error::Error for CallTimedOut {}
+
+/// The target negotiated a protocol version older than `MIN_PROTOCOL_VERSION`, so the
+/// tool was not invoked.
+#[derive(Debug)]
+pub struct UnsupportedProtocol {
+    pub version: String,
+    pub minimum: String,
+}
+
+impl std::fmt::Display for UnsupportedProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "target speaks protocol version {}, older than the required {}",
+            self.version, self.minimum
+        )
+    }
+}
+
+impl std::error::Error for UnsupportedProtocol {}
+
+/// The call was cancelled by the inspector's client before the target answered; the
+/// target was sent `notifications/cancelled` for the request.
+#[derive(Debug)]
+pub struct CallCancelled;
+
+impl std::fmt::Display for CallCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "tool call cancelled before the target answered")
+    }
+}
+
+impl std::error::Error for CallCancelled {}
+
+#[derive(Debug, Clone, Copy)]
+struct StreamDeadline {
+    at: tokio::time::Instant,
+    limit: Duration,
+}
+
+/// Whichever bound on a tool call's response expires first.
+#[derive(Debug, Clone, Copy)]
+enum ResponseDeadline {
+    Stream(StreamDeadline),
+    Call {
+        at: tokio::time::Instant,
+        limit: Duration,
+    },
+}
+
+impl ResponseDeadline {
+    fn earliest(stream: Option<StreamDeadline>, call_timeout: Option<Duration>) -> Option<Self> {
+        let call = call_timeout.map(|limit| Self::Call {
+            at: tokio::time::Instant::now() + limit,
+            limit,
+        });
+        match (stream.map(Self::Stream), call) {
+            (Some(stream), Some(call)) => Some(if call.at() < stream.at() {
+                call
+            } else {
+                stream
+            }),
+            (stream, call) => stream.or(call),
+        }
+    }
+
+    fn at(&self) -> tokio::time::Instant {
+        match self {
+            Self::Stream(deadline) => deadline.at,
+            Self::Call { at, .. } => *at,
+        }
+    }
+
+    fn limit(&self) -> Duration {
+        match self {
+            Self::Stream(deadline) => deadline.limit,
+            Self::Call { limit, .. } => *limit,
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self {
+            Self::Stream(_) => "stream max duration exceeded",
+            Self::Call { .. } => "call timeout exceeded",
+        }
+    }
+}
+
+/// Why a tool call stopped waiting for its response.
+enum Interruption {
+    Expired(ResponseDeadline),
+    Cancelled,
+}
+
+/// Resolves once `deadline` passes; never resolves without one.
+async fn expiry(deadline: Option<ResponseDeadline>) -> ResponseDeadline {
+    match deadline {
+        Some(deadline) => {
+            tokio::time::sleep_until(deadline.at()).await;
+            deadline
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// The target could not be reached or did not complete the handshake, so the tool was
+/// never invoked. Attached as context that keeps the original message; typed causes
+/// such as [`RetriesExhausted`] stay reachable through `downcast_ref`.
+#[derive(Debug)]
+pub struct TargetUnreachable {
+    message: String,
+}
+
+impl TargetUnreachable {
+    fn wrap(error: anyhow::Error) -> anyhow::Error {
+        let message = error.to_string();
+        error.context(TargetUnreachable { message })
+    }
+}
+
+impl std::fmt::Display for TargetUnreachable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// A `session_id` that names no open `inspector_connect` session: it was disconnected,
+/// closed after idling, or never existed.
+#[derive(Debug)]
+pub struct SessionNotFound {
+    pub session_id: uuid::Uuid,
+}
+
+impl std::fmt::Display for SessionNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no open session with id {}", self.session_id)
+    }
+}
+
+impl std::error::Error for SessionNotFound {}
+
+/// A target `headers` entry that cannot be sent as an HTTP header. Raised before any
+/// connection is attempted, so the call fails instead of silently dropping the header.
+#[derive(Debug)]
+pub struct InvalidHeaders {
+    pub name: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for InvalidHeaders {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid header '{}': {}", self.name, self.reason)
+    }
+}
+
+impl std::error::Error for InvalidHeaders {}
+
+/// Target `tls` settings that cannot be applied: an unreadable or malformed PEM file, a
+/// client certificate without its key, or `insecure_skip_verify` while it is not allowed.
+/// Raised before any connection is attempted.
+#[derive(Debug)]
+pub struct TlsConfigError {
+    pub reason: String,
+}
+
+impl std::fmt::Display for TlsConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid target tls config: {}", self.reason)
+    }
+}
+
+impl std::error::Error for TlsConfigError {}
+
+/// A stdio target set `shell: true` while `ALLOW_SHELL_TARGETS` is off. Raised before
+/// anything is spawned.
+#[derive(Debug)]
+pub struct ShellTargetsDisabled;
+
+impl std::fmt::Display for ShellTargetsDisabled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "shell: true requires ALLOW_SHELL_TARGETS")
+    }
+}
+
+impl std::error::Error for ShellTargetsDisabled {}
+
+impl ClientHandler for InspectorClient {
+    fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: rmcp::service::NotificationContext<RoleClient>,
+    ) -> impl std::future::Future<Output = ()> + Send + '_ {
+        self.record(progress_to_event(params.clone()));
+        self.progress_handler.handle_notification(params)
+    }
+
+    fn on_logging_message(
+        &self,
+        params: LoggingMessageNotificationParam,
+        _context: rmcp::service::NotificationContext<RoleClient>,
+    ) -> impl std::future::Future<Output = ()> + Send + '_ {
+        self.record(log_to_event(params));
+        std::future::ready(())
+    }
+}
+
+impl InspectorService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_handshake_timeouts(mut self, timeouts: HandshakeTimeouts) -> Self {
+        self.handshake_timeouts = timeouts;
+        self
+    }
+
+    /// Skip banner lines a stdio target prints to stdout before its first JSON-RPC frame.
+    pub fn with_stdio_lenient_framing(mut self, lenient: bool) -> Self {
+        self.stdio_lenient_framing = lenient;
+        self
+    }
+
+    /// Clear the environment of every stdio target so only its explicit `env` reaches it.
+    pub fn with_stdio_env_clear(mut self, clear: bool) -> Self {
+        self.stdio_env_clear = clear;
+        self
+    }
+
+    /// Keep up to `max_size` idle sessions for `idle_ttl` and reuse them for later operations
+    /// on the same target; a zero for either disables reuse.
+    pub fn with_connection_cache(mut self, idle_ttl: Duration, max_size: usize) -> Self {
+        self.connections = Arc::new(ConnectionCache::new(idle_ttl, max_size));
+        self
+    }
+
+    /// Let targets set `tls.insecure_skip_verify`; without this such requests fail.
+    pub fn with_allow_insecure_target_tls(mut self, allow: bool) -> Self {
+        self.allow_insecure_target_tls = allow;
+        self
+    }
+
+    /// Let stdio targets set `shell: true`; without this such requests fail.
+    pub fn with_allow_shell_targets(mut self, allow: bool) -> Self {
+        self.allow_shell_targets = allow;
+        self
+    }
+
+    /// `cmd` run through `sh -c`, or [`ShellTargetsDisabled`] unless the operator allows it.
+    pub fn shell_invocation(&self, cmd: &str, args: &[String]) -> Result<(String, Vec<String>)> {
+        if !self.allow_shell_targets {
+            return Err(ShellTargetsDisabled.into());
+        }
+        shell_command(cmd, args)
+    }
+
+    fn resolve_stdio_invocation(&self, req: &ProbeRequest) -> Result<(String, Vec<String>)> {
+        if let Some(cmd) = req.command.as_ref() {
+            if cmd.trim().is_empty() {
+                return Err(anyhow::anyhow!("command is required for stdio transport"));
+            }
+            if req.shell {
+                return self.shell_invocation(cmd, req.args.as_deref().unwrap_or_default());
+            }
+            if let Some(args) = req.args.as_ref() {
+                return Ok((cmd.clone(), args.clone()));
+            }
+            return parse_command(cmd);
+        }
+        if let Some(args) = req.args.as_ref()
+            && !args.is_empty()
+        {
+            return Err(anyhow::anyhow!(
+                "arguments provided without command for stdio transport"
+            ));
+        }
+        let env_cmd = env::var("INSPECTOR_STDIO_CMD").map_err(|_| {
+            anyhow::anyhow!(
+                "command is required for stdio transport; set 'command'/'args' or INSPECTOR_STDIO_CMD"
+            )
+        })?;
+        parse_command(&env_cmd)
+    }
+
+    /// Let a call target set several blocks, picking http, then sse, then unix, then stdio;
+    /// without this such requests fail with [`AmbiguousTarget`].
+    pub fn with_allow_ambiguous_target(mut self, allow: bool) -> Self {
+        self.allow_ambiguous_target = allow;
+        self
+    }
+
+    /// Reject a call whose target or fallbacks set several blocks, unless allowed.
+    pub fn check_target_blocks(&self, request: &CallRequest) -> Result<(), AmbiguousTarget> {
+        if self.allow_ambiguous_target {
+            return Ok(());
+        }
+        request.check_target_blocks()
+    }
+
+    /// Reach SSE and HTTP targets without a `proxy` of their own through `proxy`.
+    pub fn with_target_proxy(mut self, proxy: Option<TargetProxy>) -> Self {
+        self.target_proxy = proxy;
+        self
+    }
+
+    /// Close `inspector_connect` sessions left unused for longer than `timeout`.
+    pub fn with_session_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.managed = Arc::new(SessionManager::new(timeout));
+        self
+    }
+
+    /// Close cached sessions idle past the cache TTL and `inspector_connect` sessions idle
+    /// past the session idle timeout.
+    pub fn sweep_idle_connections(&self) {
+        self.connections.sweep();
+        let closed = self.managed.sweep();
+        if closed > 0 {
+            tracing::info!(closed, "closed idle inspector_connect sessions");
+        }
+    }
+
+    /// An idle cached session under `key`, counted as a cache hit or miss. `None` for `key`
+    /// means the operation opted out of reuse.
+    fn reuse_connection(&self, key: Option<&str>) -> Option<TargetSession> {
+        self.checkout_connection(key).0
+    }
+
+    /// Like [`Self::reuse_connection`], for a stdio target: a cached child that exited while
+    /// idle counts as a restart of the target, whose new count is returned with the session.
+    fn reuse_stdio_connection(&self, key: Option<&str>) -> (Option<TargetSession>, Option<u64>) {
+        let (reused, exited) = self.checkout_connection(key);
+        let restarts = match key {
+            Some(key) if exited => {
+                let mut counts = self.target_restarts.lock();
+                let count = counts.entry(key.to_string()).or_default();
+                *count += 1;
+                TARGET_RESTARTS.inc();
+                tracing::warn!(restarts = *count, "pooled stdio target exited; respawning");
+                Some(*count)
+            }
+            _ => None,
+        };
+        (reused, restarts)
+    }
+
+    /// Checkout under `key`; also tells whether a cached session had its transport closed.
+    fn checkout_connection(&self, key: Option<&str>) -> (Option<TargetSession>, bool) {
+        let Some(key) = key.filter(|_| self.connections.enabled()) else {
+            return (None, false);
+        };
+        let mut exited = false;
+        let reused = self.connections.checkout(key, |(client, _)| {
+            let closed = client.is_transport_closed();
+            exited |= closed;
+            !closed
+        });
+        if reused.is_some() {
+            CONNECTION_CACHE_HITS.inc();
+        } else {
+            CONNECTION_CACHE_MISSES.inc();
+        }
+        (reused, exited)
+    }
+
+    /// Give a freshly spawned stdio session its target's restart count.
+    fn stamp_restarts(&self, key: Option<&str>, session: &SessionHandle) {
+        if let Some(count) = key.and_then(|key| self.target_restarts.lock().get(key).copied()) {
+            session.set_restarts(count);
+        }
+    }
+
+    /// Hand a session back for reuse after a successful operation; otherwise it is closed.
+    fn release_connection(&self, key: Option<String>, connected: TargetSession) {
+        if let Some(key) = key
+            && !connected.0.is_transport_closed()
+        {
+            self.connections.checkin(key, connected);
+        }
+    }
+
+    /// Cache key of a stdio target: everything that shapes the spawned process.
+    fn stdio_connection_key(
+        &self,
+        command: &str,
+        args: &[String],
+        env: &StdioEnv,
+        cwd: Option<&str>,
+    ) -> String {
+        serde_json::json!({
+            "transport": "stdio",
+            "command": command,
+            "args": args,
+            "env": env.vars,
+            "env_clear": env.clear || self.stdio_env_clear,
+            "inherit_basic": env.inherit_basic,
+            "cwd": cwd,
+        })
+        .to_string()
+    }
+
+    fn apply_stdio_env(&self, cmd: &mut Command, env: &StdioEnv) {
+        if env.clear || self.stdio_env_clear {
+            cmd.env_clear();
+            if env.inherit_basic {
+                for name in BASIC_ENV_VARS {
+                    if let Some(value) = std::env::var_os(name) {
+                        cmd.env(name, value);
+                    }
+                }
+            }
+        }
+        if let Some(vars) = &env.vars {
+            for (k, v) in vars {
+                cmd.env(k, v);
+            }
+        }
+    }
+
+    /// Record the methods of notifications a stdio target sends that the client does not
+    /// handle into the `inspector_call` trace; off by default (they are ignored).
+    pub fn with_capture_unknown_notifications(mut self, capture: bool) -> Self {
+        self.capture_unknown_notifications = capture;
+        self
+    }
+
+    /// Default bound on a whole `stream: true` call, from connecting to the final result;
+    /// a request's `stream_max_duration_ms` overrides it. `None` leaves streams unbounded.
+    pub fn with_stream_max_duration(mut self, limit: Option<Duration>) -> Self {
+        self.stream_max_duration = limit;
+        self
+    }
+
+    /// Default bound on the tool invocation after the handshake; a request's
+    /// `call_timeout_ms` overrides it. `None` waits for the response indefinitely.
+    pub fn with_call_timeout(mut self, limit: Option<Duration>) -> Self {
+        self.call_timeout = limit;
+        self
+    }
+
+    /// Refuse tool calls to targets that negotiated a protocol version older than `minimum`
+    /// (`YYYY-MM-DD` versions compare by date).
+    pub fn with_min_protocol_version(mut self, minimum: Option<String>) -> Self {
+        self.min_protocol_version = minimum;
+        self
+    }
+
+    /// Default wait for each trailing progress event after a streaming call's final result;
+    /// a request's `stream_drain_timeout_ms` overrides it.
+    pub fn with_stream_drain_timeout(mut self, limit: Duration) -> Self {
+        self.stream_drain_timeout = Some(limit);
+        self
+    }
+
+    /// Whether a stream that drops before its final result fails or returns its progress so far.
+    pub fn with_partial_stream_policy(mut self, policy: PartialStreamPolicy) -> Self {
+        self.partial_stream_policy = policy;
+        self
+    }
+
+    /// When a streaming call started at `started` must be finished by, if it is bounded.
+    fn stream_deadline(&self, request: &CallRequest, started: Instant) -> Option<StreamDeadline> {
+        if !request.stream {
+            return None;
+        }
+        let limit = request
+            .stream_max_duration_ms
+            .map(Duration::from_millis)
+            .or(self.stream_max_duration)?;
+        Some(StreamDeadline {
+            at: tokio::time::Instant::from_std(started + limit),
+            limit,
+        })
+    }
+
+    /// Retry a failed `inspector_call` handshake up to `retries` times (exponential backoff
+    /// from 100ms, capped at `MAX_HANDSHAKE_RETRIES`); attempts are reported in the trace.
+    /// A request's own `retry` policy takes precedence.
+    pub fn with_handshake_retries(mut self, retries: u32) -> Self {
+        self.handshake_retries = retries.min(MAX_HANDSHAKE_RETRIES);
+        self
+    }
+
+    /// The policy a call's handshake is retried under: the request's own, else the
+    /// configured `HANDSHAKE_RETRIES`.
+    fn handshake_retry_policy(&self, requested: Option<&RetryPolicy>) -> Option<RetryPolicy> {
+        requested.cloned().or_else(|| {
+            (self.handshake_retries > 0).then(|| RetryPolicy {
+                max_attempts: Some(self.handshake_retries + 1),
+                ..RetryPolicy::default()
+            })
+        })
+    }
+
+    /// Run `operation` until it succeeds, fails with something `policy` does not retry, or
+    /// runs out of attempts. Without a policy it runs once and records nothing.
+    async fn retrying<F, Fut, T>(
+        &self,
+        phase: &'static str,
+        policy: Option<&RetryPolicy>,
+        mut operation: F,
+    ) -> Result<(T, Vec<AttemptRecord>)>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let Some(policy) = policy.filter(|policy| policy.max_attempts() > 1) else {
+            return operation().await.map(|value| (value, Vec::new()));
+        };
+        let mut attempts = Vec::new();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let started = Instant::now();
+            let outcome = operation().await;
+            let latency_ms = started.elapsed().as_millis() as u64;
+            match outcome {
+                Ok(value) => {
+                    attempts.push(AttemptRecord {
+                        attempt,
+                        succeeded: true,
+                        latency_ms,
+                        error: None,
+                        failure: None,
+                    });
+                    return Ok((value, attempts));
+                }
+                Err(err) => {
+                    tracing::warn!(phase, attempt, %err, "attempt failed");
+                    let failure = transient_failure(&err);
+                    let retry = failure.is_some_and(|failure| policy.retries(failure));
+                    if !retry && attempt == 1 {
+                        return Err(err);
+                    }
+                    attempts.push(AttemptRecord {
+                        attempt,
+                        succeeded: false,
+                        latency_ms,
+                        error: Some(format!("{err:#}")),
+                        failure,
+                    });
+                    if !retry || attempt >= policy.max_attempts() {
+                        let unreachable = err.downcast_ref::<TargetUnreachable>().is_some();
+                        let exhausted = anyhow::Error::from(RetriesExhausted {
+                            phase,
+                            attempts,
+                            last_error: err,
+                        });
+                        return Err(if unreachable {
+                            TargetUnreachable::wrap(exhausted)
+                        } else {
+                            exhausted
+                        });
+                    }
+                    tokio::time::sleep(self.jittered(policy.backoff(attempt))).await;
+                }
+            }
+        }
+    }
+
+    /// Run one call attempt through `attempt`, which is handed the policy its handshake is
+    /// retried under. With a `retry` policy and an `idempotency_key`, whole failed attempts
+    /// are retried instead, since the call is then safe to repeat.
+    async fn call_with_retries<F, Fut>(
+        &self,
+        request: &CallRequest,
+        mut attempt: F,
+    ) -> Result<CallOutcome>
+    where
+        F: FnMut(Option<RetryPolicy>) -> Fut,
+        Fut: Future<Output = Result<CallOutcome>>,
+    {
+        self.check_target_blocks(request)?;
+        if request.retry.is_none() || request.idempotency_key.is_none() {
+            return attempt(self.handshake_retry_policy(request.retry.as_ref())).await;
+        }
+        let (outcome, attempts) = self
+            .retrying("call", request.retry.as_ref(), || attempt(None))
+            .await?;
+        Ok(outcome.with_attempts(attempts))
+    }
+
+    /// Randomize each retry backoff with `jitter`.
+    pub fn with_retry_jitter(mut self, jitter: RetryJitter) -> Self {
+        self.retry_jitter = jitter;
+        self
+    }
+
+    /// Seed the retry jitter RNG so backoff sequences are reproducible.
+    pub fn with_retry_jitter_seed(mut self, seed: u64) -> Self {
+        self.jitter_rng = Some(Arc::new(parking_lot::Mutex::new(StdRng::seed_from_u64(
+            seed,
+        ))));
+        self
+    }
+
+    fn jittered(&self, backoff: Duration) -> Duration {
+        match &self.jitter_rng {
+            Some(rng) => self.retry_jitter.apply(backoff, &mut *rng.lock()),
+            None => self.retry_jitter.apply(backoff, &mut rand::rng()),
+        }
+    }
+
+    /// Bound on spawning a stdio target; handshake time is budgeted separately.
+    pub fn with_spawn_timeout(mut self, limit: Duration) -> Self {
+        self.spawn_timeout = Some(limit);
+        self
+    }
+
+    /// Cap on `tools/list` pages followed before a listing fails, so a target that keeps
+    /// returning `next_cursor` cannot loop forever.
+    pub fn with_max_tool_list_pages(mut self, max_pages: u32) -> Self {
+        self.max_tool_list_pages = Some(max_pages.max(1));
+        self
+    }
+
+    /// Abort the tool call once `token` is cancelled, forwarding the cancellation to the
+    /// target. Meant for a per-call clone of the service.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Safety margin kept between a caller's propagated deadline and the downstream call's
+    /// own timeout, so the result can still be recorded and returned in time.
+    pub fn with_deadline_margin(mut self, margin: Duration) -> Self {
+        self.deadline_margin = margin;
+        self
+    }
+
+    /// Bound the tool call by a caller that waits at most `budget` from `received`, less the
+    /// deadline margin. Meant for a per-call clone of the service.
+    pub fn with_caller_budget(mut self, budget: Duration, received: Instant) -> Self {
+        self.caller_deadline = Some(received + budget.saturating_sub(self.deadline_margin));
+        self
+    }
+
+    /// `limit` tightened to whatever is left of the caller's deadline.
+    fn within_caller_deadline(&self, limit: Option<Duration>) -> Option<Duration> {
+        let Some(deadline) = self.caller_deadline else {
+            return limit;
+        };
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        Some(limit.map_or(remaining, |limit| limit.min(remaining)))
+    }
+
+    /// Resolves once this call is cancelled; never resolves without a cancellation token.
+    async fn cancelled(&self) {
+        match &self.cancellation {
+            Some(token) => token.cancelled().await,
+            None => std::future::pending().await,
+        }
+    }
 
-#[derive(Clone, Default)]
-struct InspectorClient {
-    progress_handler: ProgressDispatcher,
-}
+    /// Wait for the response to `handle`. When `deadline` passes or the call is cancelled
+    /// first, the target is sent `notifications/cancelled` and the wait is abandoned.
+    async fn await_response(
+        &self,
+        handle: rmcp::service::RequestHandle<RoleClient>,
+        deadline: Option<ResponseDeadline>,
+    ) -> Result<Result<ServerResult, rmcp::ServiceError>, Interruption> {
+        let peer = handle.peer.clone();
+        let request_id = handle.id.clone();
+        let (interruption, reason) = tokio::select! {
+            response = handle.await_response() => return Ok(response),
+            deadline = expiry(deadline) => (Interruption::Expired(deadline), deadline.reason()),
+            _ = self.cancelled() => (Interruption::Cancelled, "cancelled by the inspector client"),
+        };
+        let _ = peer
+            .notify_cancelled(CancelledNotificationParam {
+                request_id,
+                reason: Some(reason.into()),
+            })
+            .await;
+        Err(interruption)
+    }
 
-impl InspectorClient {
-    fn new() -> Self {
-        Self {
-            progress_handler: ProgressDispatcher::new(),
+    /// Every tool `peer` lists, following `next_cursor` up to the configured page cap.
+    async fn list_all_tools(&self, peer: &Peer<RoleClient>) -> Result<Vec<Tool>> {
+        let max_pages = self
+            .max_tool_list_pages
+            .unwrap_or(DEFAULT_MAX_TOOL_LIST_PAGES);
+        let mut tools = Vec::new();
+        let mut cursor = None;
+        for _ in 0..max_pages {
+            let page = peer
+                .list_tools(Some(PaginatedRequestParam { cursor }))
+                .await
+                .context("tools/list")?;
+            tools.extend(page.tools);
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(tools),
+            }
         }
+        Err(anyhow::anyhow!(
+            "tools/list still returned next_cursor after {max_pages} pages"
+        ))
     }
 
-    fn dispatcher(&self) -> ProgressDispatcher {
-        self.progress_handler.clone()
+    async fn stdio_transport(
+        &self,
+        cmd: Command,
+        unhandled: Option<UnhandledNotifications>,
+        stderr: Option<StderrTail>,
+    ) -> Result<StdioTransport> {
+        let lenient = self.stdio_lenient_framing;
+        let limit = self
+            .spawn_timeout
+            .unwrap_or(Duration::from_millis(DEFAULT_SPAWN_TIMEOUT_MS));
+        spawn_with_timeout(limit, move || {
+            StdioTransport::spawn(cmd, lenient, unhandled, stderr)
+        })
+        .await
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct CallOutcome {
-    pub result: CallToolResult,
-    pub stream_events: Option<Vec<StreamEvent>>,
-}
+    /// Downstream sessions currently held open by in-flight operations.
+    pub fn sessions(&self) -> Vec<SessionInfo> {
+        self.sessions.snapshot()
+    }
 
-impl CallOutcome {
-    fn from_result(result: CallToolResult) -> Self {
-        Self {
-            result,
-            stream_events: None,
-        }
+    /// Bound for one request on an open session to `req`'s target: the configured call
+    /// timeout, else the target's handshake timeout, so a silent target cannot stall callers
+    /// that have no per-call limit of their own.
+    pub fn request_timeout(&self, req: &ProbeRequest) -> Duration {
+        self.call_timeout.unwrap_or_else(|| {
+            self.handshake_timeout(
+                req.transport.unwrap_or(TargetTransportKind::Stdio),
+                req.handshake_timeout_ms,
+            )
+        })
     }
 
-    fn with_stream(result: CallToolResult, events: Vec<StreamEvent>) -> Self {
-        Self {
-            result,
-            stream_events: Some(events),
-        }
+    fn handshake_timeout(
+        &self,
+        transport: TargetTransportKind,
+        requested_ms: Option<u64>,
+    ) -> Duration {
+        self.handshake_timeouts.resolve(transport, requested_ms)
     }
-}
 
-impl ClientHandler for InspectorClient {
-    fn on_progress(
+    /// The client an SSE or HTTP target is reached through: its `headers` on every request,
+    /// its `tls` settings and proxy on every connection.
+    fn target_client(
         &self,
-        params: ProgressNotificationParam,
-        _context: rmcp::service::NotificationContext<RoleClient>,
-    ) -> impl std::future::Future<Output = ()> + Send + '_ {
-        self.progress_handler.handle_notification(params)
+        headers: Option<&BTreeMap<String, String>>,
+        tls: Option<&TargetTls>,
+        proxy: Option<&TargetProxy>,
+    ) -> Result<reqwest::Client> {
+        header_client(headers, self.client_builder(tls, proxy, None)?)
     }
-}
 
-impl InspectorService {
-    pub fn new() -> Self {
-        Self
+    fn client_builder(
+        &self,
+        tls: Option<&TargetTls>,
+        proxy: Option<&TargetProxy>,
+        peer_certificate: Option<&PeerCertificate>,
+    ) -> Result<reqwest::ClientBuilder> {
+        let builder =
+            target_tls::client_builder(tls, self.allow_insecure_target_tls, peer_certificate)?;
+        match self.effective_proxy(proxy) {
+            Some(proxy) => proxied_builder(builder, proxy),
+            None => Ok(builder),
+        }
+    }
+
+    /// The target's own proxy, else the `TARGET_PROXY` default.
+    fn effective_proxy<'a>(
+        &'a self,
+        requested: Option<&'a TargetProxy>,
+    ) -> Option<&'a TargetProxy> {
+        requested.or(self.target_proxy.as_ref())
     }
 
     pub async fn probe(&self, req: ProbeRequest) -> Result<ProbeResult> {
-        let transport = req.transport.clone().unwrap_or(TargetTransportKind::Stdio);
-        match transport {
-            TargetTransportKind::Stdio => self.probe_stdio(req).await,
-            TargetTransportKind::Sse => self.probe_sse(req).await,
-            TargetTransportKind::Http => self.probe_http(req).await,
+        let transport = req.transport.unwrap_or(TargetTransportKind::Stdio);
+        let require_tools = req.require_tools;
+        let limit = self.handshake_timeout(transport, req.handshake_timeout_ms);
+        let (mut result, client) = match transport {
+            TargetTransportKind::Stdio => self.probe_stdio(req).await?,
+            TargetTransportKind::Sse => self.probe_sse(req).await?,
+            TargetTransportKind::Http => self.probe_http(req).await?,
+            TargetTransportKind::Unix => self.probe_unix(req).await?,
+        };
+        if let Some(client) = client.filter(|_| require_tools && result.ok) {
+            self.check_required_tools(client.peer(), limit, &mut result)
+                .await;
         }
+        Ok(result)
     }
 
+    /// Every tool the target lists; also records their total schema size per target.
     pub async fn list_tools(&self, req: ProbeRequest) -> Result<Vec<Tool>> {
-        let transport = req.transport.clone().unwrap_or(TargetTransportKind::Stdio);
+        if let Some(session_id) = req.session_id {
+            let managed = self.managed_session(session_id)?;
+            let managed = managed.lock().await;
+            let _pending = PendingGaugeGuard::new();
+            managed.connected.1.record_call();
+            let tools = self.list_all_tools(&managed.connected.0).await?;
+            let target = schema_metric_target(&managed.probe);
+            metrics::set_downstream_tool_schema_bytes(&target, total_schema_bytes(&tools));
+            return Ok(tools);
+        }
+        let target = schema_metric_target(&req);
+        let tools = self.list_target_tools(req).await?;
+        metrics::set_downstream_tool_schema_bytes(&target, total_schema_bytes(&tools));
+        Ok(tools)
+    }
+
+    /// Open a session to the target `req` describes and keep it for later list, describe
+    /// and call requests that pass the returned `session_id`.
+    pub async fn connect_session(&self, req: ProbeRequest) -> Result<ConnectResult> {
+        let _pending = PendingGaugeGuard::new();
+        let connected = match req.transport.unwrap_or(TargetTransportKind::Stdio) {
+            TargetTransportKind::Stdio => {
+                let (command, args) = self.resolve_stdio_invocation(&req)?;
+                let env = StdioEnv::from(&req);
+                let (connected, _, guard) = self
+                    .open_stdio(
+                        command,
+                        args,
+                        &env,
+                        req.cwd.clone(),
+                        req.handshake_timeout_ms,
+                    )
+                    .await?;
+                guard.disarm();
+                connected
+            }
+            TargetTransportKind::Sse => self.open_sse(&build_sse_target(&req)?).await?,
+            TargetTransportKind::Http => self.open_http(&build_http_target(&req)?).await?,
+            TargetTransportKind::Unix => self.open_unix(&build_unix_target(&req)?).await?,
+        };
+        let session_id = connected.1.id();
+        let peer_info = connected.0.peer_info();
+        let result = ConnectResult {
+            session_id,
+            target: redact_target(&probe_descriptor(&req)),
+            server_name: peer_info.map(|info| info.server_info.name.clone()),
+            version: peer_info.map(|info| info.server_info.version.clone()),
+            protocol_version: peer_info.map(|info| info.protocol_version.to_string()),
+        };
+        self.managed.insert(
+            session_id,
+            ManagedTarget {
+                probe: req,
+                connected,
+            },
+        );
+        Ok(result)
+    }
+
+    /// Close a session opened by [`Self::connect_session`]; an operation still running on
+    /// it finishes first.
+    pub fn disconnect_session(&self, session_id: uuid::Uuid) -> Result<()> {
+        if self.managed.remove(&session_id) {
+            Ok(())
+        } else {
+            Err(SessionNotFound { session_id }.into())
+        }
+    }
+
+    fn managed_session(
+        &self,
+        session_id: uuid::Uuid,
+    ) -> Result<Arc<tokio::sync::Mutex<ManagedTarget>>> {
+        self.managed
+            .get(&session_id)
+            .ok_or_else(|| SessionNotFound { session_id }.into())
+    }
+
+    /// Call a tool over an `inspector_connect` session; also returns the session's target.
+    pub async fn call_session(
+        &self,
+        session_id: uuid::Uuid,
+        request: &CallRequest,
+    ) -> Result<(CallOutcome, TargetDescriptor)> {
+        let managed = self.managed_session(session_id)?;
+        let managed = managed.lock().await;
+        let _pending = PendingGaugeGuard::new();
+        let deadline = self.stream_deadline(request, Instant::now());
+        managed.connected.1.record_call();
+        let outcome = self
+            .invoke_call(&managed.connected.0, request, deadline)
+            .await?;
+        Ok((outcome, probe_descriptor(&managed.probe)))
+    }
+
+    async fn list_target_tools(&self, req: ProbeRequest) -> Result<Vec<Tool>> {
+        let (tools, _) = self
+            .retrying("list", req.retry.as_ref(), || {
+                self.list_target_tools_once(&req)
+            })
+            .await?;
+        Ok(tools)
+    }
+
+    async fn list_target_tools_once(&self, req: &ProbeRequest) -> Result<Vec<Tool>> {
+        let transport = req.transport.unwrap_or(TargetTransportKind::Stdio);
         match transport {
             TargetTransportKind::Stdio => {
-                let (command, args) = resolve_stdio_invocation(&req)?;
-                self.list_tools_stdio(command, args, req.env.clone(), req.cwd.clone())
-                    .await
+                let (command, args) = self.resolve_stdio_invocation(req)?;
+                self.list_tools_stdio(
+                    command,
+                    args,
+                    StdioEnv::from(req),
+                    req.cwd.clone(),
+                    req.reuses_session(),
+                    req.handshake_timeout_ms,
+                )
+                .await
             }
             TargetTransportKind::Sse => {
-                let target = build_sse_target(&req)?;
-                self.list_tools_sse(&target).await
+                let target = build_sse_target(req)?;
+                self.list_tools_sse(&target, req.reuses_session()).await
             }
             TargetTransportKind::Http => {
-                let target = build_http_target(&req)?;
-                self.list_tools_http(&target).await
+                let target = build_http_target(req)?;
+                self.list_tools_http(&target, req.reuses_session()).await
             }
+            TargetTransportKind::Unix => {
+                let target = build_unix_target(req)?;
+                self.list_tools_unix(&target, req.reuses_session()).await
+            }
+        }
+    }
+
+    /// List both targets' tools concurrently and diff the catalogs.
+    pub async fn compare(&self, req: CompareRequest) -> Result<CatalogDiff> {
+        let (a, b) = tokio::try_join!(
+            async { self.list_tools(req.target_a).await.context("target_a") },
+            async { self.list_tools(req.target_b).await.context("target_b") },
+        )?;
+        Ok(catalog_diff::diff_catalogs(&a, &b))
+    }
+
+    /// Probe each transport variant of one target concurrently and rank them by handshake
+    /// latency.
+    pub async fn transport_bench(
+        &self,
+        req: TransportBenchRequest,
+    ) -> Result<TransportBenchReport> {
+        let probes: Vec<ProbeRequest> = [
+            req.stdio.as_ref().map(ProbeRequest::from),
+            req.sse.as_ref().map(ProbeRequest::from),
+            req.http.as_ref().map(ProbeRequest::from),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if probes.is_empty() {
+            anyhow::bail!("set at least one of 'stdio', 'sse' or 'http'");
         }
+        let entries = futures::future::join_all(probes.into_iter().map(|probe| async move {
+            let transport = probe_descriptor(&probe).transport;
+            match self.probe(probe).await {
+                Ok(result) => TransportBenchEntry {
+                    transport,
+                    ok: result.ok,
+                    latency_ms: result.latency_ms.filter(|_| result.ok),
+                    error: result.error,
+                },
+                Err(err) => TransportBenchEntry {
+                    transport,
+                    ok: false,
+                    latency_ms: None,
+                    error: Some(format!("{err:#}")),
+                },
+            }
+        }))
+        .await;
+        Ok(TransportBenchReport::ranked(entries))
     }
 
     pub async fn describe(&self, req: DescribeRequest) -> Result<Tool> {
@@ -118,40 +1409,316 @@ impl InspectorService {
             .ok_or_else(|| anyhow::anyhow!("tool '{}' not found", tool_name))
     }
 
-    async fn probe_stdio(&self, req: ProbeRequest) -> Result<ProbeResult> {
+    pub async fn schema_hash(&self, req: DescribeRequest) -> Result<SchemaHashReport> {
+        let tool = self.describe(req).await?;
+        let schema = serde_json::Value::Object(tool.input_schema.as_ref().clone());
+        Ok(SchemaHashReport {
+            tool_name: tool.name.to_string(),
+            algorithm: "sha256".into(),
+            hash: schema_hash::hash(&schema),
+            canonical: schema_hash::canonical_string(&schema),
+        })
+    }
+
+    /// Open a session to the target described by `probe` over its transport.
+    pub(crate) async fn connect(
+        &self,
+        probe: &ProbeRequest,
+    ) -> Result<(RunningService<RoleClient, ()>, SessionHandle)> {
+        let transport = probe.transport.unwrap_or(TargetTransportKind::Stdio);
+        let connected = match transport {
+            TargetTransportKind::Stdio => {
+                let (command, args) = self.resolve_stdio_invocation(probe)?;
+                let mut cmd = Command::new(&command);
+                cmd.args(args);
+                self.apply_stdio_env(&mut cmd, &StdioEnv::from(probe));
+                if let Some(cwd) = &probe.cwd {
+                    cmd.current_dir(cwd);
+                }
+                let handshake_timeout =
+                    self.handshake_timeout(TargetTransportKind::Stdio, probe.handshake_timeout_ms);
+                let transport = self.stdio_transport(cmd, None, None).await?;
+                let guard = transport.guard(CHILDREN_KILLED.clone());
+                let client = cold_handshake(handshake_timeout, ().serve(transport))
+                    .await
+                    .map_err(|_| anyhow::Error::from(HandshakeTimedOut { transport: "stdio" }))??;
+                guard.disarm();
+                (client, self.sessions.open(stdio_descriptor(command)))
+            }
+            TargetTransportKind::Sse => {
+                let target = build_sse_target(probe)?;
+                let handshake_timeout =
+                    self.handshake_timeout(TargetTransportKind::Sse, target.handshake_timeout_ms);
+                let http = self.target_client(
+                    target.headers.as_ref(),
+                    target.tls.as_ref(),
+                    target.proxy.as_ref(),
+                )?;
+                let transport = start_sse(http, &target.url).await?;
+                let client = cold_handshake(handshake_timeout, ().serve(transport))
+                    .await
+                    .map_err(|_| anyhow::Error::from(HandshakeTimedOut { transport: "sse" }))??;
+                let descriptor = network_descriptor("sse", &target.url, target.headers.clone());
+                (client, self.sessions.open(descriptor))
+            }
+            TargetTransportKind::Http => {
+                let target = build_http_target(probe)?;
+                let mut cfg = rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig::with_uri(
+                    target.url.clone(),
+                );
+                if let Some(tok) = &target.auth_token {
+                    cfg = cfg.auth_header(tok);
+                }
+                let handshake_timeout =
+                    self.handshake_timeout(TargetTransportKind::Http, target.handshake_timeout_ms);
+                let http = self.target_client(
+                    target.headers.as_ref(),
+                    target.tls.as_ref(),
+                    target.proxy.as_ref(),
+                )?;
+                let transport = StreamableHttpClientTransport::with_client(http, cfg);
+                let client = cold_handshake(handshake_timeout, ().serve(transport))
+                    .await
+                    .map_err(|_| anyhow::Error::from(HandshakeTimedOut { transport: "http" }))??;
+                let descriptor = network_descriptor("http", &target.url, target.headers.clone());
+                (client, self.sessions.open(descriptor))
+            }
+            TargetTransportKind::Unix => {
+                let target = build_unix_target(probe)?;
+                let handshake_timeout =
+                    self.handshake_timeout(TargetTransportKind::Unix, target.handshake_timeout_ms);
+                let stream = connect_unix(&target.path).await?;
+                let client = cold_handshake(handshake_timeout, ().serve(stream))
+                    .await
+                    .map_err(|_| anyhow::Error::from(HandshakeTimedOut { transport: "unix" }))??;
+                (client, self.sessions.open(unix_descriptor(&target.path)))
+            }
+        };
+        Ok(connected)
+    }
+
+    /// Forward `logging/setLevel` to the target, refusing targets that do not advertise logging.
+    pub async fn set_logging_level(&self, req: SetLoggingLevelRequest) -> Result<LoggingLevel> {
+        let _pending = PendingGaugeGuard::new();
+        let (client, session) = self.connect(&req.probe).await?;
+        let advertises_logging = client
+            .peer_info()
+            .is_some_and(|info| info.capabilities.logging.is_some());
+        if !advertises_logging {
+            anyhow::bail!(
+                "target does not advertise the logging capability; logging/setLevel is unavailable"
+            );
+        }
+        session.record_call();
+        client
+            .set_level(SetLevelRequestParam { level: req.level })
+            .await
+            .context("logging/setLevel")?;
+        Ok(req.level)
+    }
+
+    /// Connect and fail with [`CapabilityNotSupported`] unless `advertised` holds for the
+    /// target's capabilities.
+    async fn connect_requiring(
+        &self,
+        probe: &ProbeRequest,
+        capability: &'static str,
+        advertised: impl Fn(&ServerCapabilities) -> bool,
+    ) -> Result<(RunningService<RoleClient, ()>, SessionHandle)> {
+        let (client, session) = self.connect(probe).await?;
+        if !client
+            .peer_info()
+            .is_some_and(|info| advertised(&info.capabilities))
+        {
+            return Err(CapabilityNotSupported { capability }.into());
+        }
+        session.record_call();
+        Ok((client, session))
+    }
+
+    async fn connect_for_resources(
+        &self,
+        probe: &ProbeRequest,
+    ) -> Result<(RunningService<RoleClient, ()>, SessionHandle)> {
+        self.connect_requiring(probe, "resources", |caps| caps.resources.is_some())
+            .await
+    }
+
+    /// List every resource the target exposes, following `nextCursor` until it runs out.
+    pub async fn list_resources(&self, req: ProbeRequest) -> Result<ResourceListReport> {
+        let _pending = PendingGaugeGuard::new();
+        let (client, _session) = self.connect_for_resources(&req).await?;
+        let resources = client
+            .list_all_resources()
+            .await
+            .context("resources/list")?;
+        Ok(ResourceListReport {
+            count: resources.len(),
+            resources,
+        })
+    }
+
+    pub async fn read_resource(&self, req: ReadResourceRequest) -> Result<ResourceReadReport> {
+        let _pending = PendingGaugeGuard::new();
+        let (client, _session) = self.connect_for_resources(&req.probe).await?;
+        let result = client
+            .read_resource(ReadResourceRequestParam {
+                uri: req.uri.clone(),
+            })
+            .await
+            .context("resources/read")?;
+        Ok(ResourceReadReport {
+            uri: req.uri,
+            contents: result.contents.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// List every prompt the target exposes, following `nextCursor` until it runs out.
+    pub async fn list_prompts(&self, req: &ProbeRequest) -> Result<PromptListReport> {
+        let _pending = PendingGaugeGuard::new();
+        let (client, _session) = self
+            .connect_requiring(req, "prompts", |caps| caps.prompts.is_some())
+            .await?;
+        let prompts = client.list_all_prompts().await.context("prompts/list")?;
+        Ok(PromptListReport {
+            count: prompts.len(),
+            prompts,
+        })
+    }
+
+    pub async fn get_prompt(&self, req: &GetPromptRequest) -> Result<PromptReport> {
+        let _pending = PendingGaugeGuard::new();
+        let (client, _session) = self
+            .connect_requiring(&req.probe, "prompts", |caps| caps.prompts.is_some())
+            .await?;
+        let result = client
+            .get_prompt(GetPromptRequestParam {
+                name: req.prompt_name.clone(),
+                arguments: req.arguments.clone(),
+            })
+            .await
+            .context("prompts/get")?;
+        Ok(PromptReport {
+            prompt_name: req.prompt_name.clone(),
+            description: result.description,
+            messages: result.messages,
+        })
+    }
+
+    /// Handshake with the target and return its full `InitializeResult`.
+    pub async fn initialize(&self, req: ProbeRequest) -> Result<InitializeReport> {
+        let _pending = PendingGaugeGuard::new();
+        let (client, session) = self.connect(&req).await?;
+        session.record_call();
+        let mut result = client
+            .peer_info()
+            .cloned()
+            .context("target completed the handshake without an InitializeResult")?;
+        let instructions_truncated = result
+            .instructions
+            .as_mut()
+            .is_some_and(|text| truncate_utf8(text, MAX_INSTRUCTIONS_BYTES));
+        Ok(InitializeReport {
+            result,
+            instructions_truncated,
+        })
+    }
+
+    pub async fn schema_sizes(&self, req: ProbeRequest) -> Result<SchemaSizeReport> {
+        let tools = self.list_tools(req).await?;
+        let mut sizes = tools
+            .iter()
+            .map(|tool| {
+                let bytes = serde_json::to_vec(tool.input_schema.as_ref())
+                    .map(|encoded| encoded.len())
+                    .unwrap_or(0);
+                ToolSchemaSize {
+                    name: tool.name.to_string(),
+                    bytes,
+                }
+            })
+            .collect::<Vec<_>>();
+        sizes.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+        let total_bytes = sizes.iter().map(|entry| entry.bytes).sum();
+        Ok(SchemaSizeReport {
+            tools: sizes,
+            total_bytes,
+        })
+    }
+
+    /// Fail a successful probe with a reason when the target advertises no tools
+    /// capability, lists no tools, or cannot list them within `limit`.
+    async fn check_required_tools(
+        &self,
+        peer: &Peer<RoleClient>,
+        limit: Duration,
+        result: &mut ProbeResult,
+    ) {
+        let reason = if result.capabilities.is_some_and(|caps| !caps.tools) {
+            Some("target does not advertise the tools capability".to_string())
+        } else {
+            match tokio::time::timeout(limit, self.list_all_tools(peer)).await {
+                Ok(Ok(tools)) if tools.is_empty() => Some("target lists no tools".to_string()),
+                Ok(Ok(_)) => None,
+                Ok(Err(err)) => Some(format!("list tools: {err:#}")),
+                Err(_) => Some(format!(
+                    "list tools timed out after {} ms",
+                    limit.as_millis()
+                )),
+            }
+        };
+        if let Some(reason) = reason {
+            result.ok = false;
+            result.error = Some(format!("require_tools: {reason}"));
+        }
+    }
+
+    async fn probe_stdio(&self, req: ProbeRequest) -> Result<ProbedTarget> {
         let (program, args) = match (&req.command, &req.args) {
+            (Some(cmd), args) if req.shell => {
+                self.shell_invocation(cmd, args.as_deref().unwrap_or_default())?
+            }
             (Some(cmd), Some(args)) if !cmd.is_empty() => (cmd.clone(), args.clone()),
             (Some(cmd), None) => parse_command(cmd)?,
             _ => {
-                return Ok(ProbeResult {
-                    ok: false,
-                    transport: "stdio".into(),
-                    server_name: None,
-                    version: None,
-                    latency_ms: None,
-                    error: Some("missing command for stdio".into()),
-                });
+                return Ok((
+                    ProbeResult {
+                        ok: false,
+                        transport: "stdio".into(),
+                        server_name: None,
+                        title: None,
+                        version: None,
+                        latency_ms: None,
+                        error: Some("missing command for stdio".into()),
+                        capabilities: None,
+                        tls_subject: None,
+                        tls_not_after: None,
+                        protocol_version: None,
+                        proxied: false,
+                    },
+                    None,
+                ));
             }
         };
         let mut cmd = Command::new(program);
         cmd.args(args);
-        if let Some(env) = &req.env {
-            for (k, v) in env {
-                cmd.env(k, v);
-            }
-        }
+        self.apply_stdio_env(&mut cmd, &StdioEnv::from(&req));
         if let Some(cwd) = &req.cwd {
             cmd.current_dir(cwd);
         }
-        cmd.stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::inherit());
+        cmd.stdin(Stdio::piped()).stdout(Stdio::piped());
 
         let _pending = PendingGaugeGuard::new();
-        let transport = TokioChildProcess::new(cmd)?;
-        let handshake_timeout = Duration::from_millis(req.handshake_timeout_ms.unwrap_or(15_000));
-        let (client, latency_ms) = measure_latency(|| async move {
-            let svc = timeout(handshake_timeout, ().serve(transport))
+        let stderr = StderrTail::default();
+        let transport = self
+            .stdio_transport(cmd, None, Some(stderr.clone()))
+            .await?;
+        let guard = transport.guard(CHILDREN_KILLED.clone());
+        let handshake_timeout =
+            self.handshake_timeout(TargetTransportKind::Stdio, req.handshake_timeout_ms);
+        let client_info = probe_client_info(&req)?;
+        let handshake = measure_latency(|| async move {
+            let svc = cold_handshake(handshake_timeout, client_info.serve(transport))
                 .await
                 .map_err(|_| {
                     anyhow::anyhow!(
@@ -162,170 +1729,458 @@ impl InspectorService {
                 .context("spawn stdio target")?;
             Ok::<_, anyhow::Error>(svc)
         })
-        .await?;
-        LATENCY_HISTO.observe(latency_ms as f64);
+        .await;
+        let (client, latency_ms) = match handshake {
+            Ok(connected) => connected,
+            Err(err) => {
+                guard.kill().await;
+                return Err(stderr.attach(err).await);
+            }
+        };
+        guard.disarm();
 
         // get_info may be optional; try list_tools to poke server
+        let server_name = client.peer_info().map(|i| i.server_info.name.clone());
+        let title = client.peer_info().and_then(|i| i.server_info.title.clone());
         let version = client.peer_info().map(|i| i.server_info.version.clone());
+        let capabilities = client
+            .peer_info()
+            .map(|i| ProbeCapabilities::from(&i.capabilities));
+        let protocol_version = client.peer_info().map(|i| i.protocol_version.to_string());
 
-        Ok(ProbeResult {
+        let result = ProbeResult {
             ok: true,
             transport: "stdio".into(),
-            server_name: None,
+            server_name,
+            title,
             version,
             latency_ms: Some(latency_ms),
             error: None,
-        })
+            capabilities,
+            tls_subject: None,
+            tls_not_after: None,
+            protocol_version,
+            proxied: false,
+        };
+        Ok((result, Some(client)))
     }
 
-    async fn probe_sse(&self, req: ProbeRequest) -> Result<ProbeResult> {
+    async fn probe_sse(&self, req: ProbeRequest) -> Result<ProbedTarget> {
         let url = req.url.clone().unwrap_or_default();
         if url.is_empty() {
-            return Ok(ProbeResult {
-                ok: false,
-                transport: "sse".into(),
-                server_name: None,
-                version: None,
-                latency_ms: None,
-                error: Some("missing url".into()),
-            });
+            return Ok((
+                ProbeResult {
+                    ok: false,
+                    transport: "sse".into(),
+                    server_name: None,
+                    title: None,
+                    version: None,
+                    latency_ms: None,
+                    error: Some("missing url".into()),
+                    capabilities: None,
+                    tls_subject: None,
+                    tls_not_after: None,
+                    protocol_version: None,
+                    proxied: false,
+                },
+                None,
+            ));
         }
         // rmcp 0.8.1: the public SSE API cannot pass auth_token to start(); see help limitations
-        let handshake_timeout = Duration::from_millis(req.handshake_timeout_ms.unwrap_or(15_000));
+        let handshake_timeout =
+            self.handshake_timeout(TargetTransportKind::Sse, req.handshake_timeout_ms);
+        let http =
+            self.target_client(req.headers.as_ref(), req.tls.as_ref(), req.proxy.as_ref())?;
+        let proxied = self.effective_proxy(req.proxy.as_ref()).is_some();
+        let _pending = PendingGaugeGuard::new();
+        let transport = start_sse(http, &url).await?;
+        let client_info = probe_client_info(&req)?;
+        let (client, latency_ms) = measure_latency(|| async move {
+            let svc = cold_handshake(handshake_timeout, client_info.serve(transport))
+                .await
+                .map_err(|_| {
+                    anyhow::anyhow!(
+                        "sse handshake timed out after {} ms",
+                        handshake_timeout.as_millis()
+                    )
+                })?
+                .context("connect sse target")?;
+            Ok::<_, anyhow::Error>(svc)
+        })
+        .await?;
+        let server_name = client.peer_info().map(|i| i.server_info.name.clone());
+        let title = client.peer_info().and_then(|i| i.server_info.title.clone());
+        let version = client.peer_info().map(|i| i.server_info.version.clone());
+        let capabilities = client
+            .peer_info()
+            .map(|i| ProbeCapabilities::from(&i.capabilities));
+        let protocol_version = client.peer_info().map(|i| i.protocol_version.to_string());
+        let result = ProbeResult {
+            ok: true,
+            transport: "sse".into(),
+            server_name,
+            title,
+            version,
+            latency_ms: Some(latency_ms),
+            error: None,
+            capabilities,
+            tls_subject: None,
+            tls_not_after: None,
+            protocol_version,
+            proxied,
+        };
+        Ok((result, Some(client)))
+    }
+
+    async fn probe_http(&self, req: ProbeRequest) -> Result<ProbedTarget> {
+        let url = req.url.clone().unwrap_or_default();
+        if url.is_empty() {
+            return Ok((
+                ProbeResult {
+                    ok: false,
+                    transport: "http".into(),
+                    server_name: None,
+                    title: None,
+                    version: None,
+                    latency_ms: None,
+                    error: Some("missing url".into()),
+                    capabilities: None,
+                    tls_subject: None,
+                    tls_not_after: None,
+                    protocol_version: None,
+                    proxied: false,
+                },
+                None,
+            ));
+        }
+        // Allow Bearer tokens for HTTP via the request config
+        let mut cfg =
+            rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig::with_uri(
+                url.clone(),
+            );
+        if let Some(tok) = &req.auth_token {
+            cfg = cfg.auth_header(tok);
+        }
+        let peer_certificate = url.starts_with("https://").then(PeerCertificate::default);
+        let http = header_client(
+            req.headers.as_ref(),
+            self.client_builder(
+                req.tls.as_ref(),
+                req.proxy.as_ref(),
+                peer_certificate.as_ref(),
+            )?,
+        )?;
+        let proxied = self.effective_proxy(req.proxy.as_ref()).is_some();
+        let _pending = PendingGaugeGuard::new();
+        let transport = StreamableHttpClientTransport::with_client(http, cfg);
+        let handshake_timeout =
+            self.handshake_timeout(TargetTransportKind::Http, req.handshake_timeout_ms);
+        let client_info = probe_client_info(&req)?;
+        let (client, latency_ms) = measure_latency(|| async move {
+            let svc = cold_handshake(handshake_timeout, client_info.serve(transport))
+                .await
+                .map_err(|_| {
+                    anyhow::anyhow!(
+                        "http handshake timed out after {} ms",
+                        handshake_timeout.as_millis()
+                    )
+                })?
+                .context("connect http target")?;
+            Ok::<_, anyhow::Error>(svc)
+        })
+        .await?;
+        let server_name = client.peer_info().map(|i| i.server_info.name.clone());
+        let title = client.peer_info().and_then(|i| i.server_info.title.clone());
+        let version = client.peer_info().map(|i| i.server_info.version.clone());
+        let capabilities = client
+            .peer_info()
+            .map(|i| ProbeCapabilities::from(&i.capabilities));
+        let protocol_version = client.peer_info().map(|i| i.protocol_version.to_string());
+        let certificate = peer_certificate
+            .and_then(|seen| seen.der())
+            .and_then(|der| cert_info::parse_der(&der));
+        let result = ProbeResult {
+            ok: true,
+            transport: "http".into(),
+            server_name,
+            title,
+            version,
+            latency_ms: Some(latency_ms),
+            error: None,
+            capabilities,
+            tls_subject: certificate.as_ref().map(|cert| cert.subject.clone()),
+            tls_not_after: certificate.map(|cert| cert.not_after),
+            protocol_version,
+            proxied,
+        };
+        Ok((result, Some(client)))
+    }
+
+    async fn probe_unix(&self, req: ProbeRequest) -> Result<ProbedTarget> {
+        let path = req.socket_path.clone().unwrap_or_default();
+        if path.is_empty() {
+            return Ok((
+                ProbeResult {
+                    ok: false,
+                    transport: "unix".into(),
+                    server_name: None,
+                    title: None,
+                    version: None,
+                    latency_ms: None,
+                    error: Some("missing socket_path".into()),
+                    capabilities: None,
+                    tls_subject: None,
+                    tls_not_after: None,
+                    protocol_version: None,
+                    proxied: false,
+                },
+                None,
+            ));
+        }
         let _pending = PendingGaugeGuard::new();
-        let transport = SseClientTransport::start(url).await?;
+        let stream = connect_unix(&path).await?;
+        let handshake_timeout =
+            self.handshake_timeout(TargetTransportKind::Unix, req.handshake_timeout_ms);
+        let client_info = probe_client_info(&req)?;
         let (client, latency_ms) = measure_latency(|| async move {
-            let svc = timeout(handshake_timeout, ().serve(transport))
+            let svc = cold_handshake(handshake_timeout, client_info.serve(stream))
                 .await
                 .map_err(|_| {
                     anyhow::anyhow!(
-                        "sse handshake timed out after {} ms",
+                        "unix handshake timed out after {} ms",
                         handshake_timeout.as_millis()
                     )
                 })?
-                .context("connect sse target")?;
+                .context("connect unix target")?;
             Ok::<_, anyhow::Error>(svc)
         })
         .await?;
-        LATENCY_HISTO.observe(latency_ms as f64);
+        let server_name = client.peer_info().map(|i| i.server_info.name.clone());
+        let title = client.peer_info().and_then(|i| i.server_info.title.clone());
         let version = client.peer_info().map(|i| i.server_info.version.clone());
-        Ok(ProbeResult {
+        let capabilities = client
+            .peer_info()
+            .map(|i| ProbeCapabilities::from(&i.capabilities));
+        let protocol_version = client.peer_info().map(|i| i.protocol_version.to_string());
+        let result = ProbeResult {
             ok: true,
-            transport: "sse".into(),
-            server_name: None,
+            transport: "unix".into(),
+            server_name,
+            title,
             version,
             latency_ms: Some(latency_ms),
             error: None,
-        })
+            capabilities,
+            tls_subject: None,
+            tls_not_after: None,
+            protocol_version,
+            proxied: false,
+        };
+        Ok((result, Some(client)))
     }
 
-    async fn probe_http(&self, req: ProbeRequest) -> Result<ProbeResult> {
-        let url = req.url.clone().unwrap_or_default();
-        if url.is_empty() {
-            return Ok(ProbeResult {
-                ok: false,
-                transport: "http".into(),
-                server_name: None,
-                version: None,
-                latency_ms: None,
-                error: Some("missing url".into()),
-            });
+    /// Spawn a stdio target and complete the handshake within `requested_ms`, else the stdio
+    /// default. The stderr tail is already attached to a handshake failure; callers attach it
+    /// to later failures on the session.
+    async fn open_stdio(
+        &self,
+        command: String,
+        args: Vec<String>,
+        env: &StdioEnv,
+        cwd: Option<String>,
+        requested_ms: Option<u64>,
+    ) -> Result<(TargetSession, StderrTail, ChildGuard)> {
+        let mut cmd = Command::new(&command);
+        cmd.args(args);
+        self.apply_stdio_env(&mut cmd, env);
+        if let Some(cwd) = cwd {
+            cmd.current_dir(cwd);
         }
-        // Allow Bearer tokens for HTTP via the request config
+        let handshake_timeout = self.handshake_timeout(TargetTransportKind::Stdio, requested_ms);
+        let stderr = StderrTail::default();
+        let transport = self
+            .stdio_transport(cmd, None, Some(stderr.clone()))
+            .await?;
+        let guard = transport.guard(CHILDREN_KILLED.clone());
+        let handshake = cold_handshake(handshake_timeout, InspectorClient::new().serve(transport))
+            .await
+            .map_err(|_| anyhow::Error::from(HandshakeTimedOut { transport: "stdio" }))
+            .and_then(|connected| Ok(connected?));
+        let client = match handshake {
+            Ok(client) => client,
+            Err(err) => {
+                guard.kill().await;
+                return Err(stderr.attach(err).await);
+            }
+        };
+        let session = self.sessions.open(stdio_descriptor(command));
+        Ok(((client, session), stderr, guard))
+    }
+
+    async fn open_sse(&self, target: &SseTarget) -> Result<TargetSession> {
+        let handshake_timeout =
+            self.handshake_timeout(TargetTransportKind::Sse, target.handshake_timeout_ms);
+        let http = self.target_client(
+            target.headers.as_ref(),
+            target.tls.as_ref(),
+            target.proxy.as_ref(),
+        )?;
+        let transport = start_sse(http, &target.url).await?;
+        let client = cold_handshake(handshake_timeout, InspectorClient::new().serve(transport))
+            .await
+            .map_err(|_| anyhow::Error::from(HandshakeTimedOut { transport: "sse" }))??;
+        let session = self.sessions.open(network_descriptor(
+            "sse",
+            &target.url,
+            target.headers.clone(),
+        ));
+        Ok((client, session))
+    }
+
+    async fn open_http(&self, target: &HttpTarget) -> Result<TargetSession> {
         let mut cfg =
             rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig::with_uri(
-                url.clone(),
+                target.url.clone(),
             );
-        if let Some(tok) = &req.auth_token {
+        if let Some(tok) = &target.auth_token {
             cfg = cfg.auth_header(tok);
         }
-        let _pending = PendingGaugeGuard::new();
-        let transport = StreamableHttpClientTransport::with_client(reqwest::Client::new(), cfg);
-        let handshake_timeout = Duration::from_millis(req.handshake_timeout_ms.unwrap_or(15_000));
-        let (client, latency_ms) = measure_latency(|| async move {
-            let svc = timeout(handshake_timeout, ().serve(transport))
-                .await
-                .map_err(|_| {
-                    anyhow::anyhow!(
-                        "http handshake timed out after {} ms",
-                        handshake_timeout.as_millis()
-                    )
-                })?
-                .context("connect http target")?;
-            Ok::<_, anyhow::Error>(svc)
-        })
-        .await?;
-        LATENCY_HISTO.observe(latency_ms as f64);
-        let version = client.peer_info().map(|i| i.server_info.version.clone());
-        Ok(ProbeResult {
-            ok: true,
-            transport: "http".into(),
-            server_name: None,
-            version,
-            latency_ms: Some(latency_ms),
-            error: None,
-        })
+        let http = self.target_client(
+            target.headers.as_ref(),
+            target.tls.as_ref(),
+            target.proxy.as_ref(),
+        )?;
+        let transport = StreamableHttpClientTransport::with_client(http, cfg);
+        let handshake_timeout =
+            self.handshake_timeout(TargetTransportKind::Http, target.handshake_timeout_ms);
+        let client = cold_handshake(handshake_timeout, InspectorClient::new().serve(transport))
+            .await
+            .map_err(|_| anyhow::Error::from(HandshakeTimedOut { transport: "http" }))??;
+        let session = self.sessions.open(network_descriptor(
+            "http",
+            &target.url,
+            target.headers.clone(),
+        ));
+        Ok((client, session))
+    }
+
+    async fn open_unix(&self, target: &UnixTarget) -> Result<TargetSession> {
+        let handshake_timeout =
+            self.handshake_timeout(TargetTransportKind::Unix, target.handshake_timeout_ms);
+        let stream = connect_unix(&target.path).await?;
+        let client = cold_handshake(handshake_timeout, InspectorClient::new().serve(stream))
+            .await
+            .map_err(|_| anyhow::Error::from(HandshakeTimedOut { transport: "unix" }))??;
+        let session = self.sessions.open(unix_descriptor(&target.path));
+        Ok((client, session))
     }
 
     pub async fn list_tools_stdio(
         &self,
         command: String,
         args: Vec<String>,
-        env: Option<BTreeMap<String, String>>,
+        env: StdioEnv,
         cwd: Option<String>,
+        reuse: bool,
+        requested_ms: Option<u64>,
     ) -> Result<Vec<Tool>> {
         let _pending = PendingGaugeGuard::new();
-        let mut cmd = Command::new(command);
-        cmd.args(args);
-        if let Some(env) = env {
-            for (k, v) in env {
-                cmd.env(k, v);
+        let key = reuse.then(|| self.stdio_connection_key(&command, &args, &env, cwd.as_deref()));
+        let (connected, spawned) = match self.reuse_stdio_connection(key.as_deref()).0 {
+            Some(reused) => (reused, None),
+            None => {
+                let (connected, stderr, guard) = self
+                    .open_stdio(command, args, &env, cwd, requested_ms)
+                    .await?;
+                self.stamp_restarts(key.as_deref(), &connected.1);
+                (connected, Some((stderr, guard)))
             }
+        };
+        connected.1.record_call();
+        match self.list_all_tools(&connected.0).await {
+            Ok(tools) => {
+                if let Some((_, guard)) = spawned {
+                    guard.disarm();
+                }
+                self.release_connection(key, connected);
+                Ok(tools)
+            }
+            Err(err) => match spawned {
+                Some((stderr, guard)) => {
+                    guard.kill().await;
+                    Err(stderr.attach(err).await)
+                }
+                None => Err(err),
+            },
         }
-        if let Some(cwd) = cwd {
-            cmd.current_dir(cwd);
-        }
-        let client = ().serve(TokioChildProcess::new(cmd)?).await?;
-        let tools = client.list_tools(Default::default()).await?.tools;
-        Ok(tools)
     }
 
-    pub async fn list_tools_sse(&self, target: &SseTarget) -> Result<Vec<Tool>> {
+    pub async fn list_tools_sse(&self, target: &SseTarget, reuse: bool) -> Result<Vec<Tool>> {
         let _pending = PendingGaugeGuard::new();
         let url = target.url.clone();
         if url.is_empty() {
             anyhow::bail!("missing sse url");
         }
-        let handshake_timeout =
-            Duration::from_millis(target.handshake_timeout_ms.unwrap_or(15_000));
-        let transport = SseClientTransport::start(url).await?;
-        let client = timeout(handshake_timeout, ().serve(transport))
-            .await
-            .map_err(|_| anyhow::anyhow!("sse handshake timed out"))??;
-        let tools = client.list_tools(Default::default()).await?.tools;
+        let key = reuse.then(|| {
+            network_connection_key(
+                "sse",
+                &url,
+                target.headers.as_ref(),
+                None,
+                target.tls.as_ref(),
+                target.proxy.as_ref(),
+            )
+        });
+        let connected = match self.reuse_connection(key.as_deref()) {
+            Some(reused) => reused,
+            None => self.open_sse(target).await?,
+        };
+        connected.1.record_call();
+        let tools = self.list_all_tools(&connected.0).await?;
+        self.release_connection(key, connected);
         Ok(tools)
     }
 
-    pub async fn list_tools_http(&self, target: &HttpTarget) -> Result<Vec<Tool>> {
+    pub async fn list_tools_http(&self, target: &HttpTarget, reuse: bool) -> Result<Vec<Tool>> {
         let _pending = PendingGaugeGuard::new();
         let url = target.url.clone();
         if url.is_empty() {
             anyhow::bail!("missing http url");
         }
-        let mut cfg =
-            rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig::with_uri(
-                url.clone(),
-            );
-        if let Some(tok) = &target.auth_token {
-            cfg = cfg.auth_header(tok);
+        let key = reuse.then(|| {
+            network_connection_key(
+                "http",
+                &url,
+                target.headers.as_ref(),
+                target.auth_token.as_deref(),
+                target.tls.as_ref(),
+                target.proxy.as_ref(),
+            )
+        });
+        let connected = match self.reuse_connection(key.as_deref()) {
+            Some(reused) => reused,
+            None => self.open_http(target).await?,
+        };
+        connected.1.record_call();
+        let tools = self.list_all_tools(&connected.0).await?;
+        self.release_connection(key, connected);
+        Ok(tools)
+    }
+
+    pub async fn list_tools_unix(&self, target: &UnixTarget, reuse: bool) -> Result<Vec<Tool>> {
+        let _pending = PendingGaugeGuard::new();
+        if target.path.is_empty() {
+            anyhow::bail!("missing unix socket_path");
         }
-        let transport = StreamableHttpClientTransport::with_client(reqwest::Client::new(), cfg);
-        let handshake_timeout =
-            Duration::from_millis(target.handshake_timeout_ms.unwrap_or(15_000));
-        let client = timeout(handshake_timeout, ().serve(transport))
-            .await
-            .map_err(|_| anyhow::anyhow!("http handshake timed out"))??;
-        let tools = client.list_tools(Default::default()).await?.tools;
+        let key =
+            reuse.then(|| network_connection_key("unix", &target.path, None, None, None, None));
+        let connected = match self.reuse_connection(key.as_deref()) {
+            Some(reused) => reused,
+            None => self.open_unix(target).await?,
+        };
+        connected.1.record_call();
+        let tools = self.list_all_tools(&connected.0).await?;
+        self.release_connection(key, connected);
         Ok(tools)
     }
 
@@ -333,104 +2188,444 @@ impl InspectorService {
         &self,
         command: String,
         args: Vec<String>,
-        env: Option<BTreeMap<String, String>>,
+        env: StdioEnv,
+        cwd: Option<String>,
+        request: &CallRequest,
+    ) -> Result<CallOutcome> {
+        self.call_with_retries(request, |handshake_retry| {
+            self.call_stdio_once(
+                command.clone(),
+                args.clone(),
+                &env,
+                cwd.clone(),
+                request,
+                handshake_retry,
+            )
+        })
+        .await
+    }
+
+    pub async fn call_sse(&self, target: &SseTarget, request: &CallRequest) -> Result<CallOutcome> {
+        self.call_with_retries(request, |handshake_retry| {
+            self.call_sse_once(target, request, handshake_retry)
+        })
+        .await
+    }
+
+    pub async fn call_http(
+        &self,
+        target: &HttpTarget,
+        request: &CallRequest,
+    ) -> Result<CallOutcome> {
+        self.call_with_retries(request, |handshake_retry| {
+            self.call_http_once(target, request, handshake_retry)
+        })
+        .await
+    }
+
+    pub async fn call_unix(
+        &self,
+        target: &UnixTarget,
+        request: &CallRequest,
+    ) -> Result<CallOutcome> {
+        self.call_with_retries(request, |handshake_retry| {
+            self.call_unix_once(target, request, handshake_retry)
+        })
+        .await
+    }
+
+    async fn call_stdio_once(
+        &self,
+        command: String,
+        args: Vec<String>,
+        env: &StdioEnv,
         cwd: Option<String>,
         request: &CallRequest,
+        handshake_retry: Option<RetryPolicy>,
     ) -> Result<CallOutcome> {
         let _pending = PendingGaugeGuard::new();
-        let mut cmd = Command::new(command);
-        cmd.args(args);
-        if let Some(env) = env {
-            for (k, v) in env {
-                cmd.env(k, v);
+        let deadline = self.stream_deadline(request, Instant::now());
+        let handshake_timeout = self.handshake_timeout(TargetTransportKind::Stdio, None);
+        let unhandled = self
+            .capture_unknown_notifications
+            .then(UnhandledNotifications::default);
+        // Unknown notifications are captured per spawned transport, so those calls never reuse.
+        let key = (request.reuses_session() && unhandled.is_none())
+            .then(|| self.stdio_connection_key(&command, &args, env, cwd.as_deref()));
+        let (reused, restarted) = self.reuse_stdio_connection(key.as_deref());
+        let (connected, guard, attempts) = match reused {
+            Some(reused) => (reused, None, Vec::new()),
+            None => {
+                let ((client, guard), attempts) = self
+                    .retrying("handshake", handshake_retry.as_ref(), || async {
+                        let mut cmd = Command::new(&command);
+                        cmd.args(&args);
+                        self.apply_stdio_env(&mut cmd, env);
+                        if let Some(cwd) = &cwd {
+                            cmd.current_dir(cwd);
+                        }
+                        let handler = InspectorClient::new();
+                        let stderr = StderrTail::default();
+                        let transport = self
+                            .stdio_transport(cmd, unhandled.clone(), Some(stderr.clone()))
+                            .await?;
+                        let guard = transport.guard(CHILDREN_KILLED.clone());
+                        let handshake = cold_handshake(handshake_timeout, handler.serve(transport))
+                            .await
+                            .map_err(|_| {
+                                anyhow::Error::from(HandshakeTimedOut { transport: "stdio" })
+                            })
+                            .and_then(|connected| Ok(connected?));
+                        match handshake {
+                            Ok(client) => Ok((client, guard)),
+                            Err(err) => {
+                                guard.kill().await;
+                                Err(stderr.attach(err).await)
+                            }
+                        }
+                    })
+                    .await
+                    .map_err(TargetUnreachable::wrap)?;
+                let session = self.sessions.open(stdio_descriptor(command));
+                self.stamp_restarts(key.as_deref(), &session);
+                ((client, session), Some(guard), attempts)
             }
+        };
+        connected.1.record_call();
+        let outcome = match self.invoke_call(&connected.0, request, deadline).await {
+            Ok(outcome) => outcome,
+            Err(err) => {
+                if let Some(guard) = guard {
+                    guard.kill().await;
+                }
+                return Err(err);
+            }
+        };
+        if let Some(guard) = guard {
+            guard.disarm();
         }
-        if let Some(cwd) = cwd {
-            cmd.current_dir(cwd);
-        }
-        let handler = InspectorClient::new();
-        let client = handler.serve(TokioChildProcess::new(cmd)?).await?;
-        self.invoke_call(client, request).await
+        self.release_connection(key, connected);
+        let methods = unhandled
+            .map(|unhandled| unhandled.snapshot())
+            .unwrap_or_default();
+        Ok(outcome
+            .with_attempts(attempts)
+            .with_unknown_notifications(methods)
+            .with_target_restarted(restarted))
     }
 
-    pub async fn call_sse(&self, target: &SseTarget, request: &CallRequest) -> Result<CallOutcome> {
+    async fn call_sse_once(
+        &self,
+        target: &SseTarget,
+        request: &CallRequest,
+        handshake_retry: Option<RetryPolicy>,
+    ) -> Result<CallOutcome> {
         let _pending = PendingGaugeGuard::new();
+        let deadline = self.stream_deadline(request, Instant::now());
         let url = target.url.clone();
         if url.is_empty() {
             anyhow::bail!("missing sse url");
         }
-        let handshake_timeout =
-            Duration::from_millis(target.handshake_timeout_ms.unwrap_or(15_000));
-        let handler = InspectorClient::new();
-        let transport = SseClientTransport::start(url).await?;
-        let client = timeout(handshake_timeout, handler.serve(transport))
-            .await
-            .map_err(|_| anyhow::anyhow!("sse handshake timed out"))??;
-        self.invoke_call(client, request).await
+        let key = request.reuses_session().then(|| {
+            network_connection_key(
+                "sse",
+                &url,
+                target.headers.as_ref(),
+                None,
+                target.tls.as_ref(),
+                target.proxy.as_ref(),
+            )
+        });
+        let (connected, attempts) = match self.reuse_connection(key.as_deref()) {
+            Some(reused) => (reused, Vec::new()),
+            None => {
+                let handshake_timeout =
+                    self.handshake_timeout(TargetTransportKind::Sse, target.handshake_timeout_ms);
+                let http = self.target_client(
+                    target.headers.as_ref(),
+                    target.tls.as_ref(),
+                    target.proxy.as_ref(),
+                )?;
+                let (client, attempts) = self
+                    .retrying("handshake", handshake_retry.as_ref(), || async {
+                        let handler = InspectorClient::new();
+                        let transport = start_sse(http.clone(), &url).await?;
+                        cold_handshake(handshake_timeout, handler.serve(transport))
+                            .await
+                            .map_err(|_| {
+                                anyhow::Error::from(HandshakeTimedOut { transport: "sse" })
+                            })?
+                            .map_err(anyhow::Error::from)
+                    })
+                    .await
+                    .map_err(TargetUnreachable::wrap)?;
+                let session =
+                    self.sessions
+                        .open(network_descriptor("sse", &url, target.headers.clone()));
+                ((client, session), attempts)
+            }
+        };
+        connected.1.record_call();
+        let outcome = self.invoke_call(&connected.0, request, deadline).await?;
+        self.release_connection(key, connected);
+        Ok(outcome.with_attempts(attempts))
     }
 
-    pub async fn call_http(
+    async fn call_http_once(
         &self,
         target: &HttpTarget,
         request: &CallRequest,
+        handshake_retry: Option<RetryPolicy>,
     ) -> Result<CallOutcome> {
         let _pending = PendingGaugeGuard::new();
+        let deadline = self.stream_deadline(request, Instant::now());
         let url = target.url.clone();
         if url.is_empty() {
             anyhow::bail!("missing http url");
         }
-        let mut cfg =
-            rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig::with_uri(
-                url.clone(),
-            );
-        if let Some(tok) = &target.auth_token {
-            cfg = cfg.auth_header(tok);
+        let key = request.reuses_session().then(|| {
+            network_connection_key(
+                "http",
+                &url,
+                target.headers.as_ref(),
+                target.auth_token.as_deref(),
+                target.tls.as_ref(),
+                target.proxy.as_ref(),
+            )
+        });
+        let (connected, attempts) = match self.reuse_connection(key.as_deref()) {
+            Some(reused) => (reused, Vec::new()),
+            None => {
+                let mut cfg = rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig::with_uri(
+                    url.clone(),
+                );
+                if let Some(tok) = &target.auth_token {
+                    cfg = cfg.auth_header(tok);
+                }
+                let handshake_timeout =
+                    self.handshake_timeout(TargetTransportKind::Http, target.handshake_timeout_ms);
+                let http = self.target_client(
+                    target.headers.as_ref(),
+                    target.tls.as_ref(),
+                    target.proxy.as_ref(),
+                )?;
+                let (client, attempts) = self
+                    .retrying("handshake", handshake_retry.as_ref(), || async {
+                        let transport =
+                            StreamableHttpClientTransport::with_client(http.clone(), cfg.clone());
+                        let handler = InspectorClient::new();
+                        cold_handshake(handshake_timeout, handler.serve(transport))
+                            .await
+                            .map_err(|_| {
+                                anyhow::Error::from(HandshakeTimedOut { transport: "http" })
+                            })?
+                            .map_err(anyhow::Error::from)
+                    })
+                    .await
+                    .map_err(TargetUnreachable::wrap)?;
+                let session =
+                    self.sessions
+                        .open(network_descriptor("http", &url, target.headers.clone()));
+                ((client, session), attempts)
+            }
+        };
+        connected.1.record_call();
+        let outcome = self.invoke_call(&connected.0, request, deadline).await?;
+        self.release_connection(key, connected);
+        Ok(outcome.with_attempts(attempts))
+    }
+
+    async fn call_unix_once(
+        &self,
+        target: &UnixTarget,
+        request: &CallRequest,
+        handshake_retry: Option<RetryPolicy>,
+    ) -> Result<CallOutcome> {
+        let _pending = PendingGaugeGuard::new();
+        let deadline = self.stream_deadline(request, Instant::now());
+        if target.path.is_empty() {
+            anyhow::bail!("missing unix socket_path");
         }
-        let transport = StreamableHttpClientTransport::with_client(reqwest::Client::new(), cfg);
-        let handshake_timeout =
-            Duration::from_millis(target.handshake_timeout_ms.unwrap_or(15_000));
-        let handler = InspectorClient::new();
-        let client = timeout(handshake_timeout, handler.serve(transport))
-            .await
-            .map_err(|_| anyhow::anyhow!("http handshake timed out"))??;
-        self.invoke_call(client, request).await
+        let key = request
+            .reuses_session()
+            .then(|| network_connection_key("unix", &target.path, None, None, None, None));
+        let (connected, attempts) = match self.reuse_connection(key.as_deref()) {
+            Some(reused) => (reused, Vec::new()),
+            None => self
+                .retrying("handshake", handshake_retry.as_ref(), || {
+                    self.open_unix(target)
+                })
+                .await
+                .map_err(TargetUnreachable::wrap)?,
+        };
+        connected.1.record_call();
+        let outcome = self.invoke_call(&connected.0, request, deadline).await?;
+        self.release_connection(key, connected);
+        Ok(outcome.with_attempts(attempts))
     }
 }
 
 impl InspectorService {
     async fn invoke_call(
         &self,
-        client: rmcp::service::RunningService<RoleClient, InspectorClient>,
+        client: &RunningService<RoleClient, InspectorClient>,
         request: &CallRequest,
+        deadline: Option<StreamDeadline>,
     ) -> Result<CallOutcome> {
+        client.service().reset(request.stream);
+        if let (Some(minimum), Some(info)) = (&self.min_protocol_version, client.peer_info()) {
+            let version = info.protocol_version.to_string();
+            if version.as_str() < minimum.as_str() {
+                return Err(UnsupportedProtocol {
+                    version,
+                    minimum: minimum.clone(),
+                }
+                .into());
+            }
+        }
         let params = CallToolRequestParam {
             name: request.tool_name.clone().into(),
-            arguments: request.arguments_json.as_object().cloned(),
+            arguments: request.arguments_object()?,
         };
-        if request.stream {
-            self.call_with_stream(client, params).await
+        let capabilities = client.peer_info().map(|info| info.capabilities.clone());
+        let call_timeout = request
+            .call_timeout_ms
+            .map(Duration::from_millis)
+            .or(self.call_timeout);
+        let tool = if request.output_schema_check.is_off() && !request.validate_arguments {
+            None
         } else {
-            let res = client.call_tool(params).await?;
-            Ok(CallOutcome::from_result(res))
+            self.list_all_tools(client)
+                .await?
+                .into_iter()
+                .find(|tool| tool.name == request.tool_name)
+        };
+        if request.validate_arguments
+            && let Some(tool) = &tool
+        {
+            let arguments = serde_json::Value::Object(params.arguments.clone().unwrap_or_default());
+            let violations = schema_validation::violations(&tool.input_schema, &arguments);
+            if !violations.is_empty() {
+                return Err(InvalidArguments {
+                    tool_name: request.tool_name.clone(),
+                    violations,
+                }
+                .into());
+            }
         }
+        let output_schema = tool
+            .filter(|_| !request.output_schema_check.is_off())
+            .and_then(|tool| tool.output_schema);
+        // Whatever listing the tool took already came out of the caller's deadline.
+        let call_timeout = self.within_caller_deadline(call_timeout);
+        let warm_started = Instant::now();
+        let outcome = if request.stream {
+            let drain_timeout = request
+                .stream_drain_timeout_ms
+                .map(Duration::from_millis)
+                .or(self.stream_drain_timeout)
+                .unwrap_or(Duration::from_millis(DEFAULT_STREAM_DRAIN_TIMEOUT_MS));
+            self.call_with_stream(
+                client,
+                params,
+                request.progress_token()?,
+                output_schema.as_deref(),
+                deadline,
+                call_timeout,
+                drain_timeout,
+            )
+            .await?
+        } else {
+            let handle = client
+                .send_cancellable_request(
+                    ClientRequest::CallToolRequest(Request::new(params)),
+                    PeerRequestOptions::no_options(),
+                )
+                .await?;
+            let deadline = ResponseDeadline::earliest(None, call_timeout);
+            let res = match self.await_response(handle, deadline).await {
+                Ok(response) => match response? {
+                    ServerResult::CallToolResult(result) => result,
+                    other => {
+                        return Err(anyhow::anyhow!("unexpected server response: {:?}", other));
+                    }
+                },
+                Err(Interruption::Cancelled) => return Err(CallCancelled.into()),
+                Err(Interruption::Expired(deadline)) => {
+                    return Err(CallTimedOut {
+                        limit: deadline.limit(),
+                    }
+                    .into());
+                }
+            };
+            let violations = check_output_schema(output_schema.as_deref(), &res);
+            CallOutcome::from_result(res).with_output_schema_violations(violations)
+        };
+        LATENCY_HISTO.observe(warm_started.elapsed().as_secs_f64() * 1000.0);
+        Ok(outcome.with_target_capabilities(capabilities))
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn call_with_stream(
         &self,
-        client: rmcp::service::RunningService<RoleClient, InspectorClient>,
+        client: &RunningService<RoleClient, InspectorClient>,
         params: CallToolRequestParam,
+        progress_token: Option<ProgressToken>,
+        output_schema: Option<&JsonObject>,
+        deadline: Option<StreamDeadline>,
+        call_timeout: Option<Duration>,
+        drain_timeout: Duration,
     ) -> Result<CallOutcome> {
         let dispatcher = client.service().dispatcher();
+        let mut options = PeerRequestOptions::no_options();
+        if let Some(token) = &progress_token {
+            // Request meta is applied after rmcp sets its generated token, so this wins.
+            let mut meta = Meta::new();
+            meta.set_progress_token(token.clone());
+            options.meta = Some(meta);
+        }
+        let sent = Instant::now();
         let handle = client
             .send_cancellable_request(
                 ClientRequest::CallToolRequest(Request::new(params)),
-                PeerRequestOptions::no_options(),
+                options,
             )
             .await?;
-        let progress_token = handle.progress_token.clone();
+        let progress_token = progress_token.unwrap_or_else(|| handle.progress_token.clone());
         let mut progress_stream = dispatcher.subscribe(progress_token).await;
 
-        let response = handle.await_response().await?;
+        let deadline = ResponseDeadline::earliest(deadline, call_timeout);
+        let response = match self.await_response(handle, deadline).await {
+            Ok(response) => response,
+            Err(Interruption::Cancelled) => return Err(CallCancelled.into()),
+            Err(Interruption::Expired(expiry)) => {
+                let mut events = client.service().take_notifications();
+                return Err(match expiry {
+                    ResponseDeadline::Stream(deadline) => {
+                        events.push(max_duration_event(deadline.limit));
+                        StreamMaxDurationExceeded {
+                            limit: deadline.limit,
+                            events,
+                        }
+                        .into()
+                    }
+                    ResponseDeadline::Call { limit, .. } => CallTimedOut { limit }.into(),
+                });
+            }
+        };
+        let response = match response {
+            Ok(response) => response,
+            // A JSON-RPC error is a complete answer; anything else means the stream broke off.
+            Err(err)
+                if self.partial_stream_policy == PartialStreamPolicy::ReturnPartial
+                    && !matches!(err, rmcp::ServiceError::McpError(_)) =>
+            {
+                let events = client.service().take_notifications();
+                let first_event_ms = client.service().first_chunk_ms(sent);
+                return Ok(partial_stream_outcome(events, &err).with_first_event_ms(first_event_ms));
+            }
+            Err(err) => return Err(err.into()),
+        };
         let mut final_result = match response {
             ServerResult::CallToolResult(result) => result,
             other => {
@@ -438,16 +2633,24 @@ impl InspectorService {
             }
         };
 
-        let mut events: Vec<StreamEvent> = Vec::new();
-        loop {
-            match tokio::time::timeout(Duration::from_millis(25), progress_stream.next()).await {
-                Ok(Some(progress)) => events.push(progress_to_event(progress)),
-                Ok(None) => break,
-                Err(_) => break,
+        // Trailing progress can land after the final result; the client records it in order.
+        // Stop at the terminal chunk, or once nothing arrives within the drain timeout.
+        let mut terminal_seen = false;
+        while !terminal_seen {
+            match tokio::time::timeout(drain_timeout, progress_stream.next()).await {
+                Ok(Some(progress)) => {
+                    terminal_seen = progress
+                        .total
+                        .is_some_and(|total| progress.progress >= total);
+                }
+                _ => break,
             }
         }
+        let mut events = client.service().take_notifications();
+        let drain = StreamDrainReport::from_events(&events);
 
         events.push(result_to_event(&final_result));
+        let violations = check_output_schema(output_schema, &final_result);
         let final_snapshot = serde_json::to_value(&final_result).ok();
         let events_clone = events.clone();
         final_result.structured_content = Some(serde_json::json!({
@@ -456,8 +2659,83 @@ impl InspectorService {
             "final": final_snapshot,
         }));
 
-        Ok(CallOutcome::with_stream(final_result, events))
+        Ok(CallOutcome::with_stream(final_result, events)
+            .with_output_schema_violations(violations)
+            .with_first_event_ms(client.service().first_chunk_ms(sent))
+            .with_stream_drain(drain))
+    }
+}
+
+fn check_output_schema(schema: Option<&JsonObject>, result: &CallToolResult) -> Vec<String> {
+    let Some(schema) = schema else {
+        return Vec::new();
+    };
+    if result.is_error.unwrap_or(false) {
+        return Vec::new();
+    }
+    match result.structured_content.as_ref() {
+        Some(structured) => schema_validation::validate(schema, structured),
+        None => {
+            vec!["$: structured_content missing although the tool declares an output_schema".into()]
+        }
+    }
+}
+
+/// The transient failure class of `error`, matched against a retry policy's `retry_on`;
+/// `None` for failures a retry cannot fix.
+fn transient_failure(error: &anyhow::Error) -> Option<RetryOn> {
+    error.chain().find_map(|cause| {
+        if cause.is::<HandshakeTimedOut>()
+            || cause.is::<CallTimedOut>()
+            || cause.is::<SpawnTimeout>()
+        {
+            Some(RetryOn::Timeout)
+        } else if cause.is::<ClientInitializeError>() || cause.is::<TargetStderr>() {
+            Some(RetryOn::Handshake)
+        } else if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+            connection_lost(io.kind()).then_some(RetryOn::Connect)
+        } else if cause.is::<SseTransportError<reqwest::Error>>() {
+            Some(RetryOn::Connect)
+        } else {
+            match cause.downcast_ref::<ServiceError>()? {
+                ServiceError::TransportClosed | ServiceError::TransportSend(_) => {
+                    Some(RetryOn::Connect)
+                }
+                ServiceError::Timeout { .. } => Some(RetryOn::Timeout),
+                _ => None,
+            }
+        }
+    })
+}
+
+/// I/O failures of an established or attempted connection. Others, such as a spawn's
+/// `NotFound` or `PermissionDenied`, fail the same way on every attempt.
+fn connection_lost(kind: std::io::ErrorKind) -> bool {
+    use std::io::ErrorKind;
+    matches!(
+        kind,
+        ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::BrokenPipe
+            | ErrorKind::TimedOut
+            | ErrorKind::UnexpectedEof
+    )
+}
+
+/// `timeout(limit, handshake)` that records a completed handshake in the cold-handshake
+/// histogram. Only fresh sessions handshake; a call served from the connection cache
+/// never reaches here, so every handshake recorded is a cold one.
+async fn cold_handshake<S, E>(
+    limit: Duration,
+    handshake: impl Future<Output = Result<S, E>>,
+) -> Result<Result<S, E>, tokio::time::error::Elapsed> {
+    let started = Instant::now();
+    let outcome = timeout(limit, handshake).await;
+    if matches!(outcome, Ok(Ok(_))) {
+        COLD_HANDSHAKE_HISTO.observe(started.elapsed().as_secs_f64() * 1000.0);
     }
+    outcome
 }
 
 fn progress_to_event(progress: ProgressNotificationParam) -> StreamEvent {
@@ -469,9 +2747,75 @@ fn progress_to_event(progress: ProgressNotificationParam) -> StreamEvent {
         structured: None,
         content: None,
         error: None,
+        level: None,
+        logger: None,
+        progress_token: Some(progress.progress_token.0.into_json_value()),
+    }
+}
+
+fn log_to_event(log: LoggingMessageNotificationParam) -> StreamEvent {
+    let level = serde_json::to_value(log.level)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string));
+    let (message, structured) = match log.data {
+        serde_json::Value::String(text) => (Some(text), None),
+        other => (None, Some(other)),
+    };
+    StreamEvent {
+        event: "log".into(),
+        progress: None,
+        total: None,
+        message,
+        structured,
+        content: None,
+        error: None,
+        level,
+        logger: log.logger,
+        progress_token: None,
+    }
+}
+
+fn max_duration_event(limit: Duration) -> StreamEvent {
+    StreamEvent {
+        event: "error".into(),
+        progress: None,
+        total: None,
+        message: Some(format!(
+            "stream exceeded its maximum duration of {} ms",
+            limit.as_millis()
+        )),
+        structured: None,
+        content: None,
+        error: Some("STREAM_MAX_DURATION".into()),
+        level: None,
+        logger: None,
+        progress_token: None,
     }
 }
 
+/// The progress a broken-off stream delivered, closed by a synthetic `error` event.
+fn partial_stream_outcome(mut events: Vec<StreamEvent>, error: &rmcp::ServiceError) -> CallOutcome {
+    events.push(StreamEvent {
+        event: "error".into(),
+        progress: None,
+        total: None,
+        message: Some(format!("stream ended before the final result: {error}")),
+        structured: None,
+        content: None,
+        error: Some("STREAM_INTERRUPTED".into()),
+        level: None,
+        logger: None,
+        progress_token: None,
+    });
+    let result = CallToolResult::structured_error(serde_json::json!({
+        "mode": "stream",
+        "partial": true,
+        "events": events.clone(),
+        "final": null,
+    }));
+    CallOutcome::with_stream(result, events)
+}
+
 fn result_to_event(result: &CallToolResult) -> StreamEvent {
     let is_error = result.is_error.unwrap_or(false);
     StreamEvent {
@@ -490,32 +2834,214 @@ fn result_to_event(result: &CallToolResult) -> StreamEvent {
         } else {
             None
         },
+        level: None,
+        logger: None,
+        progress_token: None,
     }
 }
 
-fn resolve_stdio_invocation(req: &ProbeRequest) -> Result<(String, Vec<String>)> {
-    if let Some(cmd) = req.command.as_ref() {
-        if cmd.trim().is_empty() {
-            return Err(anyhow::anyhow!("command is required for stdio transport"));
-        }
-        if let Some(args) = req.args.as_ref() {
-            return Ok((cmd.clone(), args.clone()));
-        }
-        return parse_command(cmd);
+/// Client identity for a probe handshake, offering `protocol_version` when the request sets one.
+fn probe_client_info(req: &ProbeRequest) -> Result<ClientInfo> {
+    let mut info = ClientInfo::default();
+    if let Some(version) = &req.protocol_version {
+        info.protocol_version =
+            serde_json::from_value(serde_json::json!(version)).context("parse protocol_version")?;
     }
-    if let Some(args) = req.args.as_ref() {
-        if !args.is_empty() {
-            return Err(anyhow::anyhow!(
-                "arguments provided without command for stdio transport"
-            ));
-        }
+    Ok(info)
+}
+
+/// `target` label for schema size metrics: the transport plus the stdio command, the socket
+/// path, or the URL without credentials, query or fragment.
+fn schema_metric_target(probe: &ProbeRequest) -> String {
+    let descriptor = probe_descriptor(probe);
+    let location = match (&descriptor.command, &descriptor.url) {
+        (Some(command), _) => command.clone(),
+        (None, Some(raw)) => match reqwest::Url::parse(raw) {
+            Ok(mut url) => {
+                let _ = url.set_username("");
+                let _ = url.set_password(None);
+                url.set_query(None);
+                url.set_fragment(None);
+                url.to_string()
+            }
+            Err(_) => raw.clone(),
+        },
+        (None, None) => descriptor.socket_path.clone().unwrap_or_default(),
+    };
+    format!("{}:{location}", descriptor.transport)
+}
+
+/// Serialized bytes of every tool's input and output schema.
+fn total_schema_bytes(tools: &[Tool]) -> usize {
+    tools
+        .iter()
+        .map(|tool| {
+            let input = serde_json::to_vec(tool.input_schema.as_ref()).map_or(0, |b| b.len());
+            let output = tool
+                .output_schema
+                .as_ref()
+                .and_then(|schema| serde_json::to_vec(schema.as_ref()).ok())
+                .map_or(0, |b| b.len());
+            input + output
+        })
+        .sum()
+}
+
+/// Descriptor of the target `probe` points at, as recorded on run events.
+pub fn probe_descriptor(probe: &ProbeRequest) -> TargetDescriptor {
+    match probe.transport.unwrap_or(TargetTransportKind::Stdio) {
+        TargetTransportKind::Stdio => TargetDescriptor {
+            transport: "stdio".into(),
+            command: probe
+                .command
+                .clone()
+                .or_else(|| env::var("INSPECTOR_STDIO_CMD").ok()),
+            url: None,
+            headers: None,
+            socket_path: None,
+        },
+        TargetTransportKind::Sse => TargetDescriptor {
+            transport: "sse".into(),
+            command: None,
+            url: probe.url.clone(),
+            headers: probe.headers.clone(),
+            socket_path: None,
+        },
+        TargetTransportKind::Http => TargetDescriptor {
+            transport: "http".into(),
+            command: None,
+            url: probe.url.clone(),
+            headers: probe.headers.clone(),
+            socket_path: None,
+        },
+        TargetTransportKind::Unix => TargetDescriptor {
+            transport: "unix".into(),
+            command: None,
+            url: None,
+            headers: None,
+            socket_path: probe.socket_path.clone(),
+        },
     }
-    let env_cmd = env::var("INSPECTOR_STDIO_CMD").map_err(|_| {
-        anyhow::anyhow!(
-            "command is required for stdio transport; set 'command'/'args' or INSPECTOR_STDIO_CMD"
-        )
-    })?;
-    parse_command(&env_cmd)
+}
+
+fn stdio_descriptor(command: String) -> TargetDescriptor {
+    TargetDescriptor {
+        transport: "stdio".into(),
+        command: Some(command),
+        url: None,
+        headers: None,
+        socket_path: None,
+    }
+}
+
+fn unix_descriptor(path: &str) -> TargetDescriptor {
+    TargetDescriptor {
+        transport: "unix".into(),
+        command: None,
+        url: None,
+        headers: None,
+        socket_path: Some(path.to_string()),
+    }
+}
+
+/// Connect to a unix socket target; the stream carries the same newline-delimited
+/// JSON-RPC framing as stdio.
+#[cfg(unix)]
+async fn connect_unix(path: &str) -> Result<tokio::net::UnixStream> {
+    tokio::net::UnixStream::connect(path)
+        .await
+        .with_context(|| format!("connect unix socket {path}"))
+}
+
+#[cfg(not(unix))]
+async fn connect_unix(_path: &str) -> Result<tokio::io::DuplexStream> {
+    anyhow::bail!("unix socket targets are not supported on this platform")
+}
+
+/// Cache key of a network or unix socket target: the endpoint plus everything sent with
+/// each request.
+fn network_connection_key(
+    transport: &str,
+    url: &str,
+    headers: Option<&BTreeMap<String, String>>,
+    auth_token: Option<&str>,
+    tls: Option<&TargetTls>,
+    proxy: Option<&TargetProxy>,
+) -> String {
+    serde_json::json!({
+        "transport": transport,
+        "url": url,
+        "headers": headers,
+        "auth_token": auth_token,
+        "tls": tls,
+        "proxy": proxy,
+    })
+    .to_string()
+}
+
+fn network_descriptor(
+    transport: &str,
+    url: &str,
+    headers: Option<BTreeMap<String, String>>,
+) -> TargetDescriptor {
+    TargetDescriptor {
+        transport: transport.into(),
+        command: None,
+        url: Some(url.to_string()),
+        headers,
+        socket_path: None,
+    }
+}
+
+/// A reqwest client that attaches `headers` to every request it sends: the SSE stream
+/// GET and message POSTs alike. Headers rmcp sets per request (`Accept`, bearer auth) win.
+fn header_client(
+    headers: Option<&BTreeMap<String, String>>,
+    builder: reqwest::ClientBuilder,
+) -> Result<reqwest::Client> {
+    let Some(headers) = headers.filter(|headers| !headers.is_empty()) else {
+        return builder.build().context("build http client");
+    };
+    let mut map = reqwest::header::HeaderMap::new();
+    for (name, value) in headers {
+        let invalid = |reason: String| InvalidHeaders {
+            name: name.clone(),
+            reason,
+        };
+        let header_name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|err| invalid(err.to_string()))?;
+        let header_value = reqwest::header::HeaderValue::from_str(value)
+            .map_err(|err| invalid(err.to_string()))?;
+        map.insert(header_name, header_value);
+    }
+    builder
+        .default_headers(map)
+        .build()
+        .context("build http client")
+}
+
+/// Route every request of `builder` through `proxy`, with its basic-auth credentials if any.
+fn proxied_builder(
+    builder: reqwest::ClientBuilder,
+    proxy: &TargetProxy,
+) -> Result<reqwest::ClientBuilder> {
+    // The URL may carry credentials, so it is left out of the error.
+    let mut routed = reqwest::Proxy::all(&proxy.url).context("invalid proxy url")?;
+    if let Some(username) = &proxy.username {
+        routed = routed.basic_auth(username, proxy.password.as_deref().unwrap_or_default());
+    }
+    Ok(builder.proxy(routed))
+}
+
+async fn start_sse(
+    client: reqwest::Client,
+    url: &str,
+) -> Result<SseClientTransport<reqwest::Client>> {
+    let config = SseClientConfig {
+        sse_endpoint: url.into(),
+        ..Default::default()
+    };
+    Ok(SseClientTransport::start_with_client(client, config).await?)
 }
 
 fn build_sse_target(req: &ProbeRequest) -> Result<SseTarget> {
@@ -527,6 +3053,8 @@ fn build_sse_target(req: &ProbeRequest) -> Result<SseTarget> {
         url,
         headers: req.headers.clone(),
         handshake_timeout_ms: req.handshake_timeout_ms,
+        tls: req.tls.clone(),
+        proxy: req.proxy.clone(),
     })
 }
 
@@ -540,5 +3068,18 @@ fn build_http_target(req: &ProbeRequest) -> Result<HttpTarget> {
         headers: req.headers.clone(),
         auth_token: req.auth_token.clone(),
         handshake_timeout_ms: req.handshake_timeout_ms,
+        tls: req.tls.clone(),
+        proxy: req.proxy.clone(),
+    })
+}
+
+fn build_unix_target(req: &ProbeRequest) -> Result<UnixTarget> {
+    let path = req.socket_path.clone().unwrap_or_default();
+    if path.is_empty() {
+        return Err(anyhow::anyhow!("missing unix socket_path"));
+    }
+    Ok(UnixTarget {
+        path,
+        handshake_timeout_ms: req.handshake_timeout_ms,
     })
 }
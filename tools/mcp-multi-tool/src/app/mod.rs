@@ -1,4 +1,11 @@
+pub mod bench;
 pub mod compliance;
+pub mod conformance;
+pub mod connection_cache;
 pub mod error_budget;
 pub mod inspector_service;
 pub mod registry;
+pub mod session_manager;
+pub mod sessions;
+pub mod stdio_transport;
+pub mod target_tls;
@@ -10,17 +10,39 @@ fn schema_for<T: rmcp::schemars::JsonSchema + 'static>() -> Arc<rmcp::model::Jso
 #[derive(Clone)]
 pub struct ToolRegistry {
     release_track: ReleaseTrack,
+    admin_tools: bool,
 }
 
+/// Tools that expose or change server state rather than inspect a target; hidden unless enabled.
+pub const ADMIN_TOOLS: &[&str] = &[
+    "inspector_idempotency_export",
+    "inspector_idempotency_import",
+    "inspector_budget_window",
+    "inspector_cancel",
+];
+
 impl ToolRegistry {
     pub fn new(release_track: ReleaseTrack) -> Self {
-        Self { release_track }
+        Self {
+            release_track,
+            admin_tools: false,
+        }
+    }
+
+    /// Expose the [`ADMIN_TOOLS`]; off by default.
+    pub fn with_admin_tools(mut self, enabled: bool) -> Self {
+        self.admin_tools = enabled;
+        self
     }
 
     pub fn release_track(&self) -> ReleaseTrack {
         self.release_track
     }
 
+    pub fn admin_tools_enabled(&self) -> bool {
+        self.admin_tools
+    }
+
     pub fn list(&self) -> Vec<Tool> {
         let mut tools = vec![Tool::new(
             "help",
@@ -31,6 +53,11 @@ impl ToolRegistry {
 
         if self.release_track.allows_inspector() {
             tools.extend([
+                Tool::new(
+                    "inspector_health",
+                    "Report liveness and readiness (outbox writable, default target resolvable, release track).",
+                    schema_for::<Parameters<crate::shared::types::EmptyArgs>>(),
+                ),
                 Tool::new(
                     "inspector_probe",
                     "Connect to a target MCP and retrieve version/latency details.",
@@ -38,7 +65,82 @@ impl ToolRegistry {
                 ),
                 Tool::new(
                     "inspector_list_tools",
-                    "List target MCP tools across stdio/SSE/HTTP transports.",
+                    "List target MCP tools across stdio/SSE/HTTP/unix socket transports.",
+                    schema_for::<Parameters<crate::shared::types::ProbeRequest>>(),
+                ),
+                Tool::new(
+                    "inspector_sessions",
+                    "List open downstream sessions with redacted target, timestamps and call count.",
+                    schema_for::<Parameters<crate::shared::types::EmptyArgs>>(),
+                ),
+                Tool::new(
+                    "inspector_stats",
+                    "Report open fds, resident memory, tokio tasks, open sessions and idempotency records.",
+                    schema_for::<Parameters<crate::shared::types::EmptyArgs>>(),
+                ),
+                Tool::new(
+                    "inspector_schema_sizes",
+                    "Report serialized input_schema size per target tool, largest first.",
+                    schema_for::<Parameters<crate::shared::types::ProbeRequest>>(),
+                ),
+                Tool::new(
+                    "inspector_schema_hash",
+                    "Return a SHA-256 hash and canonical form of a target tool's input_schema.",
+                    schema_for::<Parameters<crate::shared::types::DescribeRequest>>(),
+                ),
+                Tool::new(
+                    "inspector_compare",
+                    "Diff the tool catalogs of two target MCPs: tools only in either, and changed descriptions or schemas.",
+                    schema_for::<Parameters<crate::shared::types::CompareRequest>>(),
+                ),
+                Tool::new(
+                    "inspector_list_resources",
+                    "List every resource of a target MCP, following pagination to the end.",
+                    schema_for::<Parameters<crate::shared::types::ProbeRequest>>(),
+                ),
+                Tool::new(
+                    "inspector_read_resource",
+                    "Read one resource of a target MCP and return its text or blob contents.",
+                    schema_for::<Parameters<crate::shared::types::ReadResourceRequest>>(),
+                ),
+                Tool::new(
+                    "inspector_list_prompts",
+                    "List every prompt of a target MCP, following pagination to the end.",
+                    schema_for::<Parameters<crate::shared::types::ProbeRequest>>(),
+                ),
+                Tool::new(
+                    "inspector_get_prompt",
+                    "Render one prompt of a target MCP with the given arguments and return its messages.",
+                    schema_for::<Parameters<crate::shared::types::GetPromptRequest>>(),
+                ),
+                Tool::new(
+                    "inspector_transport_bench",
+                    "Probe one target over its stdio, SSE and HTTP variants concurrently and rank them by handshake latency.",
+                    schema_for::<Parameters<crate::shared::types::TransportBenchRequest>>(),
+                ),
+                Tool::new(
+                    "inspector_compliance_history",
+                    "Return recent compliance run summaries and their pass-rate trend from the history db.",
+                    schema_for::<Parameters<crate::shared::types::ComplianceHistoryRequest>>(),
+                ),
+                Tool::new(
+                    "inspector_bench",
+                    "Call one tool of a target MCP repeatedly and report latency percentiles, throughput and errors.",
+                    schema_for::<Parameters<crate::shared::types::BenchRequest>>(),
+                ),
+                Tool::new(
+                    "inspector_initialize",
+                    "Return the full InitializeResult (capabilities, server info, instructions, protocol version) of a target MCP.",
+                    schema_for::<Parameters<crate::shared::types::ProbeRequest>>(),
+                ),
+                Tool::new(
+                    "inspector_set_logging_level",
+                    "Forward logging/setLevel to a target MCP that advertises the logging capability.",
+                    schema_for::<Parameters<crate::shared::types::SetLoggingLevelRequest>>(),
+                ),
+                Tool::new(
+                    "inspector_conformance",
+                    "Run a protocol conformance checklist against a target MCP and return pass/fail per check.",
                     schema_for::<Parameters<crate::shared::types::ProbeRequest>>(),
                 ),
                 Tool::new(
@@ -48,10 +150,44 @@ impl ToolRegistry {
                 ),
                 Tool::new(
                     "inspector_call",
-                    "Call a target MCP tool via stdio/SSE/HTTP transports with optional streaming progress.",
+                    "Call a target MCP tool via stdio/SSE/HTTP/unix socket transports with optional streaming progress.",
                     schema_for::<Parameters<crate::shared::types::CallRequest>>(),
                 ),
+                Tool::new(
+                    "inspector_connect",
+                    "Open a session to a target MCP and return a session_id for later list/describe/call requests.",
+                    schema_for::<Parameters<crate::shared::types::ProbeRequest>>(),
+                ),
+                Tool::new(
+                    "inspector_disconnect",
+                    "Close a session opened by inspector_connect.",
+                    schema_for::<Parameters<crate::shared::types::DisconnectRequest>>(),
+                ),
             ]);
+            if self.admin_tools {
+                tools.extend([
+                    Tool::new(
+                        "inspector_cancel",
+                        "Cancel an in-flight inspector_call by run_id, forwarding the cancellation to the target.",
+                        schema_for::<Parameters<crate::shared::types::CancelRequest>>(),
+                    ),
+                    Tool::new(
+                        "inspector_idempotency_export",
+                        "Export completed idempotency records (requests and secrets redacted) for hand-off to another instance.",
+                        schema_for::<Parameters<crate::shared::types::EmptyArgs>>(),
+                    ),
+                    Tool::new(
+                        "inspector_idempotency_import",
+                        "Seed the idempotency store with records from inspector_idempotency_export.",
+                        schema_for::<Parameters<crate::shared::types::IdempotencyImportRequest>>(),
+                    ),
+                    Tool::new(
+                        "inspector_budget_window",
+                        "Show the error budget's sample window: recent call outcomes, success rate and freeze state.",
+                        schema_for::<Parameters<crate::shared::types::EmptyArgs>>(),
+                    ),
+                ]);
+            }
         }
 
         tools
@@ -0,0 +1,126 @@
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{infra::metrics::MANAGED_SESSIONS, shared::types::DEFAULT_SESSION_IDLE_TIMEOUT_MS};
+
+/// Sessions opened with `inspector_connect`, addressed by id until disconnected or idle for
+/// longer than the idle timeout.
+///
+/// Each session sits behind an async lock so operations through one id run one at a time and
+/// never see each other's notifications.
+#[derive(Debug)]
+pub struct SessionManager<S> {
+    idle_timeout: Duration,
+    sessions: Mutex<HashMap<uuid::Uuid, ManagedSession<S>>>,
+}
+
+#[derive(Debug)]
+struct ManagedSession<S> {
+    session: Arc<tokio::sync::Mutex<S>>,
+    last_used: Instant,
+}
+
+impl<S> Default for SessionManager<S> {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(DEFAULT_SESSION_IDLE_TIMEOUT_MS))
+    }
+}
+
+impl<S> SessionManager<S> {
+    pub fn new(idle_timeout: Duration) -> Self {
+        Self {
+            idle_timeout,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn insert(&self, id: uuid::Uuid, session: S) {
+        let mut sessions = self.sessions.lock();
+        sessions.insert(
+            id,
+            ManagedSession {
+                session: Arc::new(tokio::sync::Mutex::new(session)),
+                last_used: Instant::now(),
+            },
+        );
+        MANAGED_SESSIONS.set(sessions.len() as i64);
+    }
+
+    /// The session `id` names, marked as used; `None` once it was removed or swept.
+    pub fn get(&self, id: &uuid::Uuid) -> Option<Arc<tokio::sync::Mutex<S>>> {
+        let mut sessions = self.sessions.lock();
+        let entry = sessions.get_mut(id)?;
+        entry.last_used = Instant::now();
+        Some(Arc::clone(&entry.session))
+    }
+
+    /// Close session `id`; `false` when there was none. An operation still running on it
+    /// finishes first.
+    pub fn remove(&self, id: &uuid::Uuid) -> bool {
+        let mut sessions = self.sessions.lock();
+        let removed = sessions.remove(id).is_some();
+        MANAGED_SESSIONS.set(sessions.len() as i64);
+        removed
+    }
+
+    /// Close sessions idle for longer than the idle timeout; sessions with an operation in
+    /// flight are never idle. Returns how many were closed.
+    pub fn sweep(&self) -> usize {
+        self.sweep_at(Instant::now())
+    }
+
+    fn sweep_at(&self, now: Instant) -> usize {
+        let mut sessions = self.sessions.lock();
+        let before = sessions.len();
+        sessions.retain(|_, entry| {
+            Arc::strong_count(&entry.session) > 1
+                || now.duration_since(entry.last_used) <= self.idle_timeout
+        });
+        MANAGED_SESSIONS.set(sessions.len() as i64);
+        before - sessions.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_stay_addressable_until_removed() {
+        let manager = SessionManager::new(Duration::from_secs(60));
+        let id = uuid::Uuid::new_v4();
+        manager.insert(id, "target");
+        assert!(manager.get(&id).is_some());
+        assert!(manager.remove(&id));
+        assert!(manager.get(&id).is_none());
+        assert!(!manager.remove(&id));
+    }
+
+    #[test]
+    fn idle_sessions_are_swept_unless_in_use() {
+        let manager = SessionManager::new(Duration::from_millis(10));
+        let idle = uuid::Uuid::new_v4();
+        let busy = uuid::Uuid::new_v4();
+        manager.insert(idle, 1);
+        manager.insert(busy, 2);
+        let in_use = manager.get(&busy).expect("busy session");
+
+        let later = Instant::now() + Duration::from_secs(1);
+        assert_eq!(manager.sweep_at(later), 1);
+        assert!(manager.get(&idle).is_none());
+        drop(in_use);
+        assert_eq!(manager.len(), 1);
+    }
+}
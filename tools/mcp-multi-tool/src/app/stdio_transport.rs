@@ -0,0 +1,554 @@
+use std::{
+    io,
+    pin::Pin,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    task::{Context, Poll, ready},
+    time::{Duration, Instant},
+};
+
+use prometheus::IntCounter;
+use rmcp::{
+    RoleClient,
+    service::{RxJsonRpcMessage, TxJsonRpcMessage},
+    transport::{Transport, async_rw::AsyncRwTransport},
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, ReadBuf},
+    process::{Child, ChildStdin, ChildStdout, Command},
+    sync::Notify,
+};
+
+/// Spawning the target process did not finish within the spawn timeout.
+#[derive(Debug)]
+pub struct SpawnTimeout {
+    pub limit: Duration,
+}
+
+impl std::fmt::Display for SpawnTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "stdio target did not spawn within {} ms",
+            self.limit.as_millis()
+        )
+    }
+}
+
+impl std::error::Error for SpawnTimeout {}
+
+/// Run a blocking spawn off the async runtime, giving up after `limit`.
+///
+/// `Command::spawn` waits for `exec` to succeed, which a cold filesystem can stretch
+/// out; bounding it here keeps that time out of the handshake timeout. The timer only
+/// fires on a millisecond tick, so a spawn that finished past `limit` before it did is
+/// dropped and reported as timed out too.
+pub async fn spawn_with_timeout<T, F>(limit: Duration, spawn: F) -> anyhow::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> io::Result<T> + Send + 'static,
+{
+    let started = Instant::now();
+    match tokio::time::timeout(limit, tokio::task::spawn_blocking(spawn)).await {
+        Ok(_) if started.elapsed() > limit => Err(SpawnTimeout { limit }.into()),
+        Ok(joined) => Ok(joined??),
+        Err(_) => Err(SpawnTimeout { limit }.into()),
+    }
+}
+
+/// Longest run of bytes without a newline we are willing to skip while looking for
+/// the first frame; past this the stream is handed to the JSON-RPC codec as-is.
+const MAX_SKIPPED_LINE_BYTES: usize = 64 * 1024;
+
+/// Notification methods our client handler acts on; everything else is "unhandled".
+const HANDLED_NOTIFICATIONS: &[&str] = &["notifications/progress", "notifications/message"];
+
+/// Upper bound on distinct method names kept per call, so a noisy target cannot grow the trace.
+const MAX_UNHANDLED_NOTIFICATIONS: usize = 64;
+
+/// Distinct method names of server notifications the client handler does not act on,
+/// in first-seen order. Non-standard methods are dropped by the rmcp codec before they
+/// reach the handler, so they are observed here on the raw stdout frames.
+#[derive(Debug, Clone, Default)]
+pub struct UnhandledNotifications(Arc<Mutex<Vec<String>>>);
+
+impl UnhandledNotifications {
+    fn observe(&self, line: &[u8]) {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let Ok(frame) = serde_json::from_slice::<serde_json::Value>(line) else {
+            return;
+        };
+        if frame.get("id").is_some() {
+            return;
+        }
+        let Some(method) = frame.get("method").and_then(|m| m.as_str()) else {
+            return;
+        };
+        if HANDLED_NOTIFICATIONS.contains(&method) {
+            return;
+        }
+        if let Ok(mut methods) = self.0.lock()
+            && methods.len() < MAX_UNHANDLED_NOTIFICATIONS
+            && !methods.iter().any(|seen| seen == method)
+        {
+            methods.push(method.to_string());
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<String> {
+        self.0.lock().map(|m| m.clone()).unwrap_or_default()
+    }
+}
+
+/// Bytes of target stderr kept for failure reports; older output is dropped first.
+const MAX_STDERR_TAIL_BYTES: usize = 16 * 1024;
+
+/// How long a failure report waits for the child to close stderr before using what it has.
+const STDERR_SETTLE: Duration = Duration::from_millis(200);
+
+/// The last [`MAX_STDERR_TAIL_BYTES`] a stdio target wrote to stderr. The pipe is drained
+/// for the whole life of the child so a chatty target never blocks on a full buffer.
+#[derive(Debug, Clone, Default)]
+pub struct StderrTail(Arc<StderrTailInner>);
+
+#[derive(Debug, Default)]
+struct StderrTailInner {
+    bytes: Mutex<Vec<u8>>,
+    closed: AtomicBool,
+    closed_notify: Notify,
+}
+
+impl StderrTail {
+    fn capture<R: AsyncRead + Unpin + Send + 'static>(&self, mut stderr: R) {
+        let inner = self.0.clone();
+        tokio::spawn(async move {
+            let mut chunk = [0u8; 4096];
+            while let Ok(n) = stderr.read(&mut chunk).await {
+                if n == 0 {
+                    break;
+                }
+                if let Ok(mut bytes) = inner.bytes.lock() {
+                    bytes.extend_from_slice(&chunk[..n]);
+                    let excess = bytes.len().saturating_sub(MAX_STDERR_TAIL_BYTES);
+                    bytes.drain(..excess);
+                }
+            }
+            inner.closed.store(true, Ordering::Release);
+            inner.closed_notify.notify_waiters();
+        });
+    }
+
+    /// Captured output once the child closed stderr (or [`STDERR_SETTLE`] passed), if any.
+    pub async fn settled(&self) -> Option<String> {
+        let notified = self.0.closed_notify.notified();
+        if !self.0.closed.load(Ordering::Acquire) {
+            let _ = tokio::time::timeout(STDERR_SETTLE, notified).await;
+        }
+        let bytes = self.0.bytes.lock().ok()?;
+        let tail = String::from_utf8_lossy(&bytes).trim().to_string();
+        (!tail.is_empty()).then_some(tail)
+    }
+
+    /// `error` with the captured stderr appended, or unchanged when the target wrote none.
+    pub async fn attach(&self, error: anyhow::Error) -> anyhow::Error {
+        match self.settled().await {
+            Some(tail) => TargetStderr {
+                message: format!("{error:#}"),
+                tail,
+            }
+            .into(),
+            None => error,
+        }
+    }
+}
+
+/// A stdio target failed; carries what it wrote to stderr before it did.
+#[derive(Debug)]
+pub struct TargetStderr {
+    message: String,
+    pub tail: String,
+}
+
+impl std::fmt::Display for TargetStderr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}; target stderr: {}", self.message, self.tail)
+    }
+}
+
+impl std::error::Error for TargetStderr {}
+
+/// How long a closed stdio target gets to exit on its own before it is killed.
+const CLOSE_GRACE: Duration = Duration::from_secs(3);
+
+/// The process behind a [`StdioTransport`], shared with the [`ChildGuard`] of the
+/// operation that spawned it. Once both let go, a child still running is killed and reaped.
+#[derive(Debug, Clone)]
+pub struct TargetChild(Arc<ChildSlot>);
+
+#[derive(Debug)]
+struct ChildSlot(Mutex<Option<Child>>);
+
+impl ChildSlot {
+    fn take(&self) -> Option<Child> {
+        self.0.lock().ok()?.take()
+    }
+}
+
+impl Drop for ChildSlot {
+    fn drop(&mut self) {
+        if let Some(child) = self.take() {
+            kill_and_reap(child);
+        }
+    }
+}
+
+impl TargetChild {
+    fn new(child: Child) -> Self {
+        Self(Arc::new(ChildSlot(Mutex::new(Some(child)))))
+    }
+
+    /// Wait up to `grace` for the child to exit, then kill it, and reap it either way.
+    async fn shutdown(&self, grace: Duration) {
+        let Some(mut child) = self.0.take() else {
+            return;
+        };
+        if tokio::time::timeout(grace, child.wait()).await.is_err() {
+            let _ = child.kill().await;
+        }
+    }
+}
+
+/// Send `child` SIGKILL and reap it in the background; `false` if it had already exited.
+fn kill_and_reap(mut child: Child) -> bool {
+    let running = matches!(child.try_wait(), Ok(None));
+    if running {
+        let _ = child.start_kill();
+    }
+    if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+        runtime.spawn(async move {
+            let _ = child.wait().await;
+        });
+    }
+    running
+}
+
+/// Kills and reaps a freshly spawned target unless disarmed, so a handshake or call that
+/// fails, times out or is cancelled leaves no process behind. Each kill bumps `killed`.
+#[must_use = "dropping the guard kills the target"]
+#[derive(Debug)]
+pub struct ChildGuard {
+    child: Option<TargetChild>,
+    killed: IntCounter,
+}
+
+impl ChildGuard {
+    /// The operation succeeded; the child now lives as long as its transport.
+    pub fn disarm(mut self) {
+        self.child = None;
+    }
+
+    /// Kill the child and wait for it to exit.
+    pub async fn kill(mut self) {
+        let Some(mut child) = self.child.take().and_then(|target| target.0.take()) else {
+            return;
+        };
+        if matches!(child.try_wait(), Ok(None)) && child.kill().await.is_ok() {
+            self.killed.inc();
+        }
+    }
+}
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        if let Some(child) = self.child.take().and_then(|target| target.0.take())
+            && kill_and_reap(child)
+        {
+            self.killed.inc();
+        }
+    }
+}
+
+/// Child-process transport for stdio targets, optionally tolerant of stdout noise.
+pub struct StdioTransport {
+    child: TargetChild,
+    transport: AsyncRwTransport<RoleClient, BannerSkippingReader<ChildStdout>, ChildStdin>,
+}
+
+impl StdioTransport {
+    /// Spawn `cmd`; with `lenient`, non-JSON-RPC lines printed before the first frame are
+    /// skipped, with `unhandled`, unhandled notification methods are recorded into it, and
+    /// with `stderr`, the child's stderr is captured there instead of inherited.
+    pub fn spawn(
+        mut cmd: Command,
+        lenient: bool,
+        unhandled: Option<UnhandledNotifications>,
+        stderr: Option<StderrTail>,
+    ) -> io::Result<Self> {
+        let stderr_io = if stderr.is_some() {
+            std::process::Stdio::piped()
+        } else {
+            std::process::Stdio::inherit()
+        };
+        cmd.stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(stderr_io)
+            .kill_on_drop(true);
+        let mut child = cmd.spawn()?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::other("child stdout unavailable"))?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| io::Error::other("child stdin unavailable"))?;
+        if let (Some(tail), Some(pipe)) = (stderr, child.stderr.take()) {
+            tail.capture(pipe);
+        }
+        Ok(Self {
+            child: TargetChild::new(child),
+            transport: AsyncRwTransport::new_client(
+                BannerSkippingReader::new(stdout)
+                    .skip_banner(lenient)
+                    .with_unhandled_notifications(unhandled),
+                stdin,
+            ),
+        })
+    }
+
+    /// A guard that kills this transport's child unless the operation disarms it.
+    pub fn guard(&self, killed: IntCounter) -> ChildGuard {
+        ChildGuard {
+            child: Some(self.child.clone()),
+            killed,
+        }
+    }
+}
+
+impl Transport<RoleClient> for StdioTransport {
+    type Error = io::Error;
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<RoleClient>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        self.transport.send(item)
+    }
+
+    fn receive(&mut self) -> impl Future<Output = Option<RxJsonRpcMessage<RoleClient>>> + Send {
+        self.transport.receive()
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        self.transport.close().await?;
+        self.child.shutdown(CLOSE_GRACE).await;
+        Ok(())
+    }
+}
+
+/// Drops whole lines until one parses as a JSON-RPC object, then passes bytes through untouched.
+pub struct BannerSkippingReader<R> {
+    inner: R,
+    skipping: bool,
+    line: Vec<u8>,
+    pending: Vec<u8>,
+    pending_pos: usize,
+    unhandled: Option<UnhandledNotifications>,
+    observed: Vec<u8>,
+}
+
+impl<R> BannerSkippingReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            skipping: true,
+            line: Vec::new(),
+            pending: Vec::new(),
+            pending_pos: 0,
+            unhandled: None,
+            observed: Vec::new(),
+        }
+    }
+
+    /// With `false`, bytes pass through from the start (used when only observing frames).
+    pub fn skip_banner(mut self, skip: bool) -> Self {
+        self.skipping = skip;
+        self
+    }
+
+    /// Record unhandled notification methods from the frames handed to the codec.
+    pub fn with_unhandled_notifications(
+        mut self,
+        unhandled: Option<UnhandledNotifications>,
+    ) -> Self {
+        self.unhandled = unhandled;
+        self
+    }
+
+    fn observe(&mut self, bytes: &[u8]) {
+        let Some(unhandled) = &self.unhandled else {
+            return;
+        };
+        self.observed.extend_from_slice(bytes);
+        while let Some(newline) = self.observed.iter().position(|b| *b == b'\n') {
+            unhandled.observe(&self.observed[..newline]);
+            self.observed.drain(..=newline);
+        }
+        if self.observed.len() > MAX_SKIPPED_LINE_BYTES {
+            // Oversized frame; it is no notification worth recording.
+            self.observed.clear();
+        }
+    }
+
+    /// Consume complete lines from `line`, stopping at the first JSON-RPC frame.
+    fn scan_lines(&mut self) {
+        while let Some(newline) = self.line.iter().position(|b| *b == b'\n') {
+            if is_jsonrpc_frame(&self.line[..newline]) {
+                self.finish_skipping();
+                return;
+            }
+            let skipped: Vec<u8> = self.line.drain(..=newline).collect();
+            tracing::warn!(
+                line = %String::from_utf8_lossy(&skipped).trim_end(),
+                "skipping non-JSON-RPC line on target stdout"
+            );
+        }
+        if self.line.len() > MAX_SKIPPED_LINE_BYTES {
+            self.finish_skipping();
+        }
+    }
+
+    fn finish_skipping(&mut self) {
+        self.skipping = false;
+        self.pending = std::mem::take(&mut self.line);
+        self.pending_pos = 0;
+    }
+}
+
+fn is_jsonrpc_frame(line: &[u8]) -> bool {
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    serde_json::from_slice::<serde_json::Value>(line)
+        .is_ok_and(|value| value.get("jsonrpc").is_some())
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for BannerSkippingReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let start = buf.filled().len();
+        let polled = self.as_mut().poll_frames(cx, buf);
+        if let Poll::Ready(Ok(())) = polled {
+            self.observe(&buf.filled()[start..]);
+        }
+        polled
+    }
+}
+
+impl<R: AsyncRead + Unpin> BannerSkippingReader<R> {
+    fn poll_frames(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        loop {
+            if this.pending_pos < this.pending.len() {
+                let available = &this.pending[this.pending_pos..];
+                let n = available.len().min(buf.remaining());
+                buf.put_slice(&available[..n]);
+                this.pending_pos += n;
+                return Poll::Ready(Ok(()));
+            }
+            if !this.skipping {
+                return Pin::new(&mut this.inner).poll_read(cx, buf);
+            }
+            let mut chunk = [0u8; 4096];
+            let mut chunk_buf = ReadBuf::new(&mut chunk);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut chunk_buf))?;
+            if chunk_buf.filled().is_empty() {
+                // EOF before any frame: surface whatever partial line remains.
+                this.finish_skipping();
+                if this.pending.is_empty() {
+                    return Poll::Ready(Ok(()));
+                }
+                continue;
+            }
+            this.line.extend_from_slice(chunk_buf.filled());
+            this.scan_lines();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+
+    #[tokio::test]
+    async fn slow_spawn_times_out_distinctly() {
+        let err = spawn_with_timeout(Duration::from_millis(20), || {
+            std::thread::sleep(Duration::from_millis(300));
+            Ok(())
+        })
+        .await
+        .expect_err("spawn should time out");
+        assert!(err.is::<SpawnTimeout>(), "unexpected error: {err:#}");
+
+        let value = spawn_with_timeout(Duration::from_secs(5), || Ok(7)).await;
+        assert_eq!(value.unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn stderr_tail_keeps_the_last_bytes() {
+        let tail = StderrTail::default();
+        let mut output = vec![b'a'; MAX_STDERR_TAIL_BYTES];
+        output.extend_from_slice(b"\npanicked at src/main.rs\n");
+        tail.capture(std::io::Cursor::new(output));
+        let captured = tail.settled().await.expect("stderr captured");
+        assert_eq!(captured.len(), MAX_STDERR_TAIL_BYTES - 1);
+        assert!(captured.ends_with("panicked at src/main.rs"));
+
+        let err = tail.attach(anyhow::anyhow!("connection closed")).await;
+        let stderr = err.downcast_ref::<TargetStderr>().expect("stderr attached");
+        assert!(
+            err.to_string()
+                .starts_with("connection closed; target stderr: ")
+        );
+        assert_eq!(stderr.tail, captured);
+
+        let silent = StderrTail::default();
+        silent.capture(std::io::Cursor::new(Vec::new()));
+        let err = silent.attach(anyhow::anyhow!("connection closed")).await;
+        assert!(!err.is::<TargetStderr>());
+    }
+
+    #[tokio::test]
+    async fn skips_banner_lines_until_first_frame() {
+        let input: &[u8] = b"Starting demo server v1.2\r\n\n{\"not\":\"rpc\"}\n{\"jsonrpc\":\"2.0\",\"id\":1}\nnot skipped later\n";
+        let mut reader = BannerSkippingReader::new(input);
+        let mut out = String::new();
+        reader.read_to_string(&mut out).await.unwrap();
+        assert_eq!(out, "{\"jsonrpc\":\"2.0\",\"id\":1}\nnot skipped later\n");
+    }
+
+    #[tokio::test]
+    async fn records_unhandled_notification_methods_once() {
+        let input: &[u8] =
+            b"{\"jsonrpc\":\"2.0\",\"method\":\"notifications/progress\",\"params\":{}}\n\
+{\"jsonrpc\":\"2.0\",\"method\":\"notifications/vendor/heartbeat\"}\n\
+{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"sampling/createMessage\"}\n\
+{\"jsonrpc\":\"2.0\",\"method\":\"notifications/vendor/heartbeat\"}\n";
+        let unhandled = UnhandledNotifications::default();
+        let mut reader = BannerSkippingReader::new(input)
+            .skip_banner(false)
+            .with_unhandled_notifications(Some(unhandled.clone()));
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out).await.unwrap();
+        assert_eq!(out, input);
+        assert_eq!(unhandled.snapshot(), vec!["notifications/vendor/heartbeat"]);
+    }
+}
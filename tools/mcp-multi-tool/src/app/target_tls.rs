@@ -0,0 +1,226 @@
+//! Client TLS for `https` SSE and HTTP targets.
+//!
+//! Targets without `tls` settings use reqwest's defaults. Otherwise the rustls config is
+//! built here, so the verifier can also record the certificate the target presented.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use rustls::{
+    ClientConfig, DigitallySignedStruct, DistinguishedName, RootCertStore, SignatureScheme,
+    client::{
+        WebPkiServerVerifier,
+        danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    },
+    crypto::{CryptoProvider, ring, verify_tls12_signature, verify_tls13_signature},
+    pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime, pem::PemObject},
+};
+
+use super::inspector_service::TlsConfigError;
+use crate::shared::types::TargetTls;
+
+/// DER of the end-entity certificate a client's last verified handshake saw.
+#[derive(Debug, Clone, Default)]
+pub struct PeerCertificate(Arc<Mutex<Option<Vec<u8>>>>);
+
+impl PeerCertificate {
+    pub fn der(&self) -> Option<Vec<u8>> {
+        self.0.lock().clone()
+    }
+}
+
+/// A reqwest builder honouring `tls`: it trusts `ca_cert_path` and presents the client
+/// certificate, if any, and honours `insecure_skip_verify` only when `allow_insecure` is set.
+/// With `peer_certificate` set it records the server certificate of the connections it makes.
+pub fn client_builder(
+    tls: Option<&TargetTls>,
+    allow_insecure: bool,
+    peer_certificate: Option<&PeerCertificate>,
+) -> Result<reqwest::ClientBuilder> {
+    let builder = reqwest::Client::builder();
+    if tls.is_none() && peer_certificate.is_none() {
+        return Ok(builder);
+    }
+    let tls = tls.cloned().unwrap_or_default();
+    let invalid = |reason: String| anyhow::Error::from(TlsConfigError { reason });
+    let read_pem = |field: &str, path: &str| {
+        std::fs::read(path).map_err(|err| invalid(format!("{field} {path}: {err}")))
+    };
+    let provider = Arc::new(ring::default_provider());
+
+    let mut roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    if let Some(path) = &tls.ca_cert_path {
+        let pem = read_pem("ca_cert_path", path)?;
+        let certs = CertificateDer::pem_slice_iter(&pem)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| invalid(format!("ca_cert_path {path}: {err}")))?;
+        if certs.is_empty() {
+            return Err(invalid(format!(
+                "ca_cert_path {path}: no certificates found"
+            )));
+        }
+        for cert in certs {
+            roots
+                .add(cert)
+                .map_err(|err| invalid(format!("ca_cert_path {path}: {err}")))?;
+        }
+    }
+    let bad_identity =
+        |path: &str, err: String| invalid(format!("client certificate {path}: {err}"));
+    let identity = match (&tls.client_cert_path, &tls.client_key_path) {
+        (Some(cert_path), Some(key_path)) => {
+            let chain = CertificateDer::pem_slice_iter(&read_pem("client_cert_path", cert_path)?)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| bad_identity(cert_path, err.to_string()))?;
+            let key = PrivateKeyDer::from_pem_slice(&read_pem("client_key_path", key_path)?)
+                .map_err(|err| bad_identity(cert_path, err.to_string()))?;
+            Some((cert_path, chain, key))
+        }
+        (None, None) => None,
+        _ => {
+            return Err(invalid(
+                "client_cert_path and client_key_path must be set together".into(),
+            ));
+        }
+    };
+    let verifier: Arc<dyn ServerCertVerifier> = if tls.insecure_skip_verify {
+        if !allow_insecure {
+            return Err(invalid(
+                "insecure_skip_verify requires ALLOW_INSECURE_TARGET_TLS".into(),
+            ));
+        }
+        Arc::new(AcceptAnyServerCert(provider.clone()))
+    } else {
+        WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+            .build()
+            .map_err(|err| invalid(format!("trust roots: {err}")))?
+    };
+    let verifier: Arc<dyn ServerCertVerifier> = match peer_certificate {
+        Some(seen) => Arc::new(RecordingVerifier {
+            inner: verifier,
+            seen: seen.clone(),
+        }),
+        None => verifier,
+    };
+
+    let config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|err| invalid(err.to_string()))?
+        .dangerous()
+        .with_custom_certificate_verifier(verifier);
+    let mut config = match identity {
+        Some((cert_path, chain, key)) => config
+            .with_client_auth_cert(chain, key)
+            .map_err(|err| bad_identity(cert_path, err.to_string()))?,
+        None => config.with_no_client_auth(),
+    };
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(builder.use_preconfigured_tls(config))
+}
+
+/// Delegates verification and keeps the end-entity certificate once it passes.
+#[derive(Debug)]
+struct RecordingVerifier {
+    inner: Arc<dyn ServerCertVerifier>,
+    seen: PeerCertificate,
+}
+
+impl ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        )?;
+        *self.seen.0.lock() = Some(end_entity.to_vec());
+        Ok(verified)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+
+    fn root_hint_subjects(&self) -> Option<&[DistinguishedName]> {
+        self.inner.root_hint_subjects()
+    }
+}
+
+/// `insecure_skip_verify`: any certificate is accepted, but handshake signatures are still
+/// checked so the session keys belong to whoever presented it.
+#[derive(Debug)]
+struct AcceptAnyServerCert(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyServerCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
@@ -1,8 +1,12 @@
-use std::{collections::BTreeMap, fs, path::PathBuf};
+use std::{collections::BTreeMap, fs, path::PathBuf, sync::Arc, time::Duration};
 
 use anyhow::{Context, Result, anyhow};
 use clap::Parser;
-use mcp_multi_tool::app::compliance::{ComplianceSuite, ComplianceTarget};
+use mcp_multi_tool::{
+    app::compliance::{CaseResult, ComplianceSuite, ComplianceTarget},
+    infra::compliance_history::ComplianceHistory,
+    shared::types::DEFAULT_COMPLIANCE_HISTORY_RETENTION,
+};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -43,6 +47,18 @@ struct Args {
     #[arg(long)]
     http_auth_token: Option<String>,
 
+    /// List cases whose target was not provided as skipped instead of omitting them
+    #[arg(long)]
+    include_skipped: bool,
+
+    /// Abort the whole suite after this many milliseconds; unfinished cases are reported as timed out
+    #[arg(long)]
+    overall_timeout_ms: Option<u64>,
+
+    /// Print each case's result to stderr as it completes (stdout stays the final report)
+    #[arg(long)]
+    progress: bool,
+
     /// Path to write the JSON report (optional)
     #[arg(long)]
     output_json: Option<PathBuf>,
@@ -50,6 +66,14 @@ struct Args {
     /// Path to write the Markdown report (optional)
     #[arg(long)]
     output_md: Option<PathBuf>,
+
+    /// Sqlite db to append a summary of this run to, for pass-rate trends (optional)
+    #[arg(long)]
+    history_db: Option<PathBuf>,
+
+    /// Most recent runs kept in the history db; older ones are dropped
+    #[arg(long, default_value_t = DEFAULT_COMPLIANCE_HISTORY_RETENTION)]
+    history_retention: usize,
 }
 
 fn parse_env(raw: &str) -> Result<(String, String)> {
@@ -59,6 +83,18 @@ fn parse_env(raw: &str) -> Result<(String, String)> {
     Ok((key.to_string(), value.to_string()))
 }
 
+fn print_progress(case: &CaseResult) {
+    let status = match (&case.skipped, case.passed) {
+        (Some(reason), _) => format!("skipped ({reason})"),
+        (None, true) => "pass".into(),
+        (None, false) => "FAIL".into(),
+    };
+    eprintln!(
+        "[compliance] {} {} {} ms",
+        case.name, status, case.duration_ms
+    );
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
@@ -91,8 +127,16 @@ async fn main() -> Result<()> {
         http_auth_token: args.http_auth_token,
     };
 
-    let suite = ComplianceSuite::new();
-    let report = suite.run(target).await.context("run compliance suite")?;
+    let mut suite = ComplianceSuite::new()
+        .with_include_skipped(args.include_skipped)
+        .with_overall_timeout(args.overall_timeout_ms.map(Duration::from_millis));
+    if args.progress {
+        suite = suite.with_progress(Arc::new(print_progress));
+    }
+    let report = suite
+        .run(target.clone())
+        .await
+        .context("run compliance suite")?;
 
     let json_report = serde_json::to_string_pretty(&report)?;
     println!("{}", json_report);
@@ -106,10 +150,16 @@ async fn main() -> Result<()> {
             .with_context(|| format!("write markdown report to {}", path.display()))?;
     }
 
+    if let (Some(path), Some(run)) = (args.history_db, report.history_entry(&target)) {
+        ComplianceHistory::open(&path, args.history_retention)?
+            .record(&run)
+            .with_context(|| format!("record run in compliance history {}", path.display()))?;
+    }
+
     if !report.passed() {
         eprintln!(
             "compliance pass rate below 95% (actual {:.2}%)",
-            report.pass_rate * 100.0
+            report.pass_rate.unwrap_or_default() * 100.0
         );
         std::process::exit(1);
     }
@@ -0,0 +1,92 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Context, Result, bail};
+use clap::Parser;
+use mcp_multi_tool::infra::{
+    config::AppConfig, outbox::OutboxPartition, outbox_tail::OutboxFollower,
+};
+
+#[derive(Parser, Debug)]
+#[command(
+    author,
+    version,
+    about = "Follow the JSONL outbox and print each new event to stdout."
+)]
+struct Args {
+    /// Outbox file to follow (defaults to the configured OUTBOX_PATH)
+    #[arg(long)]
+    path: Option<PathBuf>,
+
+    /// Emit events already in the file before following new ones
+    #[arg(long)]
+    from_start: bool,
+
+    /// How often to check the file for new lines, in milliseconds
+    #[arg(long, default_value_t = 250)]
+    poll_ms: u64,
+}
+
+/// The file the inspector appends events to, refusing configs where no such single file
+/// exists rather than waiting on a path nothing writes.
+fn configured_path() -> Result<PathBuf> {
+    let config = AppConfig::load().context("load config")?;
+    let main = config.outbox_paths().0;
+    if let Some(db_path) = config.outbox_db_path() {
+        bail!(
+            "events go to the sqlite outbox {} (OUTBOX_DB_PATH), which tail cannot follow",
+            db_path.display()
+        );
+    }
+    if config.outbox_partition == OutboxPartition::Daily {
+        bail!(
+            "OUTBOX_PARTITION=daily writes one file per UTC day next to {}; pass the day's file with --path",
+            main.display()
+        );
+    }
+    Ok(main)
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let path = match args.path {
+        Some(path) => path,
+        None => configured_path()?,
+    };
+    let mut follower = if args.from_start {
+        OutboxFollower::from_start(&path)
+    } else {
+        OutboxFollower::from_end(&path)
+    };
+    let interval = Duration::from_millis(args.poll_ms.max(1));
+    let stdout = io::stdout();
+    loop {
+        let lines = follower.poll()?;
+        let mut out = stdout.lock();
+        for line in lines {
+            if serde_json::from_str::<serde_json::Value>(&line).is_err() {
+                eprintln!("[tail] skipping malformed line: {line}");
+                continue;
+            }
+            if let Err(err) = writeln!(out, "{line}") {
+                // The reader (e.g. `head`) went away; that is a normal way to stop.
+                if err.kind() == io::ErrorKind::BrokenPipe {
+                    return Ok(());
+                }
+                return Err(err).context("write event to stdout");
+            }
+        }
+        if let Err(err) = out.flush() {
+            if err.kind() == io::ErrorKind::BrokenPipe {
+                return Ok(());
+            }
+            return Err(err).context("flush stdout");
+        }
+        drop(out);
+        thread::sleep(interval);
+    }
+}
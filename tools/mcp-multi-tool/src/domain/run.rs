@@ -7,6 +7,7 @@ pub enum RunState {
     Processing,
     Captured,
     Failed,
+    Cancelled,
 }
 
 #[derive(Debug, Clone)]
@@ -15,6 +16,12 @@ pub struct InspectionRun {
     pub state: RunState,
 }
 
+impl Default for InspectionRun {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl InspectionRun {
     pub fn new() -> Self {
         Self {
@@ -34,6 +41,10 @@ impl InspectionRun {
         assert!(!matches!(self.state, RunState::Captured));
         self.state = RunState::Failed;
     }
+    pub fn cancel(&mut self) {
+        assert!(matches!(self.state, RunState::Processing));
+        self.state = RunState::Cancelled;
+    }
 }
 
 impl RunState {
@@ -43,6 +54,7 @@ impl RunState {
             RunState::Processing => "processing",
             RunState::Captured => "captured",
             RunState::Failed => "failed",
+            RunState::Cancelled => "cancelled",
         }
     }
 }
@@ -60,6 +72,14 @@ mod tests {
         assert!(matches!(r.state, RunState::Captured));
     }
 
+    #[test]
+    fn cancel_from_processing() {
+        let mut r = InspectionRun::new();
+        r.start();
+        r.cancel();
+        assert_eq!(r.state.as_str(), "cancelled");
+    }
+
     #[test]
     #[should_panic]
     fn no_skip_states() {
@@ -0,0 +1,230 @@
+use std::fs::create_dir_all;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+
+/// Change in pass rate, as a fraction, below which a trend counts as steady.
+const STEADY_TOLERANCE: f64 = 0.001;
+
+/// Summary of one compliance run as kept in the history db.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ComplianceRun {
+    pub started_at: String,
+    pub finished_at: String,
+    /// The stdio command line, or the HTTP/SSE URL when no command was given.
+    pub target: String,
+    pub pass_rate: f64,
+    /// Cases that ran; skipped cases are not counted.
+    pub cases: u64,
+    /// Whether the run met the 95% pass-rate threshold.
+    pub passed: bool,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TrendDirection {
+    Improving,
+    Regressing,
+    Steady,
+}
+
+/// Pass-rate trend over the queried runs.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ComplianceTrend {
+    pub runs: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_pass_rate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_pass_rate: Option<f64>,
+    /// Latest pass rate minus the mean of the earlier runs; needs at least two runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<TrendDirection>,
+}
+
+impl ComplianceTrend {
+    /// Trend of `runs`, newest first.
+    pub fn of(runs: &[ComplianceRun]) -> Self {
+        let mean = |runs: &[ComplianceRun]| {
+            (!runs.is_empty())
+                .then(|| runs.iter().map(|run| run.pass_rate).sum::<f64>() / runs.len() as f64)
+        };
+        let latest = runs.first().map(|run| run.pass_rate);
+        let change = latest
+            .zip(mean(runs.get(1..).unwrap_or_default()))
+            .map(|(latest, earlier)| latest - earlier);
+        let direction = change.map(|change| {
+            if change > STEADY_TOLERANCE {
+                TrendDirection::Improving
+            } else if change < -STEADY_TOLERANCE {
+                TrendDirection::Regressing
+            } else {
+                TrendDirection::Steady
+            }
+        });
+        Self {
+            runs: runs.len(),
+            latest_pass_rate: latest,
+            mean_pass_rate: mean(runs),
+            change,
+            direction,
+        }
+    }
+}
+
+/// Recent runs, newest first, with their trend.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ComplianceHistoryReport {
+    pub runs: Vec<ComplianceRun>,
+    pub trend: ComplianceTrend,
+}
+
+/// Compliance run summaries in a sqlite db, bounded to the most recent `retention` runs.
+#[derive(Debug)]
+pub struct ComplianceHistory {
+    conn: Mutex<Connection>,
+    retention: usize,
+}
+
+impl ComplianceHistory {
+    pub fn open<P: AsRef<Path>>(path: P, retention: usize) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            create_dir_all(parent).with_context(|| {
+                format!(
+                    "creating directories for compliance history {}",
+                    parent.display()
+                )
+            })?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("open compliance history {}", path.display()))?;
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS compliance_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                started_at TEXT NOT NULL,
+                finished_at TEXT NOT NULL,
+                target TEXT NOT NULL,
+                pass_rate REAL NOT NULL,
+                cases INTEGER NOT NULL,
+                passed INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_compliance_runs_target ON compliance_runs (target);
+            "#,
+        )
+        .context("initialise compliance history schema")?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            retention: retention.max(1),
+        })
+    }
+
+    /// Append `run`, then drop the oldest runs past the retention bound.
+    pub fn record(&self, run: &ComplianceRun) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT INTO compliance_runs (started_at, finished_at, target, pass_rate, cases, passed)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                run.started_at,
+                run.finished_at,
+                run.target,
+                run.pass_rate,
+                run.cases as i64,
+                run.passed
+            ],
+        )
+        .context("insert compliance run")?;
+        conn.execute(
+            "DELETE FROM compliance_runs WHERE id NOT IN
+             (SELECT id FROM compliance_runs ORDER BY id DESC LIMIT ?1)",
+            params![self.retention as i64],
+        )
+        .context("prune compliance history")?;
+        Ok(())
+    }
+
+    /// Up to `limit` most recent runs, newest first, optionally of one `target` only.
+    pub fn recent(&self, target: Option<&str>, limit: usize) -> Result<Vec<ComplianceRun>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT started_at, finished_at, target, pass_rate, cases, passed
+             FROM compliance_runs
+             WHERE ?1 IS NULL OR target = ?1
+             ORDER BY id DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![target, limit as i64], |row| {
+            Ok(ComplianceRun {
+                started_at: row.get(0)?,
+                finished_at: row.get(1)?,
+                target: row.get(2)?,
+                pass_rate: row.get(3)?,
+                cases: row.get::<_, i64>(4)?.max(0) as u64,
+                passed: row.get(5)?,
+            })
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("read compliance history")
+    }
+
+    /// The most recent runs with their pass-rate trend.
+    pub fn report(&self, target: Option<&str>, limit: usize) -> Result<ComplianceHistoryReport> {
+        let runs = self.recent(target, limit)?;
+        let trend = ComplianceTrend::of(&runs);
+        Ok(ComplianceHistoryReport { runs, trend })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(finished_at: &str, target: &str, pass_rate: f64) -> ComplianceRun {
+        ComplianceRun {
+            started_at: finished_at.into(),
+            finished_at: finished_at.into(),
+            target: target.into(),
+            pass_rate,
+            cases: 10,
+            passed: pass_rate >= 0.95,
+        }
+    }
+
+    #[test]
+    fn recorded_runs_are_queried_newest_first_with_their_trend() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let history = ComplianceHistory::open(dir.path().join("history.db"), 10)?;
+        history.record(&run("2026-01-01T00:00:00Z", "mock", 1.0))?;
+        history.record(&run("2026-01-02T00:00:00Z", "mock", 0.8))?;
+
+        let report = history.report(Some("mock"), 10)?;
+        assert_eq!(report.runs.len(), 2);
+        assert_eq!(report.runs[0].finished_at, "2026-01-02T00:00:00Z");
+        assert!(!report.runs[0].passed);
+        assert_eq!(report.trend.latest_pass_rate, Some(0.8));
+        assert_eq!(report.trend.direction, Some(TrendDirection::Regressing));
+        assert!(history.report(Some("other"), 10)?.runs.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn oldest_runs_are_dropped_past_retention() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let history = ComplianceHistory::open(dir.path().join("history.db"), 2)?;
+        for day in 1..=3 {
+            history.record(&run(&format!("2026-01-0{day}T00:00:00Z"), "mock", 1.0))?;
+        }
+        let runs = history.recent(None, 10)?;
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[1].finished_at, "2026-01-02T00:00:00Z");
+        Ok(())
+    }
+}
@@ -1,32 +1,164 @@
 use crate::{
-    infra::metrics::{MetricsServerConfig, TlsConfig},
-    shared::types::ReleaseTrack,
+    infra::{
+        metrics::{MetricsServerConfig, TlsConfig},
+        outbox::{
+            DEFAULT_OUTBOX_APPEND_RETRIES, OutboxBinaryContent, OutboxIndexField, OutboxPartition,
+        },
+    },
+    shared::{
+        idempotency::{IdempotencyLimits, IdempotencyOverflowPolicy},
+        recent_events::DEFAULT_RECENT_EVENTS_CAP,
+        target_aliases::TargetAliases,
+        types::{
+            CallTarget, DEFAULT_COMPLIANCE_HISTORY_RETENTION, DEFAULT_CONNECTION_CACHE_IDLE_TTL_MS,
+            DEFAULT_CONNECTION_CACHE_MAX_SIZE, DEFAULT_DEADLINE_MARGIN_MS,
+            DEFAULT_HANDSHAKE_TIMEOUT_MS, DEFAULT_MAX_JSON_DEPTH, DEFAULT_MAX_JSON_NODES,
+            DEFAULT_MAX_TOOL_LIST_PAGES, DEFAULT_SESSION_IDLE_TIMEOUT_MS, DEFAULT_SPAWN_TIMEOUT_MS,
+            DEFAULT_STREAM_DRAIN_TIMEOUT_MS, HandshakeTimeouts, JsonLimits, PartialStreamPolicy,
+            ReleaseTrack, RetryJitter, TargetProxy, ToolNamePolicy,
+        },
+    },
 };
 use anyhow::{Context, Result, anyhow};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 
 const CONFIG_DIR_ENV: &str = "APP_CONFIG_DIR";
 const CONFIG_PROFILE_ENV: &str = "APP_CONFIG_PROFILE";
 const DEFAULT_CONFIG_DIR: &str = "config";
 const DEFAULT_PROFILE: &str = "default";
+/// Config file, next to the profiles, meant for the `targets` section alone.
+const TARGETS_FILE: &str = "targets";
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AppConfig {
     pub metrics_addr: Option<String>,
     pub allow_insecure_metrics_dev: Option<bool>,
     pub metrics_auth_token: Option<String>,
     pub metrics_tls_cert_path: Option<String>,
     pub metrics_tls_key_path: Option<String>,
+    pub dashboard_enabled: Option<bool>,
+    /// Serve unauthenticated `/healthz` and `/readyz` on the metrics server (default true).
+    pub health_routes_enabled: Option<bool>,
     pub outbox_path: Option<String>,
     pub outbox_dlq_path: Option<String>,
     pub outbox_db_path: Option<String>,
+    /// Sqlite db `inspector_compliance_history` reads compliance run summaries from.
+    pub compliance_history_db: Option<String>,
+    /// Most recent compliance runs kept in the history db.
+    pub compliance_history_retention: Option<usize>,
+    pub outbox_compress: Option<bool>,
+    pub outbox_append_retries: Option<u32>,
+    /// Share of successful events persisted (0.0–1.0); failures are always kept.
+    pub outbox_sample_rate: Option<f64>,
+    /// Event fields copied into indexed sqlite columns; empty leaves the schema untouched.
+    #[serde(default)]
+    pub outbox_index_fields: Vec<OutboxIndexField>,
+    #[serde(default)]
+    pub outbox_partition: OutboxPartition,
+    #[serde(default)]
+    pub outbox_binary_content: OutboxBinaryContent,
+    /// Top-level event fields dropped before an event is persisted, e.g. `request`.
+    #[serde(default)]
+    pub outbox_exclude_fields: Vec<String>,
+    /// Serialized size past which an event goes to the DLQ, leaving a summary in the primary.
+    pub outbox_max_event_bytes: Option<usize>,
+    /// Skip non-JSON-RPC lines a stdio target prints before its first frame.
+    pub stdio_lenient_framing: Option<bool>,
+    /// Start every stdio target with a cleared environment, as if each set `env_clear`.
+    pub stdio_env_clear: Option<bool>,
+    /// Honour `tls.insecure_skip_verify` on SSE/HTTP targets; otherwise such requests fail.
+    pub allow_insecure_target_tls: Option<bool>,
+    /// Honour `shell: true` on stdio targets; otherwise such requests fail.
+    pub allow_shell_targets: Option<bool>,
+    /// Let a call target set several of `stdio`/`sse`/`http`/`unix` and pick one by the
+    /// legacy precedence (http, sse, unix, stdio) instead of failing with `AMBIGUOUS_TARGET`.
+    pub allow_ambiguous_target: Option<bool>,
+    /// Proxy URL for SSE/HTTP targets without their own `proxy`; credentials may be embedded.
+    pub target_proxy: Option<String>,
+    /// Record unhandled notification methods from stdio targets into the call trace.
+    pub capture_unknown_notifications: Option<bool>,
+    /// Record each successful call's negotiated target capabilities on its outbox event.
+    pub capture_target_capabilities: Option<bool>,
+    /// Bound on spawning a stdio target, separate from the handshake timeout.
+    pub stdio_spawn_timeout_ms: Option<u64>,
+    /// Default bound on a whole streaming `inspector_call`; unset leaves streams unbounded.
+    pub stream_max_duration_ms: Option<u64>,
+    /// Default bound on the tool invocation after the handshake; unset waits indefinitely.
+    pub call_timeout_ms: Option<u64>,
+    /// Default wait for trailing progress events after a streaming call's final result.
+    pub stream_drain_timeout_ms: Option<u64>,
+    /// Taken off a caller's `_meta.deadlineMs` before it bounds the downstream tool call.
+    pub deadline_margin_ms: Option<u64>,
+    /// Oldest MCP protocol version a target may negotiate before calls to it are refused.
+    pub min_protocol_version: Option<String>,
+    /// Cap on `stream: true` calls in flight at once; unset leaves streams uncapped.
+    pub max_concurrent_streams: Option<usize>,
+    /// Idle target sessions kept for reuse by list/describe/call; `0` disables reuse.
+    pub connection_cache_max_size: Option<usize>,
+    /// How long an idle target session is kept for reuse; `0` disables reuse.
+    pub connection_cache_idle_ttl_ms: Option<u64>,
+    /// How long an `inspector_connect` session may go unused before it is closed.
+    pub session_idle_timeout_ms: Option<u64>,
+    /// Expose state-changing admin tools such as idempotency export/import.
+    pub admin_tools_enabled: Option<bool>,
+    /// Extra handshake attempts `inspector_call` makes against a target that fails to connect.
+    pub handshake_retries: Option<u32>,
+    /// Randomization applied to the handshake retry backoff.
+    #[serde(default)]
+    pub handshake_retry_jitter: RetryJitter,
+    /// Cap on `tools/list` pages followed when listing a target's tools.
+    pub max_tool_list_pages: Option<u32>,
+    pub default_handshake_timeout_ms: Option<u64>,
+    pub default_handshake_timeout_ms_stdio: Option<u64>,
+    pub default_handshake_timeout_ms_sse: Option<u64>,
+    pub default_handshake_timeout_ms_http: Option<u64>,
     #[serde(default)]
     pub idempotency_conflict_policy: IdempotencyConflictPolicy,
+    pub max_idempotency_records: Option<usize>,
+    /// Hash tool, arguments and target into a key when a call carries none.
+    pub idempotency_auto_key: Option<bool>,
+    #[serde(default)]
+    pub idempotency_overflow_policy: IdempotencyOverflowPolicy,
+    #[serde(default)]
+    pub empty_result_policy: EmptyResultPolicy,
+    /// What a streaming call returns when the target drops before the final result.
+    #[serde(default)]
+    pub partial_stream_policy: PartialStreamPolicy,
+    #[serde(default)]
+    pub duplicate_external_ref_policy: DuplicateExternalRefPolicy,
+    /// Whether dotted downstream tool names are exposed with underscores for clients that
+    /// mangle dots.
+    #[serde(default)]
+    pub dotted_tool_name_policy: DottedToolNamePolicy,
+    pub recent_events_cap: Option<usize>,
+    /// Tools whose stale in-flight runs the reaper leaves alone (known long runners).
+    #[serde(default)]
+    pub reaper_exempt_tools: Vec<String>,
+    /// Glob patterns of downstream tool names `inspector_call` may invoke; empty allows all.
+    #[serde(default)]
+    pub downstream_tool_allowlist: Vec<String>,
+    /// Glob patterns of downstream tool names `inspector_call` refuses; wins over the allowlist.
+    #[serde(default)]
+    pub downstream_tool_denylist: Vec<String>,
+    /// Deepest `arguments_json` nesting `inspector_call` accepts.
+    pub max_json_depth: Option<usize>,
+    /// Most values `arguments_json` may contain.
+    pub max_json_nodes: Option<usize>,
+    /// Per-tool base arguments merged under the caller's `arguments_json` (caller wins).
+    #[serde(default)]
+    pub tool_arg_defaults: BTreeMap<String, Map<String, Value>>,
+    /// Named targets a request can pick with `target_alias`, each one `stdio`, `sse`,
+    /// `http` or `unix` block. Also read from `targets.toml` in the config directory.
+    #[serde(default)]
+    pub targets: BTreeMap<String, CallTarget>,
     #[serde(default)]
     pub error_budget: ErrorBudgetSettings,
     #[serde(default)]
@@ -49,18 +181,25 @@ impl AppConfig {
         if dir.exists() {
             let mut profiles = Vec::new();
             profiles.push(DEFAULT_PROFILE.to_string());
-            if let Ok(active_profile) = env::var(CONFIG_PROFILE_ENV) {
-                if !active_profile.trim().is_empty() && active_profile != DEFAULT_PROFILE {
-                    profiles.push(active_profile);
-                }
+            if let Ok(active_profile) = env::var(CONFIG_PROFILE_ENV)
+                && !active_profile.trim().is_empty()
+                && active_profile != DEFAULT_PROFILE
+            {
+                profiles.push(active_profile);
             }
             profiles.push("local".to_string());
 
-            for profile in profiles {
+            for (index, profile) in profiles.into_iter().enumerate() {
                 let candidate = dir.join(format!("{profile}.toml"));
                 if let Some(overlay) = ConfigOverlay::from_file(&candidate)? {
                     overlays.push(overlay);
                 }
+                if index == 0 {
+                    let targets = dir.join(format!("{TARGETS_FILE}.toml"));
+                    if let Some(overlay) = ConfigOverlay::targets_from_file(&targets)? {
+                        overlays.push(overlay);
+                    }
+                }
             }
         }
 
@@ -69,6 +208,7 @@ impl AppConfig {
         for overlay in overlays {
             config.apply_overlay(overlay);
         }
+        config.error_budget.validate()?;
 
         Ok(config)
     }
@@ -85,10 +225,189 @@ impl AppConfig {
         (PathBuf::from(main), PathBuf::from(dlq))
     }
 
+    /// The configured `targets`, checked to define one target each.
+    pub fn target_aliases(&self) -> Result<TargetAliases> {
+        TargetAliases::new(self.targets.clone())
+    }
+
     pub fn outbox_db_path(&self) -> Option<PathBuf> {
-        self.outbox_db_path
-            .as_deref()
-            .map(|path| PathBuf::from(path))
+        self.outbox_db_path.as_deref().map(PathBuf::from)
+    }
+
+    pub fn compliance_history_db(&self) -> Option<PathBuf> {
+        self.compliance_history_db.as_deref().map(PathBuf::from)
+    }
+
+    pub fn compliance_history_retention(&self) -> usize {
+        self.compliance_history_retention
+            .unwrap_or(DEFAULT_COMPLIANCE_HISTORY_RETENTION)
+    }
+
+    pub fn idempotency_limits(&self) -> IdempotencyLimits {
+        IdempotencyLimits {
+            max_records: self.max_idempotency_records,
+            overflow_policy: self.idempotency_overflow_policy,
+        }
+    }
+
+    pub fn recent_events_cap(&self) -> usize {
+        self.recent_events_cap.unwrap_or(DEFAULT_RECENT_EVENTS_CAP)
+    }
+
+    pub fn outbox_compress(&self) -> bool {
+        self.outbox_compress.unwrap_or(false)
+    }
+
+    pub fn idempotency_auto_key(&self) -> bool {
+        self.idempotency_auto_key.unwrap_or(false)
+    }
+
+    pub fn stdio_lenient_framing(&self) -> bool {
+        self.stdio_lenient_framing.unwrap_or(false)
+    }
+
+    pub fn stdio_env_clear(&self) -> bool {
+        self.stdio_env_clear.unwrap_or(false)
+    }
+
+    pub fn allow_insecure_target_tls(&self) -> bool {
+        self.allow_insecure_target_tls.unwrap_or(false)
+    }
+
+    pub fn allow_shell_targets(&self) -> bool {
+        self.allow_shell_targets.unwrap_or(false)
+    }
+
+    pub fn allow_ambiguous_target(&self) -> bool {
+        self.allow_ambiguous_target.unwrap_or(false)
+    }
+
+    pub fn target_proxy(&self) -> Option<TargetProxy> {
+        self.target_proxy
+            .as_ref()
+            .filter(|url| !url.trim().is_empty())
+            .map(|url| TargetProxy {
+                url: url.trim().to_string(),
+                ..TargetProxy::default()
+            })
+    }
+
+    pub fn capture_unknown_notifications(&self) -> bool {
+        self.capture_unknown_notifications.unwrap_or(false)
+    }
+
+    pub fn capture_target_capabilities(&self) -> bool {
+        self.capture_target_capabilities.unwrap_or(false)
+    }
+
+    pub fn outbox_append_retries(&self) -> u32 {
+        self.outbox_append_retries
+            .unwrap_or(DEFAULT_OUTBOX_APPEND_RETRIES)
+    }
+
+    pub fn outbox_sample_rate(&self) -> f64 {
+        self.outbox_sample_rate.unwrap_or(1.0).clamp(0.0, 1.0)
+    }
+
+    /// `None` (no limit) when unset or `0`.
+    pub fn outbox_max_event_bytes(&self) -> Option<usize> {
+        self.outbox_max_event_bytes.filter(|limit| *limit > 0)
+    }
+
+    pub fn json_limits(&self) -> JsonLimits {
+        JsonLimits {
+            max_depth: self.max_json_depth.unwrap_or(DEFAULT_MAX_JSON_DEPTH),
+            max_nodes: self.max_json_nodes.unwrap_or(DEFAULT_MAX_JSON_NODES),
+        }
+    }
+
+    pub fn tool_name_policy(&self) -> ToolNamePolicy {
+        ToolNamePolicy {
+            allow: self.downstream_tool_allowlist.clone(),
+            deny: self.downstream_tool_denylist.clone(),
+        }
+    }
+
+    pub fn admin_tools_enabled(&self) -> bool {
+        self.admin_tools_enabled.unwrap_or(false)
+    }
+
+    pub fn deadline_margin(&self) -> Duration {
+        Duration::from_millis(
+            self.deadline_margin_ms
+                .unwrap_or(DEFAULT_DEADLINE_MARGIN_MS),
+        )
+    }
+
+    pub fn session_idle_timeout(&self) -> Duration {
+        Duration::from_millis(
+            self.session_idle_timeout_ms
+                .unwrap_or(DEFAULT_SESSION_IDLE_TIMEOUT_MS),
+        )
+    }
+
+    pub fn connection_cache_max_size(&self) -> usize {
+        self.connection_cache_max_size
+            .unwrap_or(DEFAULT_CONNECTION_CACHE_MAX_SIZE)
+    }
+
+    pub fn connection_cache_idle_ttl(&self) -> Duration {
+        Duration::from_millis(
+            self.connection_cache_idle_ttl_ms
+                .unwrap_or(DEFAULT_CONNECTION_CACHE_IDLE_TTL_MS),
+        )
+    }
+
+    pub fn max_concurrent_streams(&self) -> Option<usize> {
+        self.max_concurrent_streams.filter(|limit| *limit > 0)
+    }
+
+    pub fn call_timeout(&self) -> Option<Duration> {
+        self.call_timeout_ms
+            .filter(|ms| *ms > 0)
+            .map(Duration::from_millis)
+    }
+
+    pub fn stream_drain_timeout(&self) -> Duration {
+        Duration::from_millis(
+            self.stream_drain_timeout_ms
+                .unwrap_or(DEFAULT_STREAM_DRAIN_TIMEOUT_MS),
+        )
+    }
+
+    pub fn stream_max_duration(&self) -> Option<Duration> {
+        self.stream_max_duration_ms
+            .filter(|ms| *ms > 0)
+            .map(Duration::from_millis)
+    }
+
+    pub fn stdio_spawn_timeout(&self) -> Duration {
+        Duration::from_millis(
+            self.stdio_spawn_timeout_ms
+                .unwrap_or(DEFAULT_SPAWN_TIMEOUT_MS),
+        )
+    }
+
+    pub fn handshake_retries(&self) -> u32 {
+        self.handshake_retries.unwrap_or(0)
+    }
+
+    pub fn max_tool_list_pages(&self) -> u32 {
+        self.max_tool_list_pages
+            .unwrap_or(DEFAULT_MAX_TOOL_LIST_PAGES)
+    }
+
+    /// Per-transport handshake defaults; each falls back to `default_handshake_timeout_ms`.
+    pub fn handshake_timeouts(&self) -> HandshakeTimeouts {
+        let global = self
+            .default_handshake_timeout_ms
+            .unwrap_or(DEFAULT_HANDSHAKE_TIMEOUT_MS);
+        let pick = |value: Option<u64>| Duration::from_millis(value.unwrap_or(global));
+        HandshakeTimeouts {
+            stdio: pick(self.default_handshake_timeout_ms_stdio),
+            sse: pick(self.default_handshake_timeout_ms_sse),
+            http: pick(self.default_handshake_timeout_ms_http),
+        }
     }
 
     fn apply_overlay(&mut self, overlay: ConfigOverlay) {
@@ -107,6 +426,12 @@ impl AppConfig {
         if let Some(value) = overlay.metrics_tls_key_path {
             self.metrics_tls_key_path = Some(value);
         }
+        if let Some(value) = overlay.dashboard_enabled {
+            self.dashboard_enabled = Some(value);
+        }
+        if let Some(value) = overlay.health_routes_enabled {
+            self.health_routes_enabled = Some(value);
+        }
         if let Some(value) = overlay.outbox_path {
             self.outbox_path = Some(value);
         }
@@ -116,9 +441,162 @@ impl AppConfig {
         if let Some(value) = overlay.outbox_db_path {
             self.outbox_db_path = Some(value);
         }
+        if let Some(value) = overlay.compliance_history_db {
+            self.compliance_history_db = Some(value);
+        }
+        if let Some(value) = overlay.compliance_history_retention {
+            self.compliance_history_retention = Some(value);
+        }
+        if let Some(value) = overlay.outbox_compress {
+            self.outbox_compress = Some(value);
+        }
+        if let Some(value) = overlay.outbox_append_retries {
+            self.outbox_append_retries = Some(value);
+        }
+        if let Some(value) = overlay.outbox_sample_rate {
+            self.outbox_sample_rate = Some(value);
+        }
+        if let Some(fields) = overlay.outbox_index_fields {
+            self.outbox_index_fields = fields;
+        }
+        if let Some(fields) = overlay.outbox_exclude_fields {
+            self.outbox_exclude_fields = fields;
+        }
+        if let Some(value) = overlay.outbox_max_event_bytes {
+            self.outbox_max_event_bytes = Some(value);
+        }
+        if let Some(partition) = overlay.outbox_partition {
+            self.outbox_partition = partition;
+        }
+        if let Some(mode) = overlay.outbox_binary_content {
+            self.outbox_binary_content = mode;
+        }
+        if let Some(value) = overlay.stdio_lenient_framing {
+            self.stdio_lenient_framing = Some(value);
+        }
+        if let Some(value) = overlay.stdio_env_clear {
+            self.stdio_env_clear = Some(value);
+        }
+        if let Some(value) = overlay.allow_insecure_target_tls {
+            self.allow_insecure_target_tls = Some(value);
+        }
+        if let Some(value) = overlay.allow_shell_targets {
+            self.allow_shell_targets = Some(value);
+        }
+        if let Some(value) = overlay.allow_ambiguous_target {
+            self.allow_ambiguous_target = Some(value);
+        }
+        if let Some(value) = overlay.target_proxy {
+            self.target_proxy = Some(value);
+        }
+        if let Some(value) = overlay.capture_unknown_notifications {
+            self.capture_unknown_notifications = Some(value);
+        }
+        if let Some(value) = overlay.capture_target_capabilities {
+            self.capture_target_capabilities = Some(value);
+        }
+        if let Some(value) = overlay.stdio_spawn_timeout_ms {
+            self.stdio_spawn_timeout_ms = Some(value);
+        }
+        if let Some(value) = overlay.stream_max_duration_ms {
+            self.stream_max_duration_ms = Some(value);
+        }
+        if let Some(value) = overlay.call_timeout_ms {
+            self.call_timeout_ms = Some(value);
+        }
+        if let Some(value) = overlay.stream_drain_timeout_ms {
+            self.stream_drain_timeout_ms = Some(value);
+        }
+        if let Some(value) = overlay.deadline_margin_ms {
+            self.deadline_margin_ms = Some(value);
+        }
+        if let Some(value) = overlay.min_protocol_version {
+            self.min_protocol_version = Some(value);
+        }
+        if let Some(value) = overlay.connection_cache_max_size {
+            self.connection_cache_max_size = Some(value);
+        }
+        if let Some(value) = overlay.connection_cache_idle_ttl_ms {
+            self.connection_cache_idle_ttl_ms = Some(value);
+        }
+        if let Some(value) = overlay.session_idle_timeout_ms {
+            self.session_idle_timeout_ms = Some(value);
+        }
+        if let Some(value) = overlay.max_concurrent_streams {
+            self.max_concurrent_streams = Some(value);
+        }
+        if let Some(value) = overlay.admin_tools_enabled {
+            self.admin_tools_enabled = Some(value);
+        }
+        if let Some(value) = overlay.handshake_retries {
+            self.handshake_retries = Some(value);
+        }
+        if let Some(value) = overlay.max_tool_list_pages {
+            self.max_tool_list_pages = Some(value);
+        }
+        if let Some(value) = overlay.default_handshake_timeout_ms {
+            self.default_handshake_timeout_ms = Some(value);
+        }
+        if let Some(value) = overlay.default_handshake_timeout_ms_stdio {
+            self.default_handshake_timeout_ms_stdio = Some(value);
+        }
+        if let Some(value) = overlay.default_handshake_timeout_ms_sse {
+            self.default_handshake_timeout_ms_sse = Some(value);
+        }
+        if let Some(value) = overlay.default_handshake_timeout_ms_http {
+            self.default_handshake_timeout_ms_http = Some(value);
+        }
         if let Some(policy) = overlay.idempotency_conflict_policy {
             self.idempotency_conflict_policy = policy;
         }
+        if let Some(value) = overlay.max_idempotency_records {
+            self.max_idempotency_records = Some(value);
+        }
+        if let Some(value) = overlay.idempotency_auto_key {
+            self.idempotency_auto_key = Some(value);
+        }
+        if let Some(policy) = overlay.idempotency_overflow_policy {
+            self.idempotency_overflow_policy = policy;
+        }
+        if let Some(policy) = overlay.empty_result_policy {
+            self.empty_result_policy = policy;
+        }
+        if let Some(policy) = overlay.partial_stream_policy {
+            self.partial_stream_policy = policy;
+        }
+        if let Some(jitter) = overlay.handshake_retry_jitter {
+            self.handshake_retry_jitter = jitter;
+        }
+        if let Some(policy) = overlay.duplicate_external_ref_policy {
+            self.duplicate_external_ref_policy = policy;
+        }
+        if let Some(policy) = overlay.dotted_tool_name_policy {
+            self.dotted_tool_name_policy = policy;
+        }
+        if let Some(value) = overlay.recent_events_cap {
+            self.recent_events_cap = Some(value);
+        }
+        if let Some(tools) = overlay.reaper_exempt_tools {
+            self.reaper_exempt_tools = tools;
+        }
+        if let Some(patterns) = overlay.downstream_tool_allowlist {
+            self.downstream_tool_allowlist = patterns;
+        }
+        if let Some(patterns) = overlay.downstream_tool_denylist {
+            self.downstream_tool_denylist = patterns;
+        }
+        if let Some(value) = overlay.max_json_depth {
+            self.max_json_depth = Some(value);
+        }
+        if let Some(value) = overlay.max_json_nodes {
+            self.max_json_nodes = Some(value);
+        }
+        if let Some(defaults) = overlay.tool_arg_defaults {
+            self.tool_arg_defaults = defaults;
+        }
+        if let Some(targets) = overlay.targets {
+            self.targets.extend(targets);
+        }
         if let Some(budget) = overlay.error_budget {
             self.error_budget.apply_overlay(budget);
         }
@@ -157,28 +635,12 @@ impl AppConfig {
             auth_token: self.metrics_auth_token.clone(),
             allow_insecure,
             tls,
+            dashboard_enabled: self.dashboard_enabled.unwrap_or(false),
+            health_routes_enabled: self.health_routes_enabled.unwrap_or(true),
         }))
     }
 }
 
-impl Default for AppConfig {
-    fn default() -> Self {
-        Self {
-            metrics_addr: None,
-            allow_insecure_metrics_dev: None,
-            metrics_auth_token: None,
-            metrics_tls_cert_path: None,
-            metrics_tls_key_path: None,
-            outbox_path: None,
-            outbox_dlq_path: None,
-            outbox_db_path: None,
-            idempotency_conflict_policy: IdempotencyConflictPolicy::default(),
-            error_budget: ErrorBudgetSettings::default(),
-            release_track: ReleaseTrack::default(),
-        }
-    }
-}
-
 #[derive(Debug, Default, Deserialize)]
 struct ConfigOverlay {
     metrics_addr: Option<String>,
@@ -186,10 +648,63 @@ struct ConfigOverlay {
     metrics_auth_token: Option<String>,
     metrics_tls_cert_path: Option<String>,
     metrics_tls_key_path: Option<String>,
+    dashboard_enabled: Option<bool>,
+    health_routes_enabled: Option<bool>,
     outbox_path: Option<String>,
     outbox_dlq_path: Option<String>,
     outbox_db_path: Option<String>,
+    compliance_history_db: Option<String>,
+    compliance_history_retention: Option<usize>,
+    outbox_compress: Option<bool>,
+    outbox_append_retries: Option<u32>,
+    outbox_sample_rate: Option<f64>,
+    outbox_index_fields: Option<Vec<OutboxIndexField>>,
+    outbox_exclude_fields: Option<Vec<String>>,
+    outbox_max_event_bytes: Option<usize>,
+    outbox_partition: Option<OutboxPartition>,
+    outbox_binary_content: Option<OutboxBinaryContent>,
+    stdio_lenient_framing: Option<bool>,
+    stdio_env_clear: Option<bool>,
+    allow_insecure_target_tls: Option<bool>,
+    allow_shell_targets: Option<bool>,
+    allow_ambiguous_target: Option<bool>,
+    target_proxy: Option<String>,
+    capture_unknown_notifications: Option<bool>,
+    capture_target_capabilities: Option<bool>,
+    stdio_spawn_timeout_ms: Option<u64>,
+    stream_max_duration_ms: Option<u64>,
+    call_timeout_ms: Option<u64>,
+    stream_drain_timeout_ms: Option<u64>,
+    deadline_margin_ms: Option<u64>,
+    min_protocol_version: Option<String>,
+    max_concurrent_streams: Option<usize>,
+    connection_cache_max_size: Option<usize>,
+    connection_cache_idle_ttl_ms: Option<u64>,
+    session_idle_timeout_ms: Option<u64>,
+    admin_tools_enabled: Option<bool>,
+    handshake_retries: Option<u32>,
+    max_tool_list_pages: Option<u32>,
+    default_handshake_timeout_ms: Option<u64>,
+    default_handshake_timeout_ms_stdio: Option<u64>,
+    default_handshake_timeout_ms_sse: Option<u64>,
+    default_handshake_timeout_ms_http: Option<u64>,
     idempotency_conflict_policy: Option<IdempotencyConflictPolicy>,
+    max_idempotency_records: Option<usize>,
+    idempotency_auto_key: Option<bool>,
+    idempotency_overflow_policy: Option<IdempotencyOverflowPolicy>,
+    empty_result_policy: Option<EmptyResultPolicy>,
+    partial_stream_policy: Option<PartialStreamPolicy>,
+    handshake_retry_jitter: Option<RetryJitter>,
+    duplicate_external_ref_policy: Option<DuplicateExternalRefPolicy>,
+    dotted_tool_name_policy: Option<DottedToolNamePolicy>,
+    recent_events_cap: Option<usize>,
+    reaper_exempt_tools: Option<Vec<String>>,
+    downstream_tool_allowlist: Option<Vec<String>>,
+    downstream_tool_denylist: Option<Vec<String>>,
+    max_json_depth: Option<usize>,
+    max_json_nodes: Option<usize>,
+    tool_arg_defaults: Option<BTreeMap<String, Map<String, Value>>>,
+    targets: Option<BTreeMap<String, CallTarget>>,
     #[serde(default)]
     error_budget: Option<ErrorBudgetOverlay>,
     #[serde(default)]
@@ -208,6 +723,26 @@ impl ConfigOverlay {
         Ok(Some(overlay))
     }
 
+    /// Only the `targets` table of `path`; any other setting there is ignored.
+    fn targets_from_file(path: &Path) -> Result<Option<Self>> {
+        #[derive(Deserialize)]
+        struct TargetsFile {
+            targets: Option<BTreeMap<String, CallTarget>>,
+        }
+
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("read config file {}", path.display()))?;
+        let file: TargetsFile = toml::from_str(&contents)
+            .with_context(|| format!("parse config file {}", path.display()))?;
+        Ok(Some(Self {
+            targets: file.targets,
+            ..Self::default()
+        }))
+    }
+
     fn from_env() -> Self {
         let metrics_addr = env::var("METRICS_ADDR").ok();
         let allow_insecure_metrics_dev = env::var("ALLOW_INSECURE_METRICS_DEV")
@@ -216,12 +751,184 @@ impl ConfigOverlay {
         let metrics_auth_token = env::var("METRICS_AUTH_TOKEN").ok();
         let metrics_tls_cert_path = env::var("METRICS_TLS_CERT_PATH").ok();
         let metrics_tls_key_path = env::var("METRICS_TLS_KEY_PATH").ok();
+        let dashboard_enabled = env::var("DASHBOARD_ENABLED")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok());
+        let health_routes_enabled = env::var("HEALTH_ROUTES_ENABLED")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok());
         let outbox_path = env::var("OUTBOX_PATH").ok();
         let outbox_dlq_path = env::var("OUTBOX_DLQ_PATH").ok();
         let outbox_db_path = env::var("OUTBOX_DB_PATH").ok();
+        let compliance_history_db = env::var("COMPLIANCE_HISTORY_DB").ok();
+        let compliance_history_retention = env::var("COMPLIANCE_HISTORY_RETENTION")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok());
+        let outbox_compress = env::var("OUTBOX_COMPRESS")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok());
+        let outbox_append_retries = env::var("OUTBOX_APPEND_RETRIES")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok());
+        let outbox_sample_rate = env::var("OUTBOX_SAMPLE_RATE")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok());
+        let outbox_index_fields = env::var("OUTBOX_INDEX_FIELDS").ok().map(|raw| {
+            raw.split(',')
+                .filter(|name| !name.trim().is_empty())
+                .filter_map(|name| OutboxIndexField::from_str(name).ok())
+                .collect()
+        });
+        let outbox_exclude_fields = env::var("OUTBOX_EXCLUDE_FIELDS").ok().map(|raw| {
+            raw.split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect()
+        });
+        let outbox_max_event_bytes = env::var("OUTBOX_MAX_EVENT_BYTES")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok());
+        let outbox_partition = env::var("OUTBOX_PARTITION")
+            .ok()
+            .and_then(|raw| OutboxPartition::from_str(&raw).ok());
+        let outbox_binary_content = env::var("OUTBOX_BINARY_CONTENT")
+            .ok()
+            .and_then(|raw| OutboxBinaryContent::from_str(&raw).ok());
+        let stdio_lenient_framing = env::var("STDIO_LENIENT_FRAMING")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok());
+        let stdio_env_clear = env::var("STDIO_ENV_CLEAR")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok());
+        let allow_insecure_target_tls = env::var("ALLOW_INSECURE_TARGET_TLS")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok());
+        let allow_shell_targets = env::var("ALLOW_SHELL_TARGETS")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok());
+        let allow_ambiguous_target = env::var("ALLOW_AMBIGUOUS_TARGET")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok());
+        let target_proxy = env::var("TARGET_PROXY").ok();
+        let capture_unknown_notifications = env::var("CAPTURE_UNKNOWN_NOTIFICATIONS")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok());
+        let capture_target_capabilities = env::var("CAPTURE_TARGET_CAPABILITIES")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok());
+        let stdio_spawn_timeout_ms = env::var("STDIO_SPAWN_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok());
+        let stream_max_duration_ms = env::var("STREAM_MAX_DURATION_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok());
+        let call_timeout_ms = env::var("CALL_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok());
+        let stream_drain_timeout_ms = env::var("STREAM_DRAIN_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok());
+        let deadline_margin_ms = env::var("DEADLINE_MARGIN_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok());
+        let min_protocol_version = env::var("MIN_PROTOCOL_VERSION")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+        let max_concurrent_streams = env::var("MAX_CONCURRENT_STREAMS")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok());
+        let connection_cache_max_size = env::var("CONNECTION_CACHE_MAX_SIZE")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok());
+        let connection_cache_idle_ttl_ms = env::var("CONNECTION_CACHE_IDLE_TTL_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok());
+        let session_idle_timeout_ms = env::var("SESSION_IDLE_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok());
+        let admin_tools_enabled = env::var("ADMIN_TOOLS_ENABLED")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok());
+        let handshake_retries = env::var("HANDSHAKE_RETRIES")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok());
+        let max_tool_list_pages = env::var("MAX_TOOL_LIST_PAGES")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok());
+        let default_handshake_timeout_ms = env::var("DEFAULT_HANDSHAKE_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok());
+        let default_handshake_timeout_ms_stdio = env::var("DEFAULT_HANDSHAKE_TIMEOUT_MS_STDIO")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok());
+        let default_handshake_timeout_ms_sse = env::var("DEFAULT_HANDSHAKE_TIMEOUT_MS_SSE")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok());
+        let default_handshake_timeout_ms_http = env::var("DEFAULT_HANDSHAKE_TIMEOUT_MS_HTTP")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok());
         let idempotency_conflict_policy = env::var("IDEMPOTENCY_CONFLICT_POLICY")
             .ok()
             .and_then(|raw| IdempotencyConflictPolicy::from_str(&raw).ok());
+        let max_idempotency_records = env::var("MAX_IDEMPOTENCY_RECORDS")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok());
+        let idempotency_auto_key = env::var("IDEMPOTENCY_AUTO_KEY")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok());
+        let idempotency_overflow_policy = env::var("IDEMPOTENCY_OVERFLOW_POLICY")
+            .ok()
+            .and_then(|raw| IdempotencyOverflowPolicy::from_str(&raw).ok());
+        let empty_result_policy = env::var("EMPTY_RESULT_POLICY")
+            .ok()
+            .and_then(|raw| EmptyResultPolicy::from_str(&raw).ok());
+        let partial_stream_policy = env::var("PARTIAL_STREAM_POLICY")
+            .ok()
+            .and_then(|raw| PartialStreamPolicy::from_str(&raw).ok());
+        let handshake_retry_jitter = env::var("HANDSHAKE_RETRY_JITTER")
+            .ok()
+            .and_then(|raw| RetryJitter::from_str(&raw).ok());
+        let duplicate_external_ref_policy = env::var("DUPLICATE_EXTERNAL_REF_POLICY")
+            .ok()
+            .and_then(|raw| DuplicateExternalRefPolicy::from_str(&raw).ok());
+        let dotted_tool_name_policy = env::var("DOTTED_TOOL_NAME_POLICY")
+            .ok()
+            .and_then(|raw| DottedToolNamePolicy::from_str(&raw).ok());
+        let recent_events_cap = env::var("RECENT_EVENTS_CAP")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok());
+        let reaper_exempt_tools = env::var("REAPER_EXEMPT_TOOLS").ok().map(|raw| {
+            raw.split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect()
+        });
+        let downstream_tool_allowlist = env::var("DOWNSTREAM_TOOL_ALLOWLIST").ok().map(|raw| {
+            raw.split(',')
+                .map(str::trim)
+                .filter(|pattern| !pattern.is_empty())
+                .map(str::to_string)
+                .collect()
+        });
+        let downstream_tool_denylist = env::var("DOWNSTREAM_TOOL_DENYLIST").ok().map(|raw| {
+            raw.split(',')
+                .map(str::trim)
+                .filter(|pattern| !pattern.is_empty())
+                .map(str::to_string)
+                .collect()
+        });
+        let max_json_depth = env::var("MAX_JSON_DEPTH")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok());
+        let max_json_nodes = env::var("MAX_JSON_NODES")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok());
+        let tool_arg_defaults = env::var("TOOL_ARG_DEFAULTS")
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok());
         let error_budget = ErrorBudgetOverlay::from_env();
         let release_track = env::var("RELEASE_TRACK")
             .ok()
@@ -232,30 +939,78 @@ impl ConfigOverlay {
             metrics_auth_token,
             metrics_tls_cert_path,
             metrics_tls_key_path,
+            dashboard_enabled,
+            health_routes_enabled,
             outbox_path,
             outbox_dlq_path,
             outbox_db_path,
+            compliance_history_db,
+            compliance_history_retention,
+            outbox_compress,
+            outbox_append_retries,
+            outbox_sample_rate,
+            outbox_index_fields,
+            outbox_exclude_fields,
+            outbox_max_event_bytes,
+            outbox_partition,
+            outbox_binary_content,
+            stdio_lenient_framing,
+            stdio_env_clear,
+            allow_insecure_target_tls,
+            allow_shell_targets,
+            allow_ambiguous_target,
+            target_proxy,
+            capture_unknown_notifications,
+            capture_target_capabilities,
+            stdio_spawn_timeout_ms,
+            stream_max_duration_ms,
+            call_timeout_ms,
+            stream_drain_timeout_ms,
+            deadline_margin_ms,
+            min_protocol_version,
+            max_concurrent_streams,
+            connection_cache_max_size,
+            connection_cache_idle_ttl_ms,
+            session_idle_timeout_ms,
+            admin_tools_enabled,
+            handshake_retries,
+            max_tool_list_pages,
+            default_handshake_timeout_ms,
+            default_handshake_timeout_ms_stdio,
+            default_handshake_timeout_ms_sse,
+            default_handshake_timeout_ms_http,
             idempotency_conflict_policy,
+            max_idempotency_records,
+            idempotency_auto_key,
+            idempotency_overflow_policy,
+            empty_result_policy,
+            partial_stream_policy,
+            handshake_retry_jitter,
+            duplicate_external_ref_policy,
+            dotted_tool_name_policy,
+            recent_events_cap,
+            reaper_exempt_tools,
+            downstream_tool_allowlist,
+            downstream_tool_denylist,
+            max_json_depth,
+            max_json_nodes,
+            tool_arg_defaults,
+            targets: None,
             error_budget,
             release_track,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum IdempotencyConflictPolicy {
     ReturnExisting,
     #[serde(alias = "conflict_409", alias = "conflict")]
+    #[default]
     Conflict409,
 }
 
-impl Default for IdempotencyConflictPolicy {
-    fn default() -> Self {
-        Self::Conflict409
-    }
-}
-
 impl FromStr for IdempotencyConflictPolicy {
     type Err = anyhow::Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -267,9 +1022,81 @@ impl FromStr for IdempotencyConflictPolicy {
     }
 }
 
+/// How `inspector_call` treats a downstream result with neither `content` nor
+/// `structured_content`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EmptyResultPolicy {
+    #[default]
+    Allow,
+    Error,
+}
+
+impl FromStr for EmptyResultPolicy {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "allow" => Ok(Self::Allow),
+            "error" => Ok(Self::Error),
+            other => Err(anyhow!("unknown empty result policy '{}'", other)),
+        }
+    }
+}
+
+/// What `inspector_call` does when a successful result carries an
+/// `external_reference` already recorded for a different run.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateExternalRefPolicy {
+    /// Point the reference at the newest run.
+    #[default]
+    Overwrite,
+    /// Keep the original mapping and flag the response with a
+    /// `DUPLICATE_EXTERNAL_REFERENCE` trace warning.
+    Warn,
+}
+
+impl FromStr for DuplicateExternalRefPolicy {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "overwrite" => Ok(Self::Overwrite),
+            "warn" => Ok(Self::Warn),
+            other => Err(anyhow!(
+                "unknown duplicate external reference policy '{}'",
+                other
+            )),
+        }
+    }
+}
+
+/// How dotted downstream tool names (`files.read`) are presented to the inspector's client.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DottedToolNamePolicy {
+    /// Pass names through unchanged.
+    #[default]
+    Preserve,
+    /// List `files.read` as `files_read` and map `files_read` back to `files.read` when
+    /// `inspector_call` dispatches it.
+    Underscore,
+}
+
+impl FromStr for DottedToolNamePolicy {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "preserve" => Ok(Self::Preserve),
+            "underscore" => Ok(Self::Underscore),
+            other => Err(anyhow!("unknown dotted tool name policy '{}'", other)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shared::types::TargetTransportKind;
     use std::sync::Mutex;
     use tempfile::tempdir;
 
@@ -324,10 +1151,19 @@ mod tests {
                 ("ERROR_BUDGET_MIN_REQUESTS", None),
                 ("ERROR_BUDGET_FREEZE_SECS", None),
                 ("RELEASE_TRACK", None),
+                ("EMPTY_RESULT_POLICY", None),
+                ("DUPLICATE_EXTERNAL_REF_POLICY", None),
+                ("RECENT_EVENTS_CAP", None),
             ],
             || {
                 let cfg = AppConfig::load_from_dir(dir.path()).expect("config load");
                 assert!(cfg.metrics_addr.is_none());
+                assert_eq!(cfg.empty_result_policy, EmptyResultPolicy::Allow);
+                assert_eq!(
+                    cfg.duplicate_external_ref_policy,
+                    DuplicateExternalRefPolicy::Overwrite
+                );
+                assert_eq!(cfg.recent_events_cap(), DEFAULT_RECENT_EVENTS_CAP);
                 assert_eq!(
                     cfg.idempotency_conflict_policy,
                     IdempotencyConflictPolicy::Conflict409
@@ -370,6 +1206,7 @@ mod tests {
                 ("ERROR_BUDGET_ENABLED", Some("false")),
                 ("ERROR_BUDGET_SUCCESS_THRESHOLD", Some("0.75")),
                 ("RELEASE_TRACK", Some("canary")),
+                ("DUPLICATE_EXTERNAL_REF_POLICY", Some("warn")),
             ],
             || {
                 let cfg = AppConfig::load_from_dir(dir.path()).expect("config load");
@@ -386,11 +1223,179 @@ mod tests {
                 assert!(!cfg.error_budget.enabled);
                 assert_eq!(cfg.error_budget.success_threshold, 0.75);
                 assert_eq!(cfg.release_track, ReleaseTrack::Canary);
+                assert_eq!(
+                    cfg.duplicate_external_ref_policy,
+                    DuplicateExternalRefPolicy::Warn
+                );
+            },
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn handshake_timeouts_fall_back_per_transport() -> Result<()> {
+        let dir = tempdir()?;
+        std::fs::write(
+            dir.path().join("default.toml"),
+            "default_handshake_timeout_ms = 20000\ndefault_handshake_timeout_ms_http = 45000\n",
+        )?;
+
+        with_env(
+            &[
+                ("DEFAULT_HANDSHAKE_TIMEOUT_MS", None),
+                ("DEFAULT_HANDSHAKE_TIMEOUT_MS_STDIO", Some("2000")),
+                ("DEFAULT_HANDSHAKE_TIMEOUT_MS_SSE", None),
+                ("DEFAULT_HANDSHAKE_TIMEOUT_MS_HTTP", None),
+            ],
+            || {
+                let cfg = AppConfig::load_from_dir(dir.path()).expect("config load");
+                let timeouts = cfg.handshake_timeouts();
+                assert_eq!(timeouts.stdio, Duration::from_millis(2_000));
+                assert_eq!(timeouts.sse, Duration::from_millis(20_000));
+                assert_eq!(timeouts.http, Duration::from_millis(45_000));
+                assert_eq!(
+                    timeouts.resolve(TargetTransportKind::Http, Some(500)),
+                    Duration::from_millis(500)
+                );
             },
         );
         Ok(())
     }
 
+    #[test]
+    fn handshake_timeouts_default_without_config() {
+        let timeouts = AppConfig::default().handshake_timeouts();
+        assert_eq!(timeouts, HandshakeTimeouts::default());
+        assert_eq!(
+            timeouts.for_transport(TargetTransportKind::Sse),
+            Duration::from_millis(DEFAULT_HANDSHAKE_TIMEOUT_MS)
+        );
+    }
+
+    #[test]
+    fn reaper_exempt_tools_env_overrides_file() -> Result<()> {
+        let dir = tempdir()?;
+        std::fs::write(
+            dir.path().join("default.toml"),
+            "reaper_exempt_tools = [\"nightly_export\"]\n",
+        )?;
+
+        with_env(&[("REAPER_EXEMPT_TOOLS", None)], || {
+            let cfg = AppConfig::load_from_dir(dir.path()).expect("config load");
+            assert_eq!(cfg.reaper_exempt_tools, vec!["nightly_export".to_string()]);
+        });
+        with_env(&[("REAPER_EXEMPT_TOOLS", Some("batch, ,reindex"))], || {
+            let cfg = AppConfig::load_from_dir(dir.path()).expect("config load");
+            assert_eq!(
+                cfg.reaper_exempt_tools,
+                vec!["batch".to_string(), "reindex".to_string()]
+            );
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn tool_arg_defaults_load_from_file_and_env() -> Result<()> {
+        let dir = tempdir()?;
+        std::fs::write(
+            dir.path().join("default.toml"),
+            "[tool_arg_defaults.search]\nlimit = 10\nscope = \"repo\"\n",
+        )?;
+
+        with_env(&[("TOOL_ARG_DEFAULTS", None)], || {
+            let cfg = AppConfig::load_from_dir(dir.path()).expect("config load");
+            let search = &cfg.tool_arg_defaults["search"];
+            assert_eq!(search["limit"], 10);
+            assert_eq!(search["scope"], "repo");
+        });
+        with_env(
+            &[("TOOL_ARG_DEFAULTS", Some(r#"{"echo":{"text":"hi"}}"#))],
+            || {
+                let cfg = AppConfig::load_from_dir(dir.path()).expect("config load");
+                assert_eq!(cfg.tool_arg_defaults.len(), 1);
+                assert_eq!(cfg.tool_arg_defaults["echo"]["text"], "hi");
+            },
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn targets_load_from_targets_toml_and_profiles() -> Result<()> {
+        let dir = tempdir()?;
+        std::fs::write(
+            dir.path().join("targets.toml"),
+            "max_json_depth = 3\n\n\
+             [targets.billing-staging.http]\nurl = \"https://billing.staging/mcp\"\n\n\
+             [targets.local.stdio]\ncommand = \"mock_mcp_server\"\n",
+        )?;
+        std::fs::write(
+            dir.path().join("local.toml"),
+            "[targets.local.stdio]\ncommand = \"other_server\"\n",
+        )?;
+
+        let cfg = AppConfig::load_from_dir(dir.path())?;
+        assert_eq!(
+            cfg.targets["billing-staging"]
+                .http
+                .as_ref()
+                .map(|t| t.url.as_str()),
+            Some("https://billing.staging/mcp")
+        );
+        assert_eq!(
+            cfg.targets["local"]
+                .stdio
+                .as_ref()
+                .map(|t| t.command.as_str()),
+            Some("other_server")
+        );
+        assert_eq!(cfg.target_aliases()?.iter().count(), 2);
+        assert_eq!(
+            cfg.max_json_depth, None,
+            "only targets are read from targets.toml"
+        );
+
+        std::fs::write(dir.path().join("local.toml"), "[targets.broken]\n")?;
+        let cfg = AppConfig::load_from_dir(dir.path())?;
+        assert!(cfg.target_aliases().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn outbox_max_event_bytes_treats_zero_as_unlimited() -> Result<()> {
+        let dir = tempdir()?;
+        with_env(&[("OUTBOX_MAX_EVENT_BYTES", None)], || {
+            let cfg = AppConfig::load_from_dir(dir.path()).expect("config load");
+            assert_eq!(cfg.outbox_max_event_bytes(), None);
+        });
+        with_env(&[("OUTBOX_MAX_EVENT_BYTES", Some("65536"))], || {
+            let cfg = AppConfig::load_from_dir(dir.path()).expect("config load");
+            assert_eq!(cfg.outbox_max_event_bytes(), Some(65536));
+        });
+        with_env(&[("OUTBOX_MAX_EVENT_BYTES", Some("0"))], || {
+            let cfg = AppConfig::load_from_dir(dir.path()).expect("config load");
+            assert_eq!(cfg.outbox_max_event_bytes(), None);
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn outbox_sample_rate_defaults_to_all_and_clamps() -> Result<()> {
+        let dir = tempdir()?;
+        with_env(&[("OUTBOX_SAMPLE_RATE", None)], || {
+            let cfg = AppConfig::load_from_dir(dir.path()).expect("config load");
+            assert_eq!(cfg.outbox_sample_rate(), 1.0);
+        });
+        with_env(&[("OUTBOX_SAMPLE_RATE", Some("0.25"))], || {
+            let cfg = AppConfig::load_from_dir(dir.path()).expect("config load");
+            assert_eq!(cfg.outbox_sample_rate(), 0.25);
+        });
+        with_env(&[("OUTBOX_SAMPLE_RATE", Some("3"))], || {
+            let cfg = AppConfig::load_from_dir(dir.path()).expect("config load");
+            assert_eq!(cfg.outbox_sample_rate(), 1.0);
+        });
+        Ok(())
+    }
+
     #[test]
     fn default_config_parses() -> Result<()> {
         let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -412,12 +1417,35 @@ mod tests {
             sample_window_secs: None,
             minimum_requests: Some(10),
             freeze_window_secs: Some(60),
+            warmup_secs: Some(15),
+            freeze_allowed_tools: Some(vec!["inspector_probe".into()]),
         });
         assert!(!settings.enabled);
         assert_eq!(settings.success_threshold, 0.9);
         assert_eq!(settings.minimum_requests, 10);
         assert_eq!(settings.freeze_window_secs, 60);
         assert_eq!(settings.sample_window_secs, 120);
+        assert_eq!(settings.warmup_secs, 15);
+        assert_eq!(
+            settings.freeze_allowed_tools,
+            Some(vec!["inspector_probe".to_string()])
+        );
+    }
+
+    #[test]
+    fn freeze_allowed_tools_cannot_list_inspector_call() -> Result<()> {
+        let dir = tempdir()?;
+        with_env(
+            &[(
+                "ERROR_BUDGET_FREEZE_ALLOWED_TOOLS",
+                Some("inspector_probe,inspector.call"),
+            )],
+            || {
+                let err = AppConfig::load_from_dir(dir.path()).expect_err("inspector_call listed");
+                assert!(err.to_string().contains("inspector_call"), "{err}");
+            },
+        );
+        Ok(())
     }
 
     #[test]
@@ -439,6 +1467,8 @@ mod tests {
                 let server_cfg = server_cfg.expect("metrics enabled");
                 assert_eq!(server_cfg.addr, "0.0.0.0:9100".parse().unwrap());
                 assert!(server_cfg.tls.is_some());
+                assert!(!server_cfg.dashboard_enabled);
+                assert!(server_cfg.health_routes_enabled);
             },
         );
 
@@ -457,6 +1487,13 @@ pub struct ErrorBudgetSettings {
     pub minimum_requests: u64,
     #[serde(default = "ErrorBudgetSettings::default_freeze_window_secs")]
     pub freeze_window_secs: u64,
+    /// Seconds after startup during which failures are counted but cannot trigger a freeze.
+    #[serde(default)]
+    pub warmup_secs: u64,
+    /// Tools that stay callable while the budget is frozen; `None` leaves every tool but
+    /// `inspector_call` and `inspector_bench` callable.
+    #[serde(default)]
+    pub freeze_allowed_tools: Option<Vec<String>>,
 }
 
 impl Default for ErrorBudgetSettings {
@@ -467,6 +1504,8 @@ impl Default for ErrorBudgetSettings {
             sample_window_secs: 120,
             minimum_requests: 20,
             freeze_window_secs: 300,
+            warmup_secs: 0,
+            freeze_allowed_tools: None,
         }
     }
 }
@@ -488,6 +1527,21 @@ impl ErrorBudgetSettings {
         300
     }
 
+    /// `inspector_call` and `inspector_bench` are refused during every freeze, so listing
+    /// them in `freeze_allowed_tools` would promise something the server never does.
+    fn validate(&self) -> Result<()> {
+        let listed = self.freeze_allowed_tools.iter().flatten();
+        if let Some(name) = listed
+            .map(|name| name.replace('.', "_"))
+            .find(|name| matches!(name.as_str(), "inspector_call" | "inspector_bench"))
+        {
+            return Err(anyhow!(
+                "ERROR_BUDGET_FREEZE_ALLOWED_TOOLS cannot list {name}: it is always refused during a freeze"
+            ));
+        }
+        Ok(())
+    }
+
     fn apply_overlay(&mut self, overlay: ErrorBudgetOverlay) {
         if let Some(value) = overlay.enabled {
             self.enabled = value;
@@ -504,6 +1558,12 @@ impl ErrorBudgetSettings {
         if let Some(value) = overlay.freeze_window_secs {
             self.freeze_window_secs = value;
         }
+        if let Some(value) = overlay.warmup_secs {
+            self.warmup_secs = value;
+        }
+        if let Some(tools) = overlay.freeze_allowed_tools {
+            self.freeze_allowed_tools = Some(tools);
+        }
     }
 }
 
@@ -514,6 +1574,8 @@ struct ErrorBudgetOverlay {
     sample_window_secs: Option<u64>,
     minimum_requests: Option<u64>,
     freeze_window_secs: Option<u64>,
+    warmup_secs: Option<u64>,
+    freeze_allowed_tools: Option<Vec<String>>,
 }
 
 impl ErrorBudgetOverlay {
@@ -556,6 +1618,23 @@ impl ErrorBudgetOverlay {
             overlay.freeze_window_secs = Some(value);
             seen = true;
         }
+        if let Some(value) = env::var("ERROR_BUDGET_WARMUP_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+        {
+            overlay.warmup_secs = Some(value);
+            seen = true;
+        }
+        if let Ok(raw) = env::var("ERROR_BUDGET_FREEZE_ALLOWED_TOOLS") {
+            overlay.freeze_allowed_tools = Some(
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            );
+            seen = true;
+        }
 
         if seen { Some(overlay) } else { None }
     }
@@ -0,0 +1,400 @@
+//! Operator policy applied to run events before any [`EventSink`] backend stores them.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde_json::Value;
+
+use crate::{
+    infra::{
+        event_sink::EventSink,
+        metrics,
+        outbox::{Outbox, OutboxBinaryContent},
+    },
+    shared::types::InspectionRunEvent,
+};
+
+/// Wraps a sink with the `OUTBOX_*` event policy: binary content descriptors, success
+/// sampling, excluded fields and the size cap. Built-in and custom backends alike sit
+/// behind it, so none of them sees what the policy drops.
+pub struct PolicySink {
+    inner: Arc<dyn EventSink>,
+    dlq_path: PathBuf,
+    sample_rate: f64,
+    sampler: Mutex<StdRng>,
+    binary_content: OutboxBinaryContent,
+    excluded_fields: Vec<String>,
+    max_event_bytes: Option<usize>,
+}
+
+impl PolicySink {
+    /// `dlq_path` receives events diverted by the size cap.
+    pub fn new<P: Into<PathBuf>>(inner: Arc<dyn EventSink>, dlq_path: P) -> Result<Self> {
+        let dlq_path = dlq_path.into();
+        Outbox::ensure_parent(&dlq_path)?;
+        Ok(Self {
+            inner,
+            dlq_path,
+            sample_rate: 1.0,
+            sampler: Mutex::new(StdRng::from_os_rng()),
+            binary_content: OutboxBinaryContent::Inline,
+            excluded_fields: Vec::new(),
+            max_event_bytes: None,
+        })
+    }
+
+    /// Persist successful (`captured`) events with probability `rate` (clamped to 0..=1).
+    /// Every other event, failures and freezes included, is always written.
+    pub fn with_sample_rate(mut self, rate: f64) -> Self {
+        self.sample_rate = if rate.is_nan() {
+            1.0
+        } else {
+            rate.clamp(0.0, 1.0)
+        };
+        self
+    }
+
+    /// Seed the sampling RNG so which successes are kept is reproducible.
+    pub fn with_sample_seed(mut self, seed: u64) -> Self {
+        self.sampler = Mutex::new(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    /// Choose whether binary content blocks are stored verbatim or as size descriptors.
+    pub fn with_binary_content(mut self, mode: OutboxBinaryContent) -> Self {
+        self.binary_content = mode;
+        self
+    }
+
+    /// Drop these top-level event fields before anything is written, so they never reach
+    /// the backend, the DLQ or an index column.
+    pub fn with_excluded_fields(mut self, fields: &[String]) -> Self {
+        self.excluded_fields = fields.to_vec();
+        self
+    }
+
+    /// Divert events whose serialized line exceeds `limit` bytes to the DLQ, handing the
+    /// backend a summary instead so one giant event cannot fail or stall appends.
+    pub fn with_max_event_bytes(mut self, limit: Option<usize>) -> Self {
+        self.max_event_bytes = limit;
+        self
+    }
+
+    fn apply(&self, mut event: Value) -> Result<()> {
+        if self.binary_content == OutboxBinaryContent::Descriptor {
+            describe_binary_content(&mut event);
+        }
+        if !self.sampled_in(&event) {
+            metrics::record_outbox_sampled_out();
+            return Ok(());
+        }
+        // After the sampling decision, which reads `state` even if it is excluded.
+        if let Value::Object(map) = &mut event {
+            map.retain(|key, _| !self.excluded_fields.contains(key));
+        }
+        let mut line = serde_json::to_string(&event).context("serialize outbox event")?;
+        if let Some(limit) = self.max_event_bytes
+            && line.len() > limit
+        {
+            Outbox::write_line(&self.dlq_path, &line).context("write oversized event to DLQ")?;
+            metrics::record_outbox_oversized();
+            event = oversized_summary(&event, line.len(), limit);
+            line = serde_json::to_string(&event).context("serialize outbox event")?;
+        }
+        self.inner.append_json(&event, &line)
+    }
+
+    fn sampled_in(&self, event: &Value) -> bool {
+        if self.sample_rate >= 1.0 {
+            return true;
+        }
+        let succeeded = event.get("state").and_then(Value::as_str) == Some("captured");
+        !succeeded || self.sampler.lock().random::<f64>() < self.sample_rate
+    }
+}
+
+impl EventSink for PolicySink {
+    fn append(&self, event: &InspectionRunEvent) -> Result<()> {
+        self.apply(serde_json::to_value(event).context("serialize outbox event")?)
+    }
+
+    fn append_json(&self, event: &Value, _line: &str) -> Result<()> {
+        self.apply(event.clone())
+    }
+
+    fn check_writable(&self) -> Result<()> {
+        self.inner.check_writable()
+    }
+
+    fn description(&self) -> &'static str {
+        self.inner.description()
+    }
+}
+
+/// What the backend keeps of an event diverted for size: its identity and outcome.
+fn oversized_summary(event: &Value, bytes: usize, limit: usize) -> Value {
+    let mut summary = serde_json::Map::new();
+    for key in [
+        "event_id",
+        "run_id",
+        "tool_name",
+        "state",
+        "started_at",
+        "duration_ms",
+    ] {
+        if let Some(value) = event.get(key) {
+            summary.insert(key.into(), value.clone());
+        }
+    }
+    summary.insert(
+        "oversized".into(),
+        serde_json::json!({"bytes": bytes, "limit": limit, "diverted_to": "dlq"}),
+    );
+    Value::Object(summary)
+}
+
+/// Swap base64 blocks in `response.content` for `{type, mime, bytes}`.
+fn describe_binary_content(event: &mut Value) {
+    let Some(blocks) = event
+        .pointer_mut("/response/content")
+        .and_then(Value::as_array_mut)
+    else {
+        return;
+    };
+    for block in blocks {
+        if let Some(descriptor) = binary_descriptor(block) {
+            *block = descriptor;
+        }
+    }
+}
+
+fn binary_descriptor(block: &Value) -> Option<Value> {
+    let kind = block.get("type")?.as_str()?;
+    let (payload, data) = match kind {
+        "image" | "audio" => (block, block.get("data")?),
+        "resource" => {
+            let resource = block.get("resource")?;
+            (resource, resource.get("blob")?)
+        }
+        _ => return None,
+    };
+    Some(serde_json::json!({
+        "type": kind,
+        "mime": payload.get("mimeType"),
+        "bytes": base64_decoded_len(data.as_str()?),
+    }))
+}
+
+/// Decoded size of a base64 string, computed without decoding it.
+fn base64_decoded_len(data: &str) -> usize {
+    let len = data.trim_end_matches('=').len();
+    len / 4 * 3 + (len % 4).saturating_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    fn file_outbox(dir: &Path) -> Result<Arc<Outbox>> {
+        Ok(Arc::new(Outbox::file(
+            dir.join("events.jsonl"),
+            dir.join("dlq.jsonl"),
+        )?))
+    }
+
+    fn event(state: &str) -> Value {
+        serde_json::json!({
+            "event_id": uuid::Uuid::new_v4().to_string(),
+            "tool_name": "echo",
+            "state": state,
+        })
+    }
+
+    fn append(sink: &PolicySink, event: Value) -> Result<()> {
+        sink.append_json(&event, &event.to_string())
+    }
+
+    #[test]
+    fn zero_sample_rate_drops_successes_but_keeps_failures() -> Result<()> {
+        let dir = tempdir()?;
+        let outbox = file_outbox(dir.path())?;
+        let sink = PolicySink::new(outbox.clone(), dir.path().join("dlq.jsonl"))?
+            .with_sample_rate(0.0)
+            .with_sample_seed(7);
+        for state in ["captured", "failed", "captured", "failed"] {
+            append(&sink, event(state))?;
+        }
+        let kept = outbox.drain(10)?;
+        assert_eq!(kept.len(), 2);
+        assert!(kept.iter().all(|event| event["state"] == "failed"));
+        Ok(())
+    }
+
+    #[test]
+    fn seeded_sampling_is_reproducible() -> Result<()> {
+        let kept_with_seed = || -> Result<usize> {
+            let dir = tempdir()?;
+            let outbox = file_outbox(dir.path())?;
+            let sink = PolicySink::new(outbox.clone(), dir.path().join("dlq.jsonl"))?
+                .with_sample_rate(0.5)
+                .with_sample_seed(42);
+            for _ in 0..64 {
+                append(&sink, event("captured"))?;
+            }
+            Ok(outbox.drain(64)?.len())
+        };
+        let first = kept_with_seed()?;
+        assert_eq!(first, kept_with_seed()?);
+        assert!(first > 0 && first < 64, "rate 0.5 kept {first} of 64");
+        Ok(())
+    }
+
+    #[test]
+    fn descriptor_mode_replaces_binary_content() -> Result<()> {
+        use rmcp::model::{CallToolResult, Content};
+        let dir = tempdir()?;
+        let outbox = file_outbox(dir.path())?;
+        let sink = PolicySink::new(outbox.clone(), dir.path().join("dlq.jsonl"))?
+            .with_binary_content(OutboxBinaryContent::Descriptor);
+        // "iVBORw0KGgo=" is the 8-byte PNG signature.
+        let result = CallToolResult::success(vec![
+            Content::text("rendered chart"),
+            Content::image("iVBORw0KGgo=", "image/png"),
+        ]);
+        append(
+            &sink,
+            serde_json::json!({
+                "event_id": uuid::Uuid::new_v4().to_string(),
+                "state": "captured",
+                "response": result,
+            }),
+        )?;
+
+        let stored = outbox.drain(1)?.remove(0);
+        let content = stored["response"]["content"].as_array().expect("content");
+        assert_eq!(content[0]["text"], "rendered chart");
+        assert_eq!(
+            content[1],
+            serde_json::json!({"type": "image", "mime": "image/png", "bytes": 8})
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn excluded_fields_never_reach_the_stored_line() -> Result<()> {
+        let dir = tempdir()?;
+        let primary = dir.path().join("events.jsonl");
+        let sink = PolicySink::new(file_outbox(dir.path())?, dir.path().join("dlq.jsonl"))?
+            .with_excluded_fields(&["request".to_string(), "response".to_string()]);
+        let event_id = uuid::Uuid::new_v4().to_string();
+        append(
+            &sink,
+            serde_json::json!({
+                "event_id": event_id,
+                "state": "captured",
+                "request": {"arguments_json": {"ssn": "123-45-6789"}},
+                "response": {"content": []},
+            }),
+        )?;
+
+        let line = std::fs::read_to_string(&primary)?;
+        assert!(!line.contains("123-45-6789"), "{line}");
+        let stored: Value = serde_json::from_str(line.trim())?;
+        assert!(stored.get("request").is_none(), "{stored}");
+        assert!(stored.get("response").is_none(), "{stored}");
+        assert_eq!(stored["event_id"], event_id);
+        assert_eq!(stored["state"], "captured");
+        Ok(())
+    }
+
+    #[test]
+    fn oversized_events_go_to_the_dlq_with_a_summary_in_the_primary() -> Result<()> {
+        let dir = tempdir()?;
+        let primary = dir.path().join("events.jsonl");
+        let dlq = dir.path().join("dlq.jsonl");
+        let outbox = file_outbox(dir.path())?;
+        let sink = PolicySink::new(outbox.clone(), &dlq)?.with_max_event_bytes(Some(256));
+        let event_id = uuid::Uuid::new_v4().to_string();
+        append(
+            &sink,
+            serde_json::json!({
+                "event_id": event_id,
+                "tool_name": "inspector_call",
+                "state": "captured",
+                "response": {"text": "x".repeat(1024)},
+            }),
+        )?;
+        append(&sink, event("captured"))?;
+
+        let diverted: Value = serde_json::from_str(std::fs::read_to_string(&dlq)?.trim())?;
+        assert_eq!(diverted["event_id"], event_id);
+        assert_eq!(
+            diverted["response"]["text"].as_str().map(str::len),
+            Some(1024)
+        );
+
+        let stored = std::fs::read_to_string(&primary)?;
+        let lines: Vec<Value> = stored
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<serde_json::Result<_>>()?;
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["event_id"], event_id);
+        assert_eq!(lines[0]["tool_name"], "inspector_call");
+        assert!(lines[0].get("response").is_none(), "{}", lines[0]);
+        assert_eq!(lines[0]["oversized"]["limit"], 256);
+        assert!(lines[0]["oversized"]["bytes"].as_u64() > Some(1024));
+        assert_eq!(lines[1]["tool_name"], "echo");
+        assert!(sink.check_writable().is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn custom_backends_see_only_the_shaped_event() -> Result<()> {
+        #[derive(Default)]
+        struct MemorySink(Mutex<Vec<String>>);
+
+        impl EventSink for MemorySink {
+            fn append_json(&self, _event: &Value, line: &str) -> Result<()> {
+                self.0.lock().push(line.to_string());
+                Ok(())
+            }
+
+            fn description(&self) -> &'static str {
+                "memory"
+            }
+        }
+
+        let dir = tempdir()?;
+        let memory = Arc::new(MemorySink::default());
+        let sink = PolicySink::new(memory.clone(), dir.path().join("dlq.jsonl"))?
+            .with_excluded_fields(&["request".to_string()])
+            .with_max_event_bytes(Some(128));
+        let mut secret = event("failed");
+        secret["request"] = serde_json::json!({"token": "sk-live"});
+        append(&sink, secret)?;
+        let mut huge = event("failed");
+        huge["response"] = serde_json::json!("x".repeat(512));
+        append(&sink, huge)?;
+
+        let lines = memory.0.lock();
+        assert_eq!(lines.len(), 2);
+        assert!(!lines[0].contains("sk-live"), "{}", lines[0]);
+        assert!(lines[1].contains("\"oversized\""), "{}", lines[1]);
+        assert_eq!(sink.description(), "memory");
+        Ok(())
+    }
+
+    #[test]
+    fn base64_decoded_len_handles_padding() {
+        assert_eq!(base64_decoded_len(""), 0);
+        assert_eq!(base64_decoded_len("TQ=="), 1);
+        assert_eq!(base64_decoded_len("TWE="), 2);
+        assert_eq!(base64_decoded_len("TWFu"), 3);
+    }
+}
@@ -0,0 +1,220 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::{infra::outbox::Outbox, shared::types::InspectionRunEvent};
+
+/// Destination for run events. The built-in file and sqlite backends live in [`Outbox`];
+/// other crates can implement this to ship events elsewhere (Kafka, SQS, ...) and hand
+/// the sink to `InspectorServer` as an `Arc<dyn EventSink>`, usually wrapped in a
+/// [`DlqFallback`] and then a [`PolicySink`] so rejected events are kept and the operator's
+/// sampling, exclusions and size cap apply to it too.
+///
+/// [`PolicySink`]: crate::infra::event_policy::PolicySink
+pub trait EventSink: Send + Sync {
+    /// Persist one event; `line` is `event` serialized, so backends need not re-encode it.
+    fn append_json(&self, event: &Value, line: &str) -> Result<()>;
+
+    fn append(&self, event: &InspectionRunEvent) -> Result<()> {
+        let event = serde_json::to_value(event).context("serialize outbox event")?;
+        let line = serde_json::to_string(&event).context("serialize outbox event")?;
+        self.append_json(&event, &line)
+    }
+
+    /// Append every event, even after one fails; returns the first failure.
+    fn append_batch(&self, events: &[InspectionRunEvent]) -> Result<()> {
+        let mut first_error = None;
+        for event in events {
+            if let Err(err) = self.append(event) {
+                first_error.get_or_insert(err);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// Readiness probe backing `inspector_health`'s `outbox_writable` check.
+    fn check_writable(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Short backend name reported by health checks.
+    fn description(&self) -> &'static str;
+}
+
+impl EventSink for Outbox {
+    fn append_json(&self, event: &Value, line: &str) -> Result<()> {
+        Outbox::append_json(self, event, line)
+    }
+
+    fn check_writable(&self) -> Result<()> {
+        Outbox::check_writable(self)
+    }
+
+    fn description(&self) -> &'static str {
+        self.backend_description()
+    }
+}
+
+/// Wraps a sink so events it rejects are kept as JSON lines in a dead-letter file, the
+/// same fallback [`Outbox`] applies to its own backends. Layered under [`PolicySink`], the
+/// DLQ only ever sees events the policy has already shaped. The original error is still
+/// returned so callers can flag the run.
+///
+/// [`PolicySink`]: crate::infra::event_policy::PolicySink
+pub struct DlqFallback {
+    inner: Arc<dyn EventSink>,
+    dlq_path: PathBuf,
+    degraded: AtomicBool,
+}
+
+impl DlqFallback {
+    pub fn new<P: Into<PathBuf>>(inner: Arc<dyn EventSink>, dlq_path: P) -> Result<Self> {
+        let dlq_path = dlq_path.into();
+        Outbox::ensure_parent(&dlq_path)?;
+        Ok(Self {
+            inner,
+            dlq_path,
+            degraded: AtomicBool::new(false),
+        })
+    }
+}
+
+impl EventSink for DlqFallback {
+    fn append_json(&self, event: &Value, line: &str) -> Result<()> {
+        let result = self.inner.append_json(event, line);
+        self.degraded.store(result.is_err(), Ordering::Relaxed);
+        if let Err(primary_err) = result {
+            Outbox::write_line(&self.dlq_path, line)
+                .context("write outbox DLQ after primary failure")?;
+            return Err(primary_err);
+        }
+        Ok(())
+    }
+
+    fn check_writable(&self) -> Result<()> {
+        if self.degraded.load(Ordering::Relaxed) {
+            anyhow::bail!("last event append failed; events are diverted to the DLQ");
+        }
+        self.inner.check_writable()
+    }
+
+    fn description(&self) -> &'static str {
+        self.inner.description()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::event_policy::PolicySink;
+    use parking_lot::Mutex;
+    use tempfile::tempdir;
+
+    #[derive(Default)]
+    struct MemorySink {
+        events: Mutex<Vec<Value>>,
+        reject_tool: Option<&'static str>,
+    }
+
+    impl EventSink for MemorySink {
+        fn append_json(&self, event: &Value, _line: &str) -> Result<()> {
+            if let Some(tool) = self.reject_tool
+                && event["tool_name"] == tool
+            {
+                anyhow::bail!("memory sink rejects {tool}");
+            }
+            self.events.lock().push(event.clone());
+            Ok(())
+        }
+
+        fn description(&self) -> &'static str {
+            "memory"
+        }
+    }
+
+    fn event(tool: &str) -> InspectionRunEvent {
+        InspectionRunEvent {
+            event_id: uuid::Uuid::new_v4(),
+            run_id: uuid::Uuid::new_v4(),
+            tool_name: tool.into(),
+            state: "captured".into(),
+            started_at: "2025-01-01T00:00:00Z".into(),
+            duration_ms: 1,
+            target: None,
+            request: None,
+            response: None,
+            error: None,
+            idempotency_key: None,
+            external_reference: None,
+            target_capabilities: None,
+            requested_tool_name: None,
+        }
+    }
+
+    #[test]
+    fn custom_sink_receives_batches_through_the_trait() -> Result<()> {
+        let memory = Arc::new(MemorySink::default());
+        let sink: Arc<dyn EventSink> = memory.clone();
+        sink.append(&event("first"))?;
+        sink.append_batch(&[event("second"), event("third")])?;
+
+        let tools: Vec<String> = memory
+            .events
+            .lock()
+            .iter()
+            .map(|event| event["tool_name"].as_str().unwrap_or_default().to_string())
+            .collect();
+        assert_eq!(tools, ["first", "second", "third"]);
+        assert_eq!(sink.description(), "memory");
+        Ok(())
+    }
+
+    #[test]
+    fn rejected_events_land_in_the_dlq() -> Result<()> {
+        let dir = tempdir()?;
+        let dlq = dir.path().join("dlq.jsonl");
+        let memory = Arc::new(MemorySink {
+            reject_tool: Some("kafka-down"),
+            ..MemorySink::default()
+        });
+        let sink = DlqFallback::new(memory.clone(), &dlq)?;
+
+        sink.append(&event("accepted"))?;
+        sink.check_writable()?;
+        assert!(sink.append(&event("kafka-down")).is_err());
+        assert!(sink.check_writable().is_err());
+
+        assert_eq!(memory.events.lock().len(), 1);
+        let diverted: Vec<Value> = std::fs::read_to_string(&dlq)?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        assert_eq!(diverted.len(), 1);
+        assert_eq!(diverted[0]["tool_name"], "kafka-down");
+        Ok(())
+    }
+
+    #[test]
+    fn under_a_policy_sink_the_dlq_sees_shaped_events() -> Result<()> {
+        let dir = tempdir()?;
+        let dlq = dir.path().join("dlq.jsonl");
+        let memory = Arc::new(MemorySink {
+            reject_tool: Some("kafka-down"),
+            ..MemorySink::default()
+        });
+        let sink = PolicySink::new(Arc::new(DlqFallback::new(memory, &dlq)?), &dlq)?
+            .with_excluded_fields(&["target_capabilities".to_string()]);
+        let mut rejected = event("kafka-down");
+        rejected.target_capabilities = Some(serde_json::json!({"tools": {}}));
+
+        assert!(sink.append(&rejected).is_err());
+        let line = std::fs::read_to_string(&dlq)?;
+        let diverted: Value = serde_json::from_str(line.trim())?;
+        assert_eq!(diverted["tool_name"], "kafka-down");
+        assert!(diverted.get("target_capabilities").is_none(), "{diverted}");
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+//! Gzip codec for compressed outbox payloads.
+
+use std::io::{Read, Write};
+
+use anyhow::{Context, Result};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+
+pub fn compress(data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(data.len() / 2), Compression::default());
+    encoder.write_all(data).context("gzip payload")?;
+    encoder.finish().context("finish gzip stream")
+}
+
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(data.len() * 4);
+    GzDecoder::new(data)
+        .read_to_end(&mut out)
+        .context("gunzip payload")?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_repetitive_and_binary_payloads() -> Result<()> {
+        let json = serde_json::json!({
+            "rows": (0..200).map(|idx| serde_json::json!({"idx": idx, "status": "ok"})).collect::<Vec<_>>()
+        })
+        .to_string();
+        let binary: Vec<u8> = (0..10_000u32).map(|v| (v * 7919 % 251) as u8).collect();
+        for input in [Vec::new(), b"a".to_vec(), json.clone().into_bytes(), binary] {
+            let compressed = compress(&input)?;
+            assert_eq!(decompress(&compressed)?, input);
+        }
+        assert!(compress(json.as_bytes())?.len() < json.len() / 4);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_corrupted_streams() -> Result<()> {
+        let mut compressed = compress(b"hello hello hello hello")?;
+        let last = compressed.len() - 5;
+        compressed[last] ^= 0xff;
+        assert!(decompress(&compressed).is_err());
+        assert!(decompress(b"plain text").is_err());
+        Ok(())
+    }
+}
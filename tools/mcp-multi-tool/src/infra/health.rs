@@ -0,0 +1,140 @@
+use serde::Serialize;
+use std::env;
+
+use crate::{
+    infra::event_sink::EventSink,
+    shared::{types::ReleaseTrack, utils::parse_command},
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheck {
+    pub ok: bool,
+    pub detail: String,
+}
+
+impl HealthCheck {
+    fn pass(detail: impl Into<String>) -> Self {
+        Self {
+            ok: true,
+            detail: detail.into(),
+        }
+    }
+
+    fn fail(detail: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthChecks {
+    pub outbox_writable: HealthCheck,
+    pub default_target: HealthCheck,
+    pub release_track: HealthCheck,
+}
+
+/// `live` is true whenever the process answers; `ready` only when every check passes.
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub live: bool,
+    pub ready: bool,
+    pub checks: HealthChecks,
+}
+
+pub fn evaluate(outbox: &dyn EventSink, release_track: ReleaseTrack) -> HealthReport {
+    let default_cmd = env::var("INSPECTOR_STDIO_CMD").ok();
+    evaluate_with_default_target(outbox, release_track, default_cmd.as_deref())
+}
+
+pub fn evaluate_with_default_target(
+    outbox: &dyn EventSink,
+    release_track: ReleaseTrack,
+    default_cmd: Option<&str>,
+) -> HealthReport {
+    let outbox_writable = match outbox.check_writable() {
+        Ok(()) => HealthCheck::pass(outbox.description()),
+        Err(err) => HealthCheck::fail(format!("{err:#}")),
+    };
+    let default_target = match default_cmd {
+        None => HealthCheck::pass("no default stdio target configured"),
+        Some(cmd) => match parse_command(cmd) {
+            Ok((program, _)) => match which::which(&program) {
+                Ok(path) => HealthCheck::pass(format!("resolved {}", path.display())),
+                Err(err) => HealthCheck::fail(format!("cannot resolve '{program}': {err}")),
+            },
+            Err(err) => HealthCheck::fail(err.to_string()),
+        },
+    };
+    let release_track = if release_track.allows_inspector() {
+        HealthCheck::pass(release_track.as_str())
+    } else {
+        HealthCheck::fail("release track is rollback")
+    };
+    let ready = outbox_writable.ok && default_target.ok && release_track.ok;
+    HealthReport {
+        live: true,
+        ready,
+        checks: HealthChecks {
+            outbox_writable,
+            default_target,
+            release_track,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::outbox::Outbox;
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    #[test]
+    fn ready_when_outbox_writable_and_track_active() -> Result<()> {
+        let dir = tempdir()?;
+        let outbox = Outbox::file(
+            dir.path().join("events.jsonl"),
+            dir.path().join("dlq.jsonl"),
+        )?;
+        let report = evaluate_with_default_target(&outbox, ReleaseTrack::Stable, None);
+        assert!(report.live);
+        assert!(report.ready);
+
+        let rollback = evaluate_with_default_target(&outbox, ReleaseTrack::Rollback, None);
+        assert!(rollback.live);
+        assert!(!rollback.ready);
+        Ok(())
+    }
+
+    #[test]
+    fn readiness_flips_when_outbox_degraded() -> Result<()> {
+        let dir = tempdir()?;
+        let primary_dir = dir.path().join("primary_dir");
+        std::fs::create_dir_all(&primary_dir)?;
+        let outbox = Outbox::file(&primary_dir, dir.path().join("dlq.jsonl"))?;
+        let report = evaluate_with_default_target(&outbox, ReleaseTrack::Stable, None);
+        assert!(report.live);
+        assert!(!report.ready);
+        assert!(!report.checks.outbox_writable.ok);
+        Ok(())
+    }
+
+    #[test]
+    fn unresolvable_default_target_is_not_ready() -> Result<()> {
+        let dir = tempdir()?;
+        let outbox = Outbox::file(
+            dir.path().join("events.jsonl"),
+            dir.path().join("dlq.jsonl"),
+        )?;
+        let report = evaluate_with_default_target(
+            &outbox,
+            ReleaseTrack::Stable,
+            Some("definitely-not-a-binary --flag"),
+        );
+        assert!(!report.ready);
+        assert!(!report.checks.default_target.ok);
+        Ok(())
+    }
+}
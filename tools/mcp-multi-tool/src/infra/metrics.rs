@@ -1,24 +1,49 @@
+use crate::{
+    infra::{event_sink::EventSink, health},
+    shared::{recent_events::RecentEvents, types::ReleaseTrack},
+};
 use axum::{
-    Router,
+    Json, Router,
     extract::State,
-    http::{HeaderMap, HeaderValue, StatusCode},
-    response::IntoResponse,
+    http::{HeaderMap, HeaderValue, StatusCode, Uri},
+    response::{Html, IntoResponse},
     routing::get,
 };
 use axum_server::tls_rustls::RustlsConfig;
 use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use prometheus::{
-    Encoder, Histogram, HistogramVec, IntCounter, IntGauge, TextEncoder, register_histogram,
-    register_histogram_vec, register_int_counter, register_int_gauge,
+    Encoder, Histogram, HistogramVec, IntCounter, IntGauge, IntGaugeVec, TextEncoder,
+    register_histogram, register_histogram_vec, register_int_counter, register_int_gauge,
+    register_int_gauge_vec,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
 };
-use std::{collections::HashMap, net::SocketAddr, path::PathBuf, time::Duration};
 use tokio::net::TcpListener;
 use tracing::{error, info, warn};
 
+const DASHBOARD_HTML: &str = include_str!("dashboard.html");
+const DEFAULT_EVENTS_LIMIT: usize = 50;
+/// Distinct `target` labels `downstream_tool_schema_bytes` keeps; later targets share `other`.
+pub const MAX_SCHEMA_BYTES_TARGETS: usize = 32;
+
 pub static LATENCY_HISTO: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         "inspector_latency_ms",
-        "Latency of inspector operations in ms"
+        "Latency of warm inspector operations (tool calls over an established session) in ms"
+    )
+    .unwrap()
+});
+
+pub static COLD_HANDSHAKE_HISTO: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "inspector_cold_handshake_ms",
+        "Spawn/connect plus MCP initialize latency of fresh target sessions in ms"
     )
     .unwrap()
 });
@@ -34,6 +59,22 @@ pub static OUTBOX_BACKLOG: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static OUTBOX_SAMPLED_OUT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "outbox_sampled_out_total",
+        "Successful events skipped by outbox sampling"
+    )
+    .unwrap()
+});
+
+pub static OUTBOX_OVERSIZED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "outbox_oversized_events_total",
+        "Events over the size limit diverted to the DLQ with a summary in the primary store"
+    )
+    .unwrap()
+});
+
 pub static REAPER_TIMEOUTS: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
         "idempotency_timeouts_total",
@@ -42,6 +83,46 @@ pub static REAPER_TIMEOUTS: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static MANAGED_SESSIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "inspector_managed_sessions",
+        "Sessions opened with inspector_connect and not yet closed"
+    )
+    .unwrap()
+});
+
+pub static CONNECTION_CACHE_HITS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "connection_cache_hits_total",
+        "Inspector operations served by a reused idle target session"
+    )
+    .unwrap()
+});
+
+pub static CONNECTION_CACHE_MISSES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "connection_cache_misses_total",
+        "Inspector operations that found no idle target session to reuse"
+    )
+    .unwrap()
+});
+
+pub static TARGET_RESTARTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "target_restarts_total",
+        "Pooled stdio targets found exited and respawned"
+    )
+    .unwrap()
+});
+
+pub static CHILDREN_KILLED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "inspector_children_killed_total",
+        "Stdio target processes killed after a failed, timed-out or cancelled handshake or call"
+    )
+    .unwrap()
+});
+
 pub static ERROR_BUDGET_FROZEN: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
         "error_budget_frozen",
@@ -59,9 +140,26 @@ pub static LOCK_WAIT_HISTO: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static DOWNSTREAM_TOOL_SCHEMA_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "downstream_tool_schema_bytes",
+        "Serialized size of all tool schemas a target listed last time, in bytes",
+        &["target"]
+    )
+    .unwrap()
+});
+
+static SCHEMA_BYTES_TARGETS: Lazy<Mutex<HashSet<String>>> = Lazy::new(Mutex::default);
+
 #[derive(Clone)]
 pub struct PendingGaugeGuard;
 
+impl Default for PendingGaugeGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PendingGaugeGuard {
     pub fn new() -> Self {
         INSPECTOR_INFLIGHT.inc();
@@ -87,29 +185,54 @@ pub struct MetricsServerConfig {
     pub auth_token: Option<String>,
     pub allow_insecure: bool,
     pub tls: Option<TlsConfig>,
+    /// Serve `/dashboard` and `/events` next to `/metrics`. The page is static and public;
+    /// `/events` shares the metrics auth.
+    pub dashboard_enabled: bool,
+    /// Serve `/healthz` and `/readyz` without auth, for orchestrator probes.
+    pub health_routes_enabled: bool,
+}
+
+/// What `/readyz` evaluates: the same checks as `inspector_health`.
+#[derive(Clone)]
+pub struct HealthProbe {
+    pub outbox: Arc<dyn EventSink>,
+    pub release_track: ReleaseTrack,
 }
 
 #[derive(Clone)]
 struct MetricsState {
     auth_token: Option<String>,
+    recent_events: Arc<RecentEvents>,
+    health: HealthProbe,
 }
 
-pub async fn spawn_metrics_server(config: MetricsServerConfig) {
+pub async fn spawn_metrics_server(
+    config: MetricsServerConfig,
+    recent_events: Arc<RecentEvents>,
+    health: HealthProbe,
+) {
     let MetricsServerConfig {
         addr,
         auth_token,
         allow_insecure,
         tls,
+        dashboard_enabled,
+        health_routes_enabled,
     } = config.clone();
     if !allow_insecure && tls.is_none() {
         warn!(%addr, "metrics server skipped: TLS required but not configured");
         return;
     }
 
-    let state = MetricsState { auth_token };
-    let app = Router::new()
-        .route("/metrics", get(metrics_handler))
-        .with_state(state.clone());
+    let app = router(
+        MetricsState {
+            auth_token,
+            recent_events,
+            health,
+        },
+        dashboard_enabled,
+        health_routes_enabled,
+    );
 
     tokio::spawn(async move {
         if let Some(tls_cfg) = tls {
@@ -143,14 +266,58 @@ pub async fn spawn_metrics_server(config: MetricsServerConfig) {
     });
 }
 
+fn router(state: MetricsState, dashboard_enabled: bool, health_routes_enabled: bool) -> Router {
+    let mut app = Router::new().route("/metrics", get(metrics_handler));
+    if dashboard_enabled {
+        app = app
+            .route("/dashboard", get(dashboard_handler))
+            .route("/events", get(events_handler));
+    }
+    if health_routes_enabled {
+        app = app
+            .route("/healthz", get(healthz_handler))
+            .route("/readyz", get(readyz_handler));
+    }
+    app.with_state(state)
+}
+
+/// Liveness: answering at all means the process is serving.
+async fn healthz_handler() -> axum::response::Response {
+    Json(serde_json::json!({"live": true})).into_response()
+}
+
+/// Readiness: 503 naming the failing checks, without their details, since the route is
+/// unauthenticated.
+async fn readyz_handler(State(state): State<MetricsState>) -> axum::response::Response {
+    let report = health::evaluate(state.health.outbox.as_ref(), state.health.release_track);
+    let checks = &report.checks;
+    let failing: Vec<&str> = [
+        ("outbox_writable", &checks.outbox_writable),
+        ("default_target", &checks.default_target),
+        ("release_track", &checks.release_track),
+    ]
+    .into_iter()
+    .filter(|(_, check)| !check.ok)
+    .map(|(name, _)| name)
+    .collect();
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(serde_json::json!({"ready": report.ready, "failing": failing})),
+    )
+        .into_response()
+}
+
 async fn metrics_handler(
     State(state): State<MetricsState>,
     headers: HeaderMap,
 ) -> axum::response::Response {
-    if let Some(token) = &state.auth_token {
-        if !is_authorized(headers.get(http::header::AUTHORIZATION), token) {
-            return StatusCode::UNAUTHORIZED.into_response();
-        }
+    if !state.authorized(&headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
     }
 
     let encoder = TextEncoder::new();
@@ -172,6 +339,62 @@ async fn metrics_handler(
     resp.into_response()
 }
 
+/// The page holds no data itself; it prompts for the token before polling the guarded routes.
+async fn dashboard_handler() -> axum::response::Response {
+    Html(DASHBOARD_HTML).into_response()
+}
+
+/// Recent run events stripped to what the dashboard shows: targets, requests and responses
+/// can carry credentials or payloads that `OUTBOX_EXCLUDE_FIELDS` keeps out of storage.
+async fn events_handler(
+    State(state): State<MetricsState>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> axum::response::Response {
+    if !state.authorized(&headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let limit = uri
+        .query()
+        .and_then(|query| {
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("limit="))
+                .and_then(|raw| raw.parse::<usize>().ok())
+        })
+        .unwrap_or(DEFAULT_EVENTS_LIMIT);
+    let events: Vec<serde_json::Value> = state
+        .recent_events
+        .snapshot(limit)
+        .into_iter()
+        .map(|event| {
+            serde_json::json!({
+                "event_id": event.event_id,
+                "run_id": event.run_id,
+                "tool_name": event.tool_name,
+                "state": event.state,
+                "started_at": event.started_at,
+                "duration_ms": event.duration_ms,
+                "error": event.error,
+            })
+        })
+        .collect();
+    Json(serde_json::json!({
+        "capacity": state.recent_events.capacity(),
+        "events": events,
+    }))
+    .into_response()
+}
+
+impl MetricsState {
+    fn authorized(&self, headers: &HeaderMap) -> bool {
+        match &self.auth_token {
+            Some(token) => is_authorized(headers.get(http::header::AUTHORIZATION), token),
+            None => true,
+        }
+    }
+}
+
 fn is_authorized(header: Option<&HeaderValue>, token: &str) -> bool {
     match header.and_then(|value| value.to_str().ok()) {
         Some(value) if value.starts_with("Bearer ") => value[7..].trim() == token,
@@ -183,6 +406,19 @@ pub fn increment_outbox_backlog() {
     OUTBOX_BACKLOG.inc();
 }
 
+#[cfg(test)]
+pub fn decrement_outbox_backlog(count: usize) {
+    OUTBOX_BACKLOG.sub(count as i64);
+}
+
+pub fn record_outbox_sampled_out() {
+    OUTBOX_SAMPLED_OUT.inc();
+}
+
+pub fn record_outbox_oversized() {
+    OUTBOX_OVERSIZED.inc();
+}
+
 pub fn record_reaper_timeout(count: usize) {
     if count > 0 {
         REAPER_TIMEOUTS.inc_by(count as u64);
@@ -193,6 +429,19 @@ pub fn set_error_budget_frozen(frozen: bool) {
     ERROR_BUDGET_FROZEN.set(if frozen { 1 } else { 0 });
 }
 
+pub fn set_downstream_tool_schema_bytes(target: &str, bytes: usize) {
+    let mut targets = SCHEMA_BYTES_TARGETS.lock();
+    let label = if targets.contains(target) || targets.len() < MAX_SCHEMA_BYTES_TARGETS {
+        targets.insert(target.to_string());
+        target
+    } else {
+        "other"
+    };
+    DOWNSTREAM_TOOL_SCHEMA_BYTES
+        .with_label_values(&[label])
+        .set(bytes as i64);
+}
+
 pub fn observe_lock_wait(component: &'static str, duration: Duration) {
     let ms = duration.as_secs_f64() * 1000.0;
     LOCK_WAIT_HISTO.with_label_values(&[component]).observe(ms);
@@ -200,11 +449,21 @@ pub fn observe_lock_wait(component: &'static str, duration: Duration) {
     test_support::record_lock_wait(component, ms);
 }
 
+pub fn take_lock_wait_records() -> HashMap<String, Vec<f64>> {
+    #[cfg(test)]
+    {
+        test_support::take_lock_wait_records()
+    }
+    #[cfg(not(test))]
+    {
+        HashMap::new()
+    }
+}
+
 #[cfg(test)]
 mod test_support {
     use super::*;
     use once_cell::sync::Lazy;
-    use parking_lot::Mutex;
 
     static LOCK_WAITS: Lazy<Mutex<HashMap<&'static str, Vec<f64>>>> =
         Lazy::new(|| Mutex::new(HashMap::new()));
@@ -215,18 +474,181 @@ mod test_support {
 
     pub fn take_lock_wait_records() -> HashMap<String, Vec<f64>> {
         let mut map = LOCK_WAITS.lock();
-        let result = map.drain().map(|(k, v)| (k.to_string(), v)).collect();
-        result
+        map.drain().map(|(k, v)| (k.to_string(), v)).collect()
     }
 }
 
-pub fn take_lock_wait_records() -> HashMap<String, Vec<f64>> {
-    #[cfg(test)]
-    {
-        return test_support::take_lock_wait_records();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{infra::outbox::Outbox, shared::types::InspectionRunEvent};
+
+    fn health_probe(dir: &std::path::Path) -> HealthProbe {
+        let outbox = Outbox::file(dir.join("events.jsonl"), dir.join("dlq.jsonl")).expect("outbox");
+        HealthProbe {
+            outbox: Arc::new(outbox),
+            release_track: ReleaseTrack::Stable,
+        }
     }
-    #[cfg(not(test))]
-    {
-        HashMap::new()
+
+    async fn serve(dashboard_enabled: bool, recent_events: Arc<RecentEvents>) -> String {
+        let dir = tempfile::tempdir().expect("tempdir");
+        serve_with(dashboard_enabled, recent_events, health_probe(dir.path())).await
+    }
+
+    async fn serve_with(
+        dashboard_enabled: bool,
+        recent_events: Arc<RecentEvents>,
+        health: HealthProbe,
+    ) -> String {
+        let app = router(
+            MetricsState {
+                auth_token: Some("secret".into()),
+                recent_events,
+                health,
+            },
+            dashboard_enabled,
+            true,
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local addr");
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+        format!("http://{addr}")
+    }
+
+    fn event(tool: &str) -> InspectionRunEvent {
+        InspectionRunEvent {
+            event_id: uuid::Uuid::new_v4(),
+            run_id: uuid::Uuid::new_v4(),
+            tool_name: tool.into(),
+            state: "captured".into(),
+            started_at: "2024-01-01T00:00:00Z".into(),
+            duration_ms: 3,
+            target: None,
+            request: None,
+            response: None,
+            error: None,
+            idempotency_key: None,
+            external_reference: None,
+            target_capabilities: None,
+            requested_tool_name: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn dashboard_is_public_and_events_are_guarded_summaries() {
+        let recent = Arc::new(RecentEvents::new(16));
+        recent.push(event("echo"));
+        let mut add = event("add");
+        add.request = Some(serde_json::json!({"password": "hunter2"}));
+        add.response = Some(serde_json::json!({"token": "sk-live"}));
+        recent.push(add);
+        let base = serve(true, recent).await;
+        let client = reqwest::Client::new();
+
+        let page = client
+            .get(format!("{base}/dashboard"))
+            .send()
+            .await
+            .unwrap();
+        assert!(page.status().is_success());
+        assert!(page.text().await.unwrap().contains("/events"));
+        let denied = client.get(format!("{base}/events")).send().await.unwrap();
+        assert_eq!(denied.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let events: serde_json::Value = client
+            .get(format!("{base}/events?limit=1"))
+            .bearer_auth("secret")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let events = events["events"].as_array().expect("events array");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["tool_name"], "add");
+        assert!(events[0].get("request").is_none(), "{}", events[0]);
+        let rendered = events[0].to_string();
+        assert!(!rendered.contains("hunter2") && !rendered.contains("sk-live"));
+    }
+
+    #[tokio::test]
+    async fn dashboard_routes_absent_when_disabled() {
+        let base = serve(false, Arc::new(RecentEvents::default())).await;
+        let resp = reqwest::Client::new()
+            .get(format!("{base}/dashboard"))
+            .bearer_auth("secret")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn readyz_reports_outbox_degradation_without_auth() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let healthy = serve_with(false, Arc::default(), health_probe(dir.path())).await;
+        let client = reqwest::Client::new();
+
+        let live = client
+            .get(format!("{healthy}/healthz"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(live.status(), reqwest::StatusCode::OK);
+        let ready = client
+            .get(format!("{healthy}/readyz"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(ready.status(), reqwest::StatusCode::OK);
+
+        // A directory where the primary log should be makes the outbox unwritable.
+        let primary = dir.path().join("primary_dir");
+        std::fs::create_dir_all(&primary).unwrap();
+        let degraded = HealthProbe {
+            outbox: Arc::new(Outbox::file(&primary, dir.path().join("dlq.jsonl")).unwrap()),
+            release_track: ReleaseTrack::Stable,
+        };
+        let degraded = serve_with(false, Arc::default(), degraded).await;
+        let ready = client
+            .get(format!("{degraded}/readyz"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(ready.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        let body: serde_json::Value = ready.json().await.unwrap();
+        assert_eq!(body["failing"], serde_json::json!(["outbox_writable"]));
+        let live = client
+            .get(format!("{degraded}/healthz"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(live.status(), reqwest::StatusCode::OK);
+    }
+
+    #[test]
+    fn schema_bytes_targets_past_the_cap_share_other() {
+        for idx in 0..MAX_SCHEMA_BYTES_TARGETS {
+            set_downstream_tool_schema_bytes(&format!("stdio:/bin/target-{idx}"), 100);
+        }
+        set_downstream_tool_schema_bytes("stdio:/bin/one-too-many", 7);
+        set_downstream_tool_schema_bytes("stdio:/bin/target-0", 250);
+
+        let gauge = |target: &str| {
+            DOWNSTREAM_TOOL_SCHEMA_BYTES
+                .with_label_values(&[target])
+                .get()
+        };
+        assert_eq!(gauge("other"), 7);
+        assert_eq!(gauge("stdio:/bin/target-0"), 250);
+        assert!(
+            !SCHEMA_BYTES_TARGETS
+                .lock()
+                .contains("stdio:/bin/one-too-many")
+        );
     }
 }
@@ -1,3 +1,10 @@
+pub mod compliance_history;
 pub mod config;
+pub mod event_policy;
+pub mod event_sink;
+pub mod gzip;
+pub mod health;
 pub mod metrics;
 pub mod outbox;
+pub mod outbox_tail;
+pub mod process_stats;
@@ -1,13 +1,131 @@
-use crate::infra::metrics;
+use crate::infra::{gzip, metrics};
 use std::fs::{OpenOptions, create_dir_all};
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow};
 use parking_lot::Mutex;
-use rusqlite::{Connection, params};
-use serde::Serialize;
+use rusqlite::{
+    Connection, params, params_from_iter,
+    types::{Value as SqlValue, ValueRef},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tokio::runtime::RuntimeFlavor;
+
+pub const DEFAULT_OUTBOX_APPEND_RETRIES: u32 = 2;
+/// Cap on `OUTBOX_APPEND_RETRIES`. The writer blocks while they run, and five doubling
+/// sleeps from `APPEND_RETRY_BASE_BACKOFF` hold it for about 310 ms before the event goes
+/// to the DLQ.
+pub const MAX_OUTBOX_APPEND_RETRIES: u32 = 5;
+const APPEND_RETRY_BASE_BACKOFF: Duration = Duration::from_millis(10);
+
+/// Event field that can be copied into its own indexed sqlite column at insert time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboxIndexField {
+    ToolName,
+    RunId,
+    State,
+}
+
+impl OutboxIndexField {
+    /// Column name, which is also the event's JSON key.
+    pub fn column(self) -> &'static str {
+        match self {
+            Self::ToolName => "tool_name",
+            Self::RunId => "run_id",
+            Self::State => "state",
+        }
+    }
+}
+
+impl FromStr for OutboxIndexField {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "tool_name" => Ok(Self::ToolName),
+            "run_id" => Ok(Self::RunId),
+            "state" => Ok(Self::State),
+            other => Err(anyhow!("unknown outbox index field '{}'", other)),
+        }
+    }
+}
+
+/// How the file backend splits events across files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboxPartition {
+    /// Everything goes to the configured path.
+    #[default]
+    None,
+    /// `events.jsonl` becomes `events-YYYY-MM-DD.jsonl`, rolling at UTC midnight.
+    Daily,
+}
+
+impl FromStr for OutboxPartition {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "daily" => Ok(Self::Daily),
+            other => Err(anyhow!("unknown outbox partition '{}'", other)),
+        }
+    }
+}
+
+/// How image/audio/blob content blocks in `response.content` are persisted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboxBinaryContent {
+    /// Store the base64 payload verbatim.
+    #[default]
+    Inline,
+    /// Replace each binary block with `{type, mime, bytes}`; text and structured content are kept.
+    Descriptor,
+}
+
+impl FromStr for OutboxBinaryContent {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "inline" => Ok(Self::Inline),
+            "descriptor" => Ok(Self::Descriptor),
+            other => Err(anyhow!("unknown outbox binary content mode '{}'", other)),
+        }
+    }
+}
+
+/// Source of "now" for partitioning; replaceable so tests can cross a date boundary.
+#[derive(Clone)]
+pub struct OutboxClock(Arc<dyn Fn() -> OffsetDateTime + Send + Sync>);
+
+impl OutboxClock {
+    pub fn new<F: Fn() -> OffsetDateTime + Send + Sync + 'static>(now: F) -> Self {
+        Self(Arc::new(now))
+    }
+
+    fn now(&self) -> OffsetDateTime {
+        (self.0)()
+    }
+}
+
+impl Default for OutboxClock {
+    fn default() -> Self {
+        Self::new(OffsetDateTime::now_utc)
+    }
+}
+
+impl std::fmt::Debug for OutboxClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("OutboxClock")
+    }
+}
 
 #[derive(Debug)]
 enum Backend {
@@ -20,6 +138,12 @@ pub struct Outbox {
     backend: Backend,
     dlq_path: PathBuf,
     write_lock: Mutex<()>,
+    compress: bool,
+    degraded: AtomicBool,
+    append_retries: u32,
+    indexed_fields: Vec<OutboxIndexField>,
+    partition: OutboxPartition,
+    clock: OutboxClock,
 }
 
 impl Outbox {
@@ -34,6 +158,12 @@ impl Outbox {
             },
             dlq_path,
             write_lock: Mutex::new(()),
+            compress: false,
+            degraded: AtomicBool::new(false),
+            append_retries: 0,
+            indexed_fields: Vec::new(),
+            partition: OutboxPartition::None,
+            clock: OutboxClock::default(),
         })
     }
 
@@ -64,6 +194,7 @@ impl Outbox {
             "#,
         )
         .context("initialise sqlite outbox schema")?;
+        ensure_compressed_column(&conn)?;
 
         Ok(Self {
             backend: Backend::Sqlite {
@@ -71,33 +202,107 @@ impl Outbox {
             },
             dlq_path,
             write_lock: Mutex::new(()),
+            compress: false,
+            degraded: AtomicBool::new(false),
+            append_retries: 0,
+            indexed_fields: Vec::new(),
+            partition: OutboxPartition::None,
+            clock: OutboxClock::default(),
         })
     }
 
+    /// Gzip payloads before inserting them (sqlite backend only; JSONL files stay plain text).
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compress = enabled;
+        self
+    }
+
+    /// Retry a failed primary write up to `retries` times (exponential backoff from 10ms,
+    /// capped at `MAX_OUTBOX_APPEND_RETRIES`) before falling back to the DLQ.
+    pub fn with_append_retries(mut self, retries: u32) -> Self {
+        self.append_retries = retries.min(MAX_OUTBOX_APPEND_RETRIES);
+        self
+    }
+
+    /// Split the file backend by UTC date (ignored by sqlite). Drain and lookups
+    /// walk every partition next to the configured path.
+    pub fn with_partition(mut self, partition: OutboxPartition) -> Self {
+        self.partition = partition;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: OutboxClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Copy `fields` into dedicated indexed columns on insert (sqlite backend only).
+    ///
+    /// Columns and indexes are added with `ALTER TABLE` / `CREATE INDEX IF NOT EXISTS`,
+    /// so existing databases migrate in place; rows written earlier are backfilled from
+    /// their payloads.
+    pub fn with_indexed_fields(mut self, fields: &[OutboxIndexField]) -> Result<Self> {
+        let mut indexed = Vec::new();
+        for field in fields {
+            if !indexed.contains(field) {
+                indexed.push(*field);
+            }
+        }
+        if let Backend::Sqlite { conn } = &self.backend {
+            let conn = conn.lock();
+            for field in &indexed {
+                ensure_index_column(&conn, *field)?;
+            }
+            self.indexed_fields = indexed;
+        }
+        Ok(self)
+    }
+
     pub fn append<T: Serialize>(&self, event: &T) -> Result<()> {
-        let line = serde_json::to_string(event).context("serialize outbox event")?;
+        let event = serde_json::to_value(event).context("serialize outbox event")?;
+        let line = serde_json::to_string(&event).context("serialize outbox event")?;
+        self.append_json(&event, &line)
+    }
+
+    /// Persist `event`, whose serialized form is `line`, as given: shaping such as
+    /// sampling or field exclusion is [`PolicySink`]'s job.
+    ///
+    /// [`PolicySink`]: crate::infra::event_policy::PolicySink
+    pub fn append_json(&self, event: &Value, line: &str) -> Result<()> {
         let event_id = extract_event_id(event).unwrap_or_else(uuid::Uuid::new_v4);
+        self.persist(line, || {
+            self.locked(|| self.write_primary(event_id, line, event))
+        })
+    }
+
+    /// Run `write` under the write lock.
+    fn locked<R>(&self, write: impl FnOnce() -> R) -> R {
         let wait = Instant::now();
         let _guard = self.write_lock.lock();
         metrics::observe_lock_wait("outbox_write_lock", wait.elapsed());
+        write()
+    }
 
-        let primary_result = match &self.backend {
-            Backend::File { main_path } => Self::write_line(main_path, &line),
-            Backend::Sqlite { conn } => {
-                let wait = Instant::now();
-                let conn = conn.lock();
-                metrics::observe_lock_wait("outbox_sqlite_conn", wait.elapsed());
-                conn.execute(
-                    "INSERT INTO outbox_events (event_id, payload) VALUES (?1, ?2)",
-                    params![event_id.to_string(), line],
-                )
-                .context("insert sqlite outbox row")
-                .map(|_| ())
-            }
-        };
+    /// Try `write_primary`, retrying with backoff, then fall back to the DLQ. Each attempt
+    /// takes the write lock on its own, so other appends proceed during the backoff.
+    fn persist(&self, line: &str, mut write_primary: impl FnMut() -> Result<()>) -> Result<()> {
+        let mut primary_result = write_primary();
+        let mut backoff = APPEND_RETRY_BASE_BACKOFF;
+        let mut attempt = 0;
+        while let Err(err) = &primary_result
+            && attempt < self.append_retries
+        {
+            attempt += 1;
+            tracing::warn!(attempt, %err, "outbox append failed; retrying");
+            sleep_off_runtime(backoff);
+            backoff *= 2;
+            primary_result = write_primary();
+        }
 
+        self.degraded
+            .store(primary_result.is_err(), Ordering::Relaxed);
         if let Err(primary_err) = primary_result {
-            Self::write_line(&self.dlq_path, &line)
+            self.locked(|| Self::write_line(&self.dlq_path, line))
                 .context("write outbox DLQ after primary failure")?;
             Err(primary_err)
         } else {
@@ -106,19 +311,264 @@ impl Outbox {
         }
     }
 
-    fn write_line(path: &Path, line: &str) -> Result<()> {
+    fn write_primary(&self, event_id: uuid::Uuid, line: &str, event: &Value) -> Result<()> {
+        match &self.backend {
+            Backend::File { main_path } => Self::write_line(&self.active_file(main_path), line),
+            Backend::Sqlite { conn } => {
+                let wait = Instant::now();
+                let conn = conn.lock();
+                metrics::observe_lock_wait("outbox_sqlite_conn", wait.elapsed());
+                let payload = if self.compress {
+                    SqlValue::Blob(gzip::compress(line.as_bytes())?)
+                } else {
+                    SqlValue::Text(line.to_string())
+                };
+                let mut columns = vec!["event_id", "payload", "compressed"];
+                let mut values = vec![
+                    SqlValue::Text(event_id.to_string()),
+                    payload,
+                    SqlValue::Integer(self.compress as i64),
+                ];
+                for field in &self.indexed_fields {
+                    columns.push(field.column());
+                    values.push(
+                        event
+                            .get(field.column())
+                            .and_then(Value::as_str)
+                            .map_or(SqlValue::Null, |text| SqlValue::Text(text.to_string())),
+                    );
+                }
+                let placeholders: Vec<String> =
+                    (1..=columns.len()).map(|idx| format!("?{idx}")).collect();
+                let sql = format!(
+                    "INSERT INTO outbox_events ({}) VALUES ({})",
+                    columns.join(", "),
+                    placeholders.join(", ")
+                );
+                conn.execute(&sql, params_from_iter(values))
+                    .context("insert sqlite outbox row")
+                    .map(|_| ())
+            }
+        }
+    }
+
+    /// Remove and return up to `limit` of the oldest events, decoding compressed rows.
+    #[cfg(test)]
+    pub fn drain(&self, limit: usize) -> Result<Vec<Value>> {
+        let wait = Instant::now();
+        let _guard = self.write_lock.lock();
+        metrics::observe_lock_wait("outbox_write_lock", wait.elapsed());
+
+        let drained = match &self.backend {
+            Backend::File { main_path } => {
+                let mut drained = Vec::new();
+                for path in self.file_partitions(main_path)? {
+                    if drained.len() == limit {
+                        break;
+                    }
+                    drained.extend(Self::drain_file(&path, limit - drained.len())?);
+                }
+                drained
+            }
+            Backend::Sqlite { conn } => {
+                let wait = Instant::now();
+                let mut conn = conn.lock();
+                metrics::observe_lock_wait("outbox_sqlite_conn", wait.elapsed());
+                Self::drain_sqlite(&mut conn, limit)?
+            }
+        };
+        metrics::decrement_outbox_backlog(drained.len());
+        Ok(drained)
+    }
+
+    /// Up to `limit` stored events whose `field` equals `value`, newest first.
+    ///
+    /// Uses the indexed column when `field` was enabled via `with_indexed_fields`;
+    /// otherwise every stored event is decoded and filtered.
+    pub fn find_by(
+        &self,
+        field: OutboxIndexField,
+        value: &str,
+        limit: usize,
+    ) -> Result<Vec<Value>> {
+        let wait = Instant::now();
+        let _guard = self.write_lock.lock();
+        metrics::observe_lock_wait("outbox_write_lock", wait.elapsed());
+
+        let matches =
+            |event: &Value| event.get(field.column()).and_then(Value::as_str) == Some(value);
+        match &self.backend {
+            Backend::File { main_path } => {
+                let mut found = Vec::new();
+                for path in self.file_partitions(main_path)?.iter().rev() {
+                    if !path.exists() {
+                        continue;
+                    }
+                    let contents = std::fs::read_to_string(path)
+                        .with_context(|| format!("read outbox file {}", path.display()))?;
+                    for line in contents
+                        .lines()
+                        .rev()
+                        .filter(|line| !line.trim().is_empty())
+                    {
+                        let event: Value =
+                            serde_json::from_str(line).context("decode outbox line")?;
+                        if matches(&event) {
+                            found.push(event);
+                            if found.len() == limit {
+                                return Ok(found);
+                            }
+                        }
+                    }
+                }
+                Ok(found)
+            }
+            Backend::Sqlite { conn } => {
+                let wait = Instant::now();
+                let conn = conn.lock();
+                metrics::observe_lock_wait("outbox_sqlite_conn", wait.elapsed());
+                if self.indexed_fields.contains(&field) {
+                    let sql = format!(
+                        "SELECT id, payload, compressed FROM outbox_events WHERE {} = ?1 ORDER BY id DESC LIMIT ?2",
+                        field.column()
+                    );
+                    let mut stmt = conn.prepare(&sql)?;
+                    let mut cursor = stmt.query(params![value, limit as i64])?;
+                    let mut found = Vec::new();
+                    while let Some(row) = cursor.next()? {
+                        found.push(decode_sqlite_row(row)?.1);
+                    }
+                    Ok(found)
+                } else {
+                    let mut stmt = conn.prepare(
+                        "SELECT id, payload, compressed FROM outbox_events ORDER BY id DESC",
+                    )?;
+                    let mut cursor = stmt.query([])?;
+                    let mut found = Vec::new();
+                    while found.len() < limit
+                        && let Some(row) = cursor.next()?
+                    {
+                        let (_, event) = decode_sqlite_row(row)?;
+                        if matches(&event) {
+                            found.push(event);
+                        }
+                    }
+                    Ok(found)
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn drain_sqlite(conn: &mut Connection, limit: usize) -> Result<Vec<Value>> {
+        let tx = conn.transaction().context("begin sqlite drain")?;
+        let mut rows = Vec::new();
+        {
+            let mut stmt = tx.prepare(
+                "SELECT id, payload, compressed FROM outbox_events ORDER BY id LIMIT ?1",
+            )?;
+            let mut cursor = stmt.query(params![limit as i64])?;
+            while let Some(row) = cursor.next()? {
+                rows.push(decode_sqlite_row(row)?);
+            }
+        }
+        if let Some((last_id, _)) = rows.last() {
+            tx.execute("DELETE FROM outbox_events WHERE id <= ?1", params![last_id])
+                .context("delete drained sqlite rows")?;
+        }
+        tx.commit().context("commit sqlite drain")?;
+        Ok(rows.into_iter().map(|(_, event)| event).collect())
+    }
+
+    #[cfg(test)]
+    fn drain_file(path: &Path, limit: usize) -> Result<Vec<Value>> {
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("read outbox file {}", path.display()))?;
+        let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+        let drained = lines
+            .by_ref()
+            .take(limit)
+            .map(|line| serde_json::from_str(line).context("decode outbox line"))
+            .collect::<Result<Vec<Value>>>()?;
+        let remaining: String = lines.map(|line| format!("{line}\n")).collect();
+        let tmp_path = path.with_extension("drain.tmp");
+        std::fs::write(&tmp_path, remaining)
+            .with_context(|| format!("write outbox remainder {}", tmp_path.display()))?;
+        std::fs::rename(&tmp_path, path)
+            .with_context(|| format!("replace outbox file {}", path.display()))?;
+        Ok(drained)
+    }
+
+    /// File that receives appends right now.
+    fn active_file(&self, main_path: &Path) -> PathBuf {
+        match self.partition {
+            OutboxPartition::None => main_path.to_path_buf(),
+            OutboxPartition::Daily => {
+                let date = self.clock.now().to_offset(time::UtcOffset::UTC).date();
+                partition_path(
+                    main_path,
+                    &format!(
+                        "{:04}-{:02}-{:02}",
+                        date.year(),
+                        u8::from(date.month()),
+                        date.day()
+                    ),
+                )
+            }
+        }
+    }
+
+    /// Every file holding events, oldest first.
+    fn file_partitions(&self, main_path: &Path) -> Result<Vec<PathBuf>> {
+        if self.partition == OutboxPartition::None {
+            return Ok(vec![main_path.to_path_buf()]);
+        }
+        let dir = match main_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let (prefix, suffix) = partition_affixes(main_path);
+        let mut partitions: Vec<PathBuf> = std::fs::read_dir(dir)
+            .with_context(|| format!("list outbox partitions in {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_prefix(&prefix))
+                    .and_then(|rest| rest.strip_suffix(&suffix))
+                    .is_some_and(is_partition_date)
+            })
+            .collect();
+        // ISO dates sort chronologically as strings.
+        partitions.sort();
+        Ok(partitions)
+    }
+
+    pub(crate) fn write_line(path: &Path, line: &str) -> Result<()> {
         let mut file = OpenOptions::new()
             .create(true)
             .append(true)
             .open(path)
             .with_context(|| format!("open outbox file {}", path.display()))?;
-        writeln!(file, "{line}")
+        // One `write_all` of the whole line: `writeln!` on an unbuffered file issues
+        // separate writes for the text and the newline, which other appenders to the
+        // same file (another process, or an outbox without our lock) can split.
+        let mut record = String::with_capacity(line.len() + 1);
+        record.push_str(line);
+        record.push('\n');
+        file.write_all(record.as_bytes())
             .with_context(|| format!("append outbox line {}", path.display()))?;
         file.sync_all()
             .with_context(|| format!("fsync outbox file {}", path.display()))
     }
 
-    fn ensure_parent(path: &Path) -> Result<()> {
+    pub(crate) fn ensure_parent(path: &Path) -> Result<()> {
         if let Some(parent) = path.parent() {
             create_dir_all(parent)
                 .with_context(|| format!("create outbox directory {}", parent.display()))?;
@@ -126,7 +576,28 @@ impl Outbox {
         Ok(())
     }
 
-    #[cfg(test)]
+    /// Readiness probe: the last append reached the primary store and it still accepts writes.
+    pub fn check_writable(&self) -> Result<()> {
+        if self.degraded.load(Ordering::Relaxed) {
+            anyhow::bail!("last outbox append failed; events are diverted to the DLQ");
+        }
+        match &self.backend {
+            Backend::File { main_path } => {
+                let path = self.active_file(main_path);
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .map(|_| ())
+                    .with_context(|| format!("open outbox file {}", path.display()))
+            }
+            Backend::Sqlite { conn } => conn
+                .lock()
+                .execute_batch("BEGIN IMMEDIATE; ROLLBACK;")
+                .context("acquire sqlite outbox write lock"),
+        }
+    }
+
     pub fn backend_description(&self) -> &'static str {
         match &self.backend {
             Backend::File { .. } => "file",
@@ -135,8 +606,146 @@ impl Outbox {
     }
 }
 
-fn extract_event_id<T: Serialize>(event: &T) -> Option<uuid::Uuid> {
-    let value = serde_json::to_value(event).ok()?;
+/// Block for `delay` without starving a multi-threaded tokio runtime: its worker hands
+/// queued tasks to another thread first. Elsewhere this is a plain sleep.
+fn sleep_off_runtime(delay: Duration) {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| std::thread::sleep(delay))
+        }
+        _ => std::thread::sleep(delay),
+    }
+}
+
+/// `events.jsonl` -> (`events-`, `.jsonl`).
+fn partition_affixes(main_path: &Path) -> (String, String) {
+    let stem = main_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("events");
+    let suffix = main_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| format!(".{ext}"))
+        .unwrap_or_default();
+    (format!("{stem}-"), suffix)
+}
+
+fn partition_path(main_path: &Path, date: &str) -> PathBuf {
+    let (prefix, suffix) = partition_affixes(main_path);
+    main_path.with_file_name(format!("{prefix}{date}{suffix}"))
+}
+
+fn is_partition_date(raw: &str) -> bool {
+    let bytes = raw.as_bytes();
+    bytes.len() == 10
+        && bytes.iter().enumerate().all(|(idx, b)| match idx {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+}
+
+/// Older databases predate the `compressed` flag; add it so mixed rows stay readable.
+fn ensure_compressed_column(conn: &Connection) -> Result<()> {
+    let mut stmt = conn.prepare("PRAGMA table_info(outbox_events)")?;
+    let has_column = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(Result::ok)
+        .any(|name| name == "compressed");
+    if !has_column {
+        conn.execute(
+            "ALTER TABLE outbox_events ADD COLUMN compressed INTEGER NOT NULL DEFAULT 0",
+            [],
+        )
+        .context("add compressed column to sqlite outbox")?;
+    }
+    Ok(())
+}
+
+/// Add `field`'s column and index if missing, then fill it in for rows that predate it.
+fn ensure_index_column(conn: &Connection, field: OutboxIndexField) -> Result<()> {
+    let column = field.column();
+    let mut stmt = conn.prepare("PRAGMA table_info(outbox_events)")?;
+    let has_column = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(Result::ok)
+        .any(|name| name == column);
+    if !has_column {
+        conn.execute(
+            &format!("ALTER TABLE outbox_events ADD COLUMN {column} TEXT"),
+            [],
+        )
+        .with_context(|| format!("add {column} column to sqlite outbox"))?;
+    }
+    conn.execute(
+        &format!(
+            "CREATE INDEX IF NOT EXISTS idx_outbox_events_{column} ON outbox_events ({column})"
+        ),
+        [],
+    )
+    .with_context(|| format!("create {column} index on sqlite outbox"))?;
+    backfill_index_column(conn, field)
+        .with_context(|| format!("backfill {column} column of sqlite outbox"))
+}
+
+/// Copy `field` out of stored payloads into its column wherever that is still `NULL`:
+/// plain rows in SQL, compressed rows after decoding them here.
+fn backfill_index_column(conn: &Connection, field: OutboxIndexField) -> Result<()> {
+    let column = field.column();
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        &format!(
+            "UPDATE outbox_events SET {column} = json_extract(payload, '$.{column}') \
+             WHERE {column} IS NULL AND compressed = 0 \
+             AND json_valid(payload) AND json_type(payload, '$.{column}') = 'text'"
+        ),
+        [],
+    )?;
+    let mut compressed = Vec::new();
+    {
+        let mut stmt = tx.prepare(&format!(
+            "SELECT id, payload, compressed FROM outbox_events \
+             WHERE {column} IS NULL AND compressed = 1"
+        ))?;
+        let mut cursor = stmt.query([])?;
+        while let Some(row) = cursor.next()? {
+            let (id, event) = decode_sqlite_row(row)?;
+            if let Some(value) = event.get(column).and_then(Value::as_str) {
+                compressed.push((id, value.to_string()));
+            }
+        }
+    }
+    {
+        let mut update = tx.prepare(&format!(
+            "UPDATE outbox_events SET {column} = ?1 WHERE id = ?2"
+        ))?;
+        for (id, value) in compressed {
+            update.execute(params![value, id])?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Decode an `(id, payload, compressed)` row into its event.
+fn decode_sqlite_row(row: &rusqlite::Row<'_>) -> Result<(i64, Value)> {
+    let id: i64 = row.get(0)?;
+    let compressed: bool = row.get(2)?;
+    let raw = match row.get_ref(1)? {
+        ValueRef::Text(bytes) | ValueRef::Blob(bytes) => bytes.to_vec(),
+        other => anyhow::bail!("unexpected payload type {:?}", other.data_type()),
+    };
+    let bytes = if compressed {
+        gzip::decompress(&raw).with_context(|| format!("decompress outbox row {id}"))?
+    } else {
+        raw
+    };
+    let event: Value =
+        serde_json::from_slice(&bytes).with_context(|| format!("decode outbox row {id}"))?;
+    Ok((id, event))
+}
+
+fn extract_event_id(value: &Value) -> Option<uuid::Uuid> {
     value
         .get("event_id")
         .and_then(|v| v.as_str())
@@ -213,6 +822,46 @@ mod tests {
         Ok(())
     }
 
+    /// Append through a primary store that fails its first `failures` writes.
+    fn append_failing_first(outbox: &Outbox, failures: u32, payload: &str) -> Result<()> {
+        let event_id = uuid::Uuid::new_v4();
+        let event = serde_json::json!({"event_id": event_id, "payload": payload});
+        let line = event.to_string();
+        let mut remaining = failures;
+        outbox.persist(&line, || {
+            if remaining > 0 {
+                remaining -= 1;
+                anyhow::bail!("injected outbox failure");
+            }
+            outbox.write_primary(event_id, &line, &event)
+        })
+    }
+
+    #[test]
+    fn transient_failure_recovers_on_retry_without_dlq() -> Result<()> {
+        let dir = tempdir()?;
+        let primary = dir.path().join("events.jsonl");
+        let dlq = dir.path().join("dlq.jsonl");
+        let outbox = Outbox::file(&primary, &dlq)?.with_append_retries(2);
+        append_failing_first(&outbox, 2, "retried")?;
+        assert!(std::fs::read_to_string(&primary)?.contains("retried"));
+        assert!(!dlq.exists());
+        assert!(outbox.check_writable().is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn exhausted_retries_fall_back_to_dlq() -> Result<()> {
+        let dir = tempdir()?;
+        let primary = dir.path().join("events.jsonl");
+        let dlq = dir.path().join("dlq.jsonl");
+        let outbox = Outbox::file(&primary, &dlq)?.with_append_retries(1);
+        let result = append_failing_first(&outbox, 2, "diverted");
+        assert!(result.is_err());
+        assert!(std::fs::read_to_string(&dlq)?.contains("diverted"));
+        Ok(())
+    }
+
     #[test]
     fn file_backend_persists_across_reopen() -> Result<()> {
         let dir = tempdir()?;
@@ -264,12 +913,49 @@ mod tests {
         let data = std::fs::read_to_string(&primary)?;
         let persisted: Vec<PersistedEvent> = data
             .lines()
-            .map(|line| serde_json::from_str(line))
+            .map(serde_json::from_str)
             .collect::<Result<_, _>>()?;
         assert_eq!(persisted.len(), workers as usize);
         Ok(())
     }
 
+    #[test]
+    fn file_backend_lines_stay_whole_across_independent_writers() -> Result<()> {
+        let dir = tempdir()?;
+        let primary = dir.path().join("events.jsonl");
+        let dlq = dir.path().join("dlq.jsonl");
+        // Separate instances do not share a write lock, like separate processes.
+        let writers = 32;
+        let per_writer = 20;
+        let payload = "x".repeat(16 * 1024);
+        thread::scope(|scope| {
+            for _ in 0..writers {
+                let outbox = Outbox::file(&primary, &dlq).expect("outbox");
+                let payload = &payload;
+                scope.spawn(move || {
+                    for _ in 0..per_writer {
+                        let event = DummyEvent {
+                            event_id: uuid::Uuid::new_v4().to_string(),
+                            payload: payload.clone(),
+                        };
+                        outbox.append(&event).expect("append");
+                    }
+                });
+            }
+        });
+
+        let data = std::fs::read_to_string(&primary)?;
+        let mut ids = std::collections::HashSet::new();
+        for (idx, line) in data.lines().enumerate() {
+            let event: PersistedEvent = serde_json::from_str(line)
+                .with_context(|| format!("line {} is not a whole event", idx + 1))?;
+            assert_eq!(event.payload.len(), payload.len());
+            ids.insert(event.event_id);
+        }
+        assert_eq!(ids.len(), writers * per_writer);
+        Ok(())
+    }
+
     #[test]
     fn sqlite_backend_persists_across_reopen() -> Result<()> {
         let dir = tempdir()?;
@@ -303,6 +989,174 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn sqlite_compressed_rows_round_trip_alongside_plain_rows() -> Result<()> {
+        let dir = tempdir()?;
+        let db_path = dir.path().join("outbox.db");
+        let dlq = dir.path().join("dlq.jsonl");
+        let plain = DummyEvent {
+            event_id: uuid::Uuid::new_v4().to_string(),
+            payload: "plain".into(),
+        };
+        let large = DummyEvent {
+            event_id: uuid::Uuid::new_v4().to_string(),
+            payload: "snapshot ".repeat(2_000),
+        };
+        Outbox::sqlite(&db_path, &dlq)?.append(&plain)?;
+        let outbox = Outbox::sqlite(&db_path, &dlq)?.with_compression(true);
+        outbox.append(&large)?;
+
+        let conn = Connection::open(&db_path)?;
+        let stored: usize = conn.query_row(
+            "SELECT length(payload) FROM outbox_events WHERE compressed = 1",
+            [],
+            |row| row.get(0),
+        )?;
+        assert!(stored < large.payload.len() / 10);
+
+        let drained = outbox.drain(10)?;
+        assert_eq!(
+            drained,
+            vec![serde_json::to_value(&plain)?, serde_json::to_value(&large)?]
+        );
+        assert!(outbox.drain(10)?.is_empty());
+        Ok(())
+    }
+
+    #[derive(Serialize)]
+    struct RunEvent {
+        event_id: String,
+        run_id: String,
+        tool_name: String,
+        state: String,
+    }
+
+    fn run_event(tool: &str, state: &str) -> RunEvent {
+        RunEvent {
+            event_id: uuid::Uuid::new_v4().to_string(),
+            run_id: uuid::Uuid::new_v4().to_string(),
+            tool_name: tool.into(),
+            state: state.into(),
+        }
+    }
+
+    #[test]
+    fn sqlite_indexed_fields_migrate_and_answer_queries() -> Result<()> {
+        let dir = tempdir()?;
+        let db_path = dir.path().join("outbox.db");
+        let dlq = dir.path().join("dlq.jsonl");
+        Outbox::sqlite(&db_path, &dlq)?.append(&run_event("echo", "captured"))?;
+        Outbox::sqlite(&db_path, &dlq)?
+            .with_compression(true)
+            .append(&run_event("echo", "cancelled"))?;
+
+        let outbox = Outbox::sqlite(&db_path, &dlq)?
+            .with_compression(true)
+            .with_indexed_fields(&[OutboxIndexField::ToolName, OutboxIndexField::RunId])?;
+        let target = run_event("echo", "failed");
+        outbox.append(&target)?;
+        outbox.append(&run_event("add", "captured"))?;
+
+        // Plain and compressed rows written before the migration are backfilled.
+        let by_tool = outbox.find_by(OutboxIndexField::ToolName, "echo", 10)?;
+        let states: Vec<&Value> = by_tool.iter().map(|event| &event["state"]).collect();
+        assert_eq!(states, ["failed", "cancelled", "captured"]);
+        let by_run = outbox.find_by(OutboxIndexField::RunId, &target.run_id, 10)?;
+        assert_eq!(by_run[0]["event_id"], target.event_id.as_str());
+
+        let conn = Connection::open(&db_path)?;
+        let plan: String = conn.query_row(
+            "EXPLAIN QUERY PLAN SELECT id FROM outbox_events WHERE tool_name = 'echo'",
+            [],
+            |row| row.get(3),
+        )?;
+        assert!(plan.contains("idx_outbox_events_tool_name"), "{plan}");
+
+        // Fields without a column fall back to scanning decoded payloads.
+        let by_state = outbox.find_by(OutboxIndexField::State, "captured", 10)?;
+        assert_eq!(by_state.len(), 2);
+        assert_eq!(by_state[0]["tool_name"], "add");
+
+        // Reopening with the same configuration is a no-op migration.
+        Outbox::sqlite(&db_path, &dlq)?.with_indexed_fields(&[OutboxIndexField::ToolName])?;
+        Ok(())
+    }
+
+    #[test]
+    fn file_find_by_scans_newest_first() -> Result<()> {
+        let dir = tempdir()?;
+        let outbox = Outbox::file(
+            dir.path().join("events.jsonl"),
+            dir.path().join("dlq.jsonl"),
+        )?
+        .with_indexed_fields(&[OutboxIndexField::ToolName])?;
+        for state in ["captured", "failed", "captured"] {
+            outbox.append(&run_event("echo", state))?;
+        }
+        let found = outbox.find_by(OutboxIndexField::ToolName, "echo", 2)?;
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0]["state"], "captured");
+        assert_eq!(found[1]["state"], "failed");
+        Ok(())
+    }
+
+    #[test]
+    fn daily_partition_rolls_at_utc_midnight() -> Result<()> {
+        use std::sync::atomic::AtomicI64;
+        let dir = tempdir()?;
+        let primary = dir.path().join("events.jsonl");
+        let dlq = dir.path().join("dlq.jsonl");
+        let before_midnight = time::macros::datetime!(2025-03-09 23:59:59 UTC).unix_timestamp();
+        let now = Arc::new(AtomicI64::new(before_midnight));
+        let clock = {
+            let now = Arc::clone(&now);
+            OutboxClock::new(move || {
+                OffsetDateTime::from_unix_timestamp(now.load(Ordering::Relaxed)).expect("timestamp")
+            })
+        };
+        let outbox = Outbox::file(&primary, &dlq)?
+            .with_partition(OutboxPartition::Daily)
+            .with_clock(clock);
+
+        outbox.append(&run_event("echo", "captured"))?;
+        now.store(before_midnight + 2, Ordering::Relaxed);
+        outbox.append(&run_event("echo", "failed"))?;
+
+        let day_one = dir.path().join("events-2025-03-09.jsonl");
+        let day_two = dir.path().join("events-2025-03-10.jsonl");
+        assert!(std::fs::read_to_string(&day_one)?.contains("captured"));
+        assert!(std::fs::read_to_string(&day_two)?.contains("failed"));
+        assert!(!primary.exists());
+        assert!(!dlq.exists());
+
+        let newest = outbox.find_by(OutboxIndexField::ToolName, "echo", 1)?;
+        assert_eq!(newest[0]["state"], "failed");
+        let drained = outbox.drain(10)?;
+        assert_eq!(drained.len(), 2);
+        assert_eq!(drained[0]["state"], "captured");
+        Ok(())
+    }
+
+    #[test]
+    fn file_drain_keeps_remaining_lines() -> Result<()> {
+        let dir = tempdir()?;
+        let primary = dir.path().join("events.jsonl");
+        let outbox = Outbox::file(&primary, dir.path().join("dlq.jsonl"))?;
+        for idx in 0..3 {
+            outbox.append(&DummyEvent {
+                event_id: uuid::Uuid::new_v4().to_string(),
+                payload: format!("payload-{idx}"),
+            })?;
+        }
+        let first = outbox.drain(2)?;
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0]["payload"], "payload-0");
+        let rest = outbox.drain(10)?;
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0]["payload"], "payload-2");
+        Ok(())
+    }
+
     #[test]
     fn sqlite_backend_concurrent_appends_no_loss() -> Result<()> {
         let dir = tempdir()?;
@@ -0,0 +1,204 @@
+use std::fs::{self, File, Metadata};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+/// Follows a JSONL outbox file like `tail -F`, surviving truncation and rotation.
+///
+/// * Rotation (the path now names a different inode): the old file is read to its end,
+///   then the new file is followed from its first line.
+/// * Truncation in place (the file shrank below the read offset): reading restarts at
+///   the beginning; lines appended and truncated between two polls are lost.
+/// * A missing file is waited for; a trailing line without `\n` is held until completed.
+#[derive(Debug)]
+pub struct OutboxFollower {
+    path: PathBuf,
+    file: Option<File>,
+    identity: Option<FileIdentity>,
+    offset: u64,
+    partial: Vec<u8>,
+    start_at_end: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileIdentity {
+    dev: u64,
+    ino: u64,
+}
+
+#[cfg(unix)]
+fn file_identity(meta: &Metadata) -> FileIdentity {
+    use std::os::unix::fs::MetadataExt;
+    FileIdentity {
+        dev: meta.dev(),
+        ino: meta.ino(),
+    }
+}
+
+// Without inode numbers rotation is only noticed once the new file is shorter than the offset.
+#[cfg(not(unix))]
+fn file_identity(_meta: &Metadata) -> FileIdentity {
+    FileIdentity { dev: 0, ino: 0 }
+}
+
+impl OutboxFollower {
+    /// Follow `path` from its current end, emitting only events appended from now on.
+    pub fn from_end<P: Into<PathBuf>>(path: P) -> Self {
+        Self::new(path.into(), true)
+    }
+
+    /// Follow `path` from its first line.
+    pub fn from_start<P: Into<PathBuf>>(path: P) -> Self {
+        Self::new(path.into(), false)
+    }
+
+    fn new(path: PathBuf, start_at_end: bool) -> Self {
+        Self {
+            path,
+            file: None,
+            identity: None,
+            offset: 0,
+            partial: Vec::new(),
+            start_at_end,
+        }
+    }
+
+    /// Complete lines written since the previous poll, oldest first.
+    pub fn poll(&mut self) -> Result<Vec<String>> {
+        let mut lines = Vec::new();
+        let on_disk = match fs::metadata(&self.path) {
+            Ok(meta) => Some(meta),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                // Anything in a file created after we started watching is new.
+                self.start_at_end = false;
+                None
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("stat {}", self.path.display()));
+            }
+        };
+        if let Some(meta) = &on_disk {
+            if self.file.is_some() && self.identity != Some(file_identity(meta)) {
+                self.read_available(&mut lines)?;
+                self.file = None;
+                self.partial.clear();
+            } else if self.file.is_some() && meta.len() < self.offset {
+                self.restart()?;
+            }
+            if self.file.is_none() {
+                self.open(meta)?;
+            }
+        }
+        self.read_available(&mut lines)?;
+        Ok(lines)
+    }
+
+    fn open(&mut self, meta: &Metadata) -> Result<()> {
+        let mut file = File::open(&self.path)
+            .with_context(|| format!("open outbox file {}", self.path.display()))?;
+        self.offset = if self.start_at_end { meta.len() } else { 0 };
+        file.seek(SeekFrom::Start(self.offset))?;
+        self.identity = Some(file_identity(meta));
+        self.file = Some(file);
+        // Only the first file honours `from_end`; rotated-in files are read whole.
+        self.start_at_end = false;
+        Ok(())
+    }
+
+    fn restart(&mut self) -> Result<()> {
+        if let Some(file) = self.file.as_mut() {
+            file.seek(SeekFrom::Start(0))?;
+        }
+        self.offset = 0;
+        self.partial.clear();
+        Ok(())
+    }
+
+    fn read_available(&mut self, lines: &mut Vec<String>) -> Result<()> {
+        let Some(file) = self.file.as_mut() else {
+            return Ok(());
+        };
+        let read = file
+            .read_to_end(&mut self.partial)
+            .with_context(|| format!("read outbox file {}", self.path.display()))?;
+        self.offset += read as u64;
+        while let Some(newline) = self.partial.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.partial.drain(..=newline).collect();
+            let text = String::from_utf8_lossy(&line[..newline]);
+            let text = text.trim_end_matches('\r');
+            if !text.trim().is_empty() {
+                lines.push(text.to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn append(path: &std::path::Path, text: &str) {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .expect("open for append");
+        file.write_all(text.as_bytes()).expect("append");
+    }
+
+    #[test]
+    fn from_end_skips_existing_and_holds_partial_lines() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("events.jsonl");
+        append(&path, "{\"n\":0}\n");
+        let mut follower = OutboxFollower::from_end(&path);
+        assert!(follower.poll()?.is_empty());
+
+        append(&path, "{\"n\":1}\n{\"n\":");
+        assert_eq!(follower.poll()?, vec!["{\"n\":1}"]);
+        append(&path, "2}\n");
+        assert_eq!(follower.poll()?, vec!["{\"n\":2}"]);
+        Ok(())
+    }
+
+    #[test]
+    fn waits_for_missing_file_and_restarts_after_truncation() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("events.jsonl");
+        let mut follower = OutboxFollower::from_end(&path);
+        assert!(follower.poll()?.is_empty());
+
+        append(&path, "{\"n\":1}\n");
+        assert_eq!(follower.poll()?, vec!["{\"n\":1}"]);
+
+        fs::write(&path, "")?;
+        assert!(follower.poll()?.is_empty());
+        append(&path, "{\"n\":2}\n");
+        assert_eq!(follower.poll()?, vec!["{\"n\":2}"]);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rotation_drains_old_file_then_follows_new_one() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("events.jsonl");
+        append(&path, "{\"n\":1}\n");
+        let mut follower = OutboxFollower::from_start(&path);
+        assert_eq!(follower.poll()?, vec!["{\"n\":1}"]);
+
+        // Written to the old file after the last poll, then rotated away.
+        append(&path, "{\"n\":2}\n");
+        fs::rename(&path, dir.path().join("events.jsonl.1"))?;
+        append(&path, "{\"n\":3}\n{\"n\":4}\n");
+        assert_eq!(
+            follower.poll()?,
+            vec!["{\"n\":2}", "{\"n\":3}", "{\"n\":4}"]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use serde::Serialize;
+
+/// Process-level figures for capacity planning and leak hunting. Platform-specific
+/// readings are `None` where the platform offers no cheap answer.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsReport {
+    pub open_fds: Option<u64>,
+    pub resident_memory_bytes: Option<u64>,
+    /// Tasks spawned on the current tokio runtime that have not finished yet.
+    pub tokio_alive_tasks: Option<u64>,
+    pub open_sessions: usize,
+    pub idempotency_records: usize,
+}
+
+impl StatsReport {
+    pub fn collect(open_sessions: usize, idempotency_records: usize) -> Self {
+        Self {
+            open_fds: open_fds(),
+            resident_memory_bytes: resident_memory_bytes(),
+            tokio_alive_tasks: tokio::runtime::Handle::try_current()
+                .ok()
+                .map(|handle| handle.metrics().num_alive_tasks() as u64),
+            open_sessions,
+            idempotency_records,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn open_fds() -> Option<u64> {
+    let entries = std::fs::read_dir("/proc/self/fd").ok()?;
+    // The directory handle used for the listing shows up in it as well.
+    Some((entries.count() as u64).saturating_sub(1))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_fds() -> Option<u64> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn resident_memory_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    parse_vm_rss(&status)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn resident_memory_bytes() -> Option<u64> {
+    None
+}
+
+/// `VmRSS` from `/proc/<pid>/status`, which the kernel reports in kB.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let mut fields = line["VmRSS:".len()..].split_whitespace();
+    let value: u64 = fields.next()?.parse().ok()?;
+    match fields.next() {
+        Some("kB") | None => Some(value * 1024),
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_vm_rss_in_kilobytes() {
+        let status =
+            "Name:\tmcp-multi-tool\nVmPeak:\t  20000 kB\nVmRSS:\t   1234 kB\nThreads:\t4\n";
+        assert_eq!(parse_vm_rss(status), Some(1234 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tkthreadd\n"), None);
+    }
+}
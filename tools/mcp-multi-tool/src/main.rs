@@ -9,9 +9,17 @@ use mcp_multi_tool::{
         inspector_service::InspectorService,
         registry::ToolRegistry,
     },
-    infra::{config::AppConfig, metrics, outbox::Outbox},
-    shared::idempotency::{
-        IdempotencyStore, configure_lock_observer as configure_idempotency_observer,
+    infra::{
+        compliance_history::ComplianceHistory, config::AppConfig, event_policy::PolicySink,
+        event_sink::EventSink, metrics, outbox::Outbox,
+    },
+    shared::{
+        idempotency::{
+            IdempotencyStore, configure_lock_observer as configure_idempotency_observer,
+        },
+        recent_events::{
+            RecentEvents, configure_lock_observer as configure_recent_events_observer,
+        },
     },
 };
 use rmcp::{ServiceExt, transport::stdio};
@@ -32,6 +40,28 @@ async fn main() -> Result<()> {
         .init();
 
     let config = AppConfig::load()?;
+    let recent_events = Arc::new(RecentEvents::new(config.recent_events_cap()));
+
+    configure_idempotency_observer(metrics::observe_lock_wait);
+    configure_error_budget_observer(metrics::observe_lock_wait);
+    configure_recent_events_observer(metrics::observe_lock_wait);
+
+    let (outbox_main, outbox_dlq) = config.outbox_paths();
+    let outbox = if let Some(db_path) = config.outbox_db_path() {
+        Outbox::sqlite(db_path, outbox_dlq.clone())?
+            .with_compression(config.outbox_compress())
+            .with_indexed_fields(&config.outbox_index_fields)?
+    } else {
+        Outbox::file(outbox_main, outbox_dlq.clone())?.with_partition(config.outbox_partition)
+    }
+    .with_append_retries(config.outbox_append_retries());
+    let outbox: Arc<dyn EventSink> = Arc::new(
+        PolicySink::new(Arc::new(outbox), outbox_dlq.clone())?
+            .with_sample_rate(config.outbox_sample_rate())
+            .with_binary_content(config.outbox_binary_content)
+            .with_excluded_fields(&config.outbox_exclude_fields)
+            .with_max_event_bytes(config.outbox_max_event_bytes()),
+    );
     if let Some(metrics_cfg) = config.metrics_server_config()? {
         if metrics_cfg.allow_insecure && metrics_cfg.tls.is_none() {
             tracing::warn!(
@@ -44,26 +74,23 @@ async fn main() -> Result<()> {
                 "metrics auth token missing; set METRICS_AUTH_TOKEN for production"
             );
         }
-        metrics::spawn_metrics_server(metrics_cfg).await;
+        let health = metrics::HealthProbe {
+            outbox: outbox.clone(),
+            release_track: config.release_track,
+        };
+        metrics::spawn_metrics_server(metrics_cfg, recent_events.clone(), health).await;
     }
-
-    configure_idempotency_observer(metrics::observe_lock_wait);
-    configure_error_budget_observer(metrics::observe_lock_wait);
-
-    let (outbox_main, outbox_dlq) = config.outbox_paths();
-    let outbox = if let Some(db_path) = config.outbox_db_path() {
-        Outbox::sqlite(db_path, outbox_dlq.clone())?
-    } else {
-        Outbox::file(outbox_main, outbox_dlq.clone())?
-    };
-    let outbox = Arc::new(outbox);
-    let idempotency = Arc::new(IdempotencyStore::new());
+    let idempotency = Arc::new(
+        IdempotencyStore::with_limits(config.idempotency_limits())
+            .with_reaper_exempt_tools(config.reaper_exempt_tools.iter().cloned()),
+    );
     let error_budget = Arc::new(ErrorBudget::new(ErrorBudgetParams {
         enabled: config.error_budget.enabled,
         success_threshold: config.error_budget.success_threshold,
         minimum_requests: config.error_budget.minimum_requests as usize,
         sample_window: Duration::from_secs(config.error_budget.sample_window_secs),
         freeze_duration: Duration::from_secs(config.error_budget.freeze_window_secs),
+        warmup: Duration::from_secs(config.error_budget.warmup_secs),
     }));
     {
         let store = idempotency.clone();
@@ -91,14 +118,71 @@ async fn main() -> Result<()> {
         });
     }
 
+    let inspector = InspectorService::new()
+        .with_handshake_timeouts(config.handshake_timeouts())
+        .with_handshake_retries(config.handshake_retries())
+        .with_retry_jitter(config.handshake_retry_jitter)
+        .with_max_tool_list_pages(config.max_tool_list_pages())
+        .with_spawn_timeout(config.stdio_spawn_timeout())
+        .with_stream_max_duration(config.stream_max_duration())
+        .with_call_timeout(config.call_timeout())
+        .with_min_protocol_version(config.min_protocol_version.clone())
+        .with_stream_drain_timeout(config.stream_drain_timeout())
+        .with_deadline_margin(config.deadline_margin())
+        .with_partial_stream_policy(config.partial_stream_policy)
+        .with_stdio_lenient_framing(config.stdio_lenient_framing())
+        .with_stdio_env_clear(config.stdio_env_clear())
+        .with_allow_insecure_target_tls(config.allow_insecure_target_tls())
+        .with_allow_shell_targets(config.allow_shell_targets())
+        .with_allow_ambiguous_target(config.allow_ambiguous_target())
+        .with_target_proxy(config.target_proxy())
+        .with_capture_unknown_notifications(config.capture_unknown_notifications())
+        .with_connection_cache(
+            config.connection_cache_idle_ttl(),
+            config.connection_cache_max_size(),
+        )
+        .with_session_idle_timeout(config.session_idle_timeout());
+    {
+        let inspector = inspector.clone();
+        let cadence = config
+            .connection_cache_idle_ttl()
+            .min(config.session_idle_timeout())
+            .max(Duration::from_secs(1));
+        tokio::spawn(async move {
+            loop {
+                sleep(cadence).await;
+                inspector.sweep_idle_connections();
+            }
+        });
+    }
+
+    let compliance_history = config
+        .compliance_history_db()
+        .map(|path| ComplianceHistory::open(path, config.compliance_history_retention()))
+        .transpose()?
+        .map(Arc::new);
+
     let handler = InspectorServer::new(
-        InspectorService::new(),
-        ToolRegistry::new(config.release_track),
+        inspector,
+        ToolRegistry::new(config.release_track).with_admin_tools(config.admin_tools_enabled()),
         outbox,
         idempotency,
         config.idempotency_conflict_policy,
         error_budget,
-    );
+    )
+    .with_empty_result_policy(config.empty_result_policy)
+    .with_duplicate_external_ref_policy(config.duplicate_external_ref_policy)
+    .with_dotted_tool_name_policy(config.dotted_tool_name_policy)
+    .with_idempotency_auto_key(config.idempotency_auto_key())
+    .with_json_limits(config.json_limits())
+    .with_tool_name_policy(config.tool_name_policy())
+    .with_capture_target_capabilities(config.capture_target_capabilities())
+    .with_max_concurrent_streams(config.max_concurrent_streams())
+    .with_target_aliases(config.target_aliases()?)
+    .with_tool_arg_defaults(config.tool_arg_defaults)
+    .with_recent_events(recent_events)
+    .with_compliance_history(compliance_history)
+    .with_freeze_allowed_tools(config.error_budget.freeze_allowed_tools.clone());
     // Start the server. Emit tools/list_changed inside on_initialized so
     // the notification is not lost before the handshake completes.
     let server = handler.serve(stdio()).await?;
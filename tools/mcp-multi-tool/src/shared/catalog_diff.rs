@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use rmcp::model::Tool;
+use serde_json::Value;
+
+use crate::shared::{
+    schema_hash::canonicalize,
+    types::{CatalogDiff, ToolDifference},
+};
+
+/// Compare two tool catalogs by name. Schemas are canonicalized first, so property
+/// order and `1` vs `1.0` never count as drift.
+pub fn diff_catalogs(a: &[Tool], b: &[Tool]) -> CatalogDiff {
+    let a: BTreeMap<&str, &Tool> = a.iter().map(|tool| (tool.name.as_ref(), tool)).collect();
+    let b: BTreeMap<&str, &Tool> = b.iter().map(|tool| (tool.name.as_ref(), tool)).collect();
+    let only_in_a: Vec<String> = a
+        .keys()
+        .filter(|name| !b.contains_key(*name))
+        .map(|name| name.to_string())
+        .collect();
+    let only_in_b: Vec<String> = b
+        .keys()
+        .filter(|name| !a.contains_key(*name))
+        .map(|name| name.to_string())
+        .collect();
+    let changed: Vec<ToolDifference> = a
+        .iter()
+        .filter_map(|(name, tool_a)| {
+            let tool_b = b.get(name)?;
+            let mut paths = Vec::new();
+            diff_values(&comparable(tool_a), &comparable(tool_b), "", &mut paths);
+            (!paths.is_empty()).then(|| ToolDifference {
+                name: name.to_string(),
+                paths,
+            })
+        })
+        .collect();
+    CatalogDiff {
+        identical: only_in_a.is_empty() && only_in_b.is_empty() && changed.is_empty(),
+        only_in_a,
+        only_in_b,
+        changed,
+    }
+}
+
+/// The parts of a tool that make up its contract, in canonical form.
+fn comparable(tool: &Tool) -> Value {
+    canonicalize(&serde_json::json!({
+        "description": tool.description,
+        "inputSchema": tool.input_schema.as_ref(),
+        "outputSchema": tool.output_schema.as_deref(),
+    }))
+}
+
+/// Append the JSON pointer of every leaf where `a` and `b` differ. A key present on one
+/// side only is reported at that key; arrays are compared position by position.
+fn diff_values(a: &Value, b: &Value, pointer: &str, paths: &mut Vec<String>) {
+    match (a, b) {
+        (Value::Object(left), Value::Object(right)) => {
+            let mut keys: Vec<&String> = left.keys().chain(right.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = format!("{pointer}/{}", escape_token(key));
+                match (left.get(key), right.get(key)) {
+                    (Some(l), Some(r)) => diff_values(l, r, &child, paths),
+                    _ => paths.push(child),
+                }
+            }
+        }
+        (Value::Array(left), Value::Array(right)) => {
+            for index in 0..left.len().max(right.len()) {
+                let child = format!("{pointer}/{index}");
+                match (left.get(index), right.get(index)) {
+                    (Some(l), Some(r)) => diff_values(l, r, &child, paths),
+                    _ => paths.push(child),
+                }
+            }
+        }
+        _ if a != b => paths.push(pointer.to_string()),
+        _ => {}
+    }
+}
+
+/// RFC 6901 escaping for one reference token.
+fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use serde_json::json;
+
+    fn tool(name: &str, description: &str, schema: Value) -> Tool {
+        Tool::new(
+            name.to_string(),
+            description.to_string(),
+            Arc::new(schema.as_object().cloned().unwrap()),
+        )
+    }
+
+    #[test]
+    fn property_order_is_not_drift() {
+        let a = tool(
+            "echo",
+            "Echo text.",
+            json!({"type": "object", "properties": {"a": {"type": "string"}, "b": {"type": "integer"}}}),
+        );
+        let b = tool(
+            "echo",
+            "Echo text.",
+            json!({"properties": {"b": {"type": "integer"}, "a": {"type": "string"}}, "type": "object"}),
+        );
+        let diff = diff_catalogs(&[a], &[b]);
+        assert!(diff.identical, "{diff:?}");
+    }
+
+    #[test]
+    fn reports_membership_and_changed_paths() {
+        let a = [
+            tool("echo", "Echo text.", json!({"type": "object"})),
+            tool(
+                "add",
+                "Sum.",
+                json!({"type": "object", "required": ["values"], "properties": {"a/b": {"type": "string"}}}),
+            ),
+        ];
+        let b = [
+            tool(
+                "add",
+                "Sum numbers.",
+                json!({"type": "object", "required": ["values", "precision"], "properties": {"a/b": {"type": "number"}}}),
+            ),
+            tool("shout", "Upper-case text.", json!({"type": "object"})),
+        ];
+        let diff = diff_catalogs(&a, &b);
+        assert!(!diff.identical);
+        assert_eq!(diff.only_in_a, ["echo"]);
+        assert_eq!(diff.only_in_b, ["shout"]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].name, "add");
+        assert_eq!(
+            diff.changed[0].paths,
+            [
+                "/description",
+                "/inputSchema/properties/a~1b/type",
+                "/inputSchema/required/1",
+            ]
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use time::format_description::well_known::Rfc3339;
+
+/// Subject and expiry of an X.509 certificate, as reported by HTTPS probes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateInfo {
+    /// RFC 4514-style distinguished name, e.g. `C=US, O=Example, CN=localhost`.
+    pub subject: String,
+    /// `notAfter` as an RFC 3339 UTC timestamp.
+    pub not_after: String,
+}
+
+/// Read the subject and `notAfter` from a DER-encoded certificate; `None` if it does not parse.
+pub fn parse_der(der: &[u8]) -> Option<CertificateInfo> {
+    let (_, certificate) = x509_parser::parse_x509_certificate(der).ok()?;
+    Some(CertificateInfo {
+        subject: certificate.subject().to_string(),
+        not_after: certificate
+            .validity()
+            .not_after
+            .to_datetime()
+            .format(&Rfc3339)
+            .ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+
+    const DEV_CERT: &str = include_str!("../../../../config/certs/multitool.crt");
+
+    fn dev_cert_der() -> Vec<u8> {
+        let body: String = DEV_CERT
+            .lines()
+            .filter(|line| !line.starts_with("-----"))
+            .collect();
+        base64::engine::general_purpose::STANDARD
+            .decode(body)
+            .expect("valid PEM body")
+    }
+
+    #[test]
+    fn reads_subject_and_expiry_from_dev_certificate() {
+        let info = parse_der(&dev_cert_der()).expect("certificate parses");
+        assert_eq!(
+            info.subject,
+            "C=US, ST=CA, L=San Francisco, O=MCP MultiTool, OU=Dev, CN=localhost"
+        );
+        assert_eq!(info.not_after, "2035-10-21T21:59:07Z");
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        let der = dev_cert_der();
+        assert!(parse_der(&der[..der.len() / 2]).is_none());
+        assert!(parse_der(&[]).is_none());
+    }
+}
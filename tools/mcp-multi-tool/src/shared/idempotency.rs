@@ -1,7 +1,12 @@
-use crate::shared::types::{CallRequest, InspectionRunEvent, TargetDescriptor};
+use crate::shared::types::{
+    CallRequest, IdempotencySnapshotEntry, InspectionRunEvent, TargetDescriptor,
+};
+use anyhow::anyhow;
 use once_cell::sync::Lazy;
 use parking_lot::{Mutex, RwLock};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 use std::time::{Duration, Instant};
 use time::OffsetDateTime;
 
@@ -9,7 +14,7 @@ use time::OffsetDateTime;
 struct InFlightRecord {
     claimed_at: Instant,
     run_id: Option<uuid::Uuid>,
-    request: Option<CallRequest>,
+    request: Option<Box<CallRequest>>,
     target: Option<TargetDescriptor>,
     started_at: Option<OffsetDateTime>,
 }
@@ -31,7 +36,7 @@ enum Record {
     InFlight(InFlightRecord),
     Completed {
         claimed_at: Instant,
-        event: InspectionRunEvent,
+        event: Box<InspectionRunEvent>,
     },
 }
 
@@ -47,10 +52,41 @@ pub struct ReapedEvent {
     pub event: InspectionRunEvent,
 }
 
+/// What `claim` does once the store already holds `max_records` keys.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IdempotencyOverflowPolicy {
+    /// Refuse new keys with `ClaimOutcome::StoreFull`.
+    #[default]
+    Reject,
+    /// Drop the oldest completed record to make room; rejects only when every record is in-flight.
+    EvictOldest,
+}
+
+impl FromStr for IdempotencyOverflowPolicy {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "reject" => Ok(Self::Reject),
+            "evict_oldest" | "evict" | "lru" => Ok(Self::EvictOldest),
+            other => Err(anyhow!("unknown idempotency overflow policy '{}'", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdempotencyLimits {
+    /// Upper bound on stored keys; `None` keeps the store unbounded.
+    pub max_records: Option<usize>,
+    pub overflow_policy: IdempotencyOverflowPolicy,
+}
+
 #[derive(Debug, Default)]
 pub struct IdempotencyStore {
     records: Mutex<HashMap<String, Record>>,
     external_refs: Mutex<HashMap<String, ExternalRecord>>,
+    limits: IdempotencyLimits,
+    reaper_exempt_tools: HashSet<String>,
 }
 
 type LockObserver = fn(&'static str, Duration);
@@ -70,7 +106,9 @@ pub fn configure_lock_observer(observer: LockObserver) {
 pub enum ClaimOutcome {
     Accepted,
     InFlight,
-    Completed(InspectionRunEvent),
+    Completed(Box<InspectionRunEvent>),
+    /// The key is new but the store reached `max_records` and the overflow policy refused it.
+    StoreFull,
 }
 
 impl IdempotencyStore {
@@ -78,9 +116,36 @@ impl IdempotencyStore {
         Self {
             records: Mutex::new(HashMap::new()),
             external_refs: Mutex::new(HashMap::new()),
+            limits: IdempotencyLimits::default(),
+            reaper_exempt_tools: HashSet::new(),
         }
     }
 
+    pub fn with_limits(limits: IdempotencyLimits) -> Self {
+        Self {
+            limits,
+            ..Self::new()
+        }
+    }
+
+    /// In-flight runs of these tools are never timed out by `reap_expired`.
+    pub fn with_reaper_exempt_tools<I, S>(mut self, tools: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.reaper_exempt_tools = tools.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub fn claim(&self, key: &str) -> ClaimOutcome {
         let wait = Instant::now();
         let mut store = self.records.lock();
@@ -89,12 +154,44 @@ impl IdempotencyStore {
             Some(Record::InFlight(_)) => ClaimOutcome::InFlight,
             Some(Record::Completed { event, .. }) => ClaimOutcome::Completed(event.clone()),
             None => {
+                if !self.make_room(&mut store) {
+                    return ClaimOutcome::StoreFull;
+                }
                 store.insert(key.to_string(), Record::InFlight(InFlightRecord::new()));
                 ClaimOutcome::Accepted
             }
         }
     }
 
+    fn make_room(&self, store: &mut HashMap<String, Record>) -> bool {
+        let Some(max_records) = self.limits.max_records else {
+            return true;
+        };
+        if store.len() < max_records {
+            return true;
+        }
+        if self.limits.overflow_policy == IdempotencyOverflowPolicy::Reject {
+            return false;
+        }
+        while store.len() >= max_records {
+            let oldest = store
+                .iter()
+                .filter_map(|(key, record)| match record {
+                    Record::Completed { claimed_at, .. } => Some((key, *claimed_at)),
+                    Record::InFlight(_) => None,
+                })
+                .min_by_key(|(_, claimed_at)| *claimed_at)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(key) => {
+                    store.remove(&key);
+                }
+                None => return false,
+            }
+        }
+        true
+    }
+
     pub fn begin(&self, key: &str, run_id: uuid::Uuid, request: &CallRequest) {
         let wait = Instant::now();
         let mut store = self.records.lock();
@@ -104,7 +201,28 @@ impl IdempotencyStore {
             .or_insert_with(|| Record::InFlight(InFlightRecord::new()));
         if let Record::InFlight(record) = entry {
             record.run_id = Some(run_id);
-            record.request = Some(request.clone());
+            record.request = Some(Box::new(request.clone()));
+        }
+    }
+
+    /// Run currently holding `key`, if it is in flight.
+    pub fn in_flight_run(&self, key: &str) -> Option<uuid::Uuid> {
+        let wait = Instant::now();
+        let store = self.records.lock();
+        record_lock_wait("idempotency_records", wait.elapsed());
+        match store.get(key) {
+            Some(Record::InFlight(record)) => record.run_id,
+            _ => None,
+        }
+    }
+
+    /// Drop an in-flight claim without recording an outcome, so the key can be claimed again.
+    pub fn release(&self, key: &str) {
+        let wait = Instant::now();
+        let mut store = self.records.lock();
+        record_lock_wait("idempotency_records", wait.elapsed());
+        if matches!(store.get(key), Some(Record::InFlight(_))) {
+            store.remove(key);
         }
     }
 
@@ -134,7 +252,7 @@ impl IdempotencyStore {
             key.to_string(),
             Record::Completed {
                 claimed_at: Instant::now(),
-                event: event.clone(),
+                event: Box::new(event.clone()),
             },
         );
         drop(store);
@@ -143,6 +261,55 @@ impl IdempotencyStore {
         }
     }
 
+    /// Completed records, sorted by key; in-flight runs are not exported.
+    pub fn export_completed(&self) -> Vec<IdempotencySnapshotEntry> {
+        let wait = Instant::now();
+        let store = self.records.lock();
+        record_lock_wait("idempotency_records", wait.elapsed());
+        let mut entries: Vec<IdempotencySnapshotEntry> = store
+            .iter()
+            .filter_map(|(key, record)| match record {
+                Record::Completed { event, .. } => Some(IdempotencySnapshotEntry {
+                    key: key.clone(),
+                    event: (**event).clone(),
+                }),
+                Record::InFlight(_) => None,
+            })
+            .collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        entries
+    }
+
+    /// Seed completed records from another instance. Keys this store already knows keep
+    /// their local state, and records that do not fit under `max_records` are skipped.
+    /// Returns `(imported, skipped)`.
+    pub fn import_completed(&self, entries: Vec<IdempotencySnapshotEntry>) -> (usize, usize) {
+        let mut imported = 0;
+        let mut skipped = 0;
+        for entry in entries {
+            let wait = Instant::now();
+            let mut store = self.records.lock();
+            record_lock_wait("idempotency_records", wait.elapsed());
+            if store.contains_key(&entry.key) || !self.make_room(&mut store) {
+                skipped += 1;
+                continue;
+            }
+            store.insert(
+                entry.key,
+                Record::Completed {
+                    claimed_at: Instant::now(),
+                    event: Box::new(entry.event.clone()),
+                },
+            );
+            drop(store);
+            if let Some(reference) = entry.event.external_reference.clone() {
+                self.record_external_ref(&reference, entry.event);
+            }
+            imported += 1;
+        }
+        (imported, skipped)
+    }
+
     pub fn reap_expired(&self, ttl: Duration, now: OffsetDateTime) -> Vec<ReapedEvent> {
         let wait = Instant::now();
         let mut store = self.records.lock();
@@ -151,7 +318,9 @@ impl IdempotencyStore {
 
         store.retain(|key, record| match record {
             Record::InFlight(record) => {
-                if record.claimed_at.elapsed() > ttl {
+                if self.is_reaper_exempt(record) {
+                    true
+                } else if record.claimed_at.elapsed() > ttl {
                     if let Some(event) = build_timeout_event(key, record, now) {
                         expired.push((key.clone(), event));
                     }
@@ -169,7 +338,7 @@ impl IdempotencyStore {
                 key.clone(),
                 Record::Completed {
                     claimed_at: Instant::now(),
-                    event: event.clone(),
+                    event: Box::new(event.clone()),
                 },
             );
             results.push(ReapedEvent {
@@ -194,6 +363,13 @@ impl IdempotencyStore {
         results
     }
 
+    fn is_reaper_exempt(&self, record: &InFlightRecord) -> bool {
+        record
+            .request
+            .as_ref()
+            .is_some_and(|request| self.reaper_exempt_tools.contains(&request.tool_name))
+    }
+
     pub fn find_external_ref(&self, reference: &str) -> Option<InspectionRunEvent> {
         let wait = Instant::now();
         let store = self.external_refs.lock();
@@ -246,5 +422,7 @@ fn build_timeout_event(
         )),
         idempotency_key: request.idempotency_key.clone(),
         external_reference: request.external_reference.clone(),
+        target_capabilities: None,
+        requested_tool_name: None,
     })
 }
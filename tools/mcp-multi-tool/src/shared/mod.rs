@@ -1,3 +1,9 @@
+pub mod catalog_diff;
+pub mod cert_info;
 pub mod idempotency;
+pub mod recent_events;
+pub mod schema_hash;
+pub mod schema_validation;
+pub mod target_aliases;
 pub mod types;
 pub mod utils;
@@ -0,0 +1,161 @@
+use crate::shared::types::InspectionRunEvent;
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_RECENT_EVENTS_CAP: usize = 256;
+const SHARD_COUNT: usize = 16;
+
+type LockObserver = fn(&'static str, Duration);
+
+static LOCK_OBSERVER: Lazy<RwLock<Option<LockObserver>>> = Lazy::new(|| RwLock::new(None));
+
+fn record_lock_wait(component: &'static str, waited: Duration) {
+    if let Some(observer) = *LOCK_OBSERVER.read() {
+        observer(component, waited);
+    }
+}
+
+pub fn configure_lock_observer(observer: LockObserver) {
+    *LOCK_OBSERVER.write() = Some(observer);
+}
+
+/// Bounded in-memory tail of recent run events for debugging views.
+///
+/// Writers are spread over independent shards by a global sequence number, so each
+/// shard holds the newest events of its residue class and together they hold the
+/// newest `capacity()` events overall.
+#[derive(Debug)]
+pub struct RecentEvents {
+    shards: Vec<Mutex<VecDeque<(u64, InspectionRunEvent)>>>,
+    per_shard_cap: usize,
+    next_seq: AtomicU64,
+}
+
+impl Default for RecentEvents {
+    fn default() -> Self {
+        Self::new(DEFAULT_RECENT_EVENTS_CAP)
+    }
+}
+
+impl RecentEvents {
+    /// `cap` is the approximate total number of retained events; `0` disables retention.
+    pub fn new(cap: usize) -> Self {
+        let per_shard_cap = cap.div_ceil(SHARD_COUNT);
+        Self {
+            shards: (0..SHARD_COUNT)
+                .map(|_| Mutex::new(VecDeque::with_capacity(per_shard_cap + 1)))
+                .collect(),
+            per_shard_cap,
+            next_seq: AtomicU64::new(0),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.per_shard_cap * SHARD_COUNT
+    }
+
+    pub fn push(&self, event: InspectionRunEvent) {
+        if self.per_shard_cap == 0 {
+            return;
+        }
+        let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+        let wait = Instant::now();
+        let mut shard = self.shards[(seq as usize) % SHARD_COUNT].lock();
+        record_lock_wait("recent_events_shard", wait.elapsed());
+        // Pushes to one shard can land out of order, so evict the oldest by sequence.
+        let at = shard.partition_point(|(held, _)| *held < seq);
+        shard.insert(at, (seq, event));
+        if shard.len() > self.per_shard_cap {
+            shard.pop_front();
+        }
+    }
+
+    /// Up to `limit` events, newest first.
+    pub fn snapshot(&self, limit: usize) -> Vec<InspectionRunEvent> {
+        let mut merged: Vec<(u64, InspectionRunEvent)> = Vec::new();
+        for shard in &self.shards {
+            merged.extend(shard.lock().iter().cloned());
+        }
+        merged.sort_by_key(|(seq, _)| std::cmp::Reverse(*seq));
+        merged.truncate(limit);
+        merged.into_iter().map(|(_, event)| event).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.lock().len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(tool: &str) -> InspectionRunEvent {
+        InspectionRunEvent {
+            event_id: uuid::Uuid::new_v4(),
+            run_id: uuid::Uuid::new_v4(),
+            tool_name: tool.into(),
+            state: "captured".into(),
+            started_at: "2024-01-01T00:00:00Z".into(),
+            duration_ms: 1,
+            target: None,
+            request: None,
+            response: None,
+            error: None,
+            idempotency_key: None,
+            external_reference: None,
+            target_capabilities: None,
+            requested_tool_name: None,
+        }
+    }
+
+    #[test]
+    fn retains_newest_within_cap() {
+        let recent = RecentEvents::new(32);
+        for i in 0..100 {
+            recent.push(event(&format!("tool-{i}")));
+        }
+        assert!(recent.len() <= recent.capacity());
+        let snapshot = recent.snapshot(3);
+        let names: Vec<&str> = snapshot.iter().map(|e| e.tool_name.as_str()).collect();
+        assert_eq!(names, vec!["tool-99", "tool-98", "tool-97"]);
+    }
+
+    #[test]
+    fn concurrent_pushes_keep_the_newest_events() {
+        let recent = RecentEvents::new(64);
+        std::thread::scope(|scope| {
+            for writer in 0..8 {
+                let recent = &recent;
+                scope.spawn(move || {
+                    for i in 0..500 {
+                        recent.push(event(&format!("tool-{writer}-{i}")));
+                    }
+                });
+            }
+        });
+        let total = recent.next_seq.load(Ordering::Relaxed);
+        let mut held: Vec<u64> = recent
+            .shards
+            .iter()
+            .flat_map(|shard| shard.lock().iter().map(|(seq, _)| *seq).collect::<Vec<_>>())
+            .collect();
+        held.sort_unstable();
+        let newest: Vec<u64> = (total - recent.capacity() as u64..total).collect();
+        assert_eq!(held, newest);
+    }
+
+    #[test]
+    fn zero_cap_disables_retention() {
+        let recent = RecentEvents::new(0);
+        recent.push(event("help"));
+        assert!(recent.is_empty());
+    }
+}
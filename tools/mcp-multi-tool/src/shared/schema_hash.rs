@@ -0,0 +1,80 @@
+use serde_json::{Map, Number, Value};
+use sha2::{Digest, Sha256};
+
+/// Rebuild `value` with object keys sorted and integral floats collapsed to integers,
+/// so semantically equal schemas serialize identically.
+pub fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let mut sorted = Map::new();
+            for key in keys {
+                sorted.insert(key.clone(), canonicalize(&map[key]));
+            }
+            Value::Object(sorted)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonicalize).collect()),
+        Value::Number(number) => Value::Number(normalize_number(number)),
+        other => other.clone(),
+    }
+}
+
+fn normalize_number(number: &Number) -> Number {
+    if number.is_f64()
+        && let Some(float) = number.as_f64()
+        && float.fract() == 0.0
+        && float.abs() < i64::MAX as f64
+    {
+        return Number::from(float as i64);
+    }
+    number.clone()
+}
+
+/// Compact canonical JSON text for `value`.
+pub fn canonical_string(value: &Value) -> String {
+    // Keys are inserted in sorted order, which both map backends of serde_json preserve.
+    canonicalize(value).to_string()
+}
+
+/// Lowercase hex SHA-256 of the canonical form.
+pub fn hash(value: &Value) -> String {
+    let digest = Sha256::digest(canonical_string(value).as_bytes());
+    digest.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn hash_stable_across_key_order() {
+        let a = json!({
+            "type": "object",
+            "properties": {
+                "text": {"type": "string", "minLength": 1},
+                "count": {"type": "integer", "minimum": 0}
+            },
+            "required": ["text"]
+        });
+        let b = json!({
+            "required": ["text"],
+            "properties": {
+                "count": {"minimum": 0.0, "type": "integer"},
+                "text": {"minLength": 1, "type": "string"}
+            },
+            "type": "object"
+        });
+        assert_eq!(canonical_string(&a), canonical_string(&b));
+        assert_eq!(hash(&a), hash(&b));
+        assert_eq!(hash(&a).len(), 64);
+    }
+
+    #[test]
+    fn hash_changes_with_content() {
+        let a = json!({"type": "object", "required": ["a", "b"]});
+        let b = json!({"type": "object", "required": ["b", "a"]});
+        assert_ne!(hash(&a), hash(&b));
+    }
+}
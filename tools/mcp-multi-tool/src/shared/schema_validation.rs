@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Validate `value` against a tool's JSON Schema with the `jsonschema` crate, so `$ref`,
+/// `oneOf`/`anyOf`/`allOf`, `pattern` and the rest apply as the schema's draft defines them.
+/// Remote `$ref`s are not fetched; a schema that needs one, or is otherwise unusable,
+/// yields a single violation at `$`.
+///
+/// Returns one `"<path>: <message>"` entry per violation; empty means valid.
+pub fn validate(schema: &Map<String, Value>, value: &Value) -> Vec<String> {
+    violations(schema, value)
+        .into_iter()
+        .map(|violation| format!("{}: {}", violation.instance_path, violation.message))
+        .collect()
+}
+
+/// One schema violation: where in the instance it occurred (`$`, `$.values[1]`) and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Violation {
+    pub instance_path: String,
+    pub message: String,
+}
+
+/// Like [`validate`], but keeps each violation's instance path apart from its message.
+pub fn violations(schema: &Map<String, Value>, value: &Value) -> Vec<Violation> {
+    let validator = match jsonschema::validator_for(&Value::Object(schema.clone())) {
+        Ok(validator) => validator,
+        Err(err) => {
+            return vec![Violation {
+                instance_path: "$".into(),
+                message: format!("invalid schema: {err}"),
+            }];
+        }
+    };
+    validator
+        .iter_errors(value)
+        .map(|error| Violation {
+            instance_path: display_path(value, error.instance_path().as_str()),
+            message: error.to_string(),
+        })
+        .collect()
+}
+
+/// `$.values[1]` for the JSON pointer `/values/1`, telling indices from keys by the shape
+/// of `value` along the way.
+fn display_path(value: &Value, pointer: &str) -> String {
+    let mut path = String::from("$");
+    let mut current = Some(value);
+    for raw in pointer.split('/').skip(1) {
+        let segment = raw.replace("~1", "/").replace("~0", "~");
+        let index = segment.parse::<usize>().ok();
+        current = match (current, index) {
+            (Some(Value::Array(items)), Some(idx)) => {
+                path.push_str(&format!("[{idx}]"));
+                items.get(idx)
+            }
+            (other, _) => {
+                path.push('.');
+                path.push_str(&segment);
+                other.and_then(|node| node.get(&segment))
+            }
+        };
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Map<String, Value> {
+        json!({
+            "type": "object",
+            "required": ["sum", "count"],
+            "properties": {
+                "sum": {"type": "number"},
+                "count": {"type": "integer", "minimum": 0},
+                "tags": {"type": "array", "items": {"type": "string"}}
+            },
+            "additionalProperties": false
+        })
+        .as_object()
+        .cloned()
+        .unwrap()
+    }
+
+    #[test]
+    fn conforming_value_has_no_violations() {
+        let value = json!({"sum": 1.5, "count": 2, "tags": ["a"]});
+        assert!(validate(&schema(), &value).is_empty());
+    }
+
+    #[test]
+    fn reports_each_violation_with_path() {
+        let value = json!({"sum": "oops", "tags": ["a", 3], "extra": true});
+        let violations = validate(&schema(), &value);
+        assert!(
+            violations.contains(&"$: \"count\" is a required property".to_string()),
+            "{violations:?}"
+        );
+        assert!(violations.contains(&"$.sum: \"oops\" is not of type \"number\"".to_string()));
+        assert!(violations.contains(&"$.tags[1]: 3 is not of type \"string\"".to_string()));
+        assert!(
+            violations
+                .iter()
+                .any(|violation| violation.starts_with("$: ") && violation.contains("'extra'")),
+            "{violations:?}"
+        );
+    }
+
+    #[test]
+    fn follows_refs_and_one_of() {
+        let schema = json!({
+            "$defs": {
+                "circle": {
+                    "type": "object",
+                    "required": ["radius"],
+                    "properties": {"radius": {"type": "number"}}
+                },
+                "square": {
+                    "type": "object",
+                    "required": ["side"],
+                    "properties": {"side": {"type": "number"}}
+                }
+            },
+            "type": "object",
+            "properties": {
+                "shape": {"oneOf": [{"$ref": "#/$defs/circle"}, {"$ref": "#/$defs/square"}]}
+            }
+        })
+        .as_object()
+        .cloned()
+        .unwrap();
+
+        assert!(validate(&schema, &json!({"shape": {"radius": 1}})).is_empty());
+        let neither = violations(&schema, &json!({"shape": {"radius": "big"}}));
+        assert_eq!(neither.len(), 1, "{neither:?}");
+        assert_eq!(neither[0].instance_path, "$.shape");
+        let both = violations(&schema, &json!({"shape": {"radius": 1, "side": 2}}));
+        assert_eq!(both.len(), 1, "{both:?}");
+    }
+
+    #[test]
+    fn unusable_schema_is_one_violation() {
+        let schema = json!({"$ref": "https://schemas.example/missing.json"})
+            .as_object()
+            .cloned()
+            .unwrap();
+        let violations = validate(&schema, &json!({}));
+        assert_eq!(violations.len(), 1, "{violations:?}");
+        assert!(
+            violations[0].starts_with("$: invalid schema"),
+            "{violations:?}"
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Result, bail};
+use serde_json::{Map, Value};
+
+use crate::shared::types::{CallTarget, ProbeRequest};
+
+/// The `inspector_call` keys that each select a transport.
+const TARGET_BLOCKS: [&str; 4] = ["stdio", "sse", "http", "unix"];
+
+/// Targets named in the `targets` config section, picked by a request's `target_alias`.
+#[derive(Debug, Clone, Default)]
+pub struct TargetAliases(BTreeMap<String, CallTarget>);
+
+/// A request named a `target_alias` that is not configured.
+#[derive(Debug)]
+pub struct TargetAliasNotFound {
+    pub alias: String,
+    pub known: Vec<String>,
+}
+
+impl std::fmt::Display for TargetAliasNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown target alias '{}'", self.alias)
+    }
+}
+
+impl std::error::Error for TargetAliasNotFound {}
+
+impl TargetAliases {
+    /// Each alias must define exactly one of `stdio`, `sse`, `http` or `unix`.
+    pub fn new(targets: BTreeMap<String, CallTarget>) -> Result<Self> {
+        for (alias, target) in &targets {
+            if target.blocks().len() != 1 {
+                bail!("target alias '{alias}' must define exactly one of stdio, sse, http or unix");
+            }
+        }
+        Ok(Self(targets))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &CallTarget)> {
+        self.0.iter()
+    }
+
+    /// Fill the target fields `args` leaves out from the alias its `target_alias` names;
+    /// fields the request sets win, and maps such as `env` or `headers` are merged key by
+    /// key. `call` selects the `inspector_call` shape (`stdio`/`sse`/`http`/`unix` blocks)
+    /// over the flat probe shape; there, a request that sets a target block of its own only
+    /// takes the alias's block when it is the same one. Arguments without `target_alias`
+    /// are left untouched.
+    pub fn resolve(
+        &self,
+        args: &mut Map<String, Value>,
+        call: bool,
+    ) -> Result<(), TargetAliasNotFound> {
+        let Some(alias) = args.get("target_alias").and_then(Value::as_str) else {
+            return Ok(());
+        };
+        let Some(target) = self.0.get(alias) else {
+            return Err(TargetAliasNotFound {
+                alias: alias.to_string(),
+                known: self.0.keys().cloned().collect(),
+            });
+        };
+        let defaults = if call {
+            serde_json::to_value(target)
+        } else {
+            serde_json::to_value(probe_request(target))
+        };
+        if let Ok(Value::Object(mut defaults)) = defaults {
+            if call {
+                let requested = |block: &str| args.get(block).is_some_and(|value| !value.is_null());
+                if TARGET_BLOCKS.iter().any(|block| requested(block)) {
+                    defaults.retain(|block, _| requested(block));
+                }
+            }
+            fill_missing(args, defaults);
+        }
+        Ok(())
+    }
+}
+
+/// The flat probe form of the single block an alias defines.
+pub fn probe_request(target: &CallTarget) -> ProbeRequest {
+    if let Some(stdio) = &target.stdio {
+        ProbeRequest::from(stdio)
+    } else if let Some(sse) = &target.sse {
+        ProbeRequest::from(sse)
+    } else if let Some(http) = &target.http {
+        ProbeRequest::from(http)
+    } else if let Some(unix) = &target.unix {
+        ProbeRequest::from(unix)
+    } else {
+        ProbeRequest::default()
+    }
+}
+
+fn fill_missing(args: &mut Map<String, Value>, defaults: Map<String, Value>) {
+    for (key, default) in defaults {
+        if default.is_null() {
+            continue;
+        }
+        match (args.get_mut(&key), default) {
+            (Some(Value::Object(set)), Value::Object(default)) => fill_missing(set, default),
+            (None | Some(Value::Null), default) => {
+                args.insert(key, default);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn aliases() -> TargetAliases {
+        let targets = serde_json::from_value(json!({
+            "billing-staging": {"http": {
+                "url": "https://billing.staging/mcp",
+                "auth_token": "secret",
+                "headers": {"x-team": "billing", "x-env": "staging"}
+            }},
+            "local": {"stdio": {"command": "mock_mcp_server", "env": {"MODE": "test"}}}
+        }))
+        .unwrap();
+        TargetAliases::new(targets).unwrap()
+    }
+
+    fn resolved(args: Value, call: bool) -> Value {
+        let mut args = args.as_object().cloned().unwrap();
+        aliases().resolve(&mut args, call).expect("alias resolves");
+        Value::Object(args)
+    }
+
+    #[test]
+    fn alias_fills_the_probe_shape() {
+        let args = resolved(json!({"target_alias": "billing-staging"}), false);
+        assert_eq!(args["transport"], "http");
+        assert_eq!(args["url"], "https://billing.staging/mcp");
+        assert_eq!(args["auth_token"], "secret");
+        let probe: ProbeRequest = serde_json::from_value(args).unwrap();
+        assert_eq!(probe.target_alias.as_deref(), Some("billing-staging"));
+    }
+
+    #[test]
+    fn request_fields_override_alias_values() {
+        let args = resolved(
+            json!({
+                "target_alias": "billing-staging",
+                "url": "https://billing.canary/mcp",
+                "headers": {"x-env": "canary"}
+            }),
+            false,
+        );
+        assert_eq!(args["url"], "https://billing.canary/mcp");
+        assert_eq!(
+            args["headers"],
+            json!({"x-team": "billing", "x-env": "canary"})
+        );
+        assert_eq!(args["auth_token"], "secret");
+
+        let args = resolved(
+            json!({
+                "tool_name": "echo",
+                "target_alias": "local",
+                "stdio": {"command": "other_server", "env": {"EXTRA": "1"}}
+            }),
+            true,
+        );
+        assert_eq!(args["stdio"]["command"], "other_server");
+        assert_eq!(args["stdio"]["env"], json!({"MODE": "test", "EXTRA": "1"}));
+        assert!(args.get("http").is_none(), "{args}");
+    }
+
+    #[test]
+    fn a_request_block_replaces_an_alias_of_another_transport() {
+        let args = resolved(
+            json!({
+                "tool_name": "echo",
+                "target_alias": "billing-staging",
+                "stdio": {"command": "other_server"}
+            }),
+            true,
+        );
+        assert_eq!(args["stdio"], json!({"command": "other_server"}));
+        assert!(args.get("http").is_none(), "{args}");
+    }
+
+    #[test]
+    fn unknown_alias_lists_the_configured_ones() {
+        let mut args = json!({"target_alias": "billing-prod"})
+            .as_object()
+            .cloned()
+            .unwrap();
+        let err = aliases().resolve(&mut args, true).unwrap_err();
+        assert_eq!(err.alias, "billing-prod");
+        assert_eq!(err.known, ["billing-staging", "local"]);
+    }
+
+    #[test]
+    fn alias_must_define_exactly_one_target() {
+        let none = serde_json::from_value(json!({"empty": {}})).unwrap();
+        assert!(TargetAliases::new(none).is_err());
+        let both = serde_json::from_value(json!({"both": {
+            "stdio": {"command": "server"},
+            "http": {"url": "http://127.0.0.1:1/mcp"}
+        }}))
+        .unwrap();
+        assert!(TargetAliases::new(both).is_err());
+    }
+}
@@ -1,49 +1,440 @@
 use anyhow::anyhow;
 use rmcp::schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Value, json};
 use std::str::FromStr;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct EmptyArgs {}
 
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum TargetTransportKind {
     Stdio,
     Sse,
     Http,
+    Unix,
+}
+
+pub const DEFAULT_HANDSHAKE_TIMEOUT_MS: u64 = 15_000;
+/// Default bound on spawning a stdio target, separate from its handshake budget.
+pub const DEFAULT_SPAWN_TIMEOUT_MS: u64 = 5_000;
+/// Default cap on `tools/list` pages followed for one listing.
+pub const DEFAULT_MAX_TOOL_LIST_PAGES: u32 = 20;
+/// Default wait for trailing progress after a streaming call's final result.
+pub const DEFAULT_STREAM_DRAIN_TIMEOUT_MS: u64 = 250;
+/// Default margin between a caller's propagated deadline and the downstream call timeout.
+pub const DEFAULT_DEADLINE_MARGIN_MS: u64 = 100;
+/// Default time an `inspector_connect` session may sit unused before it is closed.
+pub const DEFAULT_SESSION_IDLE_TIMEOUT_MS: u64 = 300_000;
+/// Default number of idle target sessions kept for reuse.
+pub const DEFAULT_CONNECTION_CACHE_MAX_SIZE: usize = 8;
+/// Default time an idle target session is kept before it is closed.
+pub const DEFAULT_CONNECTION_CACHE_IDLE_TTL_MS: u64 = 30_000;
+/// Default number of compliance runs kept in the history db.
+pub const DEFAULT_COMPLIANCE_HISTORY_RETENTION: usize = 500;
+/// Default number of runs `inspector_compliance_history` returns.
+pub const DEFAULT_COMPLIANCE_HISTORY_LIMIT: usize = 20;
+/// Default attempts a `retry` policy makes, the first included.
+pub const DEFAULT_RETRY_MAX_ATTEMPTS: u32 = 3;
+/// Upper bound on a `retry` policy's `max_attempts`.
+pub const MAX_RETRY_ATTEMPTS: u32 = 10;
+/// Default wait before a `retry` policy's first retry; doubles on each further one.
+pub const DEFAULT_RETRY_INITIAL_BACKOFF_MS: u64 = 100;
+/// Default cap on the wait between two attempts of a `retry` policy.
+pub const DEFAULT_RETRY_MAX_BACKOFF_MS: u64 = 2_000;
+
+/// Handshake timeouts used when a request does not set `handshake_timeout_ms`. Unix socket
+/// targets share the stdio default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandshakeTimeouts {
+    pub stdio: Duration,
+    pub sse: Duration,
+    pub http: Duration,
+}
+
+impl Default for HandshakeTimeouts {
+    fn default() -> Self {
+        let default = Duration::from_millis(DEFAULT_HANDSHAKE_TIMEOUT_MS);
+        Self {
+            stdio: default,
+            sse: default,
+            http: default,
+        }
+    }
+}
+
+impl HandshakeTimeouts {
+    pub fn for_transport(&self, transport: TargetTransportKind) -> Duration {
+        match transport {
+            TargetTransportKind::Stdio | TargetTransportKind::Unix => self.stdio,
+            TargetTransportKind::Sse => self.sse,
+            TargetTransportKind::Http => self.http,
+        }
+    }
+
+    /// A per-request timeout always wins over the configured transport default.
+    pub fn resolve(&self, transport: TargetTransportKind, requested_ms: Option<u64>) -> Duration {
+        requested_ms
+            .map(Duration::from_millis)
+            .unwrap_or_else(|| self.for_transport(transport))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ProbeRequest {
+    /// Configured target whose fields fill in any target fields this request leaves out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_alias: Option<String>,
     pub transport: Option<TargetTransportKind>,
     // stdio
     pub command: Option<String>,
     pub args: Option<Vec<String>>,
     pub env: Option<std::collections::BTreeMap<String, String>>,
+    /// Start the child with an empty environment so only `env` reaches it.
+    #[serde(default)]
+    pub env_clear: bool,
+    /// With `env_clear`, still pass the inspector's `PATH` and `HOME`.
+    #[serde(default)]
+    pub inherit_basic: bool,
     pub cwd: Option<String>,
+    /// Run `command` through `sh -c` so pipes, redirects and `&&` work; `args` must be empty.
+    #[serde(default)]
+    pub shell: bool,
     // network
     pub url: Option<String>,
     pub headers: Option<std::collections::BTreeMap<String, String>>,
     pub auth_token: Option<String>,
+    /// TLS settings for an `https` SSE or HTTP target.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TargetTls>,
+    /// Proxy for an SSE or HTTP target instead of the `TARGET_PROXY` default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<TargetProxy>,
+    // unix
+    /// Path of the unix socket a `unix` target listens on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket_path: Option<String>,
     // behavior
     pub handshake_timeout_ms: Option<u64>,
+    /// Protocol version offered in `initialize` instead of the client default (probe only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<String>,
+    /// Report `ok` only when the target lists at least one tool, not on the handshake alone
+    /// (probe only).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub require_tools: bool,
+    /// `false` opens a fresh session instead of reusing an idle one to the same target (list
+    /// and describe only; probe always measures a fresh handshake).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reuse_session: Option<bool>,
+    /// Run list or describe over a session from `inspector_connect` instead of the target
+    /// fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    pub session_id: Option<uuid::Uuid>,
+    /// Retry list or describe on transient transport failures (probe always measures a
+    /// single attempt).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+}
+
+impl ProbeRequest {
+    pub fn reuses_session(&self) -> bool {
+        self.reuse_session.unwrap_or(true)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProbeResult {
     pub ok: bool,
     pub transport: String,
+    /// `serverInfo.name` from the target's `initialize` result.
     pub server_name: Option<String>,
+    /// Human-readable `serverInfo.title`, when the target sends one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
     pub version: Option<String>,
     pub latency_ms: Option<u64>,
     pub error: Option<String>,
+    /// Capabilities the target advertised during the handshake.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<ProbeCapabilities>,
+    /// Subject of the certificate presented by an `https` target.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_subject: Option<String>,
+    /// Expiry (`notAfter`, RFC 3339) of the certificate presented by an `https` target.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_not_after: Option<String>,
+    /// Protocol version the target answered `initialize` with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<String>,
+    /// Whether the target was reached through an HTTP proxy.
+    #[serde(default)]
+    pub proxied: bool,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ProbeCapabilities {
+    pub tools: bool,
+    pub resources: bool,
+    pub prompts: bool,
+    pub logging: bool,
+    pub completions: bool,
+}
+
+impl From<&rmcp::model::ServerCapabilities> for ProbeCapabilities {
+    fn from(caps: &rmcp::model::ServerCapabilities) -> Self {
+        Self {
+            tools: caps.tools.is_some(),
+            resources: caps.resources.is_some(),
+            prompts: caps.prompts.is_some(),
+            logging: caps.logging.is_some(),
+            completions: caps.completions.is_some(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SetLoggingLevelRequest {
+    /// Minimum severity the target should emit (`debug`, `info`, ..., `emergency`).
+    #[schemars(with = "String")]
+    pub level: rmcp::model::LoggingLevel,
+    #[serde(flatten)]
+    #[serde(default)]
+    pub probe: ProbeRequest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToolSchemaSize {
+    pub name: String,
+    pub bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReadResourceRequest {
+    /// URI of a resource listed by `inspector_list_resources`.
+    pub uri: String,
+    #[serde(flatten)]
+    #[serde(default)]
+    pub probe: ProbeRequest,
+}
+
+/// Tools a target lists. `tool_count`/`has_tools` make an empty catalog explicit so it
+/// cannot be mistaken for a failed list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolListReport {
+    pub tools: Vec<rmcp::model::Tool>,
+    pub tool_count: usize,
+    pub has_tools: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl From<Vec<rmcp::model::Tool>> for ToolListReport {
+    fn from(tools: Vec<rmcp::model::Tool>) -> Self {
+        let note = tools.is_empty().then(|| {
+            "target advertises no tools; this is valid for resource- or prompt-only servers"
+                .to_string()
+        });
+        Self {
+            tool_count: tools.len(),
+            has_tools: !tools.is_empty(),
+            tools,
+            note,
+        }
+    }
+}
+
+/// Every resource the target lists, with `resources/list` pagination followed to the end.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceListReport {
+    pub resources: Vec<rmcp::model::Resource>,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceContentKind {
+    Text,
+    Blob,
+}
+
+/// One entry of a `resources/read` result; `blob` stays base64-encoded as sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceContentEntry {
+    pub uri: String,
+    pub kind: ResourceContentKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blob: Option<String>,
+}
+
+impl From<rmcp::model::ResourceContents> for ResourceContentEntry {
+    fn from(contents: rmcp::model::ResourceContents) -> Self {
+        match contents {
+            rmcp::model::ResourceContents::TextResourceContents {
+                uri,
+                mime_type,
+                text,
+                ..
+            } => Self {
+                uri,
+                kind: ResourceContentKind::Text,
+                mime_type,
+                text: Some(text),
+                blob: None,
+            },
+            rmcp::model::ResourceContents::BlobResourceContents {
+                uri,
+                mime_type,
+                blob,
+                ..
+            } => Self {
+                uri,
+                kind: ResourceContentKind::Blob,
+                mime_type,
+                text: None,
+                blob: Some(blob),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceReadReport {
+    pub uri: String,
+    pub contents: Vec<ResourceContentEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GetPromptRequest {
+    /// Name of a prompt listed by `inspector_list_prompts`.
+    pub prompt_name: String,
+    /// Values for the prompt's declared arguments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<serde_json::Map<String, Value>>,
+    #[serde(flatten)]
+    #[serde(default)]
+    pub probe: ProbeRequest,
+}
+
+/// Every prompt the target lists, with `prompts/list` pagination followed to the end.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptListReport {
+    pub prompts: Vec<rmcp::model::Prompt>,
+    pub count: usize,
+}
+
+/// A prompt as the target rendered it for the supplied arguments.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptReport {
+    pub prompt_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub messages: Vec<rmcp::model::PromptMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BenchRequest {
+    pub tool_name: String,
+    #[serde(default)]
+    pub arguments_json: Value,
+    /// Total calls across all workers.
+    pub iterations: u32,
+    /// Workers calling in parallel, each over its own session; defaults to 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<u32>,
+    #[serde(flatten)]
+    #[serde(default)]
+    pub probe: ProbeRequest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct ComplianceHistoryRequest {
+    /// Only runs against this target label (the command line, or the URL without one).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Most recent runs to return; defaults to 20.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// One logical target reachable over several transports; set at least one variant.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct TransportBenchRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdio: Option<StdioTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sse: Option<SseTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpTarget>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct TransportBenchEntry {
+    pub transport: String,
+    pub ok: bool,
+    /// Spawn/connect plus handshake time; set only for successful handshakes.
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TransportBenchReport {
+    /// Successful transports fastest first, then the failed ones.
+    pub ranking: Vec<TransportBenchEntry>,
+    /// Fastest transport that completed its handshake, if any did.
+    pub recommended: Option<String>,
+}
+
+impl TransportBenchReport {
+    pub fn ranked(mut entries: Vec<TransportBenchEntry>) -> Self {
+        entries.sort_by_key(|entry| (!entry.ok, entry.latency_ms.unwrap_or(u64::MAX)));
+        let recommended = entries
+            .iter()
+            .find(|entry| entry.ok)
+            .map(|entry| entry.transport.clone());
+        Self {
+            ranking: entries,
+            recommended,
+        }
+    }
+}
+
+/// Complete `InitializeResult` as the target sent it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitializeReport {
+    pub result: rmcp::model::InitializeResult,
+    /// Set when `instructions` exceeded the size limit and was cut.
+    pub instructions_truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SchemaSizeReport {
+    /// Tools sorted by serialized `input_schema` size, largest first.
+    pub tools: Vec<ToolSchemaSize>,
+    pub total_bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SchemaHashReport {
+    pub tool_name: String,
+    pub algorithm: String,
+    /// Lowercase hex digest of `canonical`.
+    pub hash: String,
+    /// Compact JSON of the `input_schema` with sorted keys and normalized numbers.
+    pub canonical: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum ReleaseTrack {
+    #[default]
     Stable,
     Canary,
     Rollback,
@@ -63,12 +454,6 @@ impl ReleaseTrack {
     }
 }
 
-impl Default for ReleaseTrack {
-    fn default() -> Self {
-        ReleaseTrack::Stable
-    }
-}
-
 impl FromStr for ReleaseTrack {
     type Err = anyhow::Error;
 
@@ -94,6 +479,12 @@ pub struct CallRequest {
     pub stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub external_reference: Option<String>,
+    /// Check `structured_content` against the downstream tool's advertised `output_schema`.
+    #[serde(default, skip_serializing_if = "OutputSchemaCheck::is_off")]
+    pub output_schema_check: OutputSchemaCheck,
+    /// Configured target whose fields fill in any target fields this request leaves out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_alias: Option<String>,
     // optional stdio target overrides (takes precedence over environment defaults)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stdio: Option<StdioTarget>,
@@ -101,6 +492,523 @@ pub struct CallRequest {
     pub sse: Option<SseTarget>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub http: Option<HttpTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unix: Option<UnixTarget>,
+    /// With `stream`, bound the whole call (connect, progress and final result); on expiry
+    /// the request is cancelled and the run fails with `STREAM_MAX_DURATION`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_max_duration_ms: Option<u64>,
+    /// Bound on the tool invocation itself, after the handshake; on expiry the run fails
+    /// with `CALL_TIMEOUT`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call_timeout_ms: Option<u64>,
+    /// With `stream`, how long to wait for each further progress event after the final
+    /// result; draining stops early at a chunk whose progress reaches its total.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_drain_timeout_ms: Option<u64>,
+    /// With `stream`, the progress token sent to the target instead of a generated one, so
+    /// its logs can be matched to this call. Must be a string or an integer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress_token: Option<Value>,
+    /// Check `arguments_json` against the tool's `inputSchema` over the open session and
+    /// fail with `INVALID_ARGUMENTS` instead of calling when it does not conform.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub validate_arguments: bool,
+    /// Targets tried in order when the previous one cannot be connected to or handshaken
+    /// with; tool-level errors never fall through.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback_targets: Vec<CallTarget>,
+    /// `false` opens a fresh session instead of reusing an idle one to the same target.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reuse_session: Option<bool>,
+    /// Call over a session from `inspector_connect`; the target fields and fallbacks are
+    /// then ignored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    pub session_id: Option<uuid::Uuid>,
+    /// Retry the handshake on transient transport failures; with an `idempotency_key`, a
+    /// failed call is retried too. Overrides `HANDSHAKE_RETRIES`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+}
+
+/// One downstream target of a call; set exactly one of `stdio`, `sse`, `http` or `unix`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct CallTarget {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stdio: Option<StdioTarget>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sse: Option<SseTarget>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unix: Option<UnixTarget>,
+}
+
+impl CallTarget {
+    pub fn is_empty(&self) -> bool {
+        self.stdio.is_none() && self.sse.is_none() && self.http.is_none() && self.unix.is_none()
+    }
+
+    /// Names of the target blocks set, in field order.
+    pub fn blocks(&self) -> Vec<&'static str> {
+        [
+            ("stdio", self.stdio.is_some()),
+            ("sse", self.sse.is_some()),
+            ("http", self.http.is_some()),
+            ("unix", self.unix.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| set.then_some(name))
+        .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputSchemaCheck {
+    /// Skip output validation.
+    #[default]
+    Off,
+    /// Record violations as an `OUTPUT_SCHEMA_VIOLATION` warning in the trace.
+    Warn,
+    /// Fail the call with `OUTPUT_SCHEMA_VIOLATION` when the output does not conform.
+    Enforce,
+}
+
+impl OutputSchemaCheck {
+    pub fn is_off(&self) -> bool {
+        matches!(self, OutputSchemaCheck::Off)
+    }
+}
+
+/// What a streaming `inspector_call` returns when the connection drops before the final result.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PartialStreamPolicy {
+    /// Fail the call; progress received so far is discarded.
+    #[default]
+    Error,
+    /// Return the progress received so far, flagged `partial: true`, ending in an `error` event.
+    ReturnPartial,
+}
+
+impl FromStr for PartialStreamPolicy {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "return_partial" => Ok(Self::ReturnPartial),
+            other => Err(anyhow!("unknown partial stream policy '{}'", other)),
+        }
+    }
+}
+
+/// Randomization applied to each handshake retry backoff so a fleet does not retry in lockstep.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryJitter {
+    /// Sleep exactly the exponential backoff.
+    #[default]
+    None,
+    /// Sleep a uniform random time between zero and the backoff.
+    Full,
+    /// Sleep half the backoff plus a uniform random time up to the other half.
+    Equal,
+}
+
+impl RetryJitter {
+    pub fn apply(self, backoff: Duration, rng: &mut impl rand::Rng) -> Duration {
+        match self {
+            Self::None => backoff,
+            Self::Full => backoff.mul_f64(rng.random::<f64>()),
+            Self::Equal => {
+                let half = backoff / 2;
+                half + half.mul_f64(rng.random::<f64>())
+            }
+        }
+    }
+}
+
+/// A transient transport failure a `retry` policy can retry.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryOn {
+    /// The target could not be spawned or connected to, or dropped the connection.
+    Connect,
+    /// The target was reached but the `initialize` exchange failed.
+    Handshake,
+    /// The spawn, handshake or call ran past its timeout.
+    Timeout,
+}
+
+/// Retry with exponential backoff for transient transport failures.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts in total, the first included; defaults to 3, at most 10.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+    /// Wait before the first retry, doubled for each further one; defaults to 100.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_backoff_ms: Option<u64>,
+    /// Cap on the wait between attempts; defaults to 2000.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_backoff_ms: Option<u64>,
+    /// Failures worth retrying; empty retries all of them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub retry_on: Vec<RetryOn>,
+}
+
+impl RetryPolicy {
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+            .unwrap_or(DEFAULT_RETRY_MAX_ATTEMPTS)
+            .clamp(1, MAX_RETRY_ATTEMPTS)
+    }
+
+    pub fn retries(&self, failure: RetryOn) -> bool {
+        self.retry_on.is_empty() || self.retry_on.contains(&failure)
+    }
+
+    /// Wait before retry `retry` (1-based), before jitter.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let initial = self
+            .initial_backoff_ms
+            .unwrap_or(DEFAULT_RETRY_INITIAL_BACKOFF_MS);
+        let cap = self.max_backoff_ms.unwrap_or(DEFAULT_RETRY_MAX_BACKOFF_MS);
+        let factor = 1u64
+            .checked_shl(retry.saturating_sub(1))
+            .unwrap_or(u64::MAX);
+        Duration::from_millis(initial.saturating_mul(factor).min(cap))
+    }
+}
+
+impl FromStr for RetryJitter {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "full" => Ok(Self::Full),
+            "equal" => Ok(Self::Equal),
+            other => Err(anyhow!("unknown retry jitter '{}'", other)),
+        }
+    }
+}
+
+/// A call target setting several of `stdio`, `sse`, `http` and `unix`, which the legacy
+/// precedence would settle silently. Raised before anything connects.
+#[derive(Debug)]
+pub struct AmbiguousTarget {
+    /// `request` for the request's own blocks, or `fallback_targets[i]`.
+    pub location: String,
+    pub blocks: Vec<&'static str>,
+    /// The alias the request named, which may have supplied one of the blocks.
+    pub target_alias: Option<String>,
+}
+
+impl std::fmt::Display for AmbiguousTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} sets more than one target block ({}); keep one",
+            self.location,
+            self.blocks.join(", ")
+        )?;
+        if let Some(alias) = &self.target_alias {
+            write!(
+                f,
+                " or drop the blocks target alias '{alias}' already defines"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for AmbiguousTarget {}
+
+impl CallRequest {
+    pub fn reuses_session(&self) -> bool {
+        self.reuse_session.unwrap_or(true)
+    }
+
+    /// The request's own target blocks, possibly none.
+    pub fn primary_target(&self) -> CallTarget {
+        CallTarget {
+            stdio: self.stdio.clone(),
+            sse: self.sse.clone(),
+            http: self.http.clone(),
+            unix: self.unix.clone(),
+        }
+    }
+
+    /// Fail when the request's own target or a fallback sets more than one target block;
+    /// a call over `session_id` ignores them all and is never ambiguous.
+    pub fn check_target_blocks(&self) -> Result<(), AmbiguousTarget> {
+        if self.session_id.is_some() {
+            return Ok(());
+        }
+        let fallbacks = self
+            .fallback_targets
+            .iter()
+            .enumerate()
+            .map(|(idx, target)| (format!("fallback_targets[{idx}]"), target.blocks()));
+        for (location, blocks) in
+            std::iter::once(("request".to_string(), self.primary_target().blocks()))
+                .chain(fallbacks)
+        {
+            if blocks.len() > 1 {
+                return Err(AmbiguousTarget {
+                    location,
+                    blocks,
+                    target_alias: self.target_alias.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// The request's own target followed by its fallbacks, in the order they are tried.
+    pub fn target_chain(&self) -> Vec<CallTarget> {
+        std::iter::once(self.primary_target())
+            .filter(|target| !target.is_empty())
+            .chain(self.fallback_targets.iter().cloned())
+            .collect()
+    }
+
+    /// Downstream arguments as a JSON object; `null` means the tool is called without arguments.
+    pub fn arguments_object(&self) -> anyhow::Result<Option<serde_json::Map<String, Value>>> {
+        match &self.arguments_json {
+            Value::Object(map) => Ok(Some(map.clone())),
+            Value::Null => Ok(None),
+            other => Err(anyhow!(
+                "arguments_json must be a JSON object, got {}",
+                json_kind(other)
+            )),
+        }
+    }
+
+    /// Caller-supplied `progress_token` as an MCP token; `None` means one is generated.
+    pub fn progress_token(&self) -> anyhow::Result<Option<rmcp::model::ProgressToken>> {
+        use rmcp::model::{NumberOrString, ProgressToken};
+        match &self.progress_token {
+            None => Ok(None),
+            Some(Value::String(token)) if !token.is_empty() => Ok(Some(ProgressToken(
+                NumberOrString::String(token.as_str().into()),
+            ))),
+            Some(Value::Number(number)) if number.is_i64() => Ok(Some(ProgressToken(
+                NumberOrString::Number(number.as_i64().unwrap_or_default()),
+            ))),
+            Some(Value::String(_)) => Err(anyhow!("progress_token must not be empty")),
+            Some(other) => Err(anyhow!(
+                "progress_token must be a string or an integer, got {}",
+                json_kind(other)
+            )),
+        }
+    }
+
+    /// Key derived from the tool, canonical arguments and target overrides, so identical
+    /// calls map to the same idempotency record.
+    pub fn derived_idempotency_key(&self) -> String {
+        let mut identity = json!({
+            "tool_name": self.tool_name,
+            "arguments": self.arguments_json,
+            "stdio": self.stdio,
+            "sse": self.sse,
+            "http": self.http,
+        });
+        // Only when present, so keys derived before fallbacks existed stay stable.
+        if let Some(unix) = &self.unix {
+            identity["unix"] = json!(unix);
+        }
+        if !self.fallback_targets.is_empty() {
+            identity["fallback_targets"] = json!(self.fallback_targets);
+        }
+        if let Some(session_id) = self.session_id {
+            identity["session_id"] = json!(session_id);
+        }
+        format!("auto:{}", crate::shared::schema_hash::hash(&identity))
+    }
+
+    /// Fill keys missing from `arguments_json` with `defaults`; caller-supplied keys win.
+    /// Non-object arguments are left alone so `arguments_object` can still reject them.
+    pub fn apply_arg_defaults(&mut self, defaults: &serde_json::Map<String, Value>) {
+        if self.arguments_json.is_null() {
+            self.arguments_json = Value::Object(serde_json::Map::new());
+        }
+        if let Value::Object(args) = &mut self.arguments_json {
+            for (key, value) in defaults {
+                args.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+    }
+}
+
+pub const DEFAULT_MAX_JSON_DEPTH: usize = 64;
+pub const DEFAULT_MAX_JSON_NODES: usize = 100_000;
+
+/// Shape bounds `inspector_call` enforces on `arguments_json` before dispatch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonLimits {
+    /// Deepest allowed nesting; top-level members sit at depth 1.
+    pub max_depth: usize,
+    /// Most values (containers and scalars) allowed in total.
+    pub max_nodes: usize,
+}
+
+impl Default for JsonLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_JSON_DEPTH,
+            max_nodes: DEFAULT_MAX_JSON_NODES,
+        }
+    }
+}
+
+impl JsonLimits {
+    /// Walk `value` without recursion, failing at the first limit it exceeds.
+    pub fn check(&self, value: &Value) -> anyhow::Result<()> {
+        let mut pending = vec![(value, 0usize)];
+        let mut nodes = 0usize;
+        while let Some((value, depth)) = pending.pop() {
+            nodes += 1;
+            if nodes > self.max_nodes {
+                return Err(anyhow!(
+                    "arguments_json holds more than {} values",
+                    self.max_nodes
+                ));
+            }
+            if depth > self.max_depth {
+                return Err(anyhow!(
+                    "arguments_json nests deeper than {} levels",
+                    self.max_depth
+                ));
+            }
+            match value {
+                Value::Array(items) => pending.extend(items.iter().map(|item| (item, depth + 1))),
+                Value::Object(map) => pending.extend(map.values().map(|item| (item, depth + 1))),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Which downstream tool names `inspector_call` may invoke. Patterns are globs where `*`
+/// matches any run of characters and `?` matches one. A deny match always wins; a non-empty
+/// allowlist then requires the name to match one of its patterns.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolNamePolicy {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+impl ToolNamePolicy {
+    pub fn check(&self, tool_name: &str) -> anyhow::Result<()> {
+        if let Some(pattern) = self
+            .deny
+            .iter()
+            .find(|pattern| glob_match(pattern, tool_name))
+        {
+            return Err(anyhow!(
+                "downstream tool '{tool_name}' is denied by pattern '{pattern}'"
+            ));
+        }
+        if !self.allow.is_empty()
+            && !self
+                .allow
+                .iter()
+                .any(|pattern| glob_match(pattern, tool_name))
+        {
+            return Err(anyhow!(
+                "downstream tool '{tool_name}' is not in the allowlist"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Match `text` against a glob supporting `*` and `?`, backtracking only to the last `*`.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+fn json_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// A session opened by `inspector_connect` for `inspector_disconnect` to close.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DisconnectRequest {
+    #[schemars(with = "String")]
+    pub session_id: uuid::Uuid,
+}
+
+/// What `inspector_connect` returns: the id later requests pass as `session_id`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConnectResult {
+    #[schemars(with = "String")]
+    pub session_id: uuid::Uuid,
+    /// Target with credential-bearing headers and URL passwords masked.
+    pub target: TargetDescriptor,
+    pub server_name: Option<String>,
+    pub version: Option<String>,
+    pub protocol_version: Option<String>,
+}
+
+/// An in-flight `inspector_call` run for `inspector_cancel` to abort.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CancelRequest {
+    #[schemars(with = "String")]
+    pub run_id: uuid::Uuid,
+}
+
+/// Two targets whose tool catalogs `inspector_compare` diffs, e.g. staging and prod.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CompareRequest {
+    pub target_a: ProbeRequest,
+    pub target_b: ProbeRequest,
+}
+
+/// A tool both targets list whose description or schemas differ.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ToolDifference {
+    pub name: String,
+    /// JSON pointers into `{description, inputSchema, outputSchema}` where the two differ.
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogDiff {
+    pub identical: bool,
+    pub only_in_a: Vec<String>,
+    pub only_in_b: Vec<String>,
+    pub changed: Vec<ToolDifference>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -118,8 +1026,63 @@ pub struct StdioTarget {
     pub args: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env: Option<std::collections::BTreeMap<String, String>>,
+    /// Start the child with an empty environment so only `env` reaches it, instead of
+    /// inheriting the inspector's own variables.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub env_clear: bool,
+    /// With `env_clear`, still pass the inspector's `PATH` and `HOME`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub inherit_basic: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cwd: Option<String>,
+    /// Run `command` through `sh -c` so pipes, redirects and `&&` work; `args` must be empty.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub shell: bool,
+}
+
+impl From<&StdioTarget> for ProbeRequest {
+    fn from(target: &StdioTarget) -> Self {
+        Self {
+            transport: Some(TargetTransportKind::Stdio),
+            command: Some(target.command.clone()),
+            args: Some(target.args.clone()),
+            env: target.env.clone(),
+            env_clear: target.env_clear,
+            inherit_basic: target.inherit_basic,
+            cwd: target.cwd.clone(),
+            shell: target.shell,
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&SseTarget> for ProbeRequest {
+    fn from(target: &SseTarget) -> Self {
+        Self {
+            transport: Some(TargetTransportKind::Sse),
+            url: Some(target.url.clone()),
+            headers: target.headers.clone(),
+            handshake_timeout_ms: target.handshake_timeout_ms,
+            tls: target.tls.clone(),
+            proxy: target.proxy.clone(),
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&HttpTarget> for ProbeRequest {
+    fn from(target: &HttpTarget) -> Self {
+        Self {
+            transport: Some(TargetTransportKind::Http),
+            url: Some(target.url.clone()),
+            headers: target.headers.clone(),
+            auth_token: target.auth_token.clone(),
+            handshake_timeout_ms: target.handshake_timeout_ms,
+            tls: target.tls.clone(),
+            proxy: target.proxy.clone(),
+            ..Self::default()
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
@@ -131,6 +1094,38 @@ pub struct TargetDescriptor {
     pub url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub headers: Option<std::collections::BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket_path: Option<String>,
+}
+
+/// TLS settings for an `https` SSE or HTTP target. Paths name PEM files readable by the
+/// inspector.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default, PartialEq, Eq)]
+pub struct TargetTls {
+    /// CA certificate(s) trusted in addition to the built-in roots, e.g. a private CA.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert_path: Option<String>,
+    /// Client certificate for mTLS; requires `client_key_path`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert_path: Option<String>,
+    /// Private key of the client certificate (PKCS#8, PKCS#1 or SEC1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key_path: Option<String>,
+    /// Accept any server certificate; refused unless `ALLOW_INSECURE_TARGET_TLS` is set.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub insecure_skip_verify: bool,
+}
+
+/// HTTP proxy an SSE or HTTP target is reached through; `https` targets tunnel with CONNECT.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default, PartialEq, Eq)]
+pub struct TargetProxy {
+    /// Proxy URL, e.g. `http://proxy.internal:3128`.
+    pub url: String,
+    /// Basic-auth credentials sent to the proxy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
@@ -140,6 +1135,30 @@ pub struct SseTarget {
     pub headers: Option<std::collections::BTreeMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub handshake_timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TargetTls>,
+    /// Proxy for this target instead of the `TARGET_PROXY` default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<TargetProxy>,
+}
+
+/// A target listening on a unix socket, framed like stdio: one JSON-RPC message per line.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct UnixTarget {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handshake_timeout_ms: Option<u64>,
+}
+
+impl From<&UnixTarget> for ProbeRequest {
+    fn from(target: &UnixTarget) -> Self {
+        Self {
+            transport: Some(TargetTransportKind::Unix),
+            socket_path: Some(target.path.clone()),
+            handshake_timeout_ms: target.handshake_timeout_ms,
+            ..Self::default()
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
@@ -151,6 +1170,11 @@ pub struct HttpTarget {
     pub auth_token: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub handshake_timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TargetTls>,
+    /// Proxy for this target instead of the `TARGET_PROXY` default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<TargetProxy>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -175,6 +1199,27 @@ pub struct InspectionRunEvent {
     pub idempotency_key: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub external_reference: Option<String>,
+    /// `ServerCapabilities` the target advertised in this call's handshake; only recorded
+    /// when capture is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_capabilities: Option<Value>,
+    /// The name the client called when it was an underscored alias for the dotted
+    /// downstream `tool_name`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested_tool_name: Option<String>,
+}
+
+/// A completed idempotency record as handed between instances.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IdempotencySnapshotEntry {
+    pub key: String,
+    pub event: InspectionRunEvent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct IdempotencyImportRequest {
+    /// Records as returned by `inspector_idempotency_export`.
+    pub records: Vec<IdempotencySnapshotEntry>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -192,6 +1237,15 @@ pub struct StreamEvent {
     pub content: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Severity of a `log` event, as sent by the target (`info`, `warning`, ...).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    /// Logger name of a `log` event, when the target supplied one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logger: Option<String>,
+    /// Token the target attached to a `chunk` event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress_token: Option<Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -201,4 +1255,264 @@ pub struct CallTrace {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stream_events: Option<Vec<StreamEvent>>,
     pub outbox_persisted: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<TraceWarning>,
+    /// Attempts against the target, in order; recorded only when retries are enabled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<AttemptRecord>,
+    /// Unhandled notification methods the target sent, first-seen order; recorded only for
+    /// stdio targets with `CAPTURE_UNKNOWN_NOTIFICATIONS` enabled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unknown_notifications: Vec<String>,
+    /// Targets tried in order, recorded only when the request set `fallback_targets`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback_chain: Vec<FallbackAttempt>,
+    /// Milliseconds from sending a `stream: true` call to its first `chunk` event; absent
+    /// when no chunk arrived. Near the total duration means the tool buffered its output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_event_ms: Option<u64>,
+    /// How draining trailing progress after the final result went; `stream: true` only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_drain: Option<StreamDrainReport>,
+}
+
+/// Progress chunks captured for a streaming call, and those announced but never received.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StreamDrainReport {
+    pub captured: usize,
+    /// Progress units between the last chunk carrying a `total` and that total: exact for
+    /// targets reporting one unit per chunk; 0 when the target sends no `total`.
+    pub dropped: usize,
+    /// A chunk reached its `total`, so draining stopped without waiting out the timeout.
+    pub terminal_seen: bool,
+}
+
+impl StreamDrainReport {
+    pub fn from_events(events: &[StreamEvent]) -> Self {
+        let chunks = || events.iter().filter(|event| event.event == "chunk");
+        let terminal_seen = chunks().any(StreamEvent::is_terminal_chunk);
+        let dropped = match chunks().rfind(|event| event.total.is_some()) {
+            Some(last) if !terminal_seen => {
+                let remaining = last.total.unwrap_or_default() - last.progress.unwrap_or_default();
+                remaining.max(0.0).ceil() as usize
+            }
+            _ => 0,
+        };
+        Self {
+            captured: chunks().count(),
+            dropped,
+            terminal_seen,
+        }
+    }
+}
+
+impl StreamEvent {
+    /// A progress chunk whose progress has reached the total it announced.
+    pub fn is_terminal_chunk(&self) -> bool {
+        self.event == "chunk"
+            && matches!((self.progress, self.total), (Some(progress), Some(total)) if progress >= total)
+    }
+}
+
+/// One target tried by an `inspector_call` with `fallback_targets`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FallbackAttempt {
+    pub target: TargetDescriptor,
+    /// The call went through, even if the tool itself reported an error.
+    pub succeeded: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Outcome of one attempt against the target: its handshake, or with call retries the
+/// handshake and the call.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct AttemptRecord {
+    /// 1-based attempt number.
+    pub attempt: u32,
+    pub succeeded: bool,
+    pub latency_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Which transient failure ended the attempt; absent when it was not transient.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<RetryOn>,
+}
+
+/// A non-fatal issue noticed during a call. Every such issue is collected into
+/// `CallTrace::warnings` so agents can surface them together.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TraceWarning {
+    pub code: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<String>,
+}
+
+impl TraceWarning {
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            message: message.into(),
+            violations: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    const BLOCKS: [(&str, &str); 4] = [
+        ("stdio", r#"{"command": "server"}"#),
+        ("sse", r#"{"url": "http://127.0.0.1:1/sse"}"#),
+        ("http", r#"{"url": "http://127.0.0.1:1/mcp"}"#),
+        ("unix", r#"{"path": "/tmp/mcp.sock"}"#),
+    ];
+
+    /// The target object holding the blocks whose bits are set in `mask`.
+    fn target_blocks(mask: usize) -> serde_json::Map<String, Value> {
+        BLOCKS
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| mask & (1 << bit) != 0)
+            .map(|(_, (name, block))| (name.to_string(), serde_json::from_str(block).unwrap()))
+            .collect()
+    }
+
+    fn call_request(mut args: serde_json::Map<String, Value>) -> CallRequest {
+        args.insert("tool_name".into(), "echo".into());
+        args.insert("arguments_json".into(), Value::Null);
+        serde_json::from_value(Value::Object(args)).unwrap()
+    }
+
+    #[test]
+    fn every_combination_of_target_blocks_is_checked() {
+        for mask in 0..(1 << BLOCKS.len()) {
+            let expected: Vec<&str> = BLOCKS
+                .iter()
+                .enumerate()
+                .filter(|(bit, _)| mask & (1 << bit) != 0)
+                .map(|(_, (name, _))| *name)
+                .collect();
+            let request = call_request(target_blocks(mask));
+            match request.check_target_blocks() {
+                Ok(()) => assert!(expected.len() <= 1, "{expected:?} accepted"),
+                Err(err) => {
+                    assert!(expected.len() > 1, "{expected:?} rejected");
+                    assert_eq!(err.location, "request");
+                    assert_eq!(err.blocks, expected);
+                }
+            }
+
+            let mut args = serde_json::Map::new();
+            args.insert("stdio".into(), serde_json::json!({"command": "server"}));
+            args.insert(
+                "fallback_targets".into(),
+                serde_json::json!([{"stdio": {"command": "fallback"}}, target_blocks(mask)]),
+            );
+            let result = call_request(args).check_target_blocks();
+            assert_eq!(result.is_err(), expected.len() > 1, "{expected:?}");
+            if let Err(err) = result {
+                assert_eq!(err.location, "fallback_targets[1]");
+            }
+        }
+    }
+
+    #[test]
+    fn sessions_and_aliases_in_ambiguity_errors() {
+        let mut args = target_blocks(0b0101);
+        args.insert("session_id".into(), uuid::Uuid::new_v4().to_string().into());
+        assert!(call_request(args).check_target_blocks().is_ok());
+
+        let mut args = target_blocks(0b0101);
+        args.insert("target_alias".into(), "billing-staging".into());
+        let err = call_request(args).check_target_blocks().unwrap_err();
+        assert_eq!(err.blocks, ["stdio", "http"]);
+        assert!(err.to_string().contains("'billing-staging'"), "{err}");
+    }
+
+    #[test]
+    fn retry_jitter_stays_within_its_bounds() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let backoff = Duration::from_millis(400);
+        for _ in 0..1000 {
+            assert_eq!(RetryJitter::None.apply(backoff, &mut rng), backoff);
+            assert!(RetryJitter::Full.apply(backoff, &mut rng) <= backoff);
+            let equal = RetryJitter::Equal.apply(backoff, &mut rng);
+            assert!(equal >= backoff / 2 && equal <= backoff, "{equal:?}");
+        }
+    }
+
+    #[test]
+    fn full_jitter_spreads_delays() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let backoff = Duration::from_millis(400);
+        let delays: Vec<Duration> = (0..200)
+            .map(|_| RetryJitter::Full.apply(backoff, &mut rng))
+            .collect();
+        assert!(delays.iter().any(|delay| *delay < backoff / 4));
+        assert!(delays.iter().any(|delay| *delay > backoff * 3 / 4));
+    }
+
+    #[test]
+    fn seeded_jitter_is_reproducible() {
+        let backoff = Duration::from_millis(400);
+        let run = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..5)
+                .map(|_| RetryJitter::Equal.apply(backoff, &mut rng))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(run(42), run(42));
+    }
+
+    #[test]
+    fn transport_ranking_puts_the_fastest_success_first() {
+        let entry = |transport: &str, ok: bool, latency_ms: Option<u64>| TransportBenchEntry {
+            transport: transport.into(),
+            ok,
+            latency_ms,
+            error: (!ok).then(|| "refused".into()),
+        };
+        let report = TransportBenchReport::ranked(vec![
+            entry("stdio", true, Some(120)),
+            entry("sse", false, None),
+            entry("http", true, Some(15)),
+        ]);
+        let order: Vec<&str> = report
+            .ranking
+            .iter()
+            .map(|entry| entry.transport.as_str())
+            .collect();
+        assert_eq!(order, ["http", "stdio", "sse"]);
+        assert_eq!(report.recommended.as_deref(), Some("http"));
+
+        let failed = TransportBenchReport::ranked(vec![entry("sse", false, None)]);
+        assert_eq!(failed.recommended, None);
+    }
+
+    #[test]
+    fn retry_backoff_doubles_up_to_its_cap() {
+        let policy = RetryPolicy {
+            initial_backoff_ms: Some(100),
+            max_backoff_ms: Some(350),
+            ..RetryPolicy::default()
+        };
+        let waits: Vec<u64> = (1..=4)
+            .map(|retry| policy.backoff(retry).as_millis() as u64)
+            .collect();
+        assert_eq!(waits, [100, 200, 350, 350]);
+        assert_eq!(policy.backoff(200), Duration::from_millis(350));
+        assert_eq!(policy.max_attempts(), DEFAULT_RETRY_MAX_ATTEMPTS);
+        let capped = RetryPolicy {
+            max_attempts: Some(50),
+            retry_on: vec![RetryOn::Connect],
+            ..RetryPolicy::default()
+        };
+        assert_eq!(capped.max_attempts(), MAX_RETRY_ATTEMPTS);
+        assert!(capped.retries(RetryOn::Connect));
+        assert!(!capped.retries(RetryOn::Timeout));
+    }
 }
@@ -12,12 +12,165 @@ where
     Ok((res, elapsed))
 }
 
+/// Split a command line into program and arguments with shell quoting rules. A command may
+/// span several lines: lines starting with `#` are comments and a trailing backslash
+/// continues the line.
 pub fn parse_command(cmd: &str) -> Result<(String, Vec<String>)> {
-    // naive split by spaces respecting quotes (simple)
-    let shell_words =
-        shell_words::split(cmd).map_err(|e| anyhow!("failed to parse command '{}': {}", cmd, e))?;
+    let joined;
+    let line = if cmd.contains('\n') {
+        joined = join_command_lines(cmd);
+        joined.as_str()
+    } else {
+        cmd
+    };
+    let shell_words = shell_words::split(line)
+        .map_err(|e| anyhow!("failed to parse command '{}': {}", cmd, e))?;
     if shell_words.is_empty() {
         return Err(anyhow!("empty command"));
     }
     Ok((shell_words[0].clone(), shell_words[1..].to_vec()))
 }
+
+/// Fold a multi-line command into one line, dropping comment lines and joining
+/// backslash continuations. Inside a quoted argument lines are kept as they are, `#`
+/// lines included, and joined with their newline.
+fn join_command_lines(cmd: &str) -> String {
+    let mut joined = String::new();
+    let mut quote = None;
+    for line in cmd.lines() {
+        if quote.is_none() && line.trim_start().starts_with('#') {
+            continue;
+        }
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            match (quote, c) {
+                (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+                (None, '\'' | '"') => quote = Some(c),
+                (None | Some('"'), '\\') => {
+                    chars.next();
+                }
+                _ => {}
+            }
+        }
+        let backslashes = line.len() - line.trim_end_matches('\\').len();
+        if backslashes % 2 == 1 && quote != Some('\'') {
+            joined.push_str(&line[..line.len() - 1]);
+        } else {
+            joined.push_str(line);
+            joined.push(if quote.is_some() { '\n' } else { ' ' });
+        }
+    }
+    joined
+}
+
+/// Wrap a full command line for `sh -c`, for targets that need pipes, redirects or `&&`.
+pub fn shell_command(cmd: &str, args: &[String]) -> Result<(String, Vec<String>)> {
+    if cmd.trim().is_empty() {
+        return Err(anyhow!("empty command"));
+    }
+    if !args.is_empty() {
+        return Err(anyhow!(
+            "args are not supported in shell mode; include them in the command line"
+        ));
+    }
+    Ok(("sh".into(), vec!["-c".into(), cmd.to_string()]))
+}
+
+/// Nearest-rank percentile over already sorted samples.
+pub fn percentile(sorted: &[u64], target: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (target * sorted.len() as f64 / 100.0).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Cut `text` to at most `max_bytes` on a char boundary; returns true if anything was removed.
+pub fn truncate_utf8(text: &mut String, max_bytes: usize) -> bool {
+    if text.len() <= max_bytes {
+        return false;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(cmd: &str) -> Vec<String> {
+        let (program, mut args) = parse_command(cmd).expect("parse");
+        args.insert(0, program);
+        args
+    }
+
+    #[test]
+    fn percentile_takes_the_nearest_rank() {
+        let sorted = [10, 20, 30, 40];
+        assert_eq!(percentile(&sorted, 0.0), 10);
+        assert_eq!(percentile(&sorted, 25.0), 10);
+        assert_eq!(percentile(&sorted, 50.0), 20);
+        assert_eq!(percentile(&sorted, 51.0), 30);
+        assert_eq!(percentile(&sorted, 100.0), 40);
+        assert_eq!(percentile(&[], 50.0), 0);
+    }
+
+    #[test]
+    fn comment_lines_are_skipped() {
+        let cmd = "# launch the mock\nmock_mcp_server\n  # verbose for now\n  --verbose\n";
+        assert_eq!(words(cmd), ["mock_mcp_server", "--verbose"]);
+    }
+
+    #[test]
+    fn trailing_backslash_continues_the_line() {
+        let cmd = "node server.js \\\r\n  --port 8080 \\\n  --name 'demo server'";
+        assert_eq!(
+            words(cmd),
+            [
+                "node",
+                "server.js",
+                "--port",
+                "8080",
+                "--name",
+                "demo server"
+            ]
+        );
+        assert_eq!(words("print \\\\\nnext"), ["print", "\\", "next"]);
+    }
+
+    #[test]
+    fn hash_lines_inside_quotes_are_kept() {
+        let cmd =
+            "tool --script 'first\n# not a comment\nlast' \\\n  \"a\n  # b\"\n# dropped\n--end";
+        assert_eq!(
+            words(cmd),
+            [
+                "tool",
+                "--script",
+                "first\n# not a comment\nlast",
+                "a\n  # b",
+                "--end"
+            ]
+        );
+    }
+
+    #[test]
+    fn hash_inside_a_line_stays_literal() {
+        let cmd = "tool \\\n  --color=#fff \\\n  '#tag' \\#raw";
+        assert_eq!(words(cmd), ["tool", "--color=#fff", "#tag", "#raw"]);
+    }
+
+    #[test]
+    fn single_line_commands_parse_as_before() {
+        assert_eq!(
+            words("tool --color=#fff '#tag'"),
+            ["tool", "--color=#fff", "#tag"]
+        );
+        assert_eq!(words("tool a\\ b"), ["tool", "a b"]);
+        assert!(parse_command("   ").is_err());
+    }
+}
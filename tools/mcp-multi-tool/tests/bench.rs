@@ -0,0 +1,118 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+fn bench_args(tool_name: &str, arguments: Value, iterations: u32, concurrency: u32) -> Value {
+    json!({
+        "tool_name": tool_name,
+        "arguments_json": arguments,
+        "iterations": iterations,
+        "concurrency": concurrency,
+        "transport": "stdio",
+        "command": cargo_bin("mock_mcp_server").display().to_string(),
+        "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+    })
+}
+
+async fn bench(service: &RunningService<RoleClient, ()>, args: Value) -> Result<CallToolResult> {
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_bench".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+#[tokio::test]
+async fn bench_reports_ordered_percentiles_and_one_outbox_event() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let outbox = dir.path().join("events.jsonl");
+    let service = common::inspector(&[("OUTBOX_PATH", outbox.to_str().unwrap())]).await?;
+
+    let result = bench(
+        &service,
+        bench_args("add", json!({"values": [1, 2, 3]}), 20, 4),
+    )
+    .await?;
+    assert!(
+        !result.is_error.unwrap_or(false),
+        "bench failed: {result:?}"
+    );
+    let report = result.structured_content.expect("bench report");
+    assert_eq!(report["completed"], 20);
+    assert_eq!(report["concurrency"], 4);
+    assert_eq!(report["errors"], 0);
+    assert_eq!(report["stopped_by_freeze"], false);
+    let latency: Vec<f64> = ["min_ms", "p50_ms", "p90_ms", "p99_ms", "max_ms"]
+        .iter()
+        .map(|key| report["latency"][key].as_f64().expect("latency value"))
+        .collect();
+    assert!(
+        latency.windows(2).all(|pair| pair[0] <= pair[1]),
+        "percentiles out of order: {latency:?}"
+    );
+    assert!(report["throughput_per_sec"].as_f64().unwrap_or_default() > 0.0);
+
+    service.cancel().await?;
+    let events: Vec<Value> = std::fs::read_to_string(&outbox)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(events.len(), 1, "expected one summary event: {events:?}");
+    assert_eq!(events[0]["tool_name"], "inspector_bench");
+    assert_eq!(events[0]["state"], "captured");
+    Ok(())
+}
+
+#[tokio::test]
+async fn bench_rejects_out_of_range_iterations() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+
+    let result = bench(&service, bench_args("add", json!({"values": [1]}), 0, 1)).await?;
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("error payload");
+    assert!(
+        payload["error"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("iterations"),
+        "unexpected error: {payload}"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn bench_stops_when_the_error_budget_freezes() -> Result<()> {
+    let service = common::inspector(&[
+        ("ERROR_BUDGET_ENABLED", "true"),
+        ("ERROR_BUDGET_SUCCESS_THRESHOLD", "0.6"),
+        ("ERROR_BUDGET_MIN_REQUESTS", "3"),
+        ("ERROR_BUDGET_SAMPLE_WINDOW_SECS", "120"),
+        ("ERROR_BUDGET_FREEZE_SECS", "60"),
+    ])
+    .await?;
+
+    let result = bench(&service, bench_args("no_such_tool", json!({}), 50, 1)).await?;
+    let report = result.structured_content.expect("bench report");
+    assert_eq!(report["stopped_by_freeze"], true, "report: {report}");
+    assert_eq!(report["completed"], 3);
+    assert_eq!(report["errors"], 3);
+
+    // The freeze outlives the bench and blocks the next run up front.
+    let blocked = bench(&service, bench_args("add", json!({"values": [1]}), 1, 1)).await?;
+    assert!(blocked.is_error.unwrap_or(false));
+    let payload = blocked.structured_content.expect("freeze payload");
+    assert_eq!(payload["code"], "ERROR_BUDGET_EXHAUSTED");
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::model::CallToolRequestParam;
+use serde_json::json;
+
+mod common;
+
+#[tokio::test]
+async fn non_object_arguments_are_rejected() -> Result<()> {
+    let _bin = cargo_bin("mcp-multi-tool");
+    let mock = cargo_bin("mock_mcp_server");
+    let service = common::inspector(&[("RUST_LOG", "info")]).await?;
+
+    let args = json!({
+        "tool_name": "echo",
+        "arguments_json": ["hello"],
+        "stdio": {
+            "command": mock.display().to_string()
+        }
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("error payload");
+    assert_eq!(
+        payload.get("code").and_then(|value| value.as_str()),
+        Some("BAD_ARGUMENTS")
+    );
+    assert!(
+        payload
+            .get("error")
+            .and_then(|value| value.as_str())
+            .unwrap_or_default()
+            .contains("array")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn deeply_nested_arguments_are_rejected() -> Result<()> {
+    let _bin = cargo_bin("mcp-multi-tool");
+    let mock = cargo_bin("mock_mcp_server");
+    let service = common::inspector(&[("RUST_LOG", "info"), ("MAX_JSON_DEPTH", "8")]).await?;
+
+    let mut nested = json!("leaf");
+    for _ in 0..12 {
+        nested = json!({ "inner": nested });
+    }
+    let args = json!({
+        "tool_name": "echo",
+        "arguments_json": { "text": "deep", "payload": nested },
+        "stdio": {
+            "command": mock.display().to_string()
+        }
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("error payload");
+    assert_eq!(payload["code"], "ARGUMENTS_TOO_COMPLEX");
+    assert!(
+        payload["error"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("deeper than 8")
+    );
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    model::{CallToolRequest, CallToolRequestParam, ClientRequest, Meta, Request, ServerResult},
+    service::PeerRequestOptions,
+};
+use serde_json::json;
+use std::time::{Duration, Instant};
+
+mod common;
+
+#[tokio::test]
+async fn slow_tool_fails_with_call_timeout_and_releases_key() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let outbox = dir.path().join("events.jsonl");
+    let service = common::inspector(&[
+        ("OUTBOX_PATH", outbox.to_str().unwrap()),
+        ("IDEMPOTENCY_CONFLICT_POLICY", "return_existing"),
+    ])
+    .await?;
+    let args = json!({
+        "tool_name": "sleep",
+        "arguments_json": {"ms": 10_000},
+        "idempotency_key": "slow-1",
+        "call_timeout_ms": 300,
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        }
+    });
+    let call = || {
+        service.call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+    };
+
+    let started = Instant::now();
+    let result = call().await?;
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("error payload");
+    assert_eq!(payload["code"], "CALL_TIMEOUT");
+    let meta = result.meta.expect("trace meta");
+    assert_eq!(meta["trace"]["event"]["state"], "failed");
+
+    let persisted = std::fs::read_to_string(&outbox)?;
+    let timed_out = persisted
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find(|event| event["idempotency_key"] == "slow-1")
+        .expect("timeout event in outbox");
+    assert_eq!(timed_out["state"], "failed");
+
+    // The key is completed with the failed event, not left in-flight.
+    let replay = call().await?;
+    let replay = replay.structured_content.expect("duplicate payload");
+    assert_eq!(replay["status"], "duplicate");
+    assert_eq!(replay["event"]["state"], "failed");
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn caller_deadline_bounds_the_downstream_call() -> Result<()> {
+    let service = common::inspector(&[("DEADLINE_MARGIN_MS", "1500")]).await?;
+    let args = json!({
+        "tool_name": "sleep",
+        "arguments_json": {"ms": 10_000},
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        }
+    });
+    let mut meta = Meta::new();
+    meta.insert("deadlineMs".into(), json!(2_000));
+    let mut options = PeerRequestOptions::no_options();
+    options.meta = Some(meta);
+
+    let started = Instant::now();
+    let request: CallToolRequest = Request::new(CallToolRequestParam {
+        name: "inspector_call".into(),
+        arguments: Some(args.as_object().cloned().unwrap()),
+    });
+    let response = service
+        .send_request_with_option(ClientRequest::CallToolRequest(request), options)
+        .await?
+        .await_response()
+        .await?;
+    // The caller would give up after 2s; the downstream call got what was left less 1.5s.
+    assert!(started.elapsed() < Duration::from_secs(2));
+    let ServerResult::CallToolResult(result) = response else {
+        panic!("unexpected response: {response:?}");
+    };
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("error payload");
+    assert_eq!(payload["code"], "CALL_TIMEOUT");
+    let limit_ms: u64 = payload["error"]
+        .as_str()
+        .and_then(|error| error.split_whitespace().find_map(|word| word.parse().ok()))
+        .expect("limit in error");
+    assert!(limit_ms <= 500, "downstream limit {limit_ms} ms");
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult, ClientRequest, Request},
+    service::{PeerRequestOptions, RunningService},
+};
+use serde_json::{Value, json};
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+
+mod common;
+
+fn sleep_call(ms: u64, idempotency_key: &str) -> CallToolRequestParam {
+    let args = json!({
+        "tool_name": "sleep",
+        "arguments_json": {"ms": ms},
+        "idempotency_key": idempotency_key,
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        }
+    });
+    CallToolRequestParam {
+        name: "inspector_call".into(),
+        arguments: Some(args.as_object().cloned().unwrap()),
+    }
+}
+
+async fn cancel(
+    service: &RunningService<RoleClient, ()>,
+    run_id: &Value,
+) -> Result<CallToolResult> {
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_cancel".into(),
+            arguments: Some(json!({"run_id": run_id}).as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+/// The outbox event for `idempotency_key`, polled since events are written asynchronously.
+async fn outbox_event(outbox: &Path, idempotency_key: &str) -> Value {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let found = std::fs::read_to_string(outbox).ok().and_then(|persisted| {
+            persisted
+                .lines()
+                .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+                .find(|event| event["idempotency_key"] == idempotency_key)
+        });
+        if let Some(event) = found {
+            return event;
+        }
+        assert!(
+            Instant::now() < deadline,
+            "no outbox event for {idempotency_key}"
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+#[tokio::test]
+async fn inspector_cancel_aborts_the_run_and_frees_its_key() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let outbox = dir.path().join("events.jsonl");
+    let service = common::inspector(&[
+        ("OUTBOX_PATH", outbox.to_str().unwrap()),
+        ("IDEMPOTENCY_CONFLICT_POLICY", "return_existing"),
+        ("ADMIN_TOOLS_ENABLED", "true"),
+    ])
+    .await?;
+
+    let peer = service.peer().clone();
+    let started = Instant::now();
+    let slow = tokio::spawn(async move { peer.call_tool(sleep_call(30_000, "cancel-1")).await });
+
+    // A retry while the call is in flight names the run holding the key.
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let run_id = loop {
+        let retry = service.call_tool(sleep_call(30_000, "cancel-1")).await?;
+        let payload = retry.structured_content.expect("conflict payload");
+        if payload["code"] == "IDEMPOTENCY_CONFLICT" {
+            break payload["run_id"].clone();
+        }
+        assert!(started.elapsed() < Duration::from_secs(5), "{payload}");
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    };
+
+    let cancelled = cancel(&service, &run_id).await?;
+    assert!(!cancelled.is_error.unwrap_or(false), "{cancelled:?}");
+    assert_eq!(
+        cancelled.structured_content.expect("payload")["cancelled"],
+        true
+    );
+
+    let result = slow.await??;
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert!(result.is_error.unwrap_or(false));
+    assert_eq!(
+        result.structured_content.expect("error payload")["code"],
+        "CANCELLED"
+    );
+    let meta = result.meta.expect("trace meta");
+    assert_eq!(meta["trace"]["event"]["state"], "cancelled");
+    assert_eq!(meta["trace"]["event"]["run_id"], run_id);
+
+    let event = outbox_event(&outbox, "cancel-1").await;
+    assert_eq!(event["state"], "cancelled");
+    assert_eq!(event["run_id"], run_id);
+
+    // The key was released, so the retry runs instead of replaying the cancelled event.
+    let retry = service.call_tool(sleep_call(10, "cancel-1")).await?;
+    assert!(!retry.is_error.unwrap_or(false), "{retry:?}");
+    assert_eq!(retry.structured_content.expect("payload")["slept_ms"], 10);
+
+    let finished = cancel(&service, &run_id).await?;
+    assert!(finished.is_error.unwrap_or(false));
+    assert_eq!(
+        finished.structured_content.expect("payload")["code"],
+        "RUN_NOT_FOUND"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn inspector_cancel_is_an_admin_tool() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+
+    let tools = service.list_all_tools().await?;
+    assert!(tools.iter().all(|tool| tool.name != "inspector_cancel"));
+    let refused = cancel(&service, &json!(uuid::Uuid::new_v4())).await?;
+    assert!(refused.is_error.unwrap_or(false), "{refused:?}");
+    assert_eq!(
+        refused.structured_content.expect("error payload")["code"],
+        "ADMIN_TOOLS_DISABLED"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn client_cancellation_reaches_the_run() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let outbox = dir.path().join("events.jsonl");
+    let service = common::inspector(&[
+        ("OUTBOX_PATH", outbox.to_str().unwrap()),
+        ("IDEMPOTENCY_CONFLICT_POLICY", "return_existing"),
+    ])
+    .await?;
+
+    let handle = service
+        .send_cancellable_request(
+            ClientRequest::CallToolRequest(Request::new(sleep_call(30_000, "cancel-2"))),
+            PeerRequestOptions::no_options(),
+        )
+        .await?;
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    handle.cancel(Some("caller gave up".into())).await?;
+
+    let event = outbox_event(&outbox, "cancel-2").await;
+    assert_eq!(event["state"], "cancelled");
+
+    service.cancel().await?;
+    Ok(())
+}
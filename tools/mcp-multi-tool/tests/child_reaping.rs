@@ -0,0 +1,102 @@
+#![cfg(unix)]
+
+use std::{path::Path, time::Duration};
+
+use anyhow::Result;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+async fn call(
+    service: &RunningService<RoleClient, ()>,
+    name: &str,
+    args: Value,
+) -> Result<CallToolResult> {
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+/// A "server" that records its pid in `marker` and then never answers the handshake.
+fn silent_server(marker: &Path) -> Value {
+    json!({
+        "command": "sh",
+        "args": ["-c", "echo $$ > \"$PID_MARKER\" && exec sleep 300"],
+        "env": {"PID_MARKER": marker.display().to_string()}
+    })
+}
+
+/// Whether `pid` still exists; a zombie nobody reaped would still count.
+fn alive(pid: &str) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", pid])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+async fn assert_gone(marker: &Path) -> Result<()> {
+    let pid = std::fs::read_to_string(marker)?.trim().to_string();
+    for _ in 0..30 {
+        if !alive(&pid) {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    anyhow::bail!("target {pid} still running after the handshake timed out")
+}
+
+#[tokio::test]
+async fn probe_handshake_timeout_kills_the_target() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let marker = dir.path().join("pid");
+    let service = common::inspector(&[("DEFAULT_HANDSHAKE_TIMEOUT_MS_STDIO", "200")]).await?;
+
+    let mut probe = silent_server(&marker);
+    probe["transport"] = json!("stdio");
+    probe["handshake_timeout_ms"] = json!(200);
+    let result = call(&service, "inspector_probe", probe).await?;
+    let payload = result.structured_content.expect("probe payload");
+    assert_ne!(payload["ok"], true, "{payload}");
+    assert_gone(&marker).await?;
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn call_handshake_timeout_kills_the_target() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let marker = dir.path().join("pid");
+    let service = common::inspector(&[("DEFAULT_HANDSHAKE_TIMEOUT_MS_STDIO", "200")]).await?;
+
+    let result = call(
+        &service,
+        "inspector_call",
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {"text": "hi"},
+            "stdio": silent_server(&marker)
+        }),
+    )
+    .await?;
+    assert!(result.is_error.unwrap_or(false), "{result:?}");
+    assert_gone(&marker).await?;
+
+    let mut list = silent_server(&marker);
+    list["transport"] = json!("stdio");
+    let result = call(&service, "inspector_list_tools", list).await?;
+    assert!(result.is_error.unwrap_or(false), "{result:?}");
+    assert_gone(&marker).await?;
+
+    service.cancel().await?;
+    Ok(())
+}
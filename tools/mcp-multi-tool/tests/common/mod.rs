@@ -0,0 +1,76 @@
+//! Shared fixture for integration tests that drive the `mcp-multi-tool` binary over stdio.
+#![allow(dead_code)]
+
+use std::{
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient, ServiceExt,
+    service::RunningService,
+    transport::child_process::{ConfigureCommandExt, TokioChildProcess},
+};
+use tempfile::TempDir;
+use tokio::process::Command;
+
+/// A running inspector whose outbox and DLQ live in a directory removed on drop.
+pub struct Inspector {
+    service: RunningService<RoleClient, ()>,
+    outbox_dir: TempDir,
+}
+
+impl Inspector {
+    /// Path of the primary outbox file, unless the test overrode `OUTBOX_PATH`.
+    pub fn outbox_path(&self) -> PathBuf {
+        self.outbox_dir.path().join("events.jsonl")
+    }
+
+    pub fn outbox_dir(&self) -> &Path {
+        self.outbox_dir.path()
+    }
+
+    pub async fn cancel(self) -> Result<()> {
+        self.service.cancel().await?;
+        Ok(())
+    }
+}
+
+impl Deref for Inspector {
+    type Target = RunningService<RoleClient, ()>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.service
+    }
+}
+
+/// Spawns the inspector with the error budget off, no inherited `TARGET_PROXY` and the outbox
+/// in a tempdir rather than the shared sqlite store; `env` pairs are applied last, so they may
+/// override any of these.
+pub async fn inspector(env: &[(&str, &str)]) -> Result<Inspector> {
+    let outbox_dir = tempfile::tempdir()?;
+    let outbox = outbox_dir.path().join("events.jsonl");
+    let dlq = outbox_dir.path().join("dlq.jsonl");
+    let env: Vec<(String, String)> = env
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let service = ()
+        .serve(TokioChildProcess::new(
+            Command::new(cargo_bin("mcp-multi-tool")).configure(|cmd| {
+                cmd.env("ERROR_BUDGET_ENABLED", "false");
+                cmd.env("OUTBOX_PATH", &outbox);
+                cmd.env("OUTBOX_DLQ_PATH", &dlq);
+                cmd.env_remove("OUTBOX_DB_PATH");
+                cmd.env_remove("TARGET_PROXY");
+                cmd.envs(env);
+            }),
+        )?)
+        .await?;
+    Ok(Inspector {
+        service,
+        outbox_dir,
+    })
+}
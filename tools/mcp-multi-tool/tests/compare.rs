@@ -0,0 +1,109 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+fn mock_target(extra_env: Value) -> Value {
+    let mut env = json!({"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"});
+    if let (Some(env), Some(extra)) = (env.as_object_mut(), extra_env.as_object()) {
+        env.extend(extra.clone());
+    }
+    json!({
+        "transport": "stdio",
+        "command": cargo_bin("mock_mcp_server").display().to_string(),
+        "env": env,
+    })
+}
+
+async fn compare(
+    service: &RunningService<RoleClient, ()>,
+    target_a: Value,
+    target_b: Value,
+) -> Result<CallToolResult> {
+    let args = json!({"target_a": target_a, "target_b": target_b});
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_compare".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+#[tokio::test]
+async fn mock_against_itself_is_identical() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+
+    let result = compare(&service, mock_target(json!({})), mock_target(json!({}))).await?;
+    assert!(
+        !result.is_error.unwrap_or(false),
+        "compare failed: {result:?}"
+    );
+    let diff = result.structured_content.expect("diff");
+    assert_eq!(diff["identical"], true, "diff: {diff}");
+    assert_eq!(diff["only_in_a"], json!([]));
+    assert_eq!(diff["only_in_b"], json!([]));
+    assert_eq!(diff["changed"], json!([]));
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn drifted_mock_reports_membership_and_schema_changes() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+
+    let result = compare(
+        &service,
+        mock_target(json!({})),
+        mock_target(json!({"MOCK_TOOL_DRIFT": "1"})),
+    )
+    .await?;
+    assert!(
+        !result.is_error.unwrap_or(false),
+        "compare failed: {result:?}"
+    );
+    let diff = result.structured_content.expect("diff");
+    assert_eq!(diff["identical"], false);
+    assert_eq!(diff["only_in_a"], json!(["getenv"]));
+    assert_eq!(diff["only_in_b"], json!(["shout"]));
+    assert_eq!(
+        diff["changed"],
+        json!([
+            {"name": "add", "paths": ["/inputSchema/properties/precision"]},
+            {"name": "echo", "paths": ["/description"]}
+        ])
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn failing_side_is_named_in_the_error() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+
+    let result = compare(
+        &service,
+        mock_target(json!({})),
+        json!({"transport": "http", "url": "http://127.0.0.1:1/mcp", "handshake_timeout_ms": 500}),
+    )
+    .await?;
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("error payload");
+    assert!(
+        payload["error"]
+            .as_str()
+            .unwrap_or_default()
+            .starts_with("target_b"),
+        "unexpected error: {payload}"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
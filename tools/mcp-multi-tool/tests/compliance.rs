@@ -15,8 +15,12 @@ use harness::{mcp_multi_tool_path, mock_server_path};
 
 #[test]
 fn compliance_self_check_passes() -> Result<()> {
+    // The inspector under test inherits this env, which keeps its outbox out of the tree.
+    let outbox = tempfile::tempdir()?;
     let mut cmd = Command::new(cargo_bin("compliance"));
     cmd.arg("--command").arg(mcp_multi_tool_path());
+    cmd.env("OUTBOX_PATH", outbox.path().join("events.jsonl"));
+    cmd.env("OUTBOX_DLQ_PATH", outbox.path().join("dlq.jsonl"));
     let output = cmd.output()?;
     assert!(
         output.status.success(),
@@ -86,6 +90,16 @@ fn compliance_with_mock_transports() -> Result<()> {
         .and_then(Value::as_array)
         .cloned()
         .unwrap_or_default();
+    for probe in ["probe_sse", "probe_http"] {
+        let case = cases
+            .iter()
+            .find(|case| case.get("name").and_then(Value::as_str) == Some(probe))
+            .unwrap_or_else(|| panic!("{probe} reported"));
+        assert_eq!(case["passed"], Value::Bool(true), "{case}");
+        assert_eq!(case["detail"]["server_name"], "mock-mcp-server", "{case}");
+        assert_eq!(case["detail"]["title"], "Mock MCP Server", "{case}");
+        assert_eq!(case["detail"]["capabilities"]["tools"], true, "{case}");
+    }
     let mut has_http = false;
     let mut has_sse = false;
     let mut has_describe_http = false;
@@ -107,5 +121,74 @@ fn compliance_with_mock_transports() -> Result<()> {
     assert!(has_http, "missing HTTP call case");
     assert!(has_describe_sse, "missing SSE describe case");
     assert!(has_describe_http, "missing HTTP describe case");
+
+    let transports: Vec<&str> = report
+        .get("by_transport")
+        .and_then(Value::as_array)
+        .expect("by_transport array")
+        .iter()
+        .filter_map(|stats| stats.get("transport").and_then(Value::as_str))
+        .collect();
+    // The negative missing-command case is the only stdio entry without --command.
+    assert_eq!(transports, ["stdio", "sse", "http"]);
+    Ok(())
+}
+
+#[test]
+fn overall_timeout_bounds_hung_target() -> Result<()> {
+    let started = std::time::Instant::now();
+    let output = Command::new(cargo_bin("compliance"))
+        .args(["--command", "sleep", "--args", "30"])
+        .args(["--overall-timeout-ms", "500"])
+        .output()?;
+    assert!(
+        started.elapsed() < Duration::from_secs(10),
+        "compliance run exceeded its bound: {:?}",
+        started.elapsed()
+    );
+    assert!(!output.status.success(), "timed-out run must fail");
+
+    let report: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(report.get("timed_out").and_then(Value::as_bool), Some(true));
+    let cases = report
+        .get("cases")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    let probe = cases
+        .iter()
+        .find(|case| case.get("name").and_then(Value::as_str) == Some("probe_stdio"))
+        .expect("probe_stdio reported");
+    assert_eq!(probe.get("passed").and_then(Value::as_bool), Some(false));
+    assert_eq!(probe["detail"]["timed_out"], Value::Bool(true));
+    assert!(
+        cases.iter().any(
+            |case| case.get("name").and_then(Value::as_str) == Some("negative_missing_command")
+        ),
+        "unreached cases must still be listed"
+    );
+    Ok(())
+}
+
+#[test]
+fn progress_lines_go_to_stderr_only() -> Result<()> {
+    let output = Command::new(cargo_bin("compliance"))
+        .args(["--progress", "--include-skipped"])
+        .output()?;
+    assert!(output.status.success(), "compliance failed: {:?}", output);
+
+    // stdout must remain a single JSON document.
+    let report: Value = serde_json::from_slice(&output.stdout)?;
+    assert!(report.get("cases").is_some());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("[compliance] negative_missing_command pass"),
+        "missing progress line: {stderr}"
+    );
+    assert!(
+        stderr.contains("[compliance] probe_http skipped (no http_url provided)"),
+        "missing skipped progress line: {stderr}"
+    );
     Ok(())
 }
@@ -0,0 +1,99 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::model::CallToolRequestParam;
+use serde_json::{Value, json};
+
+mod common;
+
+#[tokio::test]
+async fn mock_server_passes_conformance_checklist() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+
+    let args = json!({
+        "transport": "stdio",
+        "command": cargo_bin("mock_mcp_server").display().to_string(),
+        "env": {
+            "MOCK_SSE_ADDR": "127.0.0.1:0",
+            "MOCK_HTTP_ADDR": "127.0.0.1:0"
+        }
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_conformance".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    let report = result.structured_content.expect("conformance report");
+    assert_eq!(report["passed"], true, "report: {report}");
+
+    let checks = report["checks"].as_array().expect("checks");
+    let names: Vec<&str> = checks
+        .iter()
+        .filter_map(|check| check["name"].as_str())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "advertises_tools",
+            "list_tools_complete",
+            "help_structured",
+            "unknown_tool_errors"
+        ]
+    );
+    let unknown = &checks[3]["detail"];
+    assert_eq!(unknown["mode"], "is_error");
+    assert_eq!(unknown["responsive_after"], true);
+    assert!(checks.iter().all(|check| check["transport"] == "stdio"));
+    assert!(
+        checks
+            .iter()
+            .all(|check| check.get("skipped").is_none_or(Value::is_null))
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn a_stalled_request_fails_its_check_within_the_call_timeout() -> Result<()> {
+    let service = common::inspector(&[("CALL_TIMEOUT_MS", "300")]).await?;
+
+    let args = json!({
+        "transport": "stdio",
+        "command": cargo_bin("mock_mcp_server").display().to_string(),
+        "env": {
+            "MOCK_SSE_ADDR": "127.0.0.1:0",
+            "MOCK_HTTP_ADDR": "127.0.0.1:0",
+            "MOCK_HELP_DELAY_MS": "30000"
+        }
+    });
+    let started = std::time::Instant::now();
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_conformance".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    let report = result.structured_content.expect("conformance report");
+    assert_eq!(report["passed"], false, "report: {report}");
+
+    let checks = report["checks"].as_array().expect("checks");
+    let help = checks
+        .iter()
+        .find(|check| check["name"] == "help_structured")
+        .expect("help check");
+    assert_eq!(help["passed"], false, "{help}");
+    assert_eq!(
+        help["detail"]["error"], "no response within 300ms",
+        "{help}"
+    );
+    let unknown = checks
+        .iter()
+        .find(|check| check["name"] == "unknown_tool_errors")
+        .expect("unknown tool check");
+    assert_eq!(unknown["passed"], true, "{unknown}");
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{RoleClient, model::CallToolRequestParam, service::RunningService};
+use serde_json::{Value, json};
+use std::path::Path;
+
+mod common;
+
+/// A stdio target that appends its pid to `marker` every time it is spawned.
+fn counted_target(marker: &Path) -> Value {
+    json!({
+        "command": "sh",
+        "args": [
+            "-c",
+            "echo $$ >> \"$SPAWN_MARKER\" && exec \"$0\"",
+            cargo_bin("mock_mcp_server").display().to_string()
+        ],
+        "env": {
+            "SPAWN_MARKER": marker.display().to_string(),
+            "MOCK_SSE_ADDR": "127.0.0.1:0",
+            "MOCK_HTTP_ADDR": "127.0.0.1:0"
+        }
+    })
+}
+
+fn spawns(marker: &Path) -> usize {
+    std::fs::read_to_string(marker)
+        .map(|log| log.lines().count())
+        .unwrap_or(0)
+}
+
+async fn call_with_meta(
+    service: &RunningService<RoleClient, ()>,
+    name: &str,
+    args: Value,
+) -> Result<(Value, Value)> {
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    assert!(
+        !result.is_error.unwrap_or(false),
+        "{name} failed: {result:?}"
+    );
+    let meta = json!(result.meta);
+    Ok((result.structured_content.expect("payload"), meta))
+}
+
+async fn call(service: &RunningService<RoleClient, ()>, name: &str, args: Value) -> Result<Value> {
+    Ok(call_with_meta(service, name, args).await?.0)
+}
+
+fn echo(target: Value, extra: Value) -> Value {
+    let mut args = json!({
+        "tool_name": "echo",
+        "arguments_json": {"text": "hi"},
+        "stdio": target
+    });
+    for (key, value) in extra.as_object().cloned().unwrap_or_default() {
+        args[key] = value;
+    }
+    args
+}
+
+#[tokio::test]
+async fn second_call_reuses_the_target_process() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let marker = dir.path().join("spawns.log");
+    let service = common::inspector(&[("CONNECTION_CACHE_MAX_SIZE", "8")]).await?;
+    let target = counted_target(&marker);
+
+    call(&service, "inspector_call", echo(target.clone(), json!({}))).await?;
+    call(&service, "inspector_call", echo(target.clone(), json!({}))).await?;
+    assert_eq!(spawns(&marker), 1);
+
+    let mut list = target;
+    list["transport"] = json!("stdio");
+    call(&service, "inspector_list_tools", list).await?;
+    assert_eq!(spawns(&marker), 1);
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn reuse_session_false_spawns_a_fresh_process() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let marker = dir.path().join("spawns.log");
+    let service = common::inspector(&[("CONNECTION_CACHE_MAX_SIZE", "8")]).await?;
+    let target = counted_target(&marker);
+
+    call(&service, "inspector_call", echo(target.clone(), json!({}))).await?;
+    let fresh = json!({"reuse_session": false});
+    call(&service, "inspector_call", echo(target, fresh)).await?;
+    assert_eq!(spawns(&marker), 2);
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn zero_cache_size_disables_reuse() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let marker = dir.path().join("spawns.log");
+    let service = common::inspector(&[("CONNECTION_CACHE_MAX_SIZE", "0")]).await?;
+    let target = counted_target(&marker);
+
+    call(&service, "inspector_call", echo(target.clone(), json!({}))).await?;
+    call(&service, "inspector_call", echo(target, json!({}))).await?;
+    assert_eq!(spawns(&marker), 2);
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn exited_pooled_child_is_respawned_and_counted() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let marker = dir.path().join("spawns.log");
+    let service = common::inspector(&[("CONNECTION_CACHE_MAX_SIZE", "8")]).await?;
+    let target = counted_target(&marker);
+
+    call(&service, "inspector_call", echo(target.clone(), json!({}))).await?;
+    let pid = std::fs::read_to_string(&marker)?.trim().to_string();
+    let killed = std::process::Command::new("kill")
+        .args(["-9", &pid])
+        .status()?;
+    assert!(killed.success());
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let (payload, meta) =
+        call_with_meta(&service, "inspector_call", echo(target, json!({}))).await?;
+    assert_eq!(payload["echoed"], "hi");
+    assert_eq!(spawns(&marker), 2);
+    let warnings = meta["trace"]["warnings"].as_array().expect("warnings");
+    assert!(
+        warnings
+            .iter()
+            .any(|warning| warning["code"] == "TARGET_RESTARTED"),
+        "{warnings:?}"
+    );
+
+    let sessions = call(&service, "inspector_sessions", json!({})).await?;
+    let pooled = sessions["sessions"].as_array().expect("sessions");
+    assert_eq!(pooled.len(), 1, "{sessions}");
+    assert_eq!(pooled[0]["restarts"], 1);
+
+    service.cancel().await?;
+    Ok(())
+}
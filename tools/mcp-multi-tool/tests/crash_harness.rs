@@ -34,7 +34,7 @@ async fn inspector_outbox_falls_back_to_dlq_on_write_failure() -> Result<()> {
         "stream": false,
         "stdio": {
             "command": "/bin/sh",
-            "args": ["-c", "exit 1"],
+            "args": ["-c", "echo 'fatal: config missing' >&2; exit 1"],
         }
     });
 
@@ -58,7 +58,14 @@ async fn inspector_outbox_falls_back_to_dlq_on_write_failure() -> Result<()> {
         entry.get("tool_name").and_then(|v| v.as_str()),
         Some("help")
     );
-    assert!(entry.get("error").is_some());
+    let error = entry
+        .get("error")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    assert!(
+        error.contains("target stderr: fatal: config missing"),
+        "stderr missing from DLQ event: {error}"
+    );
 
     Ok(())
 }
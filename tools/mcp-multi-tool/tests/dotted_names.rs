@@ -0,0 +1,117 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+fn mock_env() -> Value {
+    json!({"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"})
+}
+
+async fn call(
+    service: &RunningService<RoleClient, ()>,
+    name: &str,
+    args: Value,
+) -> Result<CallToolResult> {
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+async fn listed_names(service: &RunningService<RoleClient, ()>) -> Result<Vec<String>> {
+    let listed = call(
+        service,
+        "inspector_list_tools",
+        json!({
+            "transport": "stdio",
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": mock_env(),
+        }),
+    )
+    .await?;
+    let payload = listed.structured_content.expect("tool list");
+    Ok(payload["tools"]
+        .as_array()
+        .expect("tools array")
+        .iter()
+        .filter_map(|tool| tool["name"].as_str().map(str::to_string))
+        .collect())
+}
+
+fn upper_call(tool_name: &str) -> Value {
+    json!({
+        "tool_name": tool_name,
+        "arguments_json": {"text": "dots"},
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": mock_env(),
+        }
+    })
+}
+
+#[tokio::test]
+async fn underscored_alias_round_trips_to_the_dotted_tool() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let outbox = dir.path().join("events.jsonl");
+    let service = common::inspector(&[
+        ("OUTBOX_PATH", outbox.to_str().unwrap()),
+        ("DOTTED_TOOL_NAME_POLICY", "underscore"),
+    ])
+    .await?;
+
+    let names = listed_names(&service).await?;
+    assert!(names.iter().any(|name| name == "text_upper"), "{names:?}");
+    assert!(!names.iter().any(|name| name.contains('.')), "{names:?}");
+
+    let result = call(&service, "inspector_call", upper_call("text_upper")).await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    assert_eq!(result.structured_content.expect("payload")["upper"], "DOTS");
+
+    service.cancel().await?;
+    let event: Value = serde_json::from_str(
+        std::fs::read_to_string(&outbox)?
+            .lines()
+            .last()
+            .expect("run event"),
+    )?;
+    assert_eq!(event["tool_name"], "text.upper");
+    assert_eq!(event["requested_tool_name"], "text_upper");
+    assert_eq!(event["state"], "captured");
+    Ok(())
+}
+
+#[tokio::test]
+async fn preserve_policy_keeps_dotted_names() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let outbox = dir.path().join("events.jsonl");
+    let service = common::inspector(&[
+        ("OUTBOX_PATH", outbox.to_str().unwrap()),
+        ("DOTTED_TOOL_NAME_POLICY", "preserve"),
+    ])
+    .await?;
+
+    let names = listed_names(&service).await?;
+    assert!(names.iter().any(|name| name == "text.upper"), "{names:?}");
+
+    let result = call(&service, "inspector_call", upper_call("text.upper")).await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+
+    service.cancel().await?;
+    let event: Value = serde_json::from_str(
+        std::fs::read_to_string(&outbox)?
+            .lines()
+            .last()
+            .expect("run event"),
+    )?;
+    assert_eq!(event["tool_name"], "text.upper");
+    assert!(event.get("requested_tool_name").is_none(), "{event}");
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+async fn call_fixed_reference(service: &RunningService<RoleClient, ()>) -> Result<CallToolResult> {
+    let mock = cargo_bin("mock_mcp_server");
+    let args = json!({
+        "tool_name": "fixed_reference",
+        "arguments_json": {},
+        "stdio": {
+            "command": mock.display().to_string(),
+            "env": {
+                "MOCK_SSE_ADDR": "127.0.0.1:0",
+                "MOCK_HTTP_ADDR": "127.0.0.1:0"
+            }
+        }
+    });
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+fn trace_warnings(result: &CallToolResult) -> Vec<Value> {
+    result
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.get("trace"))
+        .and_then(|trace| trace.get("warnings"))
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn trace_run_id(result: &CallToolResult) -> Option<String> {
+    result
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.get("trace"))
+        .and_then(|trace| trace.pointer("/event/run_id"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+#[tokio::test]
+async fn colliding_reference_is_flagged_under_warn_policy() -> Result<()> {
+    let service = common::inspector(&[("DUPLICATE_EXTERNAL_REF_POLICY", "warn")]).await?;
+
+    let first = call_fixed_reference(&service).await?;
+    assert!(!first.is_error.unwrap_or(false));
+    assert!(trace_warnings(&first).is_empty());
+    let first_run = trace_run_id(&first).expect("first run id");
+
+    let second = call_fixed_reference(&service).await?;
+    assert!(!second.is_error.unwrap_or(false));
+    let warnings = trace_warnings(&second);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0]["code"], "DUPLICATE_EXTERNAL_REFERENCE");
+    let message = warnings[0]["message"].as_str().unwrap_or_default();
+    assert!(
+        message.contains(&first_run),
+        "warning should name the prior run: {message}"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn colliding_reference_overwrites_silently_by_default() -> Result<()> {
+    let service = common::inspector(&[("DUPLICATE_EXTERNAL_REF_POLICY", "overwrite")]).await?;
+
+    call_fixed_reference(&service).await?;
+    let second = call_fixed_reference(&service).await?;
+    assert!(!second.is_error.unwrap_or(false));
+    assert!(trace_warnings(&second).is_empty());
+
+    service.cancel().await?;
+    Ok(())
+}
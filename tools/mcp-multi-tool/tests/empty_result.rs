@@ -0,0 +1,58 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::model::{CallToolRequestParam, CallToolResult};
+use serde_json::json;
+
+mod common;
+
+async fn call_empty_tool(policy: &str) -> Result<CallToolResult> {
+    let _bin = cargo_bin("mcp-multi-tool");
+    let mock = cargo_bin("mock_mcp_server");
+    let service = common::inspector(&[("EMPTY_RESULT_POLICY", policy)]).await?;
+
+    let args = json!({
+        "tool_name": "empty",
+        "arguments_json": {},
+        "stdio": {
+            "command": mock.display().to_string(),
+            "env": {
+                "MOCK_SSE_ADDR": "127.0.0.1:0",
+                "MOCK_HTTP_ADDR": "127.0.0.1:0"
+            }
+        }
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    service.cancel().await?;
+    Ok(result)
+}
+
+#[tokio::test]
+async fn empty_result_passes_under_allow_policy() -> Result<()> {
+    let result = call_empty_tool("allow").await?;
+    assert!(!result.is_error.unwrap_or(false));
+    assert!(result.structured_content.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn empty_result_fails_under_error_policy() -> Result<()> {
+    let result = call_empty_tool("error").await?;
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("error payload");
+    assert_eq!(
+        payload.get("code").and_then(|value| value.as_str()),
+        Some("EMPTY_RESULT")
+    );
+    let trace = result
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.get("trace"))
+        .expect("trace attached");
+    assert_eq!(trace["event"]["state"], "failed");
+    Ok(())
+}
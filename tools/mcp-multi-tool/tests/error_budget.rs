@@ -1,27 +1,22 @@
 use anyhow::Result;
 use assert_cmd::cargo::cargo_bin;
-use rmcp::{
-    ServiceExt,
-    model::CallToolRequestParam,
-    transport::child_process::{ConfigureCommandExt, TokioChildProcess},
-};
+use rmcp::model::CallToolRequestParam;
 use serde_json::json;
-use tokio::process::Command;
+
+mod common;
+
+/// An error budget that freezes after three failures out of three.
+const FREEZE_AFTER_THREE: &[(&str, &str)] = &[
+    ("ERROR_BUDGET_ENABLED", "true"),
+    ("ERROR_BUDGET_SUCCESS_THRESHOLD", "0.6"),
+    ("ERROR_BUDGET_MIN_REQUESTS", "3"),
+    ("ERROR_BUDGET_SAMPLE_WINDOW_SECS", "120"),
+    ("ERROR_BUDGET_FREEZE_SECS", "60"),
+];
 
 #[tokio::test]
 async fn error_budget_freeze_blocks_calls() -> Result<()> {
-    let bin = cargo_bin("mcp-multi-tool");
-    let service = ()
-        .serve(TokioChildProcess::new(Command::new(&bin).configure(
-            |cmd| {
-                cmd.env("ERROR_BUDGET_ENABLED", "true");
-                cmd.env("ERROR_BUDGET_SUCCESS_THRESHOLD", "0.6");
-                cmd.env("ERROR_BUDGET_MIN_REQUESTS", "3");
-                cmd.env("ERROR_BUDGET_SAMPLE_WINDOW_SECS", "120");
-                cmd.env("ERROR_BUDGET_FREEZE_SECS", "60");
-            },
-        ))?)
-        .await?;
+    let service = common::inspector(FREEZE_AFTER_THREE).await?;
 
     let failing = json!({
         "tool_name": "help",
@@ -63,3 +58,246 @@ async fn error_budget_freeze_blocks_calls() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn error_budget_freeze_appends_transition_event() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let outbox_path = tmp.path().join("events.jsonl");
+    let dlq_path = tmp.path().join("dlq.jsonl");
+    let service = common::inspector(
+        &[
+            FREEZE_AFTER_THREE,
+            &[
+                ("OUTBOX_PATH", outbox_path.to_str().unwrap()),
+                ("OUTBOX_DLQ_PATH", dlq_path.to_str().unwrap()),
+            ],
+        ]
+        .concat(),
+    )
+    .await?;
+
+    let failing = json!({
+        "tool_name": "help",
+        "arguments_json": {},
+        "stream": false,
+        "stdio": {
+            "command": "definitely-not-a-binary"
+        }
+    });
+    let failing_map = failing.as_object().cloned().unwrap();
+    for _ in 0..3 {
+        service
+            .call_tool(CallToolRequestParam {
+                name: "inspector_call".into(),
+                arguments: Some(failing_map.clone()),
+            })
+            .await?;
+    }
+    service.cancel().await?;
+
+    let data = std::fs::read_to_string(&outbox_path)?;
+    let events: Vec<serde_json::Value> = data
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    let freeze = events
+        .iter()
+        .find(|event| event.get("state").and_then(|v| v.as_str()) == Some("freeze_triggered"))
+        .expect("freeze_triggered event in outbox");
+    let details = freeze.get("response").expect("freeze details");
+    assert_eq!(details.get("sample_size").and_then(|v| v.as_u64()), Some(3));
+    assert_eq!(
+        details.get("success_rate").and_then(|v| v.as_f64()),
+        Some(0.0)
+    );
+    assert!(details.get("frozen_until").is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn budget_window_explains_a_freeze() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let outbox_path = tmp.path().join("events.jsonl");
+    let service = common::inspector(
+        &[
+            FREEZE_AFTER_THREE,
+            &[
+                ("OUTBOX_PATH", outbox_path.to_str().unwrap()),
+                ("ADMIN_TOOLS_ENABLED", "true"),
+            ],
+        ]
+        .concat(),
+    )
+    .await?;
+
+    let failing = json!({
+        "tool_name": "help",
+        "arguments_json": {},
+        "stream": false,
+        "stdio": {
+            "command": "definitely-not-a-binary"
+        }
+    });
+    for _ in 0..3 {
+        service
+            .call_tool(CallToolRequestParam {
+                name: "inspector_call".into(),
+                arguments: failing.as_object().cloned(),
+            })
+            .await?;
+    }
+
+    let window = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_budget_window".into(),
+            arguments: Some(Default::default()),
+        })
+        .await?;
+    assert!(!window.is_error.unwrap_or(false));
+    let payload = window.structured_content.expect("window payload");
+    assert_eq!(payload["sample_size"], 3);
+    assert_eq!(payload["success_rate"], 0.0);
+    assert!(payload["frozen_until"].is_string());
+    let observations = payload["observations"].as_array().expect("observations");
+    assert_eq!(observations.len(), 3);
+    assert!(observations.iter().all(|obs| obs["success"] == false));
+    assert!(observations.iter().all(|obs| obs["at"].is_string()));
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn freeze_allowed_tools_keep_probing_while_calls_fail() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let outbox_path = tmp.path().join("events.jsonl");
+    let service = common::inspector(
+        &[
+            FREEZE_AFTER_THREE,
+            &[
+                ("OUTBOX_PATH", outbox_path.to_str().unwrap()),
+                ("ERROR_BUDGET_FREEZE_ALLOWED_TOOLS", "inspector_probe"),
+                ("ADMIN_TOOLS_ENABLED", "true"),
+            ],
+        ]
+        .concat(),
+    )
+    .await?;
+
+    let failing = json!({
+        "tool_name": "help",
+        "arguments_json": {},
+        "stream": false,
+        "stdio": {
+            "command": "definitely-not-a-binary"
+        }
+    });
+    for _ in 0..3 {
+        service
+            .call_tool(CallToolRequestParam {
+                name: "inspector_call".into(),
+                arguments: failing.as_object().cloned(),
+            })
+            .await?;
+    }
+
+    let target = json!({
+        "transport": "stdio",
+        "command": cargo_bin("mock_mcp_server").display().to_string(),
+        "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+    });
+    let probe = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_probe".into(),
+            arguments: target.as_object().cloned(),
+        })
+        .await?;
+    assert!(!probe.is_error.unwrap_or(false), "{probe:?}");
+    assert_eq!(probe.structured_content.expect("probe result")["ok"], true);
+
+    for (name, arguments) in [
+        ("inspector_list_tools", target),
+        ("inspector_call", failing),
+    ] {
+        let blocked = service
+            .call_tool(CallToolRequestParam {
+                name: name.into(),
+                arguments: arguments.as_object().cloned(),
+            })
+            .await?;
+        assert!(blocked.is_error.unwrap_or(false), "{name}: {blocked:?}");
+        assert_eq!(
+            blocked.structured_content.expect("freeze payload")["code"],
+            "ERROR_BUDGET_EXHAUSTED",
+            "{name}"
+        );
+    }
+
+    for name in ["inspector_health", "inspector_budget_window"] {
+        let served = service
+            .call_tool(CallToolRequestParam {
+                name: name.into(),
+                arguments: Some(Default::default()),
+            })
+            .await?;
+        assert!(!served.is_error.unwrap_or(false), "{name}: {served:?}");
+    }
+
+    service.cancel().await?;
+    Ok(())
+}
+
+/// Sends `arguments` to `inspector_call` more often than it takes to freeze, expecting `code`
+/// every time: a request rejected before anything connects says nothing about the target.
+async fn assert_rejections_never_freeze(arguments: serde_json::Value, code: &str) -> Result<()> {
+    let service = common::inspector(FREEZE_AFTER_THREE).await?;
+    for _ in 0..5 {
+        let result = service
+            .call_tool(CallToolRequestParam {
+                name: "inspector_call".into(),
+                arguments: arguments.as_object().cloned(),
+            })
+            .await?;
+        assert!(result.is_error.unwrap_or(false), "{result:?}");
+        assert_eq!(
+            result.structured_content.expect("error payload")["code"],
+            code
+        );
+    }
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_headers_do_not_spend_the_error_budget() -> Result<()> {
+    assert_rejections_never_freeze(
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {},
+            "http": {
+                "url": "http://127.0.0.1:9/mcp",
+                "headers": {"x-org-id": "org-7\r\nx-injected: 1"}
+            }
+        }),
+        "INVALID_HEADERS",
+    )
+    .await
+}
+
+#[tokio::test]
+async fn tls_config_errors_do_not_spend_the_error_budget() -> Result<()> {
+    assert_rejections_never_freeze(
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {},
+            "http": {
+                "url": "https://127.0.0.1:9/mcp",
+                "tls": {"ca_cert_path": "/nonexistent/ca.crt"}
+            }
+        }),
+        "TLS_CONFIG_ERROR",
+    )
+    .await
+}
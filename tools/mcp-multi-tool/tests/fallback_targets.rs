@@ -0,0 +1,108 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    model::{CallToolRequestParam, CallToolResult},
+    service::{RoleClient, RunningService},
+};
+use serde_json::{Value, json};
+
+mod common;
+
+fn mock_stdio() -> Value {
+    json!({
+        "command": cargo_bin("mock_mcp_server").display().to_string(),
+        "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+    })
+}
+
+async fn call(service: &RunningService<RoleClient, ()>, args: Value) -> Result<CallToolResult> {
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+#[tokio::test]
+async fn unreachable_target_falls_back_to_next() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+    let result = call(
+        &service,
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {"text": "via fallback"},
+            "http": {"url": "http://127.0.0.1:1/mcp", "handshake_timeout_ms": 2000},
+            "fallback_targets": [{"stdio": mock_stdio()}]
+        }),
+    )
+    .await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    assert_eq!(
+        result.structured_content.as_ref().expect("echo payload")["echoed"],
+        "via fallback"
+    );
+
+    let meta = result.meta.expect("trace meta");
+    let chain = meta["trace"]["fallback_chain"]
+        .as_array()
+        .expect("fallback chain");
+    assert_eq!(chain.len(), 2);
+    assert_eq!(chain[0]["target"]["transport"], "http");
+    assert_eq!(chain[0]["succeeded"], false);
+    assert!(chain[0]["error"].is_string());
+    assert_eq!(chain[1]["target"]["transport"], "stdio");
+    assert_eq!(chain[1]["succeeded"], true);
+    assert_eq!(meta["trace"]["event"]["target"]["transport"], "stdio");
+    Ok(())
+}
+
+#[tokio::test]
+async fn tool_errors_do_not_fall_back() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+    let result = call(
+        &service,
+        json!({
+            "tool_name": "no_such_tool",
+            "arguments_json": {},
+            "stdio": mock_stdio(),
+            "fallback_targets": [{"stdio": mock_stdio()}]
+        }),
+    )
+    .await?;
+    assert!(result.is_error.unwrap_or(false));
+    let meta = result.meta.expect("trace meta");
+    let chain = meta["trace"]["fallback_chain"]
+        .as_array()
+        .expect("fallback chain");
+    // The target was reached; the tool's own error is not a reason to move on.
+    assert_eq!(chain.len(), 1);
+    assert_eq!(chain[0]["succeeded"], true);
+    Ok(())
+}
+
+#[tokio::test]
+async fn warnings_from_one_call_are_collected_together() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+    let result = call(
+        &service,
+        json!({
+            "tool_name": "bad_output",
+            "arguments_json": {},
+            "output_schema_check": "warn",
+            "http": {"url": "http://127.0.0.1:1/mcp", "handshake_timeout_ms": 2000},
+            "fallback_targets": [{"stdio": mock_stdio()}]
+        }),
+    )
+    .await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    let meta = result.meta.expect("trace meta");
+    let codes: Vec<&str> = meta["trace"]["warnings"]
+        .as_array()
+        .expect("warnings")
+        .iter()
+        .filter_map(|warning| warning["code"].as_str())
+        .collect();
+    assert_eq!(codes, ["FALLBACK_TARGET_USED", "OUTPUT_SCHEMA_VIOLATION"]);
+    Ok(())
+}
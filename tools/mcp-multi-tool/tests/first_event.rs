@@ -0,0 +1,65 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+async fn call_stream(
+    service: &RunningService<RoleClient, ()>,
+    arguments: Value,
+    stream: bool,
+) -> Result<CallToolResult> {
+    let args = json!({
+        "tool_name": "stream",
+        "arguments_json": arguments,
+        "stream": stream,
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        }
+    });
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+#[tokio::test]
+async fn first_event_ms_measures_the_wait_for_the_first_chunk() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+
+    let result = call_stream(&service, json!({"chunks": 4, "first_delay_ms": 300}), true).await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    let trace = &result.meta.expect("trace meta")["trace"];
+    let first_event_ms = trace["first_event_ms"].as_u64().expect("first_event_ms");
+    let duration_ms = trace["event"]["duration_ms"].as_u64().expect("duration_ms");
+    assert!(first_event_ms >= 300, "first_event_ms = {first_event_ms}");
+    // Three more chunks follow 25ms apart, so the first one lands well before the end.
+    assert!(
+        first_event_ms + 50 <= duration_ms,
+        "first_event_ms = {first_event_ms}, duration_ms = {duration_ms}"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn first_event_ms_is_absent_without_streaming() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+
+    let result = call_stream(&service, json!({"chunks": 2}), false).await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    let trace = &result.meta.expect("trace meta")["trace"];
+    assert!(trace.get("first_event_ms").is_none(), "{trace}");
+
+    service.cancel().await?;
+    Ok(())
+}
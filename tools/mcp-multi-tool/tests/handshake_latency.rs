@@ -0,0 +1,69 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use mcp_multi_tool::{
+    app::{
+        bench,
+        error_budget::{ErrorBudget, ErrorBudgetParams},
+        inspector_service::InspectorService,
+    },
+    infra::metrics::{COLD_HANDSHAKE_HISTO, LATENCY_HISTO},
+    shared::types::{BenchRequest, ProbeRequest, TargetTransportKind},
+};
+use serde_json::json;
+
+fn mock_probe() -> ProbeRequest {
+    ProbeRequest {
+        transport: Some(TargetTransportKind::Stdio),
+        command: Some(cargo_bin("mock_mcp_server").display().to_string()),
+        env: Some(BTreeMap::from([
+            ("MOCK_SSE_ADDR".to_string(), "127.0.0.1:0".to_string()),
+            ("MOCK_HTTP_ADDR".to_string(), "127.0.0.1:0".to_string()),
+        ])),
+        ..ProbeRequest::default()
+    }
+}
+
+fn counts() -> (u64, u64) {
+    (
+        COLD_HANDSHAKE_HISTO.get_sample_count(),
+        LATENCY_HISTO.get_sample_count(),
+    )
+}
+
+// One test so the process-wide histograms are not shared with concurrent cases.
+#[tokio::test]
+async fn warm_calls_skip_the_cold_handshake_histogram() -> Result<()> {
+    let svc = InspectorService::new();
+
+    let (cold, warm) = counts();
+    let probe = svc.probe(mock_probe()).await?;
+    assert!(probe.ok, "probe failed: {probe:?}");
+    assert_eq!(counts(), (cold + 1, warm), "a probe is one cold handshake");
+
+    let budget = ErrorBudget::new(ErrorBudgetParams {
+        enabled: false,
+        success_threshold: 1.0,
+        minimum_requests: 1,
+        sample_window: Duration::from_secs(60),
+        freeze_duration: Duration::from_secs(60),
+        warmup: Duration::ZERO,
+    });
+    let request = BenchRequest {
+        tool_name: "add".into(),
+        arguments_json: json!({"values": [1, 2]}),
+        iterations: 10,
+        concurrency: Some(1),
+        probe: mock_probe(),
+    };
+    let (cold, warm) = counts();
+    let outcome = bench::run(&svc, &request, &budget).await?;
+    assert_eq!(outcome.report.errors, 0);
+    assert_eq!(
+        counts(),
+        (cold + 1, warm + 10),
+        "ten calls over one session: one cold handshake, ten warm samples"
+    );
+    Ok(())
+}
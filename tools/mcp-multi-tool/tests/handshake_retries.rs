@@ -0,0 +1,61 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::model::CallToolRequestParam;
+use serde_json::json;
+
+mod common;
+
+#[tokio::test]
+async fn trace_records_each_handshake_attempt() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let counter = dir.path().join("launches");
+    let service =
+        common::inspector(&[("HANDSHAKE_RETRIES", "3"), ("ALLOW_SHELL_TARGETS", "true")]).await?;
+
+    // Exits before the handshake on the first two launches, then serves normally.
+    let flaky = format!(
+        "n=$(cat \"$LAUNCH_COUNTER\" 2>/dev/null || echo 0); echo $((n + 1)) > \"$LAUNCH_COUNTER\"; \
+         [ \"$n\" -ge 2 ] && exec '{}'",
+        cargo_bin("mock_mcp_server").display()
+    );
+    let args = json!({
+        "tool_name": "echo",
+        "arguments_json": {"text": "third time lucky"},
+        "stdio": {
+            "command": flaky,
+            "shell": true,
+            "env": {
+                "LAUNCH_COUNTER": counter.display().to_string(),
+                "MOCK_SSE_ADDR": "127.0.0.1:0",
+                "MOCK_HTTP_ADDR": "127.0.0.1:0"
+            }
+        }
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    assert_eq!(
+        result.structured_content.expect("echo payload")["echoed"],
+        "third time lucky"
+    );
+
+    let meta = result.meta.expect("trace meta");
+    let attempts = meta["trace"]["attempts"].as_array().expect("attempts");
+    assert_eq!(attempts.len(), 3);
+    for (idx, attempt) in attempts.iter().enumerate() {
+        assert_eq!(attempt["attempt"], idx + 1);
+        assert!(attempt["latency_ms"].is_u64());
+    }
+    assert_eq!(attempts[0]["succeeded"], false);
+    assert!(attempts[0]["error"].is_string());
+    assert_eq!(attempts[1]["succeeded"], false);
+    assert_eq!(attempts[2]["succeeded"], true);
+    assert!(attempts[2].get("error").is_none());
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -1,23 +1,12 @@
 use anyhow::Result;
-use assert_cmd::cargo::cargo_bin;
-use rmcp::{
-    ServiceExt,
-    model::CallToolRequestParam,
-    transport::child_process::{ConfigureCommandExt, TokioChildProcess},
-};
+use rmcp::model::CallToolRequestParam;
 use serde_json::Value;
-use tokio::process::Command;
+
+mod common;
 
 #[tokio::test]
 async fn help_returns_structured_jsonl() -> Result<()> {
-    let bin = cargo_bin("mcp-multi-tool");
-    let service = ()
-        .serve(TokioChildProcess::new(Command::new(&bin).configure(
-            |cmd| {
-                cmd.env("ERROR_BUDGET_ENABLED", "false");
-            },
-        ))?)
-        .await?;
+    let service = common::inspector(&[]).await?;
 
     let help = service
         .call_tool(CallToolRequestParam {
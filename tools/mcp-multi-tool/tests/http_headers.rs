@@ -0,0 +1,132 @@
+use std::{
+    net::{TcpListener, TcpStream},
+    process::{Child, Command as StdCommand, Stdio},
+    thread,
+    time::Duration,
+};
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+struct MockGuard(Child);
+
+impl Drop for MockGuard {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Start the mock with only its SSE/HTTP transports, requiring `x-org-id: org-7` on HTTP.
+fn spawn_gated_mock() -> Result<(MockGuard, String)> {
+    let reserve = || -> Result<u16> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        Ok(listener.local_addr()?.port())
+    };
+    let (sse_port, http_port) = (reserve()?, reserve()?);
+    let child = StdCommand::new(cargo_bin("mock_mcp_server"))
+        .env("MOCK_ENABLE_STDIO", "0")
+        .env("MOCK_SSE_ADDR", format!("127.0.0.1:{sse_port}"))
+        .env("MOCK_HTTP_ADDR", format!("127.0.0.1:{http_port}"))
+        .env("MOCK_HTTP_REQUIRE_HEADER", "x-org-id=org-7")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let guard = MockGuard(child);
+    for _ in 0..50 {
+        if TcpStream::connect(("127.0.0.1", http_port)).is_ok() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    Ok((guard, format!("http://127.0.0.1:{http_port}/mcp")))
+}
+
+async fn call(
+    service: &RunningService<RoleClient, ()>,
+    name: &str,
+    args: Value,
+) -> Result<CallToolResult> {
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+fn echo_args(url: &str, headers: Value) -> Value {
+    json!({
+        "tool_name": "echo",
+        "arguments_json": {"text": "org scoped"},
+        "http": {"url": url, "headers": headers}
+    })
+}
+
+#[tokio::test]
+async fn http_headers_reach_gated_target() -> Result<()> {
+    let (_mock, url) = spawn_gated_mock()?;
+    let service = common::inspector(&[]).await?;
+    let headers = json!({"x-org-id": "org-7", "x-request-source": "inspector"});
+
+    let rejected = call(&service, "inspector_call", echo_args(&url, json!({}))).await?;
+    assert!(
+        rejected.is_error.unwrap_or(false),
+        "call without headers should fail: {rejected:?}"
+    );
+
+    let accepted = call(&service, "inspector_call", echo_args(&url, headers.clone())).await?;
+    assert!(
+        !accepted.is_error.unwrap_or(false),
+        "call failed: {accepted:?}"
+    );
+    assert_eq!(
+        accepted.structured_content.expect("echo payload")["echoed"],
+        "org scoped"
+    );
+
+    let probe_args = json!({"transport": "http", "url": url, "headers": headers});
+    let probe = call(&service, "inspector_probe", probe_args.clone()).await?;
+    assert_eq!(probe.structured_content.expect("probe result")["ok"], true);
+    let listed = call(&service, "inspector_list_tools", probe_args).await?;
+    let tools = listed.structured_content.expect("tool list")["tools"].clone();
+    assert!(
+        tools
+            .as_array()
+            .is_some_and(|tools| tools.iter().any(|tool| tool["name"] == "echo")),
+        "unexpected tools: {tools}"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn unsendable_http_header_values_are_rejected() -> Result<()> {
+    let (_mock, url) = spawn_gated_mock()?;
+    let service = common::inspector(&[]).await?;
+
+    let headers = json!({"x-org-id": "org-7\r\nx-injected: 1"});
+    let result = call(&service, "inspector_call", echo_args(&url, headers)).await?;
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("error payload");
+    assert_eq!(payload["code"], "INVALID_HEADERS");
+    assert!(
+        payload["error"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("x-org-id"),
+        "unexpected error: {payload}"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -1,7 +1,11 @@
 use std::sync::Arc;
 
-use mcp_multi_tool::shared::idempotency::{ClaimOutcome, IdempotencyStore};
-use mcp_multi_tool::shared::types::{CallRequest, InspectionRunEvent, TargetDescriptor};
+use mcp_multi_tool::shared::idempotency::{
+    ClaimOutcome, IdempotencyLimits, IdempotencyOverflowPolicy, IdempotencyStore,
+};
+use mcp_multi_tool::shared::types::{
+    CallRequest, InspectionRunEvent, OutputSchemaCheck, TargetDescriptor,
+};
 use proptest::prelude::*;
 use serde_json::json;
 use std::{thread, time::Duration};
@@ -22,12 +26,15 @@ fn dummy_event() -> InspectionRunEvent {
             command: Some("dummy".into()),
             url: None,
             headers: None,
+            socket_path: None,
         }),
         request: None,
         response: None,
         error: None,
         idempotency_key: None,
         external_reference: None,
+        target_capabilities: None,
+        requested_tool_name: None,
     }
 }
 
@@ -50,6 +57,7 @@ fn idempotency_state_machine() {
         }
         ClaimOutcome::InFlight => panic!("expected completion"),
         ClaimOutcome::Accepted => panic!("claim should not be accepted twice"),
+        ClaimOutcome::StoreFull => panic!("unbounded store reported full"),
     }
 }
 
@@ -68,6 +76,7 @@ async fn idempotency_concurrency_one_winner() {
             ClaimOutcome::Accepted => accepted += 1,
             ClaimOutcome::InFlight => {}
             ClaimOutcome::Completed(_) => {}
+            ClaimOutcome::StoreFull => panic!("unbounded store reported full"),
         }
     }
     assert_eq!(accepted, 1, "only one caller should win the claim");
@@ -76,6 +85,7 @@ async fn idempotency_concurrency_one_winner() {
         ClaimOutcome::Completed(_) => {}
         ClaimOutcome::InFlight => panic!("run still in-flight after completion"),
         ClaimOutcome::Accepted => panic!("claim reopened unexpectedly"),
+        ClaimOutcome::StoreFull => panic!("unbounded store reported full"),
     }
 }
 
@@ -112,9 +122,21 @@ fn reaper_emits_failure_event() {
         idempotency_key: Some(key.into()),
         stream: false,
         external_reference: Some("ext-demo".into()),
+        output_schema_check: OutputSchemaCheck::Off,
+        target_alias: None,
         stdio: None,
         sse: None,
         http: None,
+        unix: None,
+        fallback_targets: Vec::new(),
+        reuse_session: None,
+        session_id: None,
+        retry: None,
+        stream_max_duration_ms: None,
+        call_timeout_ms: None,
+        progress_token: None,
+        validate_arguments: false,
+        stream_drain_timeout_ms: None,
     };
 
     assert!(matches!(store.claim(key), ClaimOutcome::Accepted));
@@ -129,6 +151,7 @@ fn reaper_emits_failure_event() {
             command: Some("demo".into()),
             url: None,
             headers: None,
+            socket_path: None,
         },
     );
 
@@ -154,6 +177,48 @@ fn reaper_emits_failure_event() {
     assert_eq!(existing.tool_name, "demo");
 }
 
+#[test]
+fn reaper_skips_exempt_tools() {
+    let store = IdempotencyStore::new().with_reaper_exempt_tools(["batch_job"]);
+    let request = |tool: &str, key: &str| CallRequest {
+        tool_name: tool.into(),
+        arguments_json: json!({}),
+        idempotency_key: Some(key.into()),
+        stream: false,
+        external_reference: None,
+        output_schema_check: OutputSchemaCheck::Off,
+        target_alias: None,
+        stdio: None,
+        sse: None,
+        http: None,
+        unix: None,
+        fallback_targets: Vec::new(),
+        reuse_session: None,
+        session_id: None,
+        retry: None,
+        stream_max_duration_ms: None,
+        call_timeout_ms: None,
+        progress_token: None,
+        validate_arguments: false,
+        stream_drain_timeout_ms: None,
+    };
+    for (tool, key) in [("batch_job", "long-key"), ("demo", "short-key")] {
+        assert!(matches!(store.claim(key), ClaimOutcome::Accepted));
+        store.begin(key, Uuid::new_v4(), &request(tool, key));
+        store.mark_started(key, OffsetDateTime::now_utc());
+    }
+    thread::sleep(Duration::from_millis(5));
+
+    let reaped = store.reap_expired(Duration::from_millis(1), OffsetDateTime::now_utc());
+    assert_eq!(reaped.len(), 1);
+    assert_eq!(reaped[0].event.tool_name, "demo");
+    assert!(matches!(store.claim("long-key"), ClaimOutcome::InFlight));
+    assert!(matches!(
+        store.claim("short-key"),
+        ClaimOutcome::Completed(_)
+    ));
+}
+
 #[derive(Clone, Debug)]
 enum Operation {
     Claim,
@@ -175,6 +240,7 @@ proptest! {
                 Operation::Claim => match store.claim(key) {
                     ClaimOutcome::Accepted => accepted += 1,
                     ClaimOutcome::InFlight | ClaimOutcome::Completed(_) => {},
+                    ClaimOutcome::StoreFull => prop_assert!(false, "unbounded store reported full"),
                 },
                 Operation::Complete => {
                     store.complete(key, dummy_event());
@@ -184,3 +250,74 @@ proptest! {
         prop_assert!(accepted <= 1);
     }
 }
+
+#[test]
+fn bounded_store_rejects_new_keys_when_full() {
+    let store = IdempotencyStore::with_limits(IdempotencyLimits {
+        max_records: Some(2),
+        overflow_policy: IdempotencyOverflowPolicy::Reject,
+    });
+    assert!(matches!(store.claim("a"), ClaimOutcome::Accepted));
+    assert!(matches!(store.claim("b"), ClaimOutcome::Accepted));
+    store.complete("a", dummy_event());
+    assert!(matches!(store.claim("c"), ClaimOutcome::StoreFull));
+    // existing keys keep resolving while the store is full
+    assert!(matches!(store.claim("a"), ClaimOutcome::Completed(_)));
+    assert!(matches!(store.claim("b"), ClaimOutcome::InFlight));
+    assert_eq!(store.len(), 2);
+}
+
+#[test]
+fn bounded_store_evicts_oldest_completed_records() {
+    let store = IdempotencyStore::with_limits(IdempotencyLimits {
+        max_records: Some(2),
+        overflow_policy: IdempotencyOverflowPolicy::EvictOldest,
+    });
+    assert!(matches!(store.claim("old"), ClaimOutcome::Accepted));
+    store.complete("old", dummy_event());
+    thread::sleep(Duration::from_millis(2));
+    assert!(matches!(store.claim("newer"), ClaimOutcome::Accepted));
+    store.complete("newer", dummy_event());
+
+    assert!(matches!(store.claim("fresh"), ClaimOutcome::Accepted));
+    assert_eq!(store.len(), 2);
+    assert!(matches!(store.claim("newer"), ClaimOutcome::Completed(_)));
+    // "old" was evicted, so the store is full of one completed and one in-flight record;
+    // claiming it again evicts "newer" instead.
+    assert!(matches!(store.claim("old"), ClaimOutcome::Accepted));
+
+    // with every record in-flight there is nothing left to evict
+    assert!(matches!(store.claim("another"), ClaimOutcome::StoreFull));
+}
+
+#[test]
+fn derived_idempotency_key_ignores_argument_key_order() {
+    let request = |arguments: serde_json::Value| CallRequest {
+        tool_name: "echo".into(),
+        arguments_json: arguments,
+        idempotency_key: None,
+        stream: false,
+        external_reference: None,
+        output_schema_check: OutputSchemaCheck::Off,
+        target_alias: None,
+        stdio: None,
+        sse: None,
+        http: None,
+        unix: None,
+        fallback_targets: Vec::new(),
+        reuse_session: None,
+        session_id: None,
+        retry: None,
+        stream_max_duration_ms: None,
+        call_timeout_ms: None,
+        progress_token: None,
+        validate_arguments: false,
+        stream_drain_timeout_ms: None,
+    };
+    let a = request(json!({"text": "hi", "n": 1}));
+    let b = request(json!({"n": 1, "text": "hi"}));
+    let c = request(json!({"text": "bye", "n": 1}));
+    assert_eq!(a.derived_idempotency_key(), b.derived_idempotency_key());
+    assert_ne!(a.derived_idempotency_key(), c.derived_idempotency_key());
+    assert!(a.derived_idempotency_key().starts_with("auto:"));
+}
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{RoleClient, model::CallToolRequestParam, service::RunningService};
+use serde_json::{Value, json};
+
+mod common;
+
+async fn echo(service: &RunningService<RoleClient, ()>, arguments: Value) -> Result<Value> {
+    let args = json!({
+        "tool_name": "echo",
+        "arguments_json": arguments,
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {
+                "MOCK_SSE_ADDR": "127.0.0.1:0",
+                "MOCK_HTTP_ADDR": "127.0.0.1:0"
+            }
+        }
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    Ok(result.structured_content.expect("structured payload"))
+}
+
+#[tokio::test]
+async fn identical_calls_dedup_under_auto_key() -> Result<()> {
+    let service = common::inspector(&[
+        ("IDEMPOTENCY_AUTO_KEY", "true"),
+        ("IDEMPOTENCY_CONFLICT_POLICY", "return_existing"),
+    ])
+    .await?;
+
+    let first = echo(&service, json!({"text": "once", "pad": 1})).await?;
+    assert_eq!(first["echoed"], "once");
+
+    let repeat = echo(&service, json!({"pad": 1, "text": "once"})).await?;
+    assert_eq!(repeat["status"], "duplicate");
+    let key = repeat["event"]["idempotency_key"].as_str().expect("key");
+    assert!(key.starts_with("auto:"), "unexpected key {key}");
+
+    let different = echo(&service, json!({"text": "twice"})).await?;
+    assert_eq!(different["echoed"], "twice");
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+async fn call(
+    service: &RunningService<RoleClient, ()>,
+    name: &'static str,
+    args: Value,
+) -> Result<CallToolResult> {
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: name.into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+fn keyed_echo() -> Value {
+    json!({
+        "tool_name": "echo",
+        "arguments_json": {"text": "charge card"},
+        "idempotency_key": "deploy-handoff-1",
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        }
+    })
+}
+
+#[tokio::test]
+async fn exported_records_dedupe_on_a_fresh_instance() -> Result<()> {
+    let blue = common::inspector(&[
+        ("IDEMPOTENCY_CONFLICT_POLICY", "return_existing"),
+        ("ADMIN_TOOLS_ENABLED", "true"),
+    ])
+    .await?;
+    let first = call(&blue, "inspector_call", keyed_echo()).await?;
+    assert!(!first.is_error.unwrap_or(false), "call failed: {first:?}");
+
+    let export = call(&blue, "inspector_idempotency_export", json!({})).await?;
+    let export = export.structured_content.expect("export payload");
+    assert_eq!(export["count"], 1);
+    let record = &export["records"][0];
+    assert_eq!(record["key"], "deploy-handoff-1");
+    assert!(
+        record["event"].get("request").is_none(),
+        "request not redacted"
+    );
+
+    let green = common::inspector(&[
+        ("IDEMPOTENCY_CONFLICT_POLICY", "return_existing"),
+        ("ADMIN_TOOLS_ENABLED", "true"),
+    ])
+    .await?;
+    let import = call(
+        &green,
+        "inspector_idempotency_import",
+        json!({"records": export["records"]}),
+    )
+    .await?;
+    let import = import.structured_content.expect("import payload");
+    assert_eq!(import["imported"], 1);
+    assert_eq!(import["skipped"], 0);
+
+    let repeat = call(&green, "inspector_call", keyed_echo()).await?;
+    let repeat = repeat.structured_content.expect("duplicate payload");
+    assert_eq!(repeat["status"], "duplicate");
+    assert_eq!(repeat["event"]["run_id"], record["event"]["run_id"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn admin_tools_are_hidden_by_default() -> Result<()> {
+    let service = common::inspector(&[
+        ("IDEMPOTENCY_CONFLICT_POLICY", "return_existing"),
+        ("ADMIN_TOOLS_ENABLED", "false"),
+    ])
+    .await?;
+    let tools = service.list_tools(Default::default()).await?.tools;
+    assert!(
+        !tools
+            .iter()
+            .any(|tool| tool.name.starts_with("inspector_idempotency_"))
+    );
+    let result = call(&service, "inspector_idempotency_export", json!({})).await?;
+    assert!(result.is_error.unwrap_or(false));
+    assert_eq!(
+        result.structured_content.expect("error payload")["code"],
+        "ADMIN_TOOLS_DISABLED"
+    );
+    Ok(())
+}
@@ -1,22 +1,15 @@
 use anyhow::Result;
 use assert_cmd::cargo::cargo_bin;
-use rmcp::{
-    ServiceExt,
-    model::CallToolRequestParam,
-    transport::child_process::{ConfigureCommandExt, TokioChildProcess},
-};
+use rmcp::model::CallToolRequestParam;
 use serde_json::{Value, json};
-use tokio::process::Command;
+
+mod common;
 
 #[tokio::test]
 async fn list_tools_and_help() -> Result<()> {
     let bin = cargo_bin("mcp-multi-tool");
     println!("using binary: {}", bin.display());
-    let service = ()
-        .serve(TokioChildProcess::new(Command::new(&bin).configure(|c| {
-            c.env("RUST_LOG", "info");
-        }))?)
-        .await?;
+    let service = common::inspector(&[("RUST_LOG", "info")]).await?;
     println!("connected");
     let tools = service.list_tools(Default::default()).await?.tools;
     println!("tools response");
@@ -154,3 +147,219 @@ async fn list_tools_and_help() -> Result<()> {
     assert!(!trace_events.is_empty());
     Ok(())
 }
+
+#[tokio::test]
+async fn schema_sizes_sorted_descending() -> Result<()> {
+    let service = common::inspector(&[("RUST_LOG", "info")]).await?;
+    let mock = cargo_bin("mock_mcp_server");
+    let args = json!({
+        "transport": "stdio",
+        "command": mock.display().to_string(),
+        "args": [],
+        "handshake_timeout_ms": 5000
+    });
+    let resp = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_schema_sizes".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    let payload = resp.structured_content.expect("schema sizes payload");
+    let tools = payload
+        .get("tools")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    assert!(tools.iter().any(|tool| tool["name"] == "echo"));
+    let sizes: Vec<u64> = tools
+        .iter()
+        .map(|tool| tool["bytes"].as_u64().expect("bytes"))
+        .collect();
+    assert!(sizes.windows(2).all(|pair| pair[0] >= pair[1]));
+    assert!(sizes.iter().all(|bytes| *bytes > 0));
+    assert_eq!(
+        payload.get("total_bytes").and_then(Value::as_u64),
+        Some(sizes.iter().sum::<u64>())
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn initialize_returns_full_init_result() -> Result<()> {
+    let service = common::inspector(&[("RUST_LOG", "info")]).await?;
+    let mock = cargo_bin("mock_mcp_server");
+    let args = json!({
+        "transport": "stdio",
+        "command": mock.display().to_string(),
+        "args": [],
+        "handshake_timeout_ms": 5000
+    });
+    let resp = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_initialize".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    let payload = resp.structured_content.expect("initialize payload");
+    let result = &payload["result"];
+    assert!(result["protocolVersion"].is_string());
+    assert_eq!(result["serverInfo"]["name"], "mock-mcp-server");
+    assert_eq!(result["serverInfo"]["title"], "Mock MCP Server");
+    assert!(result["capabilities"]["tools"]["listChanged"].as_bool() == Some(true));
+    assert!(result["capabilities"]["logging"].is_object());
+    assert!(
+        result["instructions"]
+            .as_str()
+            .is_some_and(|text| text.starts_with("Mock MCP server"))
+    );
+    assert_eq!(payload["instructions_truncated"], false);
+    Ok(())
+}
+
+#[tokio::test]
+async fn stream_captures_logging_notifications() -> Result<()> {
+    let service = common::inspector(&[("RUST_LOG", "info")]).await?;
+    let mock = cargo_bin("mock_mcp_server");
+    let args = json!({
+        "tool_name": "log",
+        "arguments_json": {},
+        "stream": true,
+        "stdio": {
+            "command": mock.display().to_string(),
+            "args": []
+        }
+    });
+    let resp = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    let payload = resp.structured_content.expect("stream payload");
+    let events = payload["events"].as_array().cloned().unwrap_or_default();
+    let logs: Vec<&Value> = events.iter().filter(|e| e["event"] == "log").collect();
+    assert_eq!(logs.len(), 2, "events: {events:?}");
+    assert_eq!(logs[0]["level"], "info");
+    assert_eq!(logs[0]["logger"], "mock.worker");
+    assert_eq!(logs[0]["message"], "starting work");
+    assert_eq!(logs[1]["level"], "warning");
+    assert_eq!(logs[1]["structured"]["step"], 2);
+    assert_eq!(events.last().map(|e| &e["event"]), Some(&json!("final")));
+    Ok(())
+}
+
+#[tokio::test]
+async fn non_stream_call_does_not_capture_notifications() -> Result<()> {
+    let service = common::inspector(&[("RUST_LOG", "info")]).await?;
+    let mock = cargo_bin("mock_mcp_server");
+    let args = json!({
+        "tool_name": "log",
+        "arguments_json": {},
+        "stdio": {
+            "command": mock.display().to_string(),
+            "args": []
+        }
+    });
+    let resp = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    assert!(!resp.is_error.unwrap_or(false), "{resp:?}");
+    let meta = resp.meta.expect("trace meta");
+    assert!(meta["trace"].get("logs").is_none(), "{}", meta["trace"]);
+    assert!(meta["trace"].get("stream_events").is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn probe_offers_requested_protocol_version() -> Result<()> {
+    let service = common::inspector(&[("RUST_LOG", "info")]).await?;
+    let mock = cargo_bin("mock_mcp_server");
+    let args = json!({
+        "transport": "stdio",
+        "command": mock.display().to_string(),
+        "args": [],
+        "protocol_version": "2024-11-05"
+    });
+    let resp = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_probe".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    let payload = resp.structured_content.expect("probe payload");
+    assert_eq!(payload["ok"], true);
+    // The mock answers with whatever version the client offered.
+    assert_eq!(payload["protocol_version"], "2024-11-05");
+    Ok(())
+}
+
+#[tokio::test]
+async fn calls_run_through_a_connected_session() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+    let call = |name: &'static str, args: Value| {
+        service.call_tool(CallToolRequestParam {
+            name: name.into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+    };
+    let connected = call(
+        "inspector_connect",
+        json!({
+            "transport": "stdio",
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        }),
+    )
+    .await?;
+    let connected = connected.structured_content.expect("connect payload");
+    let session_id = connected["session_id"]
+        .as_str()
+        .expect("session id")
+        .to_string();
+    assert_eq!(connected["target"]["transport"], "stdio");
+
+    let sessions = call("inspector_sessions", json!({})).await?;
+    let sessions = sessions.structured_content.expect("sessions payload");
+    assert!(sessions.to_string().contains(&session_id));
+
+    for text in ["first", "second"] {
+        let result = call(
+            "inspector_call",
+            json!({
+                "tool_name": "echo",
+                "arguments_json": {"text": text},
+                "session_id": session_id
+            }),
+        )
+        .await?;
+        assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+        assert!(format!("{:?}", result.content).contains(text));
+    }
+    let listed = call("inspector_list_tools", json!({"session_id": session_id})).await?;
+    let listed = listed.structured_content.expect("tool list");
+    assert!(listed["tool_count"].as_u64().unwrap_or_default() > 0);
+
+    let disconnected = call("inspector_disconnect", json!({"session_id": session_id})).await?;
+    assert_eq!(
+        disconnected.structured_content.expect("disconnect payload")["disconnected"],
+        true
+    );
+
+    let stale = call(
+        "inspector_call",
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {"text": "late"},
+            "session_id": session_id
+        }),
+    )
+    .await?;
+    assert!(stale.is_error.unwrap_or(false));
+    let payload = stale.structured_content.expect("error payload");
+    assert_eq!(payload["code"], "SESSION_NOT_FOUND");
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::json;
+
+mod common;
+
+/// Call `echo` on a mock that prints two banner lines to stdout before serving.
+async fn echo_through_banner(service: &RunningService<RoleClient, ()>) -> Result<CallToolResult> {
+    let args = json!({
+        "tool_name": "echo",
+        "arguments_json": {"text": "after banner"},
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {
+                "MOCK_SSE_ADDR": "127.0.0.1:0",
+                "MOCK_HTTP_ADDR": "127.0.0.1:0",
+                "MOCK_STDOUT_BANNER": "Mock server v0.0.1|Listening on stdio"
+            }
+        }
+    });
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+#[tokio::test]
+async fn lenient_framing_skips_stdout_banner() -> Result<()> {
+    let service = common::inspector(&[("STDIO_LENIENT_FRAMING", "true")]).await?;
+    let result = echo_through_banner(&service).await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    let payload = result.structured_content.expect("echo payload");
+    assert_eq!(payload["echoed"], "after banner");
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn strict_framing_rejects_stdout_banner() -> Result<()> {
+    let service = common::inspector(&[("STDIO_LENIENT_FRAMING", "false")]).await?;
+    let result = echo_through_banner(&service).await?;
+    assert!(
+        result.is_error.unwrap_or(false),
+        "banner should break strict framing"
+    );
+    service.cancel().await?;
+    Ok(())
+}
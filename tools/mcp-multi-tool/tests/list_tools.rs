@@ -0,0 +1,72 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{RoleClient, model::CallToolRequestParam, service::RunningService};
+use serde_json::{Value, json};
+
+mod common;
+
+async fn list_tools(service: &RunningService<RoleClient, ()>, env: Value) -> Result<Value> {
+    let args = json!({
+        "transport": "stdio",
+        "command": cargo_bin("mock_mcp_server").display().to_string(),
+        "env": env,
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_list_tools".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    assert!(!result.is_error.unwrap_or(false), "list failed: {result:?}");
+    Ok(result.structured_content.expect("tool list"))
+}
+
+#[tokio::test]
+async fn empty_catalog_is_reported_explicitly() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+
+    let payload = list_tools(
+        &service,
+        json!({
+            "MOCK_SSE_ADDR": "127.0.0.1:0",
+            "MOCK_HTTP_ADDR": "127.0.0.1:0",
+            "MOCK_NO_TOOLS": "1"
+        }),
+    )
+    .await?;
+    assert_eq!(payload["tools"], json!([]));
+    assert_eq!(payload["tool_count"], 0);
+    assert_eq!(payload["has_tools"], false);
+    assert!(
+        payload["note"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("no tools"),
+        "missing note: {payload}"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn populated_catalog_reports_its_count() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+
+    let payload = list_tools(
+        &service,
+        json!({"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}),
+    )
+    .await?;
+    let count = payload["tools"]
+        .as_array()
+        .map(Vec::len)
+        .unwrap_or_default();
+    assert!(count > 0);
+    assert_eq!(payload["tool_count"], count);
+    assert_eq!(payload["has_tools"], true);
+    assert!(payload.get("note").is_none(), "unexpected note: {payload}");
+
+    service.cancel().await?;
+    Ok(())
+}
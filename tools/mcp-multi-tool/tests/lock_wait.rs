@@ -2,7 +2,7 @@ use std::{
     collections::HashMap,
     sync::Arc,
     thread,
-    time::{Duration, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 
 use anyhow::Result;
@@ -17,7 +17,8 @@ use mcp_multi_tool::{
             ClaimOutcome, IdempotencyStore,
             configure_lock_observer as configure_idempotency_observer,
         },
-        types::{CallRequest, InspectionRunEvent, TargetDescriptor},
+        recent_events::RecentEvents,
+        types::{CallRequest, InspectionRunEvent, OutputSchemaCheck, TargetDescriptor},
     },
 };
 use tempfile::tempdir;
@@ -45,9 +46,21 @@ fn idempotency_lock_wait_p99_within_budget() {
                             idempotency_key: Some(key.clone()),
                             stream: false,
                             external_reference: None,
+                            output_schema_check: OutputSchemaCheck::Off,
+                            target_alias: None,
                             stdio: None,
                             sse: None,
                             http: None,
+                            unix: None,
+                            fallback_targets: Vec::new(),
+                            reuse_session: None,
+                            session_id: None,
+                            retry: None,
+                            stream_max_duration_ms: None,
+                            call_timeout_ms: None,
+                            progress_token: None,
+                            validate_arguments: false,
+                            stream_drain_timeout_ms: None,
                         };
                         let run_id = Uuid::new_v4();
                         store.begin(&key, run_id, &req);
@@ -60,6 +73,7 @@ fn idempotency_lock_wait_p99_within_budget() {
                                 command: Some("mock".into()),
                                 url: None,
                                 headers: None,
+                                socket_path: None,
                             },
                         );
                         let event = InspectionRunEvent {
@@ -75,6 +89,8 @@ fn idempotency_lock_wait_p99_within_budget() {
                             error: None,
                             idempotency_key: Some(key.clone()),
                             external_reference: None,
+                            target_capabilities: None,
+                            requested_tool_name: None,
                         };
                         store.complete(&key, event);
                     }
@@ -127,6 +143,7 @@ fn error_budget_lock_wait_p99_within_budget() {
         minimum_requests: 10,
         sample_window: Duration::from_secs(60),
         freeze_duration: Duration::from_secs(30),
+        warmup: Duration::ZERO,
     };
     let budget = Arc::new(ErrorBudget::new(params));
     thread::scope(|scope| {
@@ -136,7 +153,7 @@ fn error_budget_lock_wait_p99_within_budget() {
                 for i in 0..128 {
                     let now = SystemTime::now() + Duration::from_millis(((t * 128 + i) as u64) % 5);
                     match budget.record(i % 3 != 0, now) {
-                        RecordOutcome::FreezeTriggered(_) | RecordOutcome::FreezeCleared => {
+                        RecordOutcome::FreezeTriggered(_) | RecordOutcome::FreezeCleared(_) => {
                             metrics::set_error_budget_frozen(false)
                         }
                         RecordOutcome::None => {}
@@ -150,6 +167,57 @@ fn error_budget_lock_wait_p99_within_budget() {
     assert_component_p99(&records, "error_budget_state");
 }
 
+/// The shard locks report no lock-wait records outside the library's own unit tests, so
+/// each push is timed here instead.
+#[test]
+fn recent_events_push_p99_within_budget() {
+    let recent = Arc::new(RecentEvents::new(256));
+    let samples: Vec<f64> = thread::scope(|scope| {
+        let writers: Vec<_> = (0..32)
+            .map(|t| {
+                let recent = Arc::clone(&recent);
+                scope.spawn(move || {
+                    let mut timings = Vec::with_capacity(256);
+                    for i in 0..256 {
+                        let event = InspectionRunEvent {
+                            event_id: Uuid::new_v4(),
+                            run_id: Uuid::new_v4(),
+                            tool_name: format!("recent-{t}-{i}"),
+                            state: "captured".into(),
+                            started_at: OffsetDateTime::now_utc().to_string(),
+                            duration_ms: 1,
+                            target: None,
+                            request: None,
+                            response: None,
+                            error: None,
+                            idempotency_key: None,
+                            external_reference: None,
+                            target_capabilities: None,
+                            requested_tool_name: None,
+                        };
+                        let started = Instant::now();
+                        recent.push(event);
+                        timings.push(started.elapsed().as_secs_f64() * 1_000.0);
+                    }
+                    timings
+                })
+            })
+            .collect();
+        writers
+            .into_iter()
+            .flat_map(|writer| writer.join().expect("writer panicked"))
+            .collect()
+    });
+
+    assert!(recent.len() <= recent.capacity());
+    assert_eq!(samples.len(), 32 * 256);
+    let p99 = percentile(samples, 99.0);
+    assert!(
+        p99 <= LOCK_P99_THRESHOLD_MS,
+        "recent events push p99 {p99:.2}ms exceeds {LOCK_P99_THRESHOLD_MS:.2}ms"
+    );
+}
+
 fn assert_component_p99(records: &HashMap<String, Vec<f64>>, component: &str) {
     if let Some(samples) = records.get(component) {
         if samples.is_empty() {
@@ -0,0 +1,75 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::model::CallToolRequestParam;
+use serde_json::{Value, json};
+
+mod common;
+
+fn stdio_target(command: String) -> Value {
+    json!({
+        "transport": "stdio",
+        "command": command,
+        "args": [],
+        "env": {
+            "MOCK_SSE_ADDR": "127.0.0.1:0",
+            "MOCK_HTTP_ADDR": "127.0.0.1:0"
+        }
+    })
+}
+
+fn with_level(mut target: Value, level: &str) -> serde_json::Map<String, Value> {
+    target["level"] = json!(level);
+    target.as_object().cloned().unwrap()
+}
+
+#[tokio::test]
+async fn probe_reports_logging_capability_and_level_is_forwarded() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+    let target = stdio_target(cargo_bin("mock_mcp_server").display().to_string());
+
+    let probe = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_probe".into(),
+            arguments: target.as_object().cloned(),
+        })
+        .await?;
+    let capabilities = probe.structured_content.expect("probe result")["capabilities"].clone();
+    assert_eq!(capabilities["tools"], true);
+    assert_eq!(capabilities["logging"], true);
+    assert_eq!(capabilities["completions"], false);
+
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_set_logging_level".into(),
+            arguments: Some(with_level(target, "debug")),
+        })
+        .await?;
+    let payload = result.structured_content.expect("set level payload");
+    assert_eq!(payload["level"], "debug");
+    assert_eq!(payload["applied"], true);
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn set_logging_level_rejects_target_without_capability() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+    // The inspector itself does not advertise logging.
+    let target = stdio_target(cargo_bin("mcp-multi-tool").display().to_string());
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_set_logging_level".into(),
+            arguments: Some(with_level(target, "info")),
+        })
+        .await?;
+    assert_eq!(result.is_error, Some(true));
+    let payload = result.structured_content.expect("error payload");
+    let message = payload["error"].as_str().unwrap_or_default();
+    assert!(
+        message.contains("logging capability"),
+        "unexpected error: {message}"
+    );
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::model::{CallToolRequestParam, CallToolResult};
+use serde_json::{Value, json};
+
+mod common;
+
+async fn call_bad_output(check: &str) -> Result<CallToolResult> {
+    let _bin = cargo_bin("mcp-multi-tool");
+    let mock = cargo_bin("mock_mcp_server");
+    let service = common::inspector(&[]).await?;
+
+    let args = json!({
+        "tool_name": "bad_output",
+        "arguments_json": {},
+        "output_schema_check": check,
+        "stdio": {
+            "command": mock.display().to_string(),
+            "env": {
+                "MOCK_SSE_ADDR": "127.0.0.1:0",
+                "MOCK_HTTP_ADDR": "127.0.0.1:0"
+            }
+        }
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    service.cancel().await?;
+    Ok(result)
+}
+
+fn trace_warnings(result: &CallToolResult) -> Vec<Value> {
+    result
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.get("trace"))
+        .and_then(|trace| trace.get("warnings"))
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn output_schema_violation_flagged_as_warning() -> Result<()> {
+    let result = call_bad_output("warn").await?;
+    assert!(!result.is_error.unwrap_or(false));
+    let warnings = trace_warnings(&result);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0]["code"], "OUTPUT_SCHEMA_VIOLATION");
+    let violations = warnings[0]["violations"].as_array().expect("violations");
+    assert!(
+        violations
+            .iter()
+            .any(|v| v.as_str().unwrap_or_default().starts_with("$.sum"))
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn output_schema_violation_fails_when_enforced() -> Result<()> {
+    let result = call_bad_output("enforce").await?;
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("error payload");
+    assert_eq!(
+        payload.get("code").and_then(Value::as_str),
+        Some("OUTPUT_SCHEMA_VIOLATION")
+    );
+    assert!(payload.get("violations").is_some());
+    Ok(())
+}
+
+#[tokio::test]
+async fn output_schema_not_checked_by_default() -> Result<()> {
+    let result = call_bad_output("off").await?;
+    assert!(!result.is_error.unwrap_or(false));
+    assert!(trace_warnings(&result).is_empty());
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::model::{CallToolRequestParam, CallToolResult};
+use serde_json::json;
+
+mod common;
+
+/// Stream five chunks from a mock that exits after the second one.
+async fn dropped_stream(policy: &str) -> Result<CallToolResult> {
+    let service = common::inspector(&[("PARTIAL_STREAM_POLICY", policy)]).await?;
+    let args = json!({
+        "tool_name": "stream",
+        "arguments_json": {"chunks": 5, "drop_after": 2},
+        "stream": true,
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        }
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    service.cancel().await?;
+    Ok(result)
+}
+
+#[tokio::test]
+async fn return_partial_keeps_progress_from_a_dropped_stream() -> Result<()> {
+    let result = dropped_stream("return_partial").await?;
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("partial payload");
+    assert_eq!(payload["mode"], "stream");
+    assert_eq!(payload["partial"], true);
+    assert!(payload["final"].is_null());
+
+    let events = payload["events"].as_array().expect("stream events");
+    let chunks: Vec<_> = events
+        .iter()
+        .filter(|event| event["event"] == "chunk")
+        .map(|event| event["progress"].clone())
+        .collect();
+    assert_eq!(chunks, [json!(1.0), json!(2.0)]);
+    let last = events.last().expect("terminal event");
+    assert_eq!(last["event"], "error");
+    assert_eq!(last["error"], "STREAM_INTERRUPTED");
+
+    let meta = result.meta.expect("trace meta");
+    assert_eq!(meta["trace"]["stream_events"], payload["events"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn error_policy_fails_a_dropped_stream() -> Result<()> {
+    let result = dropped_stream("error").await?;
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("error payload");
+    assert!(payload.get("partial").is_none(), "{payload}");
+    assert!(payload["error"].is_string());
+    assert_eq!(
+        result.meta.expect("trace meta")["trace"]["event"]["state"],
+        "failed"
+    );
+    Ok(())
+}
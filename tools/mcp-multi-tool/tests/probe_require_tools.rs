@@ -0,0 +1,76 @@
+use std::{collections::BTreeMap, time::Instant};
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use mcp_multi_tool::{
+    app::inspector_service::InspectorService,
+    shared::types::{ProbeRequest, TargetTransportKind},
+};
+
+fn mock_probe(no_tools: bool, require_tools: bool) -> ProbeRequest {
+    let mut env = BTreeMap::from([
+        ("MOCK_SSE_ADDR".to_string(), "127.0.0.1:0".to_string()),
+        ("MOCK_HTTP_ADDR".to_string(), "127.0.0.1:0".to_string()),
+    ]);
+    if no_tools {
+        env.insert("MOCK_NO_TOOLS".into(), "1".into());
+    }
+    ProbeRequest {
+        transport: Some(TargetTransportKind::Stdio),
+        command: Some(cargo_bin("mock_mcp_server").display().to_string()),
+        env: Some(env),
+        require_tools,
+        ..ProbeRequest::default()
+    }
+}
+
+#[tokio::test]
+async fn require_tools_fails_a_probe_of_a_server_without_tools() -> Result<()> {
+    let svc = InspectorService::new();
+
+    let handshake_only = svc.probe(mock_probe(true, false)).await?;
+    assert!(handshake_only.ok, "{handshake_only:?}");
+
+    let empty = svc.probe(mock_probe(true, true)).await?;
+    assert!(!empty.ok, "{empty:?}");
+    assert!(
+        empty
+            .error
+            .as_deref()
+            .is_some_and(|error| error.contains("lists no tools")),
+        "{empty:?}"
+    );
+    assert!(
+        empty.latency_ms.is_some(),
+        "the handshake is still measured"
+    );
+
+    let populated = svc.probe(mock_probe(false, true)).await?;
+    assert!(populated.ok, "{populated:?}");
+    assert!(populated.error.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn require_tools_listing_is_bounded_by_the_handshake_timeout() -> Result<()> {
+    let svc = InspectorService::new();
+    let mut req = mock_probe(false, true);
+    req.handshake_timeout_ms = Some(2_000);
+    req.env
+        .as_mut()
+        .unwrap()
+        .insert("MOCK_LIST_TOOLS_DELAY_MS".into(), "30000".into());
+
+    let started = Instant::now();
+    let stalled = svc.probe(req).await?;
+    assert!(started.elapsed().as_secs() < 10, "{:?}", started.elapsed());
+    assert!(!stalled.ok, "{stalled:?}");
+    assert!(
+        stalled
+            .error
+            .as_deref()
+            .is_some_and(|error| error.contains("list tools timed out")),
+        "{stalled:?}"
+    );
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use anyhow::Result;
+use rmcp::model::CallToolRequestParam;
+
+mod common;
+
+#[tokio::test]
+async fn stats_report_process_and_store_figures() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_stats".into(),
+            arguments: None,
+        })
+        .await?;
+    assert!(
+        !result.is_error.unwrap_or(false),
+        "stats failed: {result:?}"
+    );
+    let stats = result.structured_content.expect("stats payload");
+    for key in [
+        "open_fds",
+        "resident_memory_bytes",
+        "tokio_alive_tasks",
+        "open_sessions",
+        "idempotency_records",
+    ] {
+        assert!(stats.get(key).is_some(), "missing {key}: {stats}");
+    }
+    assert_eq!(stats["open_sessions"], 0);
+    assert_eq!(stats["idempotency_records"], 0);
+    assert!(stats["tokio_alive_tasks"].is_u64());
+    if cfg!(target_os = "linux") {
+        assert!(stats["open_fds"].as_u64().unwrap() > 0);
+        assert!(stats["resident_memory_bytes"].as_u64().unwrap() > 0);
+    }
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+async fn stream_with_token(
+    service: &RunningService<RoleClient, ()>,
+    token: Value,
+) -> Result<CallToolResult> {
+    let args = json!({
+        "tool_name": "stream",
+        "arguments_json": {"chunks": 2},
+        "stream": true,
+        "progress_token": token,
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        }
+    });
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+fn chunk_tokens(result: &CallToolResult) -> Vec<Value> {
+    let payload = result.structured_content.as_ref().expect("stream payload");
+    payload["events"]
+        .as_array()
+        .expect("stream events")
+        .iter()
+        .filter(|event| event["event"] == "chunk")
+        .map(|event| event["progress_token"].clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn supplied_progress_token_reaches_the_target() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+
+    let result = stream_with_token(&service, json!("audit-run-42")).await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    assert_eq!(
+        chunk_tokens(&result),
+        [json!("audit-run-42"), json!("audit-run-42")]
+    );
+
+    let result = stream_with_token(&service, json!(7)).await?;
+    assert_eq!(chunk_tokens(&result), [json!(7), json!(7)]);
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn non_scalar_progress_token_is_rejected() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+    for token in [json!(1.5), json!({"id": 1}), json!("")] {
+        let result = stream_with_token(&service, token.clone()).await?;
+        assert!(result.is_error.unwrap_or(false), "accepted {token}");
+        let payload = result.structured_content.expect("error payload");
+        assert_eq!(payload["code"], "INVALID_PROGRESS_TOKEN", "{payload}");
+    }
+    service.cancel().await?;
+    Ok(())
+}
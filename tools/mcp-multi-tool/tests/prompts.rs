@@ -0,0 +1,200 @@
+use std::{
+    net::{TcpListener, TcpStream},
+    path::Path,
+    process::{Child, Command as StdCommand, Stdio},
+    thread,
+    time::Duration,
+};
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+struct MockGuard(Child);
+
+impl Drop for MockGuard {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Start the mock with only its SSE/HTTP transports; returns the SSE and HTTP endpoints.
+fn spawn_network_mock() -> Result<(MockGuard, String, String)> {
+    let reserve = || -> Result<u16> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        Ok(listener.local_addr()?.port())
+    };
+    let (sse_port, http_port) = (reserve()?, reserve()?);
+    let child = StdCommand::new(cargo_bin("mock_mcp_server"))
+        .env("MOCK_ENABLE_STDIO", "0")
+        .env("MOCK_SSE_ADDR", format!("127.0.0.1:{sse_port}"))
+        .env("MOCK_HTTP_ADDR", format!("127.0.0.1:{http_port}"))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let guard = MockGuard(child);
+    for port in [sse_port, http_port] {
+        for _ in 0..50 {
+            if TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+    Ok((
+        guard,
+        format!("http://127.0.0.1:{sse_port}/sse"),
+        format!("http://127.0.0.1:{http_port}/mcp"),
+    ))
+}
+
+fn stdio_target(command: String) -> Value {
+    json!({
+        "transport": "stdio",
+        "command": command,
+        "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+    })
+}
+
+async fn call(
+    service: &RunningService<RoleClient, ()>,
+    name: &str,
+    args: Value,
+) -> Result<CallToolResult> {
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+fn prompt_names(result: &CallToolResult) -> Vec<String> {
+    let payload = result.structured_content.as_ref().expect("prompt list");
+    payload["prompts"]
+        .as_array()
+        .expect("prompts array")
+        .iter()
+        .filter_map(|prompt| prompt["name"].as_str().map(str::to_string))
+        .collect()
+}
+
+fn outbox_events(outbox: &Path) -> Result<Vec<Value>> {
+    std::fs::read_to_string(outbox)?
+        .lines()
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+#[tokio::test]
+async fn prompts_are_listed_and_rendered_over_stdio_with_outbox_events() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let outbox = dir.path().join("events.jsonl");
+    let service = common::inspector(&[("OUTBOX_PATH", outbox.to_str().unwrap())]).await?;
+    let target = stdio_target(cargo_bin("mock_mcp_server").display().to_string());
+
+    let listed = call(&service, "inspector_list_prompts", target.clone()).await?;
+    assert!(!listed.is_error.unwrap_or(false), "list failed: {listed:?}");
+    // The mock serves one prompt per page.
+    assert_eq!(prompt_names(&listed), ["greet", "status"]);
+
+    let mut args = target;
+    args["prompt_name"] = json!("greet");
+    args["arguments"] = json!({"name": "Ada"});
+    let rendered = call(&service, "inspector_get_prompt", args).await?;
+    assert!(
+        !rendered.is_error.unwrap_or(false),
+        "get failed: {rendered:?}"
+    );
+    let payload = rendered.structured_content.expect("rendered prompt");
+    assert_eq!(payload["prompt_name"], "greet");
+    assert_eq!(payload["messages"][0]["role"], "user");
+    assert_eq!(
+        payload["messages"][0]["content"]["text"],
+        "Say hello to Ada."
+    );
+
+    service.cancel().await?;
+    let events = outbox_events(&outbox)?;
+    let tools: Vec<&str> = events
+        .iter()
+        .filter_map(|event| event["tool_name"].as_str())
+        .collect();
+    assert_eq!(tools, ["inspector_list_prompts", "inspector_get_prompt"]);
+    assert!(events.iter().all(|event| event["state"] == "captured"));
+    assert_eq!(events[1]["request"]["prompt_name"], "greet");
+    assert_eq!(events[1]["target"]["transport"], "stdio");
+    Ok(())
+}
+
+#[tokio::test]
+async fn prompts_work_over_http_and_sse() -> Result<()> {
+    let (_mock, sse_url, http_url) = spawn_network_mock()?;
+    let service = common::inspector(&[]).await?;
+
+    for (transport, url) in [("http", &http_url), ("sse", &sse_url)] {
+        let listed = call(
+            &service,
+            "inspector_list_prompts",
+            json!({"transport": transport, "url": url}),
+        )
+        .await?;
+        assert_eq!(prompt_names(&listed), ["greet", "status"], "{transport}");
+
+        let rendered = call(
+            &service,
+            "inspector_get_prompt",
+            json!({"transport": transport, "url": url, "prompt_name": "status"}),
+        )
+        .await?;
+        let payload = rendered.structured_content.expect("rendered prompt");
+        assert_eq!(
+            payload["messages"][0]["content"]["text"], "Report your current status.",
+            "{transport}"
+        );
+    }
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn prompt_failures_are_recorded_as_failed_events() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let outbox = dir.path().join("events.jsonl");
+    let service = common::inspector(&[("OUTBOX_PATH", outbox.to_str().unwrap())]).await?;
+    // The inspector itself does not advertise prompts.
+    let target = stdio_target(cargo_bin("mcp-multi-tool").display().to_string());
+
+    let listed = call(&service, "inspector_list_prompts", target).await?;
+    assert!(listed.is_error.unwrap_or(false));
+    let payload = listed.structured_content.expect("error payload");
+    assert_eq!(payload["code"], "CAPABILITY_NOT_SUPPORTED", "{payload}");
+
+    let mut args = stdio_target(cargo_bin("mock_mcp_server").display().to_string());
+    args["prompt_name"] = json!("greet");
+    let missing_argument = call(&service, "inspector_get_prompt", args).await?;
+    assert!(missing_argument.is_error.unwrap_or(false));
+
+    service.cancel().await?;
+    let events = outbox_events(&outbox)?;
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|event| event["state"] == "failed"));
+    assert!(
+        events[1]["error"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("prompts/get"),
+        "{}",
+        events[1]
+    );
+    Ok(())
+}
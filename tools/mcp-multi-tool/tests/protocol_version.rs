@@ -0,0 +1,61 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::json;
+
+mod common;
+
+async fn echo(
+    service: &RunningService<RoleClient, ()>,
+    advertised: Option<&str>,
+) -> Result<CallToolResult> {
+    let mut env = json!({"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"});
+    if let Some(version) = advertised {
+        env["MOCK_PROTOCOL_VERSION"] = json!(version);
+    }
+    let args = json!({
+        "tool_name": "echo",
+        "arguments_json": {"text": "hi"},
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": env
+        }
+    });
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+#[tokio::test]
+async fn target_below_the_minimum_version_is_refused() -> Result<()> {
+    let service = common::inspector(&[("MIN_PROTOCOL_VERSION", "2025-03-26")]).await?;
+
+    let result = echo(&service, Some("2024-11-05")).await?;
+    assert!(result.is_error.unwrap_or(false), "{result:?}");
+    let payload = result.structured_content.expect("error payload");
+    assert_eq!(payload["code"], "UNSUPPORTED_PROTOCOL");
+    let message = payload["error"].as_str().expect("message");
+    assert!(message.contains("2024-11-05"), "{message}");
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn target_at_a_supported_version_is_called() -> Result<()> {
+    let service = common::inspector(&[("MIN_PROTOCOL_VERSION", "2025-03-26")]).await?;
+
+    let result = echo(&service, None).await?;
+    assert!(!result.is_error.unwrap_or(false), "{result:?}");
+    assert_eq!(result.structured_content.expect("echo")["echoed"], "hi");
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -1,23 +1,12 @@
 use anyhow::Result;
-use assert_cmd::cargo::cargo_bin;
-use rmcp::{
-    ServiceExt,
-    model::CallToolRequestParam,
-    transport::child_process::{ConfigureCommandExt, TokioChildProcess},
-};
+use rmcp::model::CallToolRequestParam;
 use serde_json::{Map, Value};
-use tokio::process::Command;
+
+mod common;
 
 #[tokio::test]
 async fn rollback_disables_inspector_tools() -> Result<()> {
-    let bin = cargo_bin("mcp-multi-tool");
-    let service = ()
-        .serve(TokioChildProcess::new(Command::new(&bin).configure(
-            |cmd| {
-                cmd.env("RELEASE_TRACK", "rollback");
-            },
-        ))?)
-        .await?;
+    let service = common::inspector(&[("RELEASE_TRACK", "rollback")]).await?;
 
     let listed = service.list_tools(Default::default()).await?.tools;
     assert_eq!(listed.len(), 1, "rollback should expose only help tool");
@@ -46,3 +35,27 @@ async fn rollback_disables_inspector_tools() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn rollback_reports_live_but_not_ready() -> Result<()> {
+    let service = common::inspector(&[("RELEASE_TRACK", "rollback")]).await?;
+
+    let response = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_health".into(),
+            arguments: None,
+        })
+        .await?;
+    assert!(!response.is_error.unwrap_or(false));
+    let payload = response.structured_content.expect("health payload");
+    assert_eq!(payload.get("live").and_then(Value::as_bool), Some(true));
+    assert_eq!(payload.get("ready").and_then(Value::as_bool), Some(false));
+    assert_eq!(
+        payload
+            .pointer("/checks/release_track/ok")
+            .and_then(Value::as_bool),
+        Some(false)
+    );
+
+    Ok(())
+}
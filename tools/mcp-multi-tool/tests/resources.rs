@@ -0,0 +1,110 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{RoleClient, model::CallToolRequestParam, service::RunningService};
+use serde_json::{Value, json};
+
+mod common;
+
+fn stdio_target(command: String) -> Value {
+    json!({
+        "transport": "stdio",
+        "command": command,
+        "args": [],
+        "env": {
+            "MOCK_SSE_ADDR": "127.0.0.1:0",
+            "MOCK_HTTP_ADDR": "127.0.0.1:0"
+        }
+    })
+}
+
+fn mock_target() -> Value {
+    stdio_target(cargo_bin("mock_mcp_server").display().to_string())
+}
+
+async fn call(
+    service: &RunningService<RoleClient, ()>,
+    name: &str,
+    args: Value,
+) -> Result<rmcp::model::CallToolResult> {
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: args.as_object().cloned(),
+        })
+        .await?)
+}
+
+#[tokio::test]
+async fn list_resources_follows_every_page() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+    let result = call(&service, "inspector_list_resources", mock_target()).await?;
+    assert!(!result.is_error.unwrap_or(false), "list failed: {result:?}");
+    let payload = result.structured_content.expect("resource list");
+    // The mock serves two resources per page.
+    assert_eq!(payload["count"], 3);
+    let uris: Vec<&str> = payload["resources"]
+        .as_array()
+        .expect("resources array")
+        .iter()
+        .filter_map(|resource| resource["uri"].as_str())
+        .collect();
+    assert_eq!(
+        uris,
+        [
+            "mock://notes/readme",
+            "mock://notes/changelog",
+            "mock://bin/logo"
+        ]
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn read_resource_distinguishes_text_and_blob() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+
+    let mut args = mock_target();
+    args["uri"] = json!("mock://notes/readme");
+    let text = call(&service, "inspector_read_resource", args).await?;
+    let payload = text.structured_content.expect("text contents");
+    assert_eq!(payload["contents"][0]["kind"], "text");
+    assert_eq!(payload["contents"][0]["text"], "mock readme");
+    assert!(payload["contents"][0].get("blob").is_none());
+
+    let mut args = mock_target();
+    args["uri"] = json!("mock://bin/logo");
+    let blob = call(&service, "inspector_read_resource", args).await?;
+    let payload = blob.structured_content.expect("blob contents");
+    assert_eq!(payload["contents"][0]["kind"], "blob");
+    assert_eq!(payload["contents"][0]["blob"], "AAECAw==");
+    assert_eq!(
+        payload["contents"][0]["mime_type"],
+        "application/octet-stream"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn resources_on_target_without_capability_report_code() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+    let mut target = mock_target();
+    target["env"]["MOCK_DISABLE_RESOURCES"] = json!("1");
+
+    let listed = call(&service, "inspector_list_resources", target.clone()).await?;
+    assert!(listed.is_error.unwrap_or(false));
+    let payload = listed.structured_content.expect("error payload");
+    assert_eq!(payload["code"], "CAPABILITY_NOT_SUPPORTED", "{payload}");
+
+    let mut args = target;
+    args["uri"] = json!("mock://notes/readme");
+    let read = call(&service, "inspector_read_resource", args).await?;
+    let payload = read.structured_content.expect("error payload");
+    assert_eq!(payload["code"], "CAPABILITY_NOT_SUPPORTED", "{payload}");
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,256 @@
+use std::{
+    net::{TcpListener as StdTcpListener, TcpStream as StdTcpStream},
+    process::{Child, Command as StdCommand, Stdio},
+    thread,
+    time::Duration,
+};
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+use tokio::net::{TcpListener, TcpStream};
+
+mod common;
+
+/// An error budget that freezes on the first failure it records.
+const STRICT_BUDGET: &[(&str, &str)] = &[
+    ("ERROR_BUDGET_ENABLED", "true"),
+    ("ERROR_BUDGET_SUCCESS_THRESHOLD", "1.0"),
+    ("ERROR_BUDGET_MIN_REQUESTS", "1"),
+    ("CONNECTION_CACHE_MAX_SIZE", "0"),
+];
+
+struct MockGuard(Child);
+
+impl Drop for MockGuard {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Start the mock with only its SSE transport; returns its port.
+fn spawn_sse_mock() -> Result<(MockGuard, u16)> {
+    let reserve =
+        || -> Result<u16> { Ok(StdTcpListener::bind("127.0.0.1:0")?.local_addr()?.port()) };
+    let (sse_port, http_port) = (reserve()?, reserve()?);
+    let child = StdCommand::new(cargo_bin("mock_mcp_server"))
+        .env("MOCK_ENABLE_STDIO", "0")
+        .env("MOCK_SSE_ADDR", format!("127.0.0.1:{sse_port}"))
+        .env("MOCK_HTTP_ADDR", format!("127.0.0.1:{http_port}"))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let guard = MockGuard(child);
+    for _ in 0..50 {
+        if StdTcpStream::connect(("127.0.0.1", sse_port)).is_ok() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    Ok((guard, sse_port))
+}
+
+/// A gateway in front of `upstream` that drops the first connection it accepts and
+/// forwards every later one; returns its SSE URL.
+async fn flaky_gateway(upstream: u16) -> Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let port = listener.local_addr()?.port();
+    tokio::spawn(async move {
+        let Ok((first, _)) = listener.accept().await else {
+            return;
+        };
+        drop(first);
+        while let Ok((mut inbound, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                if let Ok(mut outbound) = TcpStream::connect(("127.0.0.1", upstream)).await {
+                    let _ = tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await;
+                }
+            });
+        }
+    });
+    Ok(format!("http://127.0.0.1:{port}/sse"))
+}
+
+async fn call(
+    service: &RunningService<RoleClient, ()>,
+    name: &str,
+    args: Value,
+) -> Result<CallToolResult> {
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+fn attempts(result: &CallToolResult) -> Vec<Value> {
+    result
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.get("trace"))
+        .and_then(|trace| trace["attempts"].as_array().cloned())
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn dropped_first_connection_is_retried_and_counts_once() -> Result<()> {
+    let (_mock, sse_port) = spawn_sse_mock()?;
+    let url = flaky_gateway(sse_port).await?;
+    let service = common::inspector(STRICT_BUDGET).await?;
+
+    let result = call(
+        &service,
+        "inspector_call",
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {"text": "second time"},
+            "sse": {"url": url},
+            "retry": {"max_attempts": 3, "initial_backoff_ms": 10}
+        }),
+    )
+    .await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    let attempts = attempts(&result);
+    assert_eq!(attempts.len(), 2, "{attempts:?}");
+    assert_eq!(attempts[0]["succeeded"], false);
+    assert_eq!(attempts[0]["failure"], "connect");
+    assert!(attempts[0]["error"].is_string());
+    assert_eq!(attempts[1]["succeeded"], true);
+
+    // The failed attempt did not count: a budget that freezes on one failure stays open.
+    let again = call(
+        &service,
+        "inspector_call",
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {"text": "still open"},
+            "sse": {"url": url}
+        }),
+    )
+    .await?;
+    assert!(!again.is_error.unwrap_or(false), "call failed: {again:?}");
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn idempotent_call_retries_the_whole_attempt() -> Result<()> {
+    let (_mock, sse_port) = spawn_sse_mock()?;
+    let url = flaky_gateway(sse_port).await?;
+    let service = common::inspector(STRICT_BUDGET).await?;
+
+    let result = call(
+        &service,
+        "inspector_call",
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {"text": "once"},
+            "idempotency_key": "retry-policy-whole-attempt",
+            "sse": {"url": url},
+            "retry": {"initial_backoff_ms": 10}
+        }),
+    )
+    .await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    let payload = result.structured_content.as_ref().expect("echo payload");
+    assert_eq!(payload["echoed"], "once");
+    let attempts = attempts(&result);
+    assert_eq!(attempts.len(), 2, "{attempts:?}");
+    assert_eq!(attempts[0]["failure"], "connect");
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn failures_outside_retry_on_are_not_retried() -> Result<()> {
+    let (_mock, sse_port) = spawn_sse_mock()?;
+    let url = flaky_gateway(sse_port).await?;
+    let service = common::inspector(STRICT_BUDGET).await?;
+
+    let result = call(
+        &service,
+        "inspector_call",
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {"text": "never"},
+            "sse": {"url": url},
+            "retry": {"initial_backoff_ms": 10, "retry_on": ["timeout"]}
+        }),
+    )
+    .await?;
+    assert!(
+        result.is_error.unwrap_or(false),
+        "dropped connection fails the call"
+    );
+    assert!(attempts(&result).is_empty(), "{result:?}");
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn missing_commands_are_not_retried() -> Result<()> {
+    let service = common::inspector(STRICT_BUDGET).await?;
+
+    let result = call(
+        &service,
+        "inspector_call",
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {"text": "never"},
+            "stdio": {"command": "definitely-not-a-binary"},
+            "retry": {"max_attempts": 3, "initial_backoff_ms": 10}
+        }),
+    )
+    .await?;
+    assert!(result.is_error.unwrap_or(false), "{result:?}");
+    assert!(attempts(&result).is_empty(), "{result:?}");
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_retries_a_target_that_fails_its_first_handshake() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let counter = dir.path().join("launches");
+    let service =
+        common::inspector(&[STRICT_BUDGET, &[("ALLOW_SHELL_TARGETS", "true")]].concat()).await?;
+
+    // Exits before the handshake on the first launch, then serves normally.
+    let flaky = format!(
+        "n=$(cat \"$LAUNCH_COUNTER\" 2>/dev/null || echo 0); echo $((n + 1)) > \"$LAUNCH_COUNTER\"; \
+         [ \"$n\" -ge 1 ] && exec '{}'",
+        cargo_bin("mock_mcp_server").display()
+    );
+    let result = call(
+        &service,
+        "inspector_list_tools",
+        json!({
+            "transport": "stdio",
+            "command": flaky,
+            "shell": true,
+            "env": {
+                "LAUNCH_COUNTER": counter.display().to_string(),
+                "MOCK_SSE_ADDR": "127.0.0.1:0",
+                "MOCK_HTTP_ADDR": "127.0.0.1:0"
+            },
+            "retry": {"initial_backoff_ms": 10}
+        }),
+    )
+    .await?;
+    assert!(!result.is_error.unwrap_or(false), "list failed: {result:?}");
+    assert_eq!(std::fs::read_to_string(&counter)?.trim(), "2");
+
+    service.cancel().await?;
+    Ok(())
+}
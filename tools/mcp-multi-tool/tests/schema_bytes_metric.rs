@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use mcp_multi_tool::{
+    app::inspector_service::InspectorService,
+    infra::metrics::DOWNSTREAM_TOOL_SCHEMA_BYTES,
+    shared::types::{DescribeRequest, ProbeRequest, TargetTransportKind},
+};
+
+fn mock_probe(drift: bool) -> ProbeRequest {
+    let mut env = BTreeMap::from([
+        ("MOCK_SSE_ADDR".to_string(), "127.0.0.1:0".to_string()),
+        ("MOCK_HTTP_ADDR".to_string(), "127.0.0.1:0".to_string()),
+    ]);
+    if drift {
+        env.insert("MOCK_TOOL_DRIFT".to_string(), "1".to_string());
+    }
+    ProbeRequest {
+        transport: Some(TargetTransportKind::Stdio),
+        command: Some(cargo_bin("mock_mcp_server").display().to_string()),
+        env: Some(env),
+        ..ProbeRequest::default()
+    }
+}
+
+#[tokio::test]
+async fn listing_tools_updates_the_schema_bytes_gauge() -> Result<()> {
+    let svc = InspectorService::new();
+    let label = format!("stdio:{}", cargo_bin("mock_mcp_server").display());
+    let gauge = || {
+        DOWNSTREAM_TOOL_SCHEMA_BYTES
+            .with_label_values(&[&label])
+            .get()
+    };
+
+    let tools = svc.list_tools(mock_probe(false)).await?;
+    let expected: usize = tools
+        .iter()
+        .map(|tool| {
+            serde_json::to_vec(tool.input_schema.as_ref()).map_or(0, |encoded| encoded.len())
+                + tool.output_schema.as_ref().map_or(0, |schema| {
+                    serde_json::to_vec(schema.as_ref()).map_or(0, |encoded| encoded.len())
+                })
+        })
+        .sum();
+    assert!(expected > 0);
+    assert_eq!(gauge(), expected as i64);
+
+    // The drifted catalog adds a tool and a property, so the same target reports more.
+    svc.describe(DescribeRequest {
+        tool_name: "add".into(),
+        probe: mock_probe(true),
+    })
+    .await?;
+    assert!(gauge() > expected as i64, "gauge = {}", gauge());
+    Ok(())
+}
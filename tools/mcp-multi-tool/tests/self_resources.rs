@@ -0,0 +1,62 @@
+use anyhow::Result;
+use rmcp::{
+    RoleClient,
+    model::{ReadResourceRequestParam, ResourceContents},
+    service::RunningService,
+};
+use serde_json::Value;
+
+mod common;
+
+async fn read_text(service: &RunningService<RoleClient, ()>, uri: &str) -> Result<String> {
+    let result = service
+        .read_resource(ReadResourceRequestParam { uri: uri.into() })
+        .await?;
+    match result.contents.into_iter().next() {
+        Some(ResourceContents::TextResourceContents { text, .. }) => Ok(text),
+        other => anyhow::bail!("expected text contents for {uri}, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn tool_catalog_is_readable_as_a_resource() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+    let advertises_resources = service
+        .peer_info()
+        .is_some_and(|info| info.capabilities.resources.is_some());
+    assert!(advertises_resources);
+
+    let uris: Vec<String> = service
+        .list_all_resources()
+        .await?
+        .into_iter()
+        .map(|resource| resource.raw.uri)
+        .collect();
+    assert_eq!(uris, ["inspector://tools", "inspector://help"]);
+
+    let catalog: Value = serde_json::from_str(&read_text(&service, "inspector://tools").await?)?;
+    let names: Vec<&str> = catalog
+        .as_array()
+        .expect("tool array")
+        .iter()
+        .filter_map(|tool| tool["name"].as_str())
+        .collect();
+    let listed = service.list_all_tools().await?;
+    assert_eq!(names.len(), listed.len());
+    assert!(names.contains(&"inspector_probe"));
+    assert!(names.contains(&"inspector_call"));
+
+    let help = read_text(&service, "inspector://help").await?;
+    let first: Value = serde_json::from_str(help.lines().next().expect("help line"))?;
+    assert_eq!(first["section"], "summary");
+
+    let missing = service
+        .read_resource(ReadResourceRequestParam {
+            uri: "inspector://nope".into(),
+        })
+        .await;
+    assert!(missing.is_err());
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::model::CallToolRequestParam;
+use serde_json::{Value, json};
+
+mod common;
+
+/// Shell targets are refused unless the operator opts in.
+const SHELL_ALLOWED: &[(&str, &str)] = &[("ALLOW_SHELL_TARGETS", "true")];
+
+/// The mock's stdout piped through `cat`: only a shell can run this.
+fn piped_mock() -> String {
+    format!(
+        "'{}' 2>/dev/null | cat",
+        cargo_bin("mock_mcp_server").display()
+    )
+}
+
+fn mock_env() -> Value {
+    json!({
+        "MOCK_SSE_ADDR": "127.0.0.1:0",
+        "MOCK_HTTP_ADDR": "127.0.0.1:0"
+    })
+}
+
+#[tokio::test]
+async fn probe_runs_piped_command_in_shell_mode() -> Result<()> {
+    let service = common::inspector(SHELL_ALLOWED).await?;
+    let args = json!({
+        "transport": "stdio",
+        "command": piped_mock(),
+        "shell": true,
+        "env": mock_env(),
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_probe".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    let payload = result.structured_content.expect("probe payload");
+    assert_eq!(payload["ok"], true, "probe failed: {payload}");
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn call_runs_piped_command_in_shell_mode() -> Result<()> {
+    let service = common::inspector(SHELL_ALLOWED).await?;
+    let args = json!({
+        "tool_name": "echo",
+        "arguments_json": {"text": "through a pipe"},
+        "stdio": {
+            "command": piped_mock(),
+            "shell": true,
+            "env": mock_env(),
+        }
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    let payload = result.structured_content.expect("call payload");
+    assert_eq!(payload["echoed"], "through a pipe");
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn shell_mode_rejects_separate_args() -> Result<()> {
+    let service = common::inspector(SHELL_ALLOWED).await?;
+    let args = json!({
+        "tool_name": "echo",
+        "arguments_json": {},
+        "stdio": {
+            "command": piped_mock(),
+            "args": ["--extra"],
+            "shell": true,
+        }
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("error payload");
+    assert!(
+        payload["error"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("shell mode"),
+        "unexpected error: {payload}"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn shell_mode_is_refused_unless_allowed() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+    let probe = json!({
+        "transport": "stdio",
+        "command": piped_mock(),
+        "shell": true,
+        "env": mock_env(),
+    });
+    let call = json!({
+        "tool_name": "echo",
+        "arguments_json": {"text": "through a pipe"},
+        "stdio": {
+            "command": piped_mock(),
+            "shell": true,
+            "env": mock_env(),
+        }
+    });
+    for (tool, args) in [("inspector_probe", probe), ("inspector_call", call)] {
+        let result = service
+            .call_tool(CallToolRequestParam {
+                name: tool.into(),
+                arguments: Some(args.as_object().cloned().unwrap()),
+            })
+            .await?;
+        assert!(result.is_error.unwrap_or(false), "{tool}: {result:?}");
+        let payload = result.structured_content.expect("error payload");
+        assert_eq!(
+            payload["code"], "SHELL_TARGETS_DISABLED",
+            "{tool}: {payload}"
+        );
+    }
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::model::CallToolRequestParam;
+use serde_json::json;
+
+mod common;
+
+#[tokio::test]
+async fn a_spawn_slower_than_its_bound_fails_with_spawn_timeout() -> Result<()> {
+    // No child can start within a zero bound, so every spawn is too slow for it.
+    let service = common::inspector(&[("STDIO_SPAWN_TIMEOUT_MS", "0")]).await?;
+
+    let args = json!({
+        "tool_name": "echo",
+        "arguments_json": {"text": "never sent"},
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        }
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: args.as_object().cloned(),
+        })
+        .await?;
+    assert!(result.is_error.unwrap_or(false), "{result:?}");
+    let payload = result.structured_content.expect("error payload");
+    assert_eq!(payload["code"], "SPAWN_TIMEOUT", "{payload}");
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use std::{
+    net::{TcpListener, TcpStream},
+    process::{Child, Command as StdCommand, Stdio},
+    thread,
+    time::Duration,
+};
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+struct MockGuard(Child);
+
+impl Drop for MockGuard {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Start the mock with only its SSE/HTTP transports, requiring `x-tenant: acme` on SSE.
+fn spawn_gated_mock() -> Result<(MockGuard, String)> {
+    let reserve = || -> Result<u16> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        Ok(listener.local_addr()?.port())
+    };
+    let (sse_port, http_port) = (reserve()?, reserve()?);
+    let child = StdCommand::new(cargo_bin("mock_mcp_server"))
+        .env("MOCK_ENABLE_STDIO", "0")
+        .env("MOCK_SSE_ADDR", format!("127.0.0.1:{sse_port}"))
+        .env("MOCK_HTTP_ADDR", format!("127.0.0.1:{http_port}"))
+        .env("MOCK_SSE_REQUIRE_HEADER", "x-tenant=acme")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let guard = MockGuard(child);
+    for _ in 0..50 {
+        if TcpStream::connect(("127.0.0.1", sse_port)).is_ok() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    Ok((guard, format!("http://127.0.0.1:{sse_port}/sse")))
+}
+
+async fn call_echo(
+    service: &RunningService<RoleClient, ()>,
+    url: &str,
+    headers: Option<Value>,
+) -> Result<CallToolResult> {
+    let mut sse = json!({ "url": url });
+    if let Some(headers) = headers {
+        sse["headers"] = headers;
+    }
+    let args = json!({
+        "tool_name": "echo",
+        "arguments_json": {"text": "through the gateway"},
+        "sse": sse
+    });
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+#[tokio::test]
+async fn sse_headers_reach_gated_target() -> Result<()> {
+    let (_mock, url) = spawn_gated_mock()?;
+    let service = common::inspector(&[]).await?;
+
+    let rejected = call_echo(&service, &url, None).await?;
+    assert!(
+        rejected.is_error.unwrap_or(false),
+        "unauthenticated SSE call should fail: {rejected:?}"
+    );
+
+    // The gate covers message POSTs too, so a successful echo proves both carry the header.
+    let headers = json!({"authorization": "Bearer s3cret", "x-tenant": "acme"});
+    let accepted = call_echo(&service, &url, Some(headers.clone())).await?;
+    assert!(
+        !accepted.is_error.unwrap_or(false),
+        "call failed: {accepted:?}"
+    );
+    assert_eq!(
+        accepted.structured_content.expect("echo payload")["echoed"],
+        "through the gateway"
+    );
+
+    let probe = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_probe".into(),
+            arguments: Some(
+                json!({"transport": "sse", "url": url, "headers": headers})
+                    .as_object()
+                    .cloned()
+                    .unwrap(),
+            ),
+        })
+        .await?;
+    assert_eq!(probe.structured_content.expect("probe result")["ok"], true);
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn unsendable_headers_fail_with_structured_error() -> Result<()> {
+    let (_mock, url) = spawn_gated_mock()?;
+    let service = common::inspector(&[]).await?;
+
+    let result = call_echo(&service, &url, Some(json!({"x tenant": "acme"}))).await?;
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("error payload");
+    assert_eq!(payload["code"], "INVALID_HEADERS");
+    assert!(
+        payload["error"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("x tenant"),
+        "unexpected error: {payload}"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
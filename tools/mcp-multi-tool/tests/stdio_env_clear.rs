@@ -0,0 +1,98 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{RoleClient, model::CallToolRequestParam, service::RunningService};
+use serde_json::{Value, json};
+
+mod common;
+
+const CANARY: &str = "INSPECTOR_ENV_CANARY";
+
+/// Value of `name` in the target's environment, with `options` merged into the stdio target.
+async fn seen_by_target(
+    service: &RunningService<RoleClient, ()>,
+    name: &str,
+    options: Value,
+) -> Result<Value> {
+    let mut stdio = json!({
+        "command": cargo_bin("mock_mcp_server").display().to_string(),
+        "env": {
+            "MOCK_SSE_ADDR": "127.0.0.1:0",
+            "MOCK_HTTP_ADDR": "127.0.0.1:0"
+        }
+    });
+    for (key, value) in options.as_object().cloned().unwrap_or_default() {
+        stdio[key] = value;
+    }
+    let args = json!({
+        "tool_name": "getenv",
+        "arguments_json": {"name": name},
+        "stdio": stdio
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    let payload = result.structured_content.expect("call payload");
+    Ok(payload["value"].clone())
+}
+
+#[tokio::test]
+async fn stdio_target_inherits_inspector_env_by_default() -> Result<()> {
+    let service =
+        common::inspector(&[("STDIO_ENV_CLEAR", "false"), (CANARY, "inspector-secret")]).await?;
+    assert_eq!(
+        seen_by_target(&service, CANARY, json!({})).await?,
+        "inspector-secret"
+    );
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn env_clear_keeps_inspector_env_from_target() -> Result<()> {
+    let service =
+        common::inspector(&[("STDIO_ENV_CLEAR", "false"), (CANARY, "inspector-secret")]).await?;
+    let cleared = json!({ "env_clear": true });
+    assert_eq!(
+        seen_by_target(&service, CANARY, cleared.clone()).await?,
+        Value::Null
+    );
+    assert_eq!(
+        seen_by_target(&service, "PATH", cleared).await?,
+        Value::Null
+    );
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn inherit_basic_passes_path_but_not_secrets() -> Result<()> {
+    let service =
+        common::inspector(&[("STDIO_ENV_CLEAR", "false"), (CANARY, "inspector-secret")]).await?;
+    let cleared = json!({ "env_clear": true, "inherit_basic": true });
+    assert_eq!(
+        seen_by_target(&service, CANARY, cleared.clone()).await?,
+        Value::Null
+    );
+    assert_eq!(
+        seen_by_target(&service, "PATH", cleared).await?,
+        json!(std::env::var("PATH")?)
+    );
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn config_default_clears_env_for_every_target() -> Result<()> {
+    let service =
+        common::inspector(&[("STDIO_ENV_CLEAR", "true"), (CANARY, "inspector-secret")]).await?;
+    assert_eq!(
+        seen_by_target(&service, CANARY, json!({ "env_clear": false })).await?,
+        Value::Null
+    );
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use mcp_multi_tool::{
+    app::inspector_service::{HandshakeTimedOut, InspectorService},
+    shared::types::{ProbeRequest, TargetTransportKind},
+};
+
+/// A stdio target that starts but never answers `initialize`.
+fn silent_target() -> ProbeRequest {
+    ProbeRequest {
+        transport: Some(TargetTransportKind::Stdio),
+        command: Some("sleep".into()),
+        args: Some(vec!["30".into()]),
+        handshake_timeout_ms: Some(300),
+        ..ProbeRequest::default()
+    }
+}
+
+fn assert_timed_out_quickly(result: Result<impl std::fmt::Debug>, started: Instant) {
+    let err = result.expect_err("handshake should time out");
+    assert!(
+        err.chain().any(|cause| cause.is::<HandshakeTimedOut>()),
+        "{err:#}"
+    );
+    assert!(
+        started.elapsed() < Duration::from_secs(5),
+        "took {:?}",
+        started.elapsed()
+    );
+}
+
+#[tokio::test]
+async fn stdio_list_tools_honours_the_request_handshake_timeout() -> Result<()> {
+    let svc = InspectorService::new();
+    let started = Instant::now();
+    assert_timed_out_quickly(svc.list_tools(silent_target()).await, started);
+    Ok(())
+}
+
+#[tokio::test]
+async fn stdio_connect_honours_the_request_handshake_timeout() -> Result<()> {
+    let svc = InspectorService::new();
+    let started = Instant::now();
+    assert_timed_out_quickly(svc.connect_session(silent_target()).await, started);
+    Ok(())
+}
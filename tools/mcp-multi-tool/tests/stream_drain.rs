@@ -0,0 +1,85 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+use std::time::{Duration, Instant};
+
+mod common;
+
+/// Stream three chunks, the last one 100ms after the final result.
+async fn call_with_trailing_chunk(
+    service: &RunningService<RoleClient, ()>,
+    drain_timeout_ms: u64,
+) -> Result<CallToolResult> {
+    let args = json!({
+        "tool_name": "stream",
+        "arguments_json": {"chunks": 3, "trailing_delay_ms": 100},
+        "stream": true,
+        "stream_drain_timeout_ms": drain_timeout_ms,
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        }
+    });
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+fn chunk_progress(result: &CallToolResult) -> Vec<Value> {
+    let payload = result.structured_content.as_ref().expect("stream payload");
+    payload["events"]
+        .as_array()
+        .expect("stream events")
+        .iter()
+        .filter(|event| event["event"] == "chunk")
+        .map(|event| event["progress"].clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn trailing_chunk_is_captured_and_ends_the_drain() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+
+    let started = Instant::now();
+    let result = call_with_trailing_chunk(&service, 5_000).await?;
+    // The terminal chunk stops the drain long before the timeout would.
+    assert!(started.elapsed() < Duration::from_secs(4));
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    assert_eq!(
+        chunk_progress(&result),
+        vec![json!(1.0), json!(2.0), json!(3.0)]
+    );
+    let trace = &result.meta.as_ref().expect("trace meta")["trace"];
+    assert_eq!(
+        trace["stream_drain"],
+        json!({"captured": 3, "dropped": 0, "terminal_seen": true})
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn short_drain_reports_the_dropped_chunk() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+
+    let result = call_with_trailing_chunk(&service, 20).await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    assert_eq!(chunk_progress(&result), vec![json!(1.0), json!(2.0)]);
+    let trace = &result.meta.as_ref().expect("trace meta")["trace"];
+    assert_eq!(
+        trace["stream_drain"],
+        json!({"captured": 2, "dropped": 1, "terminal_seen": false})
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::Peer,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+fn call_args(tool: &str, arguments: Value, stream: bool) -> Value {
+    json!({
+        "tool_name": tool,
+        "arguments_json": arguments,
+        "stream": stream,
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        }
+    })
+}
+
+async fn call(peer: Peer<RoleClient>, args: Value) -> Result<CallToolResult> {
+    Ok(peer
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+#[tokio::test]
+async fn streams_over_the_limit_fail_with_stream_limit() -> Result<()> {
+    let service = common::inspector(&[("MAX_CONCURRENT_STREAMS", "1")]).await?;
+    let peer = service.peer().clone();
+
+    // Forty chunks at 25 ms apiece keep the only slot busy for about a second.
+    let long_stream = tokio::spawn(call(
+        peer.clone(),
+        call_args("stream", json!({"chunks": 40}), true),
+    ));
+    tokio::time::sleep(Duration::from_millis(400)).await;
+
+    let rejected = call(
+        peer.clone(),
+        call_args("stream", json!({"chunks": 1}), true),
+    )
+    .await?;
+    assert!(rejected.is_error.unwrap_or(false));
+    let payload = rejected.structured_content.expect("error payload");
+    assert_eq!(payload["code"], "STREAM_LIMIT", "{payload}");
+
+    let plain = call(
+        peer.clone(),
+        call_args("echo", json!({"text": "not a stream"}), false),
+    )
+    .await?;
+    assert!(
+        !plain.is_error.unwrap_or(false),
+        "non-stream call refused: {plain:?}"
+    );
+
+    let first = long_stream.await??;
+    assert!(
+        !first.is_error.unwrap_or(false),
+        "long stream failed: {first:?}"
+    );
+
+    // The slot is free again once the long stream completes.
+    let after = call(peer, call_args("stream", json!({"chunks": 1}), true)).await?;
+    assert!(
+        !after.is_error.unwrap_or(false),
+        "slot not released: {after:?}"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
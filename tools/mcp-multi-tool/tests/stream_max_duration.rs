@@ -0,0 +1,48 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::model::CallToolRequestParam;
+use serde_json::json;
+use std::time::{Duration, Instant};
+
+mod common;
+
+#[tokio::test]
+async fn endless_stream_is_cut_off_at_max_duration() -> Result<()> {
+    let service = common::inspector(&[]).await?;
+    let args = json!({
+        "tool_name": "stream",
+        "arguments_json": {"forever": true},
+        "stream": true,
+        "stream_max_duration_ms": 1500,
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        }
+    });
+    let started = Instant::now();
+    let result = tokio::time::timeout(
+        Duration::from_secs(30),
+        service.call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        }),
+    )
+    .await
+    .expect("call must not run forever")?;
+    assert!(started.elapsed() < Duration::from_secs(10));
+
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("error payload");
+    assert_eq!(payload["code"], "STREAM_MAX_DURATION");
+
+    let meta = result.meta.expect("trace meta");
+    assert_eq!(meta["trace"]["event"]["state"], "failed");
+    let events = meta["trace"]["stream_events"]
+        .as_array()
+        .expect("stream events");
+    assert!(events.iter().any(|event| event["event"] == "chunk"));
+    let last = events.last().expect("terminal event");
+    assert_eq!(last["event"], "error");
+    assert_eq!(last["error"], "STREAM_MAX_DURATION");
+    Ok(())
+}
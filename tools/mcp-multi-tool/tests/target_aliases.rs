@@ -0,0 +1,178 @@
+use std::path::Path;
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+fn mock_command() -> String {
+    cargo_bin("mock_mcp_server").display().to_string()
+}
+
+/// `mock` reaches the mock server; `stale` names a missing binary but carries the env the
+/// mock needs; `billing-staging` holds credentials `help` must not reveal.
+fn write_targets(dir: &Path) -> Result<()> {
+    let targets = format!(
+        r#"
+[targets.mock.stdio]
+command = {mock:?}
+env = {{ MOCK_SSE_ADDR = "127.0.0.1:0", MOCK_HTTP_ADDR = "127.0.0.1:0" }}
+
+[targets.stale.stdio]
+command = "/nonexistent/mock_mcp_server"
+env = {{ MOCK_SSE_ADDR = "127.0.0.1:0", MOCK_HTTP_ADDR = "127.0.0.1:0" }}
+
+[targets.billing-staging.http]
+url = "https://billing.staging.example/mcp"
+auth_token = "alias-secret-token"
+headers = {{ Authorization = "Bearer alias-secret-header", x-team = "billing" }}
+"#,
+        mock = mock_command()
+    );
+    std::fs::write(dir.join("targets.toml"), targets)?;
+    Ok(())
+}
+
+async fn call(
+    service: &RunningService<RoleClient, ()>,
+    name: &str,
+    args: Value,
+) -> Result<CallToolResult> {
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+fn payload(result: CallToolResult) -> Value {
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    result.structured_content.expect("payload")
+}
+
+#[tokio::test]
+async fn aliases_resolve_with_request_fields_taking_precedence() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    write_targets(dir.path())?;
+    let service = common::inspector(&[("APP_CONFIG_DIR", dir.path().to_str().unwrap())]).await?;
+
+    let echoed = call(
+        &service,
+        "inspector_call",
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {"text": "via alias"},
+            "target_alias": "mock"
+        }),
+    )
+    .await?;
+    assert_eq!(payload(echoed)["echoed"], "via alias");
+
+    let probe = call(&service, "inspector_probe", json!({"target_alias": "mock"})).await?;
+    assert_eq!(payload(probe)["ok"], true);
+
+    let stale = call(
+        &service,
+        "inspector_probe",
+        json!({"target_alias": "stale"}),
+    )
+    .await?;
+    let stale = stale.structured_content.expect("probe payload");
+    assert_ne!(stale["ok"], true, "{stale}");
+
+    let overridden = call(
+        &service,
+        "inspector_call",
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {"text": "overridden"},
+            "target_alias": "stale",
+            "stdio": {"command": mock_command()}
+        }),
+    )
+    .await?;
+    assert_eq!(payload(overridden)["echoed"], "overridden");
+
+    let probe = call(
+        &service,
+        "inspector_probe",
+        json!({"target_alias": "stale", "command": mock_command()}),
+    )
+    .await?;
+    assert_eq!(payload(probe)["ok"], true);
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn unknown_aliases_fail_and_help_hides_secrets() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    write_targets(dir.path())?;
+    let service = common::inspector(&[("APP_CONFIG_DIR", dir.path().to_str().unwrap())]).await?;
+
+    let unknown = call(
+        &service,
+        "inspector_call",
+        json!({"tool_name": "echo", "target_alias": "billing-prod"}),
+    )
+    .await?;
+    assert!(unknown.is_error.unwrap_or(false), "{unknown:?}");
+    let unknown = unknown.structured_content.expect("error payload");
+    assert_eq!(unknown["code"], "TARGET_ALIAS_NOT_FOUND", "{unknown}");
+    assert_eq!(
+        unknown["aliases"],
+        json!(["billing-staging", "mock", "stale"])
+    );
+
+    let replaced = call(
+        &service,
+        "inspector_call",
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {"text": "replaced"},
+            "target_alias": "billing-staging",
+            "stdio": {
+                "command": mock_command(),
+                "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+            }
+        }),
+    )
+    .await?;
+    assert_eq!(payload(replaced)["echoed"], "replaced");
+
+    let help = payload(call(&service, "help", json!({})).await?);
+    let lines: Vec<Value> = help["lines"]
+        .as_array()
+        .expect("help lines")
+        .iter()
+        .map(|line| serde_json::from_str(line.as_str().unwrap()).unwrap())
+        .collect();
+    let section = lines
+        .iter()
+        .find(|line| line["section"] == "target_aliases")
+        .expect("target_aliases section");
+    let billing = section["aliases"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["alias"] == "billing-staging")
+        .expect("billing-staging listed");
+    assert_eq!(
+        billing["target"]["url"],
+        "https://billing.staging.example/mcp"
+    );
+    assert_eq!(billing["target"]["headers"]["x-team"], "billing");
+    let rendered = section.to_string();
+    assert!(!rendered.contains("alias-secret"), "{rendered}");
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::model::CallToolRequestParam;
+use serde_json::{Value, json};
+
+mod common;
+
+async fn traced_event(capture: &str) -> Result<Value> {
+    let service = common::inspector(&[("CAPTURE_TARGET_CAPABILITIES", capture)]).await?;
+    let args = json!({
+        "tool_name": "echo",
+        "arguments_json": {"text": "caps"},
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        }
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    service.cancel().await?;
+    let meta = result.meta.expect("trace meta");
+    Ok(meta["trace"]["event"].clone())
+}
+
+#[tokio::test]
+async fn events_record_target_capabilities_when_enabled() -> Result<()> {
+    let event = traced_event("true").await?;
+    let caps = event
+        .get("target_capabilities")
+        .and_then(Value::as_object)
+        .expect("target_capabilities object");
+    assert!(
+        caps.contains_key("tools"),
+        "unexpected capabilities: {caps:?}"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn events_omit_target_capabilities_by_default() -> Result<()> {
+    let event = traced_event("false").await?;
+    assert!(event.get("target_capabilities").is_none(), "{event}");
+    Ok(())
+}
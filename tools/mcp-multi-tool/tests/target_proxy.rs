@@ -0,0 +1,212 @@
+use std::{
+    net::{TcpListener as StdTcpListener, TcpStream as StdTcpStream},
+    process::{Child, Command as StdCommand, Stdio},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+mod common;
+
+struct MockGuard(Child);
+
+impl Drop for MockGuard {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn spawn_mock() -> Result<(MockGuard, String)> {
+    let reserve = || -> Result<u16> {
+        let listener = StdTcpListener::bind("127.0.0.1:0")?;
+        Ok(listener.local_addr()?.port())
+    };
+    let (sse_port, http_port) = (reserve()?, reserve()?);
+    let child = StdCommand::new(cargo_bin("mock_mcp_server"))
+        .env("MOCK_ENABLE_STDIO", "0")
+        .env("MOCK_SSE_ADDR", format!("127.0.0.1:{sse_port}"))
+        .env("MOCK_HTTP_ADDR", format!("127.0.0.1:{http_port}"))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let guard = MockGuard(child);
+    for _ in 0..50 {
+        if StdTcpStream::connect(("127.0.0.1", http_port)).is_ok() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    Ok((guard, format!("http://127.0.0.1:{http_port}/mcp")))
+}
+
+/// Request heads the proxy received: the request line plus any `Proxy-Authorization`.
+type ProxyLog = Arc<Mutex<Vec<(String, Option<String>)>>>;
+
+/// A forward proxy that tunnels `CONNECT` and relays absolute-form requests to their host,
+/// logging each connection's first request head.
+async fn spawn_proxy() -> Result<(String, ProxyLog)> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}", listener.local_addr()?);
+    let log = ProxyLog::default();
+    let seen = Arc::clone(&log);
+    tokio::spawn(async move {
+        while let Ok((client, _)) = listener.accept().await {
+            let seen = Arc::clone(&seen);
+            tokio::spawn(async move {
+                let _ = relay(client, seen).await;
+            });
+        }
+    });
+    Ok((url, log))
+}
+
+async fn relay(mut client: TcpStream, log: ProxyLog) -> Result<()> {
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        let byte = client.read_u8().await?;
+        head.push(byte);
+    }
+    let text = String::from_utf8_lossy(&head).to_string();
+    let request_line = text.lines().next().unwrap_or_default().to_string();
+    let auth = text.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.eq_ignore_ascii_case("proxy-authorization")
+            .then(|| value.trim().to_string())
+    });
+    log.lock().unwrap().push((request_line.clone(), auth));
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or_default();
+    let mut upstream = if method == "CONNECT" {
+        let upstream = TcpStream::connect(target).await?;
+        client
+            .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+            .await?;
+        upstream
+    } else {
+        let authority = target
+            .trim_start_matches("http://")
+            .split('/')
+            .next()
+            .unwrap_or_default();
+        let mut upstream = TcpStream::connect(authority).await?;
+        upstream.write_all(&head).await?;
+        upstream
+    };
+    tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(())
+}
+
+async fn call(
+    service: &RunningService<RoleClient, ()>,
+    name: &str,
+    args: Value,
+) -> Result<CallToolResult> {
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+fn payload(result: CallToolResult) -> Value {
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    result.structured_content.expect("payload")
+}
+
+#[tokio::test]
+async fn target_proxy_relays_calls_with_its_credentials() -> Result<()> {
+    let (_mock, url) = spawn_mock()?;
+    let (proxy_url, log) = spawn_proxy().await?;
+    let service = common::inspector(&[]).await?;
+    let proxy = json!({"url": proxy_url, "username": "ci", "password": "secret"});
+
+    let echoed = call(
+        &service,
+        "inspector_call",
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {"text": "via proxy"},
+            "http": {"url": url, "proxy": proxy}
+        }),
+    )
+    .await?;
+    assert_eq!(payload(echoed)["echoed"], "via proxy");
+    {
+        let log = log.lock().unwrap();
+        assert!(!log.is_empty(), "proxy saw no traffic");
+        assert!(
+            log.iter().all(
+                |(line, auth)| line.contains(url.trim_start_matches("http://"))
+                    && auth.as_deref() == Some("Basic Y2k6c2VjcmV0")
+            ),
+            "{log:?}"
+        );
+    }
+
+    let proxied = call(
+        &service,
+        "inspector_probe",
+        json!({"transport": "http", "url": url, "proxy": proxy}),
+    )
+    .await?;
+    assert_eq!(payload(proxied)["proxied"], true);
+
+    let direct = call(
+        &service,
+        "inspector_probe",
+        json!({"transport": "http", "url": url}),
+    )
+    .await?;
+    assert_eq!(payload(direct)["proxied"], false);
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn target_proxy_config_applies_to_targets_without_their_own() -> Result<()> {
+    let (_mock, url) = spawn_mock()?;
+    let (proxy_url, log) = spawn_proxy().await?;
+    let service = common::inspector(&[("TARGET_PROXY", &proxy_url)]).await?;
+
+    let echoed = call(
+        &service,
+        "inspector_call",
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {"text": "default proxy"},
+            "http": {"url": url}
+        }),
+    )
+    .await?;
+    assert_eq!(payload(echoed)["echoed"], "default proxy");
+    assert!(!log.lock().unwrap().is_empty(), "proxy saw no traffic");
+
+    let probe = call(
+        &service,
+        "inspector_probe",
+        json!({"transport": "http", "url": url}),
+    )
+    .await?;
+    assert_eq!(payload(probe)["proxied"], true);
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use std::{
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    process::{Child, Command as StdCommand, Stdio},
+    thread,
+    time::Duration,
+};
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+struct MockGuard(Child);
+
+impl Drop for MockGuard {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn fixture(name: &str) -> String {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/tls")
+        .join(name)
+        .display()
+        .to_string()
+}
+
+/// Start the mock with its HTTP endpoint behind TLS, signed by the fixture CA. With
+/// `require_client_cert` it also demands a client certificate from that CA.
+fn spawn_tls_mock(require_client_cert: bool) -> Result<(MockGuard, String)> {
+    let reserve = || -> Result<u16> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        Ok(listener.local_addr()?.port())
+    };
+    let (sse_port, http_port) = (reserve()?, reserve()?);
+    let mut cmd = StdCommand::new(cargo_bin("mock_mcp_server"));
+    cmd.env("MOCK_ENABLE_STDIO", "0")
+        .env("MOCK_SSE_ADDR", format!("127.0.0.1:{sse_port}"))
+        .env("MOCK_HTTP_ADDR", format!("127.0.0.1:{http_port}"))
+        .env("MOCK_HTTP_TLS_CERT", fixture("server.crt"))
+        .env("MOCK_HTTP_TLS_KEY", fixture("server.key"))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if require_client_cert {
+        cmd.env("MOCK_HTTP_TLS_CLIENT_CA", fixture("ca.crt"));
+    }
+    let guard = MockGuard(cmd.spawn()?);
+    for _ in 0..50 {
+        if TcpStream::connect(("127.0.0.1", http_port)).is_ok() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    Ok((guard, format!("https://127.0.0.1:{http_port}/mcp")))
+}
+
+async fn call(
+    service: &RunningService<RoleClient, ()>,
+    name: &str,
+    args: Value,
+) -> Result<CallToolResult> {
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+fn echo_args(url: &str, tls: Value) -> Value {
+    json!({
+        "tool_name": "echo",
+        "arguments_json": {"text": "over tls"},
+        "http": {"url": url, "tls": tls}
+    })
+}
+
+fn echoed(result: CallToolResult) -> Value {
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    result.structured_content.expect("echo payload")["echoed"].clone()
+}
+
+#[tokio::test]
+async fn private_ca_and_client_certificate_reach_an_mtls_target() -> Result<()> {
+    let (_mock, url) = spawn_tls_mock(true)?;
+    let service = common::inspector(&[("ALLOW_INSECURE_TARGET_TLS", "false")]).await?;
+    let mtls = json!({
+        "ca_cert_path": fixture("ca.crt"),
+        "client_cert_path": fixture("client.crt"),
+        "client_key_path": fixture("client.key")
+    });
+
+    let untrusted = call(&service, "inspector_call", echo_args(&url, json!({}))).await?;
+    assert!(untrusted.is_error.unwrap_or(false), "{untrusted:?}");
+    let anonymous = json!({"ca_cert_path": fixture("ca.crt")});
+    let rejected = call(&service, "inspector_call", echo_args(&url, anonymous)).await?;
+    assert!(rejected.is_error.unwrap_or(false), "{rejected:?}");
+
+    let accepted = call(&service, "inspector_call", echo_args(&url, mtls.clone())).await?;
+    assert_eq!(echoed(accepted), "over tls");
+
+    let probe = call(
+        &service,
+        "inspector_probe",
+        json!({"transport": "http", "url": url, "tls": mtls}),
+    )
+    .await?;
+    let probe = probe.structured_content.expect("probe result");
+    assert_eq!(probe["ok"], true, "{probe}");
+    assert!(
+        probe["tls_subject"]
+            .as_str()
+            .is_some_and(|subject| subject.contains("localhost")),
+        "{probe}"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn insecure_skip_verify_needs_the_operator_flag() -> Result<()> {
+    let (_mock, url) = spawn_tls_mock(false)?;
+    let insecure = json!({"insecure_skip_verify": true});
+
+    let strict = common::inspector(&[("ALLOW_INSECURE_TARGET_TLS", "false")]).await?;
+    let refused = call(&strict, "inspector_call", echo_args(&url, insecure.clone())).await?;
+    assert!(refused.is_error.unwrap_or(false));
+    let payload = refused.structured_content.expect("error payload");
+    assert_eq!(payload["code"], "TLS_CONFIG_ERROR", "{payload}");
+    assert!(
+        payload["error"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("ALLOW_INSECURE_TARGET_TLS"),
+        "{payload}"
+    );
+    strict.cancel().await?;
+
+    let permissive = common::inspector(&[("ALLOW_INSECURE_TARGET_TLS", "true")]).await?;
+    let accepted = call(&permissive, "inspector_call", echo_args(&url, insecure)).await?;
+    assert_eq!(echoed(accepted), "over tls");
+    permissive.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn unreadable_tls_files_fail_with_tls_config_error() -> Result<()> {
+    let (_mock, url) = spawn_tls_mock(false)?;
+    let service = common::inspector(&[("ALLOW_INSECURE_TARGET_TLS", "false")]).await?;
+
+    let missing = json!({"ca_cert_path": fixture("absent.crt")});
+    let result = call(&service, "inspector_call", echo_args(&url, missing.clone())).await?;
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("error payload");
+    assert_eq!(payload["code"], "TLS_CONFIG_ERROR", "{payload}");
+    assert!(
+        payload["error"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("absent.crt"),
+        "{payload}"
+    );
+
+    let probe = call(
+        &service,
+        "inspector_probe",
+        json!({"transport": "http", "url": url, "tls": missing}),
+    )
+    .await?;
+    assert!(probe.is_error.unwrap_or(false));
+    assert_eq!(
+        probe.structured_content.expect("error payload")["code"],
+        "TLS_CONFIG_ERROR"
+    );
+
+    let keyless = json!({"client_cert_path": fixture("client.crt")});
+    let result = call(&service, "inspector_call", echo_args(&url, keyless)).await?;
+    assert_eq!(
+        result.structured_content.expect("error payload")["code"],
+        "TLS_CONFIG_ERROR"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{RoleClient, model::CallToolRequestParam, service::RunningService};
+use serde_json::{Value, json};
+
+mod common;
+
+async fn echo(service: &RunningService<RoleClient, ()>, arguments: Value) -> Result<Value> {
+    let args = json!({
+        "tool_name": "echo",
+        "arguments_json": arguments,
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {
+                "MOCK_SSE_ADDR": "127.0.0.1:0",
+                "MOCK_HTTP_ADDR": "127.0.0.1:0"
+            }
+        }
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    Ok(result.structured_content.expect("echo payload"))
+}
+
+#[tokio::test]
+async fn tool_arg_defaults_fill_missing_arguments_and_yield_to_caller() -> Result<()> {
+    let service =
+        common::inspector(&[("TOOL_ARG_DEFAULTS", r#"{"echo":{"text":"from defaults"}}"#)]).await?;
+
+    let defaulted = echo(&service, Value::Null).await?;
+    assert_eq!(defaulted["echoed"], "from defaults");
+
+    let overridden = echo(&service, json!({"text": "from caller"})).await?;
+    assert_eq!(overridden["echoed"], "from caller");
+
+    service.cancel().await?;
+    Ok(())
+}
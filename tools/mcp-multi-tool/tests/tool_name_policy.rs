@@ -0,0 +1,92 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::json;
+
+mod common;
+
+async fn call(service: &RunningService<RoleClient, ()>, tool_name: &str) -> Result<CallToolResult> {
+    let args = json!({
+        "tool_name": tool_name,
+        "arguments_json": {"text": "hi"},
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        }
+    });
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+fn forbidden_code(result: &CallToolResult) -> Option<String> {
+    if !result.is_error.unwrap_or(false) {
+        return None;
+    }
+    result
+        .structured_content
+        .as_ref()
+        .and_then(|payload| payload["code"].as_str())
+        .map(str::to_string)
+}
+
+#[tokio::test]
+async fn allowlist_admits_only_matching_tools() -> Result<()> {
+    let service = common::inspector(&[
+        ("DOWNSTREAM_TOOL_ALLOWLIST", "ech?, stream*"),
+        ("DOWNSTREAM_TOOL_DENYLIST", ""),
+    ])
+    .await?;
+
+    let allowed = call(&service, "echo").await?;
+    assert!(
+        !allowed.is_error.unwrap_or(false),
+        "echo refused: {allowed:?}"
+    );
+    assert_eq!(
+        allowed.structured_content.expect("echo payload")["echoed"],
+        "hi"
+    );
+
+    let refused = call(&service, "log").await?;
+    assert_eq!(
+        forbidden_code(&refused).as_deref(),
+        Some("DOWNSTREAM_TOOL_FORBIDDEN")
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn denylist_wins_over_allowlist() -> Result<()> {
+    let service = common::inspector(&[
+        ("DOWNSTREAM_TOOL_ALLOWLIST", "*"),
+        ("DOWNSTREAM_TOOL_DENYLIST", "delete_*,ech*"),
+    ])
+    .await?;
+
+    let refused = call(&service, "echo").await?;
+    assert_eq!(
+        forbidden_code(&refused).as_deref(),
+        Some("DOWNSTREAM_TOOL_FORBIDDEN")
+    );
+    let message = refused.structured_content.expect("error payload")["error"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    assert!(message.contains("ech*"), "unexpected message: {message}");
+
+    let allowed = call(&service, "log").await?;
+    assert_eq!(forbidden_code(&allowed), None, "log refused: {allowed:?}");
+
+    service.cancel().await?;
+    Ok(())
+}
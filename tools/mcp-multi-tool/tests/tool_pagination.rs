@@ -0,0 +1,98 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+fn mock_target(extra_env: Value) -> Value {
+    let mut env = json!({"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"});
+    if let (Some(env), Some(extra)) = (env.as_object_mut(), extra_env.as_object()) {
+        env.extend(extra.clone());
+    }
+    json!({
+        "transport": "stdio",
+        "command": cargo_bin("mock_mcp_server").display().to_string(),
+        "env": env,
+    })
+}
+
+async fn call(
+    service: &RunningService<RoleClient, ()>,
+    name: &str,
+    args: Value,
+) -> Result<CallToolResult> {
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+fn tool_names(result: &CallToolResult) -> Vec<String> {
+    let payload = result.structured_content.as_ref().expect("tool list");
+    payload["tools"]
+        .as_array()
+        .expect("tools array")
+        .iter()
+        .filter_map(|tool| tool["name"].as_str().map(str::to_string))
+        .collect()
+}
+
+#[tokio::test]
+async fn listing_merges_every_page() -> Result<()> {
+    let service = common::inspector(&[("MAX_TOOL_LIST_PAGES", "10")]).await?;
+
+    let unpaged = call(&service, "inspector_list_tools", mock_target(json!({}))).await?;
+    let paged = call(
+        &service,
+        "inspector_list_tools",
+        mock_target(json!({"MOCK_TOOLS_PAGE_SIZE": "2"})),
+    )
+    .await?;
+    assert!(!paged.is_error.unwrap_or(false), "list failed: {paged:?}");
+    let names = tool_names(&paged);
+    assert!(names.len() > 2, "only the first page came back: {names:?}");
+    assert_eq!(names, tool_names(&unpaged));
+
+    // The last tool only exists on the final page.
+    let mut describe = mock_target(json!({"MOCK_TOOLS_PAGE_SIZE": "2"}));
+    describe["tool_name"] = json!(names.last().expect("a tool"));
+    let described = call(&service, "inspector_describe", describe).await?;
+    assert!(
+        !described.is_error.unwrap_or(false),
+        "describe failed: {described:?}"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn endless_cursor_stops_at_the_page_cap() -> Result<()> {
+    let service = common::inspector(&[("MAX_TOOL_LIST_PAGES", "10")]).await?;
+
+    let result = call(
+        &service,
+        "inspector_list_tools",
+        mock_target(json!({"MOCK_TOOLS_PAGE_SIZE": "2", "MOCK_TOOLS_ENDLESS_CURSOR": "1"})),
+    )
+    .await?;
+    assert!(result.is_error.unwrap_or(false));
+    let payload = result.structured_content.expect("error payload");
+    assert!(
+        payload["error"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("after 10 pages"),
+        "unexpected error: {payload}"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
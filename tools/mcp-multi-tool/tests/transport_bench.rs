@@ -0,0 +1,114 @@
+use std::{
+    net::{TcpListener, TcpStream},
+    process::{Child, Command as StdCommand, Stdio},
+    thread,
+    time::Duration,
+};
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::model::CallToolRequestParam;
+use serde_json::{Value, json};
+
+mod common;
+
+struct MockGuard(Child);
+
+impl Drop for MockGuard {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn reserve_port() -> Result<u16> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// Start the mock with only its SSE/HTTP transports; returns their URLs.
+fn spawn_network_mock() -> Result<(MockGuard, String, String)> {
+    let (sse_port, http_port) = (reserve_port()?, reserve_port()?);
+    let child = StdCommand::new(cargo_bin("mock_mcp_server"))
+        .env("MOCK_ENABLE_STDIO", "0")
+        .env("MOCK_SSE_ADDR", format!("127.0.0.1:{sse_port}"))
+        .env("MOCK_HTTP_ADDR", format!("127.0.0.1:{http_port}"))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let guard = MockGuard(child);
+    for port in [sse_port, http_port] {
+        for _ in 0..50 {
+            if TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+    Ok((
+        guard,
+        format!("http://127.0.0.1:{sse_port}/sse"),
+        format!("http://127.0.0.1:{http_port}/mcp"),
+    ))
+}
+
+async fn transport_bench(args: Value) -> Result<Value> {
+    let service = common::inspector(&[]).await?;
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_transport_bench".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    service.cancel().await?;
+    assert!(
+        !result.is_error.unwrap_or(false),
+        "bench failed: {result:?}"
+    );
+    Ok(result.structured_content.expect("bench report"))
+}
+
+#[tokio::test]
+async fn every_transport_is_ranked_by_handshake_latency() -> Result<()> {
+    let (_mock, sse_url, http_url) = spawn_network_mock()?;
+    let report = transport_bench(json!({
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        },
+        "sse": {"url": sse_url},
+        "http": {"url": http_url}
+    }))
+    .await?;
+
+    let ranking = report["ranking"].as_array().expect("ranking");
+    assert_eq!(ranking.len(), 3, "{report}");
+    assert!(ranking.iter().all(|entry| entry["ok"] == true), "{report}");
+    let latencies: Vec<u64> = ranking
+        .iter()
+        .map(|entry| entry["latency_ms"].as_u64().expect("latency"))
+        .collect();
+    assert!(latencies.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_eq!(report["recommended"], ranking[0]["transport"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn unreachable_transport_is_ranked_last() -> Result<()> {
+    let closed_port = reserve_port()?;
+    let report = transport_bench(json!({
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        },
+        "http": {"url": format!("http://127.0.0.1:{closed_port}/mcp"), "handshake_timeout_ms": 2000}
+    }))
+    .await?;
+
+    assert_eq!(report["recommended"], "stdio");
+    let ranking = report["ranking"].as_array().expect("ranking");
+    assert_eq!(ranking[1]["transport"], "http");
+    assert_eq!(ranking[1]["ok"], false);
+    assert!(ranking[1]["error"].is_string(), "{report}");
+    Ok(())
+}
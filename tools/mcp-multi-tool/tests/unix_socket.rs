@@ -0,0 +1,144 @@
+#![cfg(unix)]
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+use std::process::Stdio;
+use tokio::{net::UnixListener, process::Command};
+
+mod common;
+
+/// Serve the stdio mock behind `listener`: every accepted connection gets its own mock
+/// process with the socket piped to its stdin and stdout.
+fn serve_mock(listener: UnixListener) {
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut child = Command::new(cargo_bin("mock_mcp_server"))
+                    .env("MOCK_SSE_ADDR", "127.0.0.1:0")
+                    .env("MOCK_HTTP_ADDR", "127.0.0.1:0")
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .kill_on_drop(true)
+                    .spawn()
+                    .expect("spawn mock");
+                let mut stdin = child.stdin.take().expect("stdin");
+                let mut stdout = child.stdout.take().expect("stdout");
+                let (mut read, mut write) = socket.into_split();
+                let inbound = async move {
+                    let _ = tokio::io::copy(&mut read, &mut stdin).await;
+                };
+                let outbound = tokio::io::copy(&mut stdout, &mut write);
+                let _ = tokio::join!(inbound, outbound);
+            });
+        }
+    });
+}
+
+async fn call(
+    service: &RunningService<RoleClient, ()>,
+    name: &str,
+    args: Value,
+) -> Result<CallToolResult> {
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+async fn payload(service: &RunningService<RoleClient, ()>, name: &str, args: Value) -> Value {
+    let result = call(service, name, args).await.expect("tool call");
+    assert!(
+        !result.is_error.unwrap_or(false),
+        "{name} failed: {result:?}"
+    );
+    result.structured_content.expect("payload")
+}
+
+#[tokio::test]
+async fn probe_list_and_call_over_a_unix_socket() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("mock.sock");
+    serve_mock(UnixListener::bind(&path)?);
+    let socket_path = path.display().to_string();
+    let service = common::inspector(&[]).await?;
+
+    let probe = payload(
+        &service,
+        "inspector_probe",
+        json!({"transport": "unix", "socket_path": socket_path}),
+    )
+    .await;
+    assert_eq!(probe["ok"], true, "{probe}");
+    assert_eq!(probe["transport"], "unix");
+    assert!(probe["server_name"].is_string(), "{probe}");
+
+    let listed = payload(
+        &service,
+        "inspector_list_tools",
+        json!({"transport": "unix", "socket_path": socket_path}),
+    )
+    .await;
+    let tools = listed["tools"].as_array().expect("tools");
+    assert!(tools.iter().any(|tool| tool["name"] == "echo"), "{listed}");
+
+    let echoed = payload(
+        &service,
+        "inspector_call",
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {"text": "hi"},
+            "unix": {"path": socket_path}
+        }),
+    )
+    .await;
+    assert_eq!(echoed["echoed"], "hi");
+
+    let sessions = payload(&service, "inspector_sessions", json!({})).await;
+    let pooled = sessions["sessions"].as_array().expect("sessions");
+    assert!(
+        pooled
+            .iter()
+            .any(|session| session["target"]["transport"] == "unix"
+                && session["target"]["socket_path"] == socket_path.as_str()),
+        "{sessions}"
+    );
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn missing_socket_fails_the_call() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let socket_path = dir.path().join("absent.sock").display().to_string();
+    let service = common::inspector(&[]).await?;
+
+    let result = call(
+        &service,
+        "inspector_call",
+        json!({
+            "tool_name": "echo",
+            "arguments_json": {"text": "hi"},
+            "unix": {"path": socket_path}
+        }),
+    )
+    .await?;
+    assert!(result.is_error.unwrap_or(false), "{result:?}");
+    let message = result.structured_content.expect("error payload")["error"]
+        .as_str()
+        .expect("message")
+        .to_string();
+    assert!(message.contains("connect unix socket"), "{message}");
+
+    service.cancel().await?;
+    Ok(())
+}
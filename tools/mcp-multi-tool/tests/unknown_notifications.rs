@@ -0,0 +1,56 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::model::{CallToolRequestParam, CallToolResult};
+use serde_json::{Value, json};
+
+mod common;
+
+/// Call `echo` on a mock that announces a vendor notification before serving normally.
+async fn call_chatty_target(capture: bool) -> Result<CallToolResult> {
+    let service = common::inspector(&[
+        ("CAPTURE_UNKNOWN_NOTIFICATIONS", &capture.to_string()),
+        ("ALLOW_SHELL_TARGETS", "true"),
+    ])
+    .await?;
+    let chatty = format!(
+        "printf '%s\\n' '{{\"jsonrpc\":\"2.0\",\"method\":\"notifications/vendor/heartbeat\",\"params\":{{}}}}'; \
+         exec '{}'",
+        cargo_bin("mock_mcp_server").display()
+    );
+    let args = json!({
+        "tool_name": "echo",
+        "arguments_json": {"text": "hi"},
+        "stdio": {
+            "command": chatty,
+            "shell": true,
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"}
+        }
+    });
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    Ok(result)
+}
+
+#[tokio::test]
+async fn unknown_notifications_are_recorded_when_enabled() -> Result<()> {
+    let result = call_chatty_target(true).await?;
+    let meta = result.meta.expect("trace meta");
+    assert_eq!(
+        meta["trace"]["unknown_notifications"],
+        json!(["notifications/vendor/heartbeat"])
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn unknown_notifications_are_ignored_by_default() -> Result<()> {
+    let result = call_chatty_target(false).await?;
+    let meta = result.meta.expect("trace meta");
+    assert_eq!(meta["trace"].get("unknown_notifications"), None::<&Value>);
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin;
+use rmcp::{
+    RoleClient,
+    model::{CallToolRequestParam, CallToolResult},
+    service::RunningService,
+};
+use serde_json::{Value, json};
+
+mod common;
+
+/// An error budget that would freeze after three failures out of three.
+const FREEZE_AFTER_THREE: &[(&str, &str)] = &[
+    ("ERROR_BUDGET_ENABLED", "true"),
+    ("ERROR_BUDGET_SUCCESS_THRESHOLD", "0.6"),
+    ("ERROR_BUDGET_MIN_REQUESTS", "3"),
+    ("ERROR_BUDGET_SAMPLE_WINDOW_SECS", "120"),
+    ("ERROR_BUDGET_FREEZE_SECS", "60"),
+];
+
+async fn call_add(
+    service: &RunningService<RoleClient, ()>,
+    arguments: Value,
+) -> Result<CallToolResult> {
+    let args = json!({
+        "tool_name": "add",
+        "arguments_json": arguments,
+        "validate_arguments": true,
+        "stdio": {
+            "command": cargo_bin("mock_mcp_server").display().to_string(),
+            "env": {"MOCK_SSE_ADDR": "127.0.0.1:0", "MOCK_HTTP_ADDR": "127.0.0.1:0"},
+        }
+    });
+    Ok(service
+        .call_tool(CallToolRequestParam {
+            name: "inspector_call".into(),
+            arguments: Some(args.as_object().cloned().unwrap()),
+        })
+        .await?)
+}
+
+#[tokio::test]
+async fn conforming_arguments_reach_the_tool() -> Result<()> {
+    let service = common::inspector(FREEZE_AFTER_THREE).await?;
+
+    let result = call_add(&service, json!({"values": [1, 2, 3.5]})).await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    assert_eq!(result.structured_content.expect("payload")["sum"], 6.5);
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn violations_are_listed_without_calling_or_spending_budget() -> Result<()> {
+    let service = common::inspector(FREEZE_AFTER_THREE).await?;
+
+    for _ in 0..4 {
+        let result = call_add(&service, json!({"values": [1, "two"]})).await?;
+        assert!(result.is_error.unwrap_or(false));
+        let payload = result.structured_content.expect("error payload");
+        assert_eq!(payload["code"], "INVALID_ARGUMENTS", "{payload}");
+        assert_eq!(
+            payload["violations"],
+            json!([{
+                "instance_path": "$.values[1]",
+                "message": "\"two\" is not of type \"number\""
+            }])
+        );
+    }
+
+    let wrong_type = call_add(&service, json!({"values": "1,2"})).await?;
+    let payload = wrong_type.structured_content.expect("error payload");
+    assert_eq!(payload["violations"][0]["instance_path"], "$.values");
+
+    // Five rejected calls would have frozen the budget had they counted.
+    let result = call_add(&service, json!({"values": [2, 2]})).await?;
+    assert!(!result.is_error.unwrap_or(false), "call failed: {result:?}");
+    assert_eq!(result.structured_content.expect("payload")["sum"], 4.0);
+
+    service.cancel().await?;
+    Ok(())
+}
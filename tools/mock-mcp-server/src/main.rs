@@ -1,12 +1,12 @@
-use std::{env, net::SocketAddr, time::Duration};
+use std::{env, net::SocketAddr, sync::Arc, time::Duration};
 
-use anyhow::Result;
-use axum::Router;
+use anyhow::{Context, Result};
+use axum::{Router, extract::Request, http::StatusCode, middleware::Next, response::IntoResponse};
 use rmcp::schemars::JsonSchema;
 use rmcp::{
     ServiceExt,
     transport::{
-        sse_server::SseServer,
+        sse_server::{SseServer, SseServerConfig},
         stdio,
         streamable_http_server::{
             session::local::LocalSessionManager, tower::StreamableHttpService,
@@ -14,9 +14,44 @@ use rmcp::{
     },
 };
 use tokio::{net::TcpListener, signal, time::sleep};
+use tokio_rustls::{
+    TlsAcceptor,
+    rustls::{self, server::WebPkiClientVerifier},
+};
 use tokio_util::sync::CancellationToken;
 use tracing_subscriber::EnvFilter;
 
+/// Reference stamped on every `fixed_reference` result, so repeat calls collide.
+const FIXED_EXTERNAL_REFERENCE: &str = "mock-fixed-ref";
+const MOCK_RESOURCE_PAGE_SIZE: usize = 2;
+
+enum MockResourceBody {
+    Text(&'static str),
+    /// Base64, as it travels on the wire.
+    Blob(&'static str),
+}
+
+/// `(uri, name, body)` served by `resources/list` and `resources/read`.
+const MOCK_RESOURCES: &[(&str, &str, MockResourceBody)] = &[
+    (
+        "mock://notes/readme",
+        "readme",
+        MockResourceBody::Text("mock readme"),
+    ),
+    (
+        "mock://notes/changelog",
+        "changelog",
+        MockResourceBody::Text("v1: first"),
+    ),
+    (
+        "mock://bin/logo",
+        "logo",
+        MockResourceBody::Blob("AAECAw=="),
+    ),
+];
+
+const MOCK_INSTRUCTIONS: &str = "Mock MCP server for inspector tests; call help for usage.";
+
 #[derive(Clone, Default)]
 struct MockServer;
 
@@ -43,11 +78,57 @@ impl MockServer {
                 "Sum a list of numbers and return the total.",
                 schema_for::<Parameters<MockAddArgs>>(),
             ),
+            rmcp::model::Tool::new(
+                "fixed_reference",
+                "Succeed with the same `externalReference` in `_meta` on every call.",
+                schema_for::<Parameters<MockHelpArgs>>(),
+            ),
+            rmcp::model::Tool::new(
+                "empty",
+                "Return a blank text result with no structured content.",
+                schema_for::<Parameters<MockHelpArgs>>(),
+            ),
+            {
+                let mut tool = rmcp::model::Tool::new(
+                    "bad_output",
+                    "Advertise an output_schema and return output that violates it.",
+                    schema_for::<Parameters<MockHelpArgs>>(),
+                );
+                tool.output_schema = serde_json::json!({
+                    "type": "object",
+                    "required": ["sum"],
+                    "properties": {"sum": {"type": "number"}}
+                })
+                .as_object()
+                .cloned()
+                .map(std::sync::Arc::new);
+                tool
+            },
             rmcp::model::Tool::new(
                 "stream",
                 "Emit progress notifications followed by a final structured payload.",
                 schema_for::<Parameters<MockStreamArgs>>(),
             ),
+            rmcp::model::Tool::new(
+                "sleep",
+                "Wait `ms` milliseconds, then return how long it slept.",
+                schema_for::<Parameters<MockSleepArgs>>(),
+            ),
+            rmcp::model::Tool::new(
+                "getenv",
+                "Return the value of environment variable `name`, or null when unset.",
+                schema_for::<Parameters<MockGetenvArgs>>(),
+            ),
+            rmcp::model::Tool::new(
+                "text.upper",
+                "Return the supplied text in upper case; its name is dotted on purpose.",
+                schema_for::<Parameters<MockEchoArgs>>(),
+            ),
+            rmcp::model::Tool::new(
+                "log",
+                "Emit logging notifications, then return a small structured payload.",
+                schema_for::<Parameters<MockHelpArgs>>(),
+            ),
         ]
     }
 
@@ -90,6 +171,46 @@ impl MockServer {
                     "count": args.values.len(),
                 }))
             }
+            "text.upper" => {
+                let args = request
+                    .arguments
+                    .and_then(|map| {
+                        serde_json::from_value::<MockEchoArgs>(serde_json::Value::Object(map)).ok()
+                    })
+                    .unwrap_or_default();
+                rmcp::model::CallToolResult::structured(serde_json::json!({
+                    "upper": args.text.to_uppercase(),
+                }))
+            }
+            "bad_output" => rmcp::model::CallToolResult::structured(serde_json::json!({
+                "sum": "not-a-number",
+            })),
+            "fixed_reference" => {
+                let mut result =
+                    rmcp::model::CallToolResult::structured(serde_json::json!({"status": "ok"}));
+                let mut meta = rmcp::model::Meta::new();
+                meta.insert(
+                    "externalReference".into(),
+                    serde_json::Value::String(FIXED_EXTERNAL_REFERENCE.into()),
+                );
+                result.meta = Some(meta);
+                result
+            }
+            "getenv" => {
+                let args = request
+                    .arguments
+                    .and_then(|map| {
+                        serde_json::from_value::<MockGetenvArgs>(serde_json::Value::Object(map))
+                            .ok()
+                    })
+                    .unwrap_or_default();
+                rmcp::model::CallToolResult::structured(serde_json::json!({
+                    "name": args.name,
+                    "value": std::env::var(&args.name).ok(),
+                }))
+            }
+            // A single blank text block: rmcp rejects results with no content at all.
+            "empty" => rmcp::model::CallToolResult::success(vec![rmcp::model::Content::text("")]),
             other => rmcp::model::CallToolResult::structured_error(serde_json::json!({
                 "error": format!("unknown tool: {other}"),
             })),
@@ -116,6 +237,30 @@ struct MockAddArgs {
 struct MockStreamArgs {
     #[serde(default = "default_stream_chunks")]
     chunks: u32,
+    /// Keep reporting progress and never return.
+    #[serde(default)]
+    forever: bool,
+    /// Exit the process after this many progress notifications, dropping the connection.
+    #[serde(default)]
+    drop_after: Option<u32>,
+    /// Wait this long before the first progress notification.
+    #[serde(default)]
+    first_delay_ms: u64,
+    /// Send the last progress notification this long after returning the result.
+    #[serde(default)]
+    trailing_delay_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize, JsonSchema)]
+struct MockSleepArgs {
+    #[serde(default)]
+    ms: u64,
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize, JsonSchema)]
+struct MockGetenvArgs {
+    #[serde(default)]
+    name: String,
 }
 
 fn default_stream_chunks() -> u32 {
@@ -123,48 +268,211 @@ fn default_stream_chunks() -> u32 {
 }
 
 impl rmcp::ServerHandler for MockServer {
-    fn initialize(
+    async fn list_resources(
+        &self,
+        request: Option<rmcp::model::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<rmcp::model::ListResourcesResult, rmcp::ErrorData> {
+        // Pages of MOCK_RESOURCE_PAGE_SIZE so clients have to follow the cursor.
+        let start = request
+            .and_then(|param| param.cursor)
+            .and_then(|cursor| cursor.parse::<usize>().ok())
+            .unwrap_or(0);
+        let end = (start + MOCK_RESOURCE_PAGE_SIZE).min(MOCK_RESOURCES.len());
+        let resources = MOCK_RESOURCES[start..end]
+            .iter()
+            .map(|(uri, name, _)| {
+                use rmcp::model::AnnotateAble;
+                rmcp::model::RawResource::new(*uri, *name).no_annotation()
+            })
+            .collect();
+        Ok(rmcp::model::ListResourcesResult {
+            resources,
+            next_cursor: (end < MOCK_RESOURCES.len()).then(|| end.to_string()),
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: rmcp::model::ReadResourceRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<rmcp::model::ReadResourceResult, rmcp::ErrorData> {
+        let Some((uri, _, body)) = MOCK_RESOURCES
+            .iter()
+            .find(|(uri, _, _)| *uri == request.uri)
+        else {
+            return Err(rmcp::ErrorData::resource_not_found(
+                format!("unknown resource: {}", request.uri),
+                None,
+            ));
+        };
+        let contents = match body {
+            MockResourceBody::Text(text) => rmcp::model::ResourceContents::text(*text, *uri),
+            MockResourceBody::Blob(blob) => rmcp::model::ResourceContents::BlobResourceContents {
+                uri: (*uri).into(),
+                mime_type: Some("application/octet-stream".into()),
+                blob: (*blob).into(),
+                meta: None,
+            },
+        };
+        Ok(rmcp::model::ReadResourceResult {
+            contents: vec![contents],
+        })
+    }
+
+    async fn list_prompts(
+        &self,
+        request: Option<rmcp::model::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<rmcp::model::ListPromptsResult, rmcp::ErrorData> {
+        // One prompt per page so clients have to follow the cursor.
+        let prompts = [
+            rmcp::model::Prompt::new(
+                "greet",
+                Some("Greet someone by name."),
+                Some(vec![rmcp::model::PromptArgument {
+                    name: "name".into(),
+                    title: None,
+                    description: Some("Who to greet.".into()),
+                    required: Some(true),
+                }]),
+            ),
+            rmcp::model::Prompt::new("status", Some("Ask for a status report."), None),
+        ];
+        let start = request
+            .and_then(|param| param.cursor)
+            .and_then(|cursor| cursor.parse::<usize>().ok())
+            .unwrap_or(0);
+        let next = start + 1;
+        Ok(rmcp::model::ListPromptsResult {
+            prompts: prompts.into_iter().skip(start).take(1).collect(),
+            next_cursor: (next < 2).then(|| next.to_string()),
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        request: rmcp::model::GetPromptRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<rmcp::model::GetPromptResult, rmcp::ErrorData> {
+        use rmcp::model::{PromptMessage, PromptMessageRole};
+        let text = match request.name.as_str() {
+            "greet" => {
+                let name = request
+                    .arguments
+                    .as_ref()
+                    .and_then(|args| args.get("name"))
+                    .and_then(|name| name.as_str())
+                    .ok_or_else(|| {
+                        rmcp::ErrorData::invalid_params("greet requires a 'name' argument", None)
+                    })?;
+                format!("Say hello to {name}.")
+            }
+            "status" => "Report your current status.".to_string(),
+            other => {
+                return Err(rmcp::ErrorData::invalid_params(
+                    format!("unknown prompt: {other}"),
+                    None,
+                ));
+            }
+        };
+        Ok(rmcp::model::GetPromptResult {
+            description: Some(format!("mock prompt {}", request.name)),
+            messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+        })
+    }
+
+    async fn initialize(
         &self,
         request: rmcp::model::InitializeRequestParam,
         _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
-    ) -> impl std::future::Future<Output = Result<rmcp::model::InitializeResult, rmcp::ErrorData>>
-    + Send
-    + '_ {
-        async move {
-            let capabilities = rmcp::model::ServerCapabilities::builder()
-                .enable_tools()
-                .enable_tool_list_changed()
-                .build();
-            let info = rmcp::model::ServerInfo {
-                capabilities,
-                server_info: rmcp::model::Implementation {
-                    name: "mock-mcp-server".into(),
-                    title: Some("Mock MCP Server".into()),
-                    version: env!("CARGO_PKG_VERSION").into(),
-                    icons: None,
-                    website_url: None,
-                },
-                protocol_version: request.protocol_version,
-                instructions: None,
-            };
-            tracing::info!("initialize complete");
-            Ok(info)
+    ) -> Result<rmcp::model::InitializeResult, rmcp::ErrorData> {
+        let mut capabilities = rmcp::model::ServerCapabilities::builder()
+            .enable_tools()
+            .enable_tool_list_changed()
+            .enable_logging()
+            .enable_resources()
+            .enable_prompts()
+            .build();
+        // Lets tests exercise clients against a target without resources.
+        if env::var("MOCK_DISABLE_RESOURCES").is_ok_and(|v| matches!(v.as_str(), "1" | "true")) {
+            capabilities.resources = None;
         }
+        // MOCK_PROTOCOL_VERSION advertises a fixed version instead of echoing the client's.
+        let protocol_version = env::var("MOCK_PROTOCOL_VERSION")
+            .ok()
+            .and_then(|v| serde_json::from_value(serde_json::Value::String(v)).ok())
+            .unwrap_or(request.protocol_version);
+        let info = rmcp::model::ServerInfo {
+            capabilities,
+            server_info: rmcp::model::Implementation {
+                name: "mock-mcp-server".into(),
+                title: Some("Mock MCP Server".into()),
+                version: env!("CARGO_PKG_VERSION").into(),
+                icons: None,
+                website_url: None,
+            },
+            protocol_version,
+            instructions: Some(MOCK_INSTRUCTIONS.into()),
+        };
+        tracing::info!("initialize complete");
+        Ok(info)
+    }
+
+    async fn set_level(
+        &self,
+        request: rmcp::model::SetLevelRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<(), rmcp::ErrorData> {
+        tracing::info!(level = ?request.level, "logging level updated");
+        Ok(())
     }
 
     fn list_tools(
         &self,
-        _request: Option<rmcp::model::PaginatedRequestParam>,
+        request: Option<rmcp::model::PaginatedRequestParam>,
         _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
     ) -> impl std::future::Future<Output = Result<rmcp::model::ListToolsResult, rmcp::ErrorData>>
     + Send
     + '_ {
-        let tools = self.list_tools();
+        // Lets tests exercise clients against a target that advertises no tools.
+        let tools = if env::var("MOCK_NO_TOOLS").is_ok_and(|v| matches!(v.as_str(), "1" | "true")) {
+            Vec::new()
+        } else if env::var("MOCK_TOOL_DRIFT").is_ok_and(|v| matches!(v.as_str(), "1" | "true")) {
+            drift_catalog(self.list_tools())
+        } else {
+            self.list_tools()
+        };
+        // MOCK_TOOLS_PAGE_SIZE splits the catalog into offset-cursor pages;
+        // MOCK_TOOLS_ENDLESS_CURSOR makes the last page point back at the first.
+        let page_size = env::var("MOCK_TOOLS_PAGE_SIZE")
+            .ok()
+            .and_then(|raw| raw.parse::<usize>().ok())
+            .filter(|size| *size > 0)
+            .unwrap_or(usize::MAX);
+        let endless =
+            env::var("MOCK_TOOLS_ENDLESS_CURSOR").is_ok_and(|v| matches!(v.as_str(), "1" | "true"));
+        let start = request
+            .and_then(|param| param.cursor)
+            .and_then(|cursor| cursor.parse::<usize>().ok())
+            .unwrap_or(0)
+            .min(tools.len());
+        let end = start.saturating_add(page_size).min(tools.len());
+        let next_cursor = if end < tools.len() {
+            Some(end.to_string())
+        } else {
+            endless.then(|| "0".to_string())
+        };
+        let tools = tools[start..end].to_vec();
+        // Lets tests exercise clients against a target whose tools/list stalls.
+        let delay = env::var("MOCK_LIST_TOOLS_DELAY_MS")
+            .ok()
+            .and_then(|raw| raw.parse::<u64>().ok());
         async move {
-            Ok(rmcp::model::ListToolsResult {
-                tools,
-                next_cursor: None,
-            })
+            if let Some(delay) = delay {
+                sleep(Duration::from_millis(delay)).await;
+            }
+            Ok(rmcp::model::ListToolsResult { tools, next_cursor })
         }
     }
 
@@ -177,6 +485,14 @@ impl rmcp::ServerHandler for MockServer {
     + '_ {
         let server = self.clone();
         async move {
+            // Lets tests exercise clients against a target whose help call stalls.
+            if request.name.as_ref() == "help"
+                && let Some(delay) = env::var("MOCK_HELP_DELAY_MS")
+                    .ok()
+                    .and_then(|raw| raw.parse::<u64>().ok())
+            {
+                sleep(Duration::from_millis(delay)).await;
+            }
             if request.name.as_ref() == "stream" {
                 let args = request
                     .arguments
@@ -188,9 +504,45 @@ impl rmcp::ServerHandler for MockServer {
                         .ok()
                     })
                     .unwrap_or_default();
+                if args.forever {
+                    let token = context.meta.get_progress_token();
+                    for idx in 1.. {
+                        if let Some(token) = token.clone() {
+                            let _ = context
+                                .peer
+                                .notify_progress(rmcp::model::ProgressNotificationParam {
+                                    progress_token: token,
+                                    progress: idx as f64,
+                                    total: None,
+                                    message: Some(format!("chunk {idx}")),
+                                })
+                                .await;
+                        }
+                        sleep(Duration::from_millis(25)).await;
+                    }
+                }
+                sleep(Duration::from_millis(args.first_delay_ms)).await;
                 if let Some(token) = context.meta.get_progress_token() {
                     let chunks = args.chunks.max(1);
                     for idx in 0..chunks {
+                        if idx + 1 == chunks
+                            && let Some(delay) = args.trailing_delay_ms
+                        {
+                            let peer = context.peer.clone();
+                            let token = token.clone();
+                            tokio::spawn(async move {
+                                sleep(Duration::from_millis(delay)).await;
+                                let _ = peer
+                                    .notify_progress(rmcp::model::ProgressNotificationParam {
+                                        progress_token: token,
+                                        progress: chunks as f64,
+                                        total: Some(chunks as f64),
+                                        message: Some(format!("chunk {chunks}")),
+                                    })
+                                    .await;
+                            });
+                            break;
+                        }
                         let _ = context
                             .peer
                             .notify_progress(rmcp::model::ProgressNotificationParam {
@@ -201,12 +553,50 @@ impl rmcp::ServerHandler for MockServer {
                             })
                             .await;
                         sleep(Duration::from_millis(25)).await;
+                        if args.drop_after == Some(idx + 1) {
+                            std::process::exit(1);
+                        }
                     }
                 }
                 Ok(rmcp::model::CallToolResult::structured(serde_json::json!({
                     "status": "complete",
                     "chunks": args.chunks.max(1),
                 })))
+            } else if request.name.as_ref() == "sleep" {
+                let args = request
+                    .arguments
+                    .and_then(|map| {
+                        serde_json::from_value::<MockSleepArgs>(serde_json::Value::Object(map)).ok()
+                    })
+                    .unwrap_or_default();
+                sleep(Duration::from_millis(args.ms)).await;
+                Ok(rmcp::model::CallToolResult::structured(serde_json::json!({
+                    "slept_ms": args.ms,
+                })))
+            } else if request.name.as_ref() == "log" {
+                let messages = [
+                    (
+                        rmcp::model::LoggingLevel::Info,
+                        serde_json::json!("starting work"),
+                    ),
+                    (
+                        rmcp::model::LoggingLevel::Warning,
+                        serde_json::json!({"step": 2, "detail": "slow path"}),
+                    ),
+                ];
+                for (level, data) in messages {
+                    let _ = context
+                        .peer
+                        .notify_logging_message(rmcp::model::LoggingMessageNotificationParam {
+                            level,
+                            logger: Some("mock.worker".into()),
+                            data,
+                        })
+                        .await;
+                }
+                Ok(rmcp::model::CallToolResult::structured(
+                    serde_json::json!({"status": "logged", "messages": 2}),
+                ))
             } else {
                 let response = server.handle_simple_tool(request);
                 Ok(response)
@@ -215,6 +605,135 @@ impl rmcp::ServerHandler for MockServer {
     }
 }
 
+/// A "promoted" variant of the catalog for diff tests: `getenv` removed, `shout` added,
+/// `echo` reworded and `add` given an extra property.
+fn drift_catalog(tools: Vec<rmcp::model::Tool>) -> Vec<rmcp::model::Tool> {
+    let mut tools: Vec<rmcp::model::Tool> = tools
+        .into_iter()
+        .filter(|tool| tool.name != "getenv")
+        .map(|mut tool| {
+            match tool.name.as_ref() {
+                "echo" => tool.description = Some("Echo the supplied text back.".into()),
+                "add" => {
+                    let mut schema = tool.input_schema.as_ref().clone();
+                    if let Some(serde_json::Value::Object(properties)) =
+                        schema.get_mut("properties")
+                    {
+                        properties
+                            .insert("precision".into(), serde_json::json!({"type": "integer"}));
+                    }
+                    tool.input_schema = std::sync::Arc::new(schema);
+                }
+                _ => {}
+            }
+            tool
+        })
+        .collect();
+    tools.push(rmcp::model::Tool::new(
+        "shout",
+        "Echo the supplied text in upper case.",
+        rmcp::handler::server::common::cached_schema_for_type::<
+            rmcp::handler::server::wrapper::Parameters<MockEchoArgs>,
+        >(),
+    ));
+    tools
+}
+
+/// `<var>=name=value` makes `router` answer 401 to requests that lack the header.
+fn require_header(router: Router, var: &str) -> Router {
+    let Some((name, value)) = env::var(var).ok().and_then(|raw| {
+        raw.split_once('=')
+            .map(|(n, v)| (n.to_string(), v.to_string()))
+    }) else {
+        return router;
+    };
+    router.layer(axum::middleware::from_fn(
+        move |request: Request, next: Next| {
+            let authorized = request
+                .headers()
+                .get(name.as_str())
+                .is_some_and(|sent| sent.as_bytes() == value.as_bytes());
+            async move {
+                if authorized {
+                    next.run(request).await
+                } else {
+                    StatusCode::UNAUTHORIZED.into_response()
+                }
+            }
+        },
+    ))
+}
+
+/// Accepts TCP connections and completes a TLS handshake on each before handing it to axum.
+/// Failed handshakes are logged and skipped.
+struct TlsListener {
+    tcp: TcpListener,
+    acceptor: TlsAcceptor,
+}
+
+impl axum::serve::Listener for TlsListener {
+    type Io = tokio_rustls::server::TlsStream<tokio::net::TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            let (stream, addr) = match self.tcp.accept().await {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    tracing::warn!(%err, "tls accept failed");
+                    sleep(Duration::from_millis(50)).await;
+                    continue;
+                }
+            };
+            match tokio::time::timeout(Duration::from_secs(5), self.acceptor.accept(stream)).await {
+                Ok(Ok(tls)) => return (tls, addr),
+                Ok(Err(err)) => tracing::warn!(%err, %addr, "tls handshake failed"),
+                Err(_) => tracing::warn!(%addr, "tls handshake timed out"),
+            }
+        }
+    }
+
+    fn local_addr(&self) -> std::io::Result<Self::Addr> {
+        self.tcp.local_addr()
+    }
+}
+
+/// TLS for the HTTP endpoint from `MOCK_HTTP_TLS_CERT` and `MOCK_HTTP_TLS_KEY` (PEM files);
+/// with `MOCK_HTTP_TLS_CLIENT_CA` set, clients must present a certificate signed by that CA.
+fn http_tls_acceptor() -> Result<Option<TlsAcceptor>> {
+    let (Ok(cert_path), Ok(key_path)) = (
+        env::var("MOCK_HTTP_TLS_CERT"),
+        env::var("MOCK_HTTP_TLS_KEY"),
+    ) else {
+        return Ok(None);
+    };
+    let read_pem = |path: &str| -> Result<Vec<u8>> {
+        std::fs::read(path).with_context(|| format!("read {path}"))
+    };
+    let certs = rustls_pemfile::certs(&mut read_pem(&cert_path)?.as_slice())
+        .collect::<Result<Vec<_>, _>>()?;
+    let key = rustls_pemfile::private_key(&mut read_pem(&key_path)?.as_slice())?
+        .with_context(|| format!("no private key in {key_path}"))?;
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = rustls::ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()?;
+    let builder = match env::var("MOCK_HTTP_TLS_CLIENT_CA") {
+        Ok(ca_path) => {
+            let mut roots = rustls::RootCertStore::empty();
+            for cert in rustls_pemfile::certs(&mut read_pem(&ca_path)?.as_slice()) {
+                roots.add(cert?)?;
+            }
+            let verifier =
+                WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider).build()?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        Err(_) => builder.with_no_client_auth(),
+    };
+    let mut config = builder.with_single_cert(certs, key)?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(Some(TlsAcceptor::from(Arc::new(config))))
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let _ = tracing_subscriber::fmt()
@@ -233,27 +752,59 @@ async fn main() -> Result<()> {
 
     tracing::info!(%sse_addr, %http_addr, "mock server starting");
 
-    let sse_handle = SseServer::serve(sse_addr)
-        .await?
-        .with_service(|| MockServer::default());
+    let (sse_server, sse_router) = SseServer::new(SseServerConfig {
+        bind: sse_addr,
+        sse_path: "/sse".into(),
+        post_path: "/message".into(),
+        ct: CancellationToken::new(),
+        sse_keep_alive: None,
+    });
+    let sse_router = require_header(sse_router, "MOCK_SSE_REQUIRE_HEADER");
+    let sse_listener = TcpListener::bind(sse_addr).await?;
+    let sse_ct = sse_server.config.ct.child_token();
+    tokio::spawn(async move {
+        let _ = axum::serve(sse_listener, sse_router)
+            .with_graceful_shutdown(async move { sse_ct.cancelled().await })
+            .await;
+    });
+    let sse_handle = sse_server.with_service(|| MockServer);
 
     let http_service: StreamableHttpService<MockServer, LocalSessionManager> =
         StreamableHttpService::new(
-            || Ok(MockServer::default()),
+            || Ok(MockServer),
             std::sync::Arc::new(LocalSessionManager::default()),
             Default::default(),
         );
 
-    let http_router = Router::new().nest_service("/mcp", http_service);
+    let http_router = require_header(
+        Router::new().nest_service("/mcp", http_service),
+        "MOCK_HTTP_REQUIRE_HEADER",
+    );
     let http_listener = TcpListener::bind(http_addr).await?;
+    let http_tls = http_tls_acceptor()?;
     let http_ct = CancellationToken::new();
     let http_task = tokio::spawn({
         let ct = http_ct.clone();
         async move {
-            tracing::info!("http server listening");
-            let _ = axum::serve(http_listener, http_router)
-                .with_graceful_shutdown(async move { ct.cancelled().await })
-                .await;
+            let shutdown = async move { ct.cancelled().await };
+            match http_tls {
+                Some(acceptor) => {
+                    tracing::info!("https server listening");
+                    let listener = TlsListener {
+                        tcp: http_listener,
+                        acceptor,
+                    };
+                    let _ = axum::serve(listener, http_router)
+                        .with_graceful_shutdown(shutdown)
+                        .await;
+                }
+                None => {
+                    tracing::info!("http server listening");
+                    let _ = axum::serve(http_listener, http_router)
+                        .with_graceful_shutdown(shutdown)
+                        .await;
+                }
+            }
         }
     });
 
@@ -262,7 +813,16 @@ async fn main() -> Result<()> {
         .unwrap_or(true);
 
     if enable_stdio {
-        let server = MockServer::default().serve(stdio()).await?;
+        // Simulate a misbehaving server that prints a banner before the JSON-RPC stream.
+        if let Ok(banner) = env::var("MOCK_STDOUT_BANNER") {
+            use std::io::Write;
+            let mut stdout = std::io::stdout().lock();
+            for line in banner.split('|') {
+                writeln!(stdout, "{line}")?;
+            }
+            stdout.flush()?;
+        }
+        let server = MockServer.serve(stdio()).await?;
         tracing::info!("stdio server ready");
         server.waiting().await?;
     } else {